@@ -0,0 +1,144 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use tracing::{info, warn};
+
+use super::database::Database;
+use super::export::{escape_html, strip_html_tags};
+use super::models::{ImportFailure, LibraryExportReport, Slice};
+use super::pii;
+
+/// An AppleScript string literal holding `text`.
+pub fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The note's title: the slice title, or its file name when it has none.
+fn note_title(slice: &Slice) -> &str {
+    slice.title.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or(&slice.original_audio_file_name)
+}
+
+/// Notes stores bodies as HTML; its first line becomes the note's name in the list.
+fn note_body(slice: &Slice) -> String {
+    let mut body = format!("<h1>{}</h1>", escape_html(note_title(slice)));
+    if let Some(date) = slice.recording_date.and_then(|ts| Local.timestamp_opt(ts, 0).single()) {
+        body.push_str(&format!("<div>Recorded {}</div>", date.format("%Y-%m-%d %H:%M")));
+    }
+    if let Some(transcription) = &slice.transcription {
+        body.push_str(&format!("<div><br></div><div>{}</div>", escape_html(&strip_html_tags(transcription))));
+    }
+    body
+}
+
+/// Script creating one note in `folder` of the default Notes account, making the folder
+/// first if it doesn't exist yet.
+fn create_note_script(folder: &str, slice: &Slice) -> String {
+    let folder = applescript_string(folder);
+    format!(
+        r#"tell application "Notes"
+    set targetAccount to default account
+    if not (exists folder {folder} of targetAccount) then
+        make new folder at targetAccount with properties {{name:{folder}}}
+    end if
+    make new note at folder {folder} of targetAccount with properties {{name:{name}, body:{body}}}
+end tell
+"#,
+        folder = folder,
+        name = applescript_string(note_title(slice)),
+        body = applescript_string(&note_body(slice)),
+    )
+}
+
+/// Run an AppleScript through osascript. The script goes in on stdin, as a long transcript
+/// could overflow the argument list.
+pub fn run_applescript(script: &str) -> Result<()> {
+    let mut child = Command::new("/usr/bin/osascript")
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run osascript")?;
+    child.stdin.take().context("osascript has no stdin")?.write_all(script.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("{}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Create one Apple Notes note per transcribed slice in `folder`. Untranscribed slices and
+/// unknown IDs are skipped. The report's `destination` is the folder and `exported_files`
+/// the titles of the notes made.
+pub fn send_to_apple_notes(db: &Database, slice_ids: &[i64], folder: &str) -> Result<LibraryExportReport> {
+    let folder = folder.trim();
+    if folder.is_empty() {
+        return Err(anyhow::anyhow!("Apple Notes folder name must not be empty"));
+    }
+
+    let mut report = LibraryExportReport {
+        destination: folder.to_string(),
+        ..LibraryExportReport::default()
+    };
+    for id in slice_ids {
+        let Some(slice) = db.get_slice(*id)?.filter(|s| s.transcription.is_some()).map(pii::for_export) else { continue };
+        match run_applescript(&create_note_script(folder, &slice)) {
+            Ok(()) => report.exported_files.push(note_title(&slice).to_string()),
+            Err(e) => {
+                warn!("Failed to create Apple Notes note for {}: {}", slice.original_audio_file_name, e);
+                report.errors.push(ImportFailure {
+                    file_path: slice.original_audio_file_name.clone(),
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+
+    info!(
+        "Sent {} transcripts to Apple Notes folder '{}' ({} failed)",
+        report.exported_files.len(),
+        folder,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+
+    #[test]
+    fn test_create_note_script_escapes_text() {
+        let slice = Slice {
+            id: Some(1),
+            title: Some("Say \"hi\" \\ <now>".to_string()),
+            transcribed: true,
+            transcription: Some("<p>Fish & chips</p>".to_string()),
+            ..create_test_slice("memo.m4a")
+        };
+
+        let script = create_note_script("Voice \"Memos\"", &slice);
+        assert!(script.contains(r#"exists folder "Voice \"Memos\"" of targetAccount"#));
+        assert!(script.contains(r#"name:"Say \"hi\" \\ <now>""#));
+        assert!(script.contains(r#"<h1>Say &quot;hi&quot; \\ &lt;now&gt;</h1>"#));
+        assert!(script.contains("<div>Fish &amp; chips</div>"));
+    }
+}
@@ -0,0 +1,136 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Model name recorded on slices whose transcription came from Voice Memos itself.
+pub const APPLE_MODEL: &str = "apple";
+
+/// MP4 box holding the transcript Voice Memos generates (macOS 15 / iOS 18 and later).
+const TRANSCRIPT_BOX: &[u8; 4] = b"tsrp";
+
+/// Boxes the transcript may be nested in.
+const CONTAINER_BOXES: [&[u8; 4]; 3] = [b"moov", b"trak", b"udta"];
+
+/// Transcript JSON is small; anything bigger is not what we are looking for.
+const MAX_TRANSCRIPT_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Find the transcript box among the boxes in `[start, end)`, descending into containers.
+fn find_transcript_box(file: &mut File, start: u64, end: u64) -> Result<Option<Vec<u8>>> {
+    let mut offset = start;
+    while offset + 8 <= end {
+        file.seek(SeekFrom::Start(offset))?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        let mut size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let kind = [header[4], header[5], header[6], header[7]];
+        let mut header_len = 8;
+
+        if size == 1 {
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - offset; // Box runs to the end of its parent
+        }
+        if size < header_len || offset + size > end {
+            break; // Truncated or not an MP4 box
+        }
+
+        let body_start = offset + header_len;
+        let body_len = size - header_len;
+        if &kind == TRANSCRIPT_BOX && body_len <= MAX_TRANSCRIPT_BYTES {
+            let mut body = vec![0u8; body_len as usize];
+            file.seek(SeekFrom::Start(body_start))?;
+            file.read_exact(&mut body)?;
+            return Ok(Some(body));
+        }
+        if CONTAINER_BOXES.contains(&&kind) {
+            if let Some(body) = find_transcript_box(file, body_start, offset + size)? {
+                return Ok(Some(body));
+            }
+        }
+        offset += size;
+    }
+    Ok(None)
+}
+
+/// Plain text of a Voice Memos transcript. Its `attributedString.runs` alternate text
+/// fragments with indexes into a table of timings: `["Hello", 0, " world", 1]`.
+fn transcript_text(json: &[u8]) -> Option<String> {
+    let value: Value = serde_json::from_slice(json).ok()?;
+    let runs = value.get("attributedString")?.get("runs")?.as_array()?;
+    let text: String = runs.iter().filter_map(Value::as_str).collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// The transcript Voice Memos stored in a recording, if it has one.
+pub fn read_apple_transcript(path: &Path) -> Result<Option<String>> {
+    let mut file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let len = file.metadata()?.len();
+    Ok(find_transcript_box(&mut file, 0, len)?.and_then(|json| transcript_text(&json)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(body);
+        out
+    }
+
+    #[test]
+    fn test_read_apple_transcript() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let json = br#"{"attributedString":{"attributeTable":[{"timeRange":[0,0.4]},{"timeRange":[0.4,0.9]}],
+            "runs":["Buy",0," milk",1," ",0]},"locale":{"identifier":"en_US","current":0}}"#;
+
+        let udta = mp4_box(b"udta", &mp4_box(TRANSCRIPT_BOX, json));
+        let trak = mp4_box(b"trak", &[mp4_box(b"tkhd", &[0; 12]), udta].concat());
+        let moov = mp4_box(b"moov", &[mp4_box(b"mvhd", &[0; 20]), trak].concat());
+        let file = [mp4_box(b"ftyp", b"M4A \0\0\0\0"), mp4_box(b"mdat", &[7; 64]), moov].concat();
+
+        let with_transcript = temp_dir.path().join("memo.m4a");
+        std::fs::write(&with_transcript, file)?;
+        assert_eq!(read_apple_transcript(&with_transcript)?, Some("Buy milk".to_string()));
+
+        let without = temp_dir.path().join("plain.m4a");
+        std::fs::write(&without, [mp4_box(b"ftyp", b"M4A \0\0\0\0"), mp4_box(b"moov", &[])].concat())?;
+        assert_eq!(read_apple_transcript(&without)?, None);
+
+        let not_mp4 = temp_dir.path().join("garbage.m4a");
+        std::fs::write(&not_mp4, b"definitely not an mp4 file")?;
+        assert_eq!(read_apple_transcript(&not_mp4)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript_text_ignores_empty_or_malformed() {
+        assert_eq!(transcript_text(br#"{"attributedString":{"runs":[" ",0]}}"#), None);
+        assert_eq!(transcript_text(br#"{"locale":{}}"#), None);
+        assert_eq!(transcript_text(b"not json"), None);
+    }
+}
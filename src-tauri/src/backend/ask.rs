@@ -0,0 +1,121 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Questions about the library answered by the local LLM from the transcript passages the
+//! index finds closest to the question, with the answer's sources cited by slice.
+
+use anyhow::Result;
+use regex::Regex;
+
+use super::config::Config;
+use super::embeddings;
+use super::models::{TranscriptAnswer, TranscriptPassage};
+use super::ollama::Ollama;
+
+const ANSWER_INSTRUCTIONS: &str = "You answer questions about a person's voice recordings using only the \
+numbered transcript passages given. Cite the passages you use with their numbers in square brackets, like [2] \
+or [1][3]. If the passages don't contain the answer, say so rather than guessing. Answer in the question's language.";
+
+/// Passages used when the caller doesn't say.
+pub const DEFAULT_PASSAGES: usize = 8;
+
+/// The passages as the model sees them: numbered from 1, each headed by its slice.
+fn build_context(passages: &[TranscriptPassage]) -> String {
+    passages
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let date = p
+                .recording_date
+                .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                .map(|d| format!(", recorded {}", d.format("%Y-%m-%d")))
+                .unwrap_or_default();
+            let title = p.title.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or("untitled");
+            format!("[{}] (slice {}, \"{}\"{})\n{}", i + 1, p.slice_id, title, date, p.text.trim())
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Slice IDs of the passages `answer` cites as `[n]` (or `[n, m]`), in order of first
+/// citation. Numbers that don't match a passage are ignored.
+pub fn cited_slice_ids(answer: &str, passages: &[TranscriptPassage]) -> Vec<i64> {
+    let brackets = Regex::new(r"\[(\d+(?:\s*,\s*\d+)*)\]").expect("valid regex");
+    let mut ids = Vec::new();
+    for cap in brackets.captures_iter(answer) {
+        for number in cap[1].split(',') {
+            let passage = number
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|i| passages.get(i));
+            if let Some(p) = passage {
+                if !ids.contains(&p.slice_id) {
+                    ids.push(p.slice_id);
+                }
+            }
+        }
+    }
+    ids
+}
+
+/// Answer `question` from the `max_passages` most relevant indexed passages.
+pub async fn ask(config: &Config, question: &str, max_passages: usize) -> Result<TranscriptAnswer> {
+    let ollama = Ollama::new(config)?;
+    let passages = embeddings::search(config, &ollama, question, max_passages.max(1)).await?;
+    if passages.is_empty() {
+        return Ok(TranscriptAnswer {
+            answer: "No transcript passages were found for this question.".to_string(),
+            cited_slice_ids: Vec::new(),
+            passages,
+        });
+    }
+    let prompt = format!("Passages:\n\n{}\n\nQuestion: {}", build_context(&passages), question.trim());
+    let answer = ollama.generate(Some(ANSWER_INSTRUCTIONS), &prompt).await?;
+    let cited_slice_ids = cited_slice_ids(&answer, &passages);
+    Ok(TranscriptAnswer { answer, cited_slice_ids, passages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passage(slice_id: i64) -> TranscriptPassage {
+        TranscriptPassage {
+            slice_id,
+            title: Some(format!("Memo {}", slice_id)),
+            recording_date: Some(1_700_000_000),
+            text: "text".to_string(),
+            score: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_cited_slice_ids() {
+        let passages = vec![passage(10), passage(20), passage(10), passage(30)];
+        assert_eq!(cited_slice_ids("Yes [2], see also [3, 1] and [9].", &passages), vec![20, 10]);
+        assert_eq!(cited_slice_ids("[4][0] [x]", &passages), vec![30]);
+        assert!(cited_slice_ids("No sources.", &passages).is_empty());
+    }
+
+    #[test]
+    fn test_build_context() {
+        let context = build_context(&[passage(7), TranscriptPassage { title: None, recording_date: None, ..passage(8) }]);
+        assert!(context.starts_with("[1] (slice 7, \"Memo 7\", recorded 2023-11-14)\ntext"));
+        assert!(context.contains("\n\n[2] (slice 8, \"untitled\")\ntext"));
+    }
+}
@@ -0,0 +1,186 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+use super::config::Config;
+use super::database::{self, Database};
+use super::db_pool::{self, DbPool};
+use super::models::BackupRestoreReport;
+
+/// How long to wait for connections to the database being replaced to be returned.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Check that `path` is an intact CiderPress database; returns how many slices it holds.
+/// Opened read-only, so a file that turns out to be something else is left untouched.
+/// `key` is the SQLCipher passphrase, for backups of an encrypted library.
+pub fn validate_backup(path: &Path, key: Option<&str>) -> Result<u32> {
+    if !path.is_file() {
+        return Err(anyhow::anyhow!("Backup file not found: {:?}", path));
+    }
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Failed to open backup {:?}", path))?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key)?;
+    }
+
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .with_context(|| format!("{:?} is not a SQLite database", path))?;
+    if integrity != "ok" {
+        return Err(anyhow::anyhow!("Backup {:?} is damaged: {}", path, integrity));
+    }
+
+    let slice_count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM slices", [], |row| row.get(0))
+        .with_context(|| format!("{:?} is not a CiderPress database", path))?;
+    Ok(slice_count as u32)
+}
+
+/// SQLite's write-ahead log and shared-memory files beside a database.
+fn sidecar_files(db_path: &Path) -> Vec<PathBuf> {
+    let name = db_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    vec![
+        db_path.with_file_name(format!("{}-wal", name)),
+        db_path.with_file_name(format!("{}-shm", name)),
+    ]
+}
+
+/// Move `new_file` into place as the database at `db_path`, and open a fresh pool on it
+/// (running any schema migrations). Fails, leaving the database as it was, if connections
+/// to the old file are still in use after `CLOSE_TIMEOUT`; stop background work first.
+pub fn replace_database(db_path: &Path, new_file: &Path) -> Result<DbPool> {
+    db_pool::close(db_path, CLOSE_TIMEOUT)?;
+    let swapped = sidecar_files(db_path)
+        .iter()
+        .filter(|f| f.exists())
+        .try_for_each(fs::remove_file)
+        .and_then(|()| fs::rename(new_file, db_path));
+    let pool = db_pool::reopen(db_path);
+    swapped?;
+    pool
+}
+
+/// Save a copy of the library database to the backups folder, named for what is about to
+/// happen to it (`before` = "restore", "redaction", ...), and return its path.
+pub fn save_copy(config: &Config, db: &Database, before: &str) -> Result<PathBuf> {
+    let backups_dir = config.backups_dir();
+    fs::create_dir_all(&backups_dir)?;
+    let stem = format!("CiderPress-db-before-{}-{}", before, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let mut copy = backups_dir.join(format!("{}.sqlite", stem));
+    let mut n = 2;
+    while copy.exists() {
+        copy = backups_dir.join(format!("{} {}.sqlite", stem, n));
+        n += 1;
+    }
+    db.backup_to(&copy)
+        .context("Failed to save a copy of the current database")?;
+    Ok(copy)
+}
+
+/// Copy the current library database, if there is one, to the backups folder before a
+/// restore, returning the copy's path.
+fn save_previous(config: &Config, db_path: &Path) -> Result<Option<String>> {
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let copy = save_copy(config, &Database::connect(db_path)?, "restore")?;
+    Ok(Some(copy.to_string_lossy().to_string()))
+}
+
+/// Replace the library database with the backup at `backup_path`, returning a pool on the
+/// restored database. The current database is first copied to the backups folder, and the
+/// backup staged beside it, so a failure part way leaves the library as it was. Schema
+/// migrations run when the restored database is reopened, so older backups come up to date.
+pub fn restore_backup(config: &Config, backup_path: &Path) -> Result<(DbPool, BackupRestoreReport)> {
+    let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+    let slice_count = validate_backup(backup_path, database::encryption_key(&db_path).as_deref())?;
+
+    if db_path.exists() && fs::canonicalize(backup_path)? == fs::canonicalize(&db_path)? {
+        return Err(anyhow::anyhow!("{:?} is the current library database", backup_path));
+    }
+
+    let staged = db_path.with_extension("sqlite.restoring");
+    let swapped = fs::copy(backup_path, &staged)
+        .with_context(|| format!("Failed to copy {:?} into the library", backup_path))
+        .and_then(|_| save_previous(config, &db_path))
+        .and_then(|previous| Ok((replace_database(&db_path, &staged)?, previous)));
+    let (pool, previous_database) = match swapped {
+        Ok(swapped) => swapped,
+        Err(e) => {
+            let _ = fs::remove_file(&staged);
+            return Err(e);
+        }
+    };
+    info!("Restored database from {:?} ({} slices)", backup_path, slice_count);
+    Ok((pool, BackupRestoreReport {
+        restored_from: backup_path.to_string_lossy().to_string(),
+        previous_database,
+        slice_count,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_restore_backup_swaps_database() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+
+        let backup_path = temp_dir.path().join("backup.sqlite");
+        {
+            let db = db_pool::connect(&db_path)?;
+            db.insert_slice(&create_test_slice("kept.m4a"))?;
+            db.backup_to(&backup_path)?;
+            db.insert_slice(&create_test_slice("after-backup.m4a"))?;
+        }
+
+        let not_a_db = temp_dir.path().join("notes.txt");
+        fs::write(&not_a_db, b"not a database")?;
+        assert!(restore_backup(&config, &not_a_db).is_err());
+        assert!(restore_backup(&config, &db_path).is_err());
+
+        // No copy of the current database can be saved, so nothing is restored or left behind
+        fs::write(config.backups_dir(), b"in the way")?;
+        assert!(restore_backup(&config, &backup_path).is_err());
+        assert!(!db_path.with_extension("sqlite.restoring").exists());
+        fs::remove_file(config.backups_dir())?;
+
+        let (pool, report) = restore_backup(&config, &backup_path)?;
+        assert_eq!(report.slice_count, 1);
+        let names: Vec<_> = pool.get()?.list_all_slices()?.into_iter().map(|s| s.original_audio_file_name).collect();
+        assert_eq!(names, vec!["kept.m4a"]);
+
+        // The replaced database was kept, newer slice and all
+        let previous = PathBuf::from(report.previous_database.unwrap());
+        assert_eq!(validate_backup(&previous, None)?, 2);
+        Ok(())
+    }
+}
@@ -36,6 +36,53 @@ pub enum VoiceMemoValidation {
     NoRecordings,
 }
 
+/// How migrated audio is put into the library.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyMode {
+    /// Plain byte copy
+    #[default]
+    Copy,
+    /// APFS clone (copy-on-write); shares blocks with the source until either changes
+    Clone,
+    /// Hard link; the library file and the source are the same file
+    Hardlink,
+}
+
+/// How label keywords are matched against transcripts when auto-labeling.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordMatchMode {
+    /// Keyword appears anywhere, even inside a longer word ("art" matches "start")
+    #[default]
+    Substring,
+    /// Keyword appears as whole words
+    WordBoundary,
+    /// Whole words, tolerating small misspellings from the transcription
+    Fuzzy,
+}
+
+/// What a migration source profile points at.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// A Voice Memos Recordings folder (with CloudRecordings.db), e.g. from another Mac
+    #[default]
+    VoiceMemos,
+    /// An unencrypted iPhone/iPad backup folder
+    IosBackup,
+}
+
+/// A named migration source, migrated on its own. Slices it brings in get a label
+/// with the profile's name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceProfile {
+    pub name: String,
+    pub path: String,
+    #[serde(default)]
+    pub kind: SourceKind,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub voice_memo_root: String,
@@ -50,12 +97,111 @@ pub struct Config {
     pub password_hash: Option<String>,
     #[serde(default = "default_lock_timeout_minutes")]
     pub lock_timeout_minutes: u32,
+    #[serde(default = "default_call_recordings_root")]
+    pub call_recordings_root: String,
+    #[serde(default)]
+    pub watch_mode_enabled: bool,
+    #[serde(default)]
+    pub migration_interval_hours: u32, // 0 = scheduled migration disabled
+    #[serde(default)]
+    pub additional_sources: Vec<String>, // extra folders (e.g. iCloud Drive) scanned for audio on migration
+    #[serde(default)]
+    pub copy_mode: CopyMode, // clone/hardlink fall back to a copy across volumes
+    #[serde(default)]
+    pub source_profiles: Vec<SourceProfile>, // named sources beyond voice_memo_root, e.g. a work Mac
+    #[serde(default)]
+    pub include_recently_deleted: bool, // migrate memos in Voice Memos' Recently Deleted (labelled as such)
+    #[serde(default)]
+    pub inbox_folder: Option<String>, // drop folder (e.g. for AirDrop) imported as files arrive; None = off
+    #[serde(default)]
+    pub inbox_auto_transcribe: bool,
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32, // trashed slices are deleted for good after this long; 0 = never
+    #[serde(default)]
+    pub keyword_match_mode: KeywordMatchMode,
+    #[serde(default)]
+    pub database_encrypted: bool, // SQLCipher passphrase is in the keychain; set by enable_database_encryption
+    #[serde(default = "default_apple_notes_folder")]
+    pub apple_notes_folder: String, // Notes folder transcripts are sent to, created on first use
+    #[serde(default)]
+    pub sync_export_folder: Option<String>, // kept up to date with Markdown transcripts (e.g. in iCloud Drive); None = off
+    #[serde(default = "default_sync_export_interval_minutes")]
+    pub sync_export_interval_minutes: u32,
+    #[serde(default = "default_search_history_enabled")]
+    pub search_history_enabled: bool, // remember search text for recall; off = nothing new is recorded
+    #[serde(default)]
+    pub drive_client_id: Option<String>, // Google OAuth desktop client for Drive uploads
+    #[serde(default)]
+    pub drive_client_secret: Option<String>,
+    #[serde(default)]
+    pub drive_folder_id: Option<String>, // Drive folder uploads go into; None = My Drive
+    #[serde(default)]
+    pub nlm_auto_upload_notebook: Option<String>, // every finished transcript goes to this notebook
+    #[serde(default = "default_nlm_timeout_seconds")]
+    pub nlm_timeout_seconds: u32, // NotebookLM list/create/details calls
+    #[serde(default = "default_nlm_upload_timeout_seconds")]
+    pub nlm_upload_timeout_seconds: u32, // NotebookLM uploads, which can be large audio files
+    #[serde(default = "default_ollama_url")]
+    pub ollama_url: String, // local Ollama server used for summaries and other LLM features
+    #[serde(default = "default_ollama_model")]
+    pub ollama_model: String,
+    #[serde(default = "default_ollama_embedding_model")]
+    pub ollama_embedding_model: String, // embeds transcript passages for question answering
+    #[serde(default)]
+    pub translation_model: Option<String>, // Ollama model for translations, if not `ollama_model`
+    #[serde(default)]
+    pub pii_redaction_enabled: bool, // mask phone numbers, emails, addresses and card numbers
 }
 
 fn default_lock_timeout_minutes() -> u32 {
     5
 }
 
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_sync_export_interval_minutes() -> u32 {
+    60
+}
+
+fn default_nlm_timeout_seconds() -> u32 {
+    30
+}
+
+fn default_nlm_upload_timeout_seconds() -> u32 {
+    900
+}
+
+fn default_ollama_url() -> String {
+    "http://localhost:11434".to_string()
+}
+
+fn default_ollama_model() -> String {
+    "llama3.2".to_string()
+}
+
+fn default_ollama_embedding_model() -> String {
+    "nomic-embed-text".to_string()
+}
+
+fn default_search_history_enabled() -> bool {
+    true
+}
+
+fn default_apple_notes_folder() -> String {
+    "CiderPress".to_string()
+}
+
+fn default_call_recordings_root() -> String {
+    // Call recordings are kept in their own container, separate from regular voice memos
+    home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("Library/Group Containers/group.com.apple.VoiceMemos.shared/CallRecordings")
+        .to_string_lossy()
+        .to_string()
+}
+
 fn default_skip_already_transcribed() -> bool {
     true // Default to skipping already transcribed slices
 }
@@ -78,6 +224,33 @@ impl Default for Config {
             password_enabled: false,
             password_hash: None,
             lock_timeout_minutes: 5,
+            call_recordings_root: default_call_recordings_root(),
+            watch_mode_enabled: false,
+            migration_interval_hours: 0,
+            additional_sources: Vec::new(),
+            copy_mode: CopyMode::Copy,
+            source_profiles: Vec::new(),
+            include_recently_deleted: false,
+            inbox_folder: None,
+            inbox_auto_transcribe: false,
+            trash_retention_days: 30,
+            keyword_match_mode: KeywordMatchMode::Substring,
+            database_encrypted: false,
+            apple_notes_folder: default_apple_notes_folder(),
+            sync_export_folder: None,
+            sync_export_interval_minutes: 60,
+            search_history_enabled: true,
+            drive_client_id: None,
+            drive_client_secret: None,
+            drive_folder_id: None,
+            nlm_auto_upload_notebook: None,
+            nlm_timeout_seconds: 30,
+            nlm_upload_timeout_seconds: 900,
+            ollama_url: default_ollama_url(),
+            ollama_model: default_ollama_model(),
+            ollama_embedding_model: default_ollama_embedding_model(),
+            translation_model: None,
+            pii_redaction_enabled: false,
         }
     }
 }
@@ -132,6 +305,54 @@ impl Config {
         PathBuf::from(&self.voice_memo_root)
     }
 
+    pub fn call_recordings_root_path(&self) -> PathBuf {
+        PathBuf::from(&self.call_recordings_root)
+    }
+
+    /// Additional migration source folders, with a leading `~/` expanded to the home directory.
+    pub fn additional_source_paths(&self) -> Vec<PathBuf> {
+        self.additional_sources
+            .iter()
+            .map(|source| match (source.strip_prefix("~/"), home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(source),
+            })
+            .collect()
+    }
+
+    pub fn source_profile(&self, name: &str) -> Option<&SourceProfile> {
+        self.source_profiles.iter().find(|p| p.name == name)
+    }
+
+    /// This config narrowed to a Voice Memos profile: its Recordings folder, the
+    /// CallRecordings folder beside it, and no additional sources.
+    pub fn for_voice_memos_profile(&self, profile: &SourceProfile) -> Config {
+        let root = PathBuf::from(&profile.path);
+        let call_recordings = root.parent()
+            .map(|container| container.join("CallRecordings"))
+            .unwrap_or_else(|| root.join("CallRecordings"));
+        Config {
+            voice_memo_root: profile.path.clone(),
+            call_recordings_root: call_recordings.to_string_lossy().to_string(),
+            additional_sources: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// The folder transcripts are kept in step with, if one is configured.
+    pub fn sync_export_path(&self) -> Option<PathBuf> {
+        self.sync_export_folder.as_deref()
+            .filter(|folder| !folder.trim().is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// The inbox drop folder, if one is configured.
+    pub fn inbox_path(&self) -> Option<PathBuf> {
+        self.inbox_folder.as_deref()
+            .filter(|folder| !folder.trim().is_empty())
+            .map(PathBuf::from)
+    }
+
     pub fn audio_dir(&self) -> PathBuf {
         self.ciderpress_home_path().join("audio")
     }
@@ -144,6 +365,10 @@ impl Config {
         self.ciderpress_home_path().join("logs")
     }
 
+    pub fn backups_dir(&self) -> PathBuf {
+        self.ciderpress_home_path().join("backups")
+    }
+
     /// Validate that the voice memo root contains the expected files.
     /// Returns a structured result distinguishing permission errors from missing dirs.
     pub fn validate_voice_memo_root(&self) -> VoiceMemoValidation {
@@ -0,0 +1,240 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use ffmpeg_next::{codec, encoder, filter, format, media, ChannelLayout, Rational};
+use ffmpeg_next::util::frame::audio::Audio;
+use std::path::Path;
+
+/// Bit rate for converted recordings; plenty for speech.
+const AAC_BIT_RATE: usize = 64_000;
+
+/// Transcode any audio file ffmpeg can read (opus, ogg, ...) into an AAC `.m4a`,
+/// so imported recordings play and transcribe like the rest of the library.
+pub fn transcode_to_m4a(input: &Path, output: &Path) -> Result<()> {
+    let input_str = input.to_str().context("Invalid input path")?;
+    let output_str = output.to_str().context("Invalid output path")?;
+
+    tracing::info!("Transcoding {} to {}", input.display(), output.display());
+
+    let mut ictx = format::input(input_str)
+        .with_context(|| format!("Failed to open input: {}", input_str))?;
+
+    let input_stream = ictx.streams().best(media::Type::Audio)
+        .context("No audio stream found in input")?;
+    let input_stream_index = input_stream.index();
+
+    let decoder_context = codec::context::Context::from_parameters(input_stream.parameters())
+        .context("Failed to create decoder context")?;
+    let mut decoder = decoder_context.decoder().audio()
+        .context("Failed to open audio decoder")?;
+    let input_time_base = decoder.time_base();
+
+    let src_channel_layout = if decoder.channel_layout().is_empty() {
+        ChannelLayout::MONO
+    } else {
+        decoder.channel_layout()
+    };
+
+    let mut octx = format::output(output_str)
+        .with_context(|| format!("Failed to create output: {}", output_str))?;
+    let global_header = octx.format().flags().contains(format::Flags::GLOBAL_HEADER);
+
+    let codec = ffmpeg_next::encoder::find(codec::Id::AAC)
+        .context("AAC encoder not found")?
+        .audio()
+        .context("AAC encoder is not an audio codec")?;
+    let dst_channel_layout = codec.channel_layouts()
+        .map(|layouts| layouts.best(src_channel_layout.channels()))
+        .unwrap_or(ChannelLayout::MONO);
+    let dst_format = codec.formats()
+        .and_then(|mut formats| formats.next())
+        .context("AAC encoder reports no sample formats")?;
+    let dst_rate = decoder.rate();
+
+    let mut output_stream = octx.add_stream(codec)
+        .context("Failed to add output stream")?;
+    let encoder_context = codec::context::Context::from_parameters(output_stream.parameters())
+        .context("Failed to create encoder context")?;
+    let mut encoder = encoder_context.encoder().audio()
+        .context("Failed to open audio encoder")?;
+
+    encoder.set_rate(dst_rate as i32);
+    encoder.set_channel_layout(dst_channel_layout);
+    encoder.set_format(dst_format);
+    encoder.set_bit_rate(AAC_BIT_RATE);
+    encoder.set_time_base((1, dst_rate as i32));
+    output_stream.set_time_base((1, dst_rate as i32));
+
+    if global_header {
+        encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+    }
+
+    let mut encoder = encoder.open_as(codec)
+        .context("Failed to open AAC encoder")?;
+    output_stream.set_parameters(&encoder);
+
+    // AAC takes fixed-size frames, which the resampler alone doesn't produce,
+    // so route samples through an abuffer -> abuffersink graph that re-chunks them
+    let mut graph = filter::Graph::new();
+    let args = format!(
+        "time_base={}:sample_rate={}:sample_fmt={}:channel_layout=0x{:x}",
+        input_time_base,
+        decoder.rate(),
+        decoder.format().name(),
+        src_channel_layout.bits()
+    );
+    graph.add(&filter::find("abuffer").context("abuffer filter not found")?, "in", &args)?;
+    graph.add(&filter::find("abuffersink").context("abuffersink filter not found")?, "out", "")?;
+    {
+        let mut out = graph.get("out").context("Missing filter sink")?;
+        out.set_sample_format(encoder.format());
+        out.set_channel_layout(encoder.channel_layout());
+        out.set_sample_rate(encoder.rate());
+    }
+    graph.output("in", 0)?.input("out", 0)?.parse("anull")?;
+    graph.validate()?;
+    if !codec.capabilities().contains(codec::capabilities::Capabilities::VARIABLE_FRAME_SIZE) {
+        graph.get("out").context("Missing filter sink")?.sink().set_frame_size(encoder.frame_size());
+    }
+
+    octx.write_header().context("Failed to write output header")?;
+    let output_time_base = octx.stream(0).context("Missing output stream")?.time_base();
+
+    let mut decoded_frame = Audio::empty();
+    for (stream, mut packet) in ictx.packets() {
+        if stream.index() != input_stream_index {
+            continue;
+        }
+        packet.rescale_ts(stream.time_base(), input_time_base);
+        decoder.send_packet(&packet)?;
+        while decoder.receive_frame(&mut decoded_frame).is_ok() {
+            let timestamp = decoded_frame.timestamp();
+            decoded_frame.set_pts(timestamp);
+            graph.get("in").context("Missing filter source")?.source().add(&decoded_frame)?;
+            drain_filter(&mut graph, &mut encoder, &mut octx, input_time_base, output_time_base)?;
+        }
+    }
+
+    // Flush decoder, then filter, then encoder
+    decoder.send_eof()?;
+    while decoder.receive_frame(&mut decoded_frame).is_ok() {
+        let timestamp = decoded_frame.timestamp();
+        decoded_frame.set_pts(timestamp);
+        graph.get("in").context("Missing filter source")?.source().add(&decoded_frame)?;
+        drain_filter(&mut graph, &mut encoder, &mut octx, input_time_base, output_time_base)?;
+    }
+
+    graph.get("in").context("Missing filter source")?.source().flush()?;
+    drain_filter(&mut graph, &mut encoder, &mut octx, input_time_base, output_time_base)?;
+
+    encoder.send_eof()?;
+    write_packets(&mut encoder, &mut octx, input_time_base, output_time_base)?;
+
+    octx.write_trailer().context("Failed to write output trailer")?;
+
+    if !output.exists() {
+        return Err(anyhow::anyhow!("M4A file was not created: {}", output.display()));
+    }
+
+    tracing::info!("Successfully transcoded to M4A: {}", output.display());
+    Ok(())
+}
+
+/// Copy the audio of `input` into a new file at `output` without re-encoding, setting the
+/// given container metadata (e.g. `title`, `creation_time`) on top of what is already there.
+pub fn remux_with_metadata(input: &Path, output: &Path, metadata: &[(&str, String)]) -> Result<()> {
+    let input_str = input.to_str().context("Invalid input path")?;
+    let output_str = output.to_str().context("Invalid output path")?;
+
+    let mut ictx = format::input(input_str)
+        .with_context(|| format!("Failed to open input: {}", input_str))?;
+    let mut octx = format::output(output_str)
+        .with_context(|| format!("Failed to create output: {}", output_str))?;
+
+    // Input stream index -> output stream index; non-audio streams are dropped
+    let mut stream_mapping: Vec<Option<usize>> = vec![None; ictx.nb_streams() as usize];
+    let mut input_time_bases = vec![Rational(0, 1); ictx.nb_streams() as usize];
+    let mut output_index = 0;
+    for (input_index, stream) in ictx.streams().enumerate() {
+        if stream.parameters().medium() != media::Type::Audio {
+            continue;
+        }
+        stream_mapping[input_index] = Some(output_index);
+        input_time_bases[input_index] = stream.time_base();
+        output_index += 1;
+        let mut output_stream = octx.add_stream(encoder::find(codec::Id::None))
+            .context("Failed to add output stream")?;
+        output_stream.set_parameters(stream.parameters());
+    }
+    if output_index == 0 {
+        return Err(anyhow::anyhow!("No audio stream found in input"));
+    }
+
+    let mut dictionary = ictx.metadata().to_owned();
+    for (key, value) in metadata {
+        dictionary.set(key, value);
+    }
+    octx.set_metadata(dictionary);
+    octx.write_header().context("Failed to write output header")?;
+
+    for (stream, mut packet) in ictx.packets() {
+        let input_index = stream.index();
+        let Some(output_index) = stream_mapping[input_index] else {
+            continue;
+        };
+        let output_time_base = octx.stream(output_index).context("Missing output stream")?.time_base();
+        packet.rescale_ts(input_time_bases[input_index], output_time_base);
+        packet.set_position(-1);
+        packet.set_stream(output_index);
+        packet.write_interleaved(&mut octx)?;
+    }
+
+    octx.write_trailer().context("Failed to write output trailer")?;
+    Ok(())
+}
+
+/// Helper: pull every ready frame out of the filter sink and encode it
+fn drain_filter(
+    graph: &mut filter::Graph,
+    encoder: &mut ffmpeg_next::encoder::Audio,
+    octx: &mut format::context::Output,
+    input_tb: ffmpeg_next::Rational,
+    output_tb: ffmpeg_next::Rational,
+) -> Result<()> {
+    let mut filtered = Audio::empty();
+    while graph.get("out").context("Missing filter sink")?.sink().frame(&mut filtered).is_ok() {
+        encoder.send_frame(&filtered)?;
+        write_packets(encoder, octx, input_tb, output_tb)?;
+    }
+    Ok(())
+}
+
+/// Helper: write every packet the encoder has ready
+fn write_packets(
+    encoder: &mut ffmpeg_next::encoder::Audio,
+    octx: &mut format::context::Output,
+    input_tb: ffmpeg_next::Rational,
+    output_tb: ffmpeg_next::Rational,
+) -> Result<()> {
+    let mut encoded_packet = ffmpeg_next::Packet::empty();
+    while encoder.receive_packet(&mut encoded_packet).is_ok() {
+        encoded_packet.set_stream(0);
+        encoded_packet.rescale_ts(input_tb, output_tb);
+        encoded_packet.write_interleaved(octx)?;
+    }
+    Ok(())
+}
@@ -16,10 +16,227 @@
 
 use anyhow::Result;
 use rusqlite::{Connection, params};
-use std::collections::HashMap;
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::models::{DerivedDocument, DriveFile, EntityCount, EntityKind, MoodPoint, Recording, Transcript, RecordingWithTranscript, Stats, YearCount, AudioLengthBucket, Slice, SliceChange, Collection, RecentSlice, RelatedSlice, ReplaceReport, SearchHistoryEntry, SearchMode, SearchResult, SearchResultPage, SliceFilter, SlicePage, SliceQuery, SliceEntity, SliceReplaceCount, SliceSentiment, SliceSortField, SliceTranslation, TranscriptChunk, TranscriptReplacement, TrashedSlice, Label, MigrationBatchFile, NlmBatchItem, NlmInvocation, NlmSource, NlmSourceKind, NlmSourcePart, NlmUpload, LabelNotebook, LabelPrompt};
+use super::labeling;
+use super::pii;
+use super::search;
+
+/// Most searches kept in the history; older ones are dropped as new ones are recorded.
+const SEARCH_HISTORY_LIMIT: i64 = 100;
+
+/// How long a statement waits on another connection's write lock before failing with SQLITE_BUSY.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+// SQLCipher passphrases for encrypted databases, by path; every connection to one needs it
+lazy_static::lazy_static! {
+    static ref ENCRYPTION_KEYS: Mutex<HashMap<PathBuf, String>> = Mutex::new(HashMap::new());
+}
+
+/// The passphrase set for `db_path`, if it is encrypted.
+pub fn encryption_key<P: AsRef<Path>>(db_path: P) -> Option<String> {
+    ENCRYPTION_KEYS.lock().unwrap().get(db_path.as_ref()).cloned()
+}
+
+/// Set (or with `None` forget) the passphrase connections to `db_path` are opened with.
+pub fn set_encryption_key<P: AsRef<Path>>(db_path: P, key: Option<String>) {
+    let mut keys = ENCRYPTION_KEYS.lock().unwrap();
+    match key {
+        Some(key) => keys.insert(db_path.as_ref().to_path_buf(), key),
+        None => keys.remove(db_path.as_ref()),
+    };
+}
+
+/// Slice text about to be stored, masked while PII redaction mode is on. Every write of a
+/// slice's text (transcript, title, notes, summary and what is derived from them) goes
+/// through here.
+fn stored_text(text: Option<&str>) -> Option<Cow<'_, str>> {
+    text.map(pii::redact_if_enabled)
+}
+
+/// Slice fields tracked in `slice_history`.
+const HISTORY_NAME: &str = "name";
+const HISTORY_TITLE: &str = "title";
+const HISTORY_TRANSCRIPTION: &str = "transcription";
+const HISTORY_NOTES: &str = "notes";
+
+/// Column list shared by every query that materializes a full `Slice`.
+const SLICE_COLUMNS: &str = "id, original_audio_file_name, title, transcribed, audio_file_size, audio_file_type,
+    estimated_time_to_transcribe, audio_time_length_seconds, transcription, transcription_time_taken,
+    transcription_word_count, transcription_model, recording_date, source, starred, was_edited, content_hash,
+    source_relative_path, notes, pinned, summary";
+
+/// `SLICE_COLUMNS` with the transcription text left out, for listings that don't show it.
+const SLICE_LIST_COLUMNS: &str = "id, original_audio_file_name, title, transcribed, audio_file_size, audio_file_type,
+    estimated_time_to_transcribe, audio_time_length_seconds, NULL AS transcription, transcription_time_taken,
+    transcription_word_count, transcription_model, recording_date, source, starred, was_edited, content_hash,
+    source_relative_path, notes, pinned, summary";
+
+/// Map a row selected with `SLICE_COLUMNS` or `SLICE_LIST_COLUMNS` into a `Slice`.
+fn slice_from_row(row: &rusqlite::Row) -> rusqlite::Result<Slice> {
+    Ok(Slice {
+        id: Some(row.get("id")?),
+        original_audio_file_name: row.get("original_audio_file_name")?,
+        title: row.get("title")?,
+        transcribed: row.get::<_, i32>("transcribed")? != 0,
+        audio_file_size: row.get("audio_file_size")?,
+        audio_file_type: row.get("audio_file_type")?,
+        estimated_time_to_transcribe: row.get("estimated_time_to_transcribe")?,
+        audio_time_length_seconds: row.get("audio_time_length_seconds")?,
+        transcription: row.get("transcription")?,
+        transcription_time_taken: row.get("transcription_time_taken")?,
+        transcription_word_count: row.get("transcription_word_count")?,
+        transcription_model: row.get("transcription_model")?,
+        recording_date: row.get("recording_date")?,
+        source: row.get("source")?,
+        starred: row.get::<_, i32>("starred")? != 0,
+        was_edited: row.get::<_, i32>("was_edited")? != 0,
+        content_hash: row.get("content_hash")?,
+        source_relative_path: row.get("source_relative_path")?,
+        notes: row.get("notes")?,
+        pinned: row.get::<_, i32>("pinned")? != 0,
+        summary: row.get("summary")?,
+    })
+}
+
+/// Map a row of `nlm_sources` (notebook_id, source_id, slice_id, kind, title, added_at,
+/// content_hash) into an `NlmSource`.
+fn nlm_source_from_row(row: &rusqlite::Row) -> rusqlite::Result<NlmSource> {
+    let kind: String = row.get(3)?;
+    Ok(NlmSource {
+        notebook_id: row.get(0)?,
+        source_id: row.get(1)?,
+        slice_id: row.get(2)?,
+        kind: NlmSourceKind::parse(&kind).unwrap_or(NlmSourceKind::Text),
+        title: row.get(4)?,
+        added_at: row.get(5)?,
+        content_hash: row.get(6)?,
+    })
+}
+
+/// An entity name as matched and grouped on: lowercased, with runs of whitespace collapsed
+/// and surrounding punctuation dropped, so "Dr. Alvarez" and "dr.  alvarez," are one.
+pub fn normalize_entity_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// SQL expression a slice listing sorts on.
+fn sort_column(field: SliceSortField) -> &'static str {
+    match field {
+        SliceSortField::Id => "id",
+        SliceSortField::RecordingDate => "recording_date",
+        SliceSortField::Duration => "audio_time_length_seconds",
+        SliceSortField::Size => "audio_file_size",
+        SliceSortField::Title => "COALESCE(NULLIF(title, ''), original_audio_file_name) COLLATE NOCASE",
+        SliceSortField::WordCount => "transcription_word_count",
+        SliceSortField::Transcribed => "transcribed",
+    }
+}
+
+/// Append a WHERE condition per field set in `filter`, with `values` holding the
+/// parameters so far (conditions refer to them by position).
+fn push_filter_conditions(filter: &SliceFilter, conditions: &mut Vec<String>, values: &mut Vec<rusqlite::types::Value>) {
+    let mut push = |condition: &str, value: rusqlite::types::Value| {
+        values.push(value);
+        conditions.push(condition.replace('?', &format!("?{}", values.len())));
+    };
+
+    if let Some(after) = filter.recorded_after {
+        push("recording_date >= ?", after.into());
+    }
+    if let Some(before) = filter.recorded_before {
+        push("recording_date < ?", before.into());
+    }
+    if let Some(min) = filter.min_duration_seconds {
+        push("audio_time_length_seconds >= ?", min.into());
+    }
+    if let Some(max) = filter.max_duration_seconds {
+        push("audio_time_length_seconds <= ?", max.into());
+    }
+    if let Some(transcribed) = filter.transcribed {
+        push("transcribed = ?", i64::from(transcribed).into());
+    }
+    if let Some(model) = &filter.transcription_model {
+        push("transcribed = 1 AND transcription_model = ?", model.clone().into());
+    }
+    if let Some(starred) = filter.starred {
+        push("starred = ?", i64::from(starred).into());
+    }
+}
+
+/// WHERE clause and bound values selecting the slices a `SliceQuery` lists.
+fn slice_query_where(query: &SliceQuery) -> (String, Vec<rusqlite::types::Value>) {
+    let mut conditions = vec![
+        "deleted_at IS NULL".to_string(),
+        format!("archived = {}", i32::from(query.archived)),
+    ];
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+
+    let search = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let phonetic_codes = search
+        .filter(|_| query.search_mode == SearchMode::Phonetic)
+        .map(search::phonetic_codes)
+        .unwrap_or_default();
+    if !phonetic_codes.is_empty() {
+        // Every search word has to sound like some word of the transcript
+        for code in phonetic_codes {
+            values.push(code.into());
+            conditions.push(format!(
+                "id IN (SELECT slice_id FROM phonetic_index WHERE code = ?{})",
+                values.len()
+            ));
+        }
+    } else if let Some(search) = search.filter(|_| search_outside_sql(query).is_none()) {
+        values.push(format!("%{}%", search).into());
+        let n = values.len();
+        conditions.push(format!(
+            "(title LIKE ?{n} OR original_audio_file_name LIKE ?{n} OR transcription LIKE ?{n} OR notes LIKE ?{n})"
+        ));
+    }
+    if let Some(label_id) = query.label_id {
+        values.push(label_id.into());
+        conditions.push(format!(
+            "id IN (SELECT slice_id FROM slice_labels WHERE label_id = ?{})",
+            values.len()
+        ));
+    }
+    if let Some(collection_id) = query.collection_id {
+        values.push(collection_id.into());
+        conditions.push(format!(
+            "id IN (SELECT slice_id FROM slice_collections WHERE collection_id = ?{})",
+            values.len()
+        ));
+    }
+    push_filter_conditions(&query.filter, &mut conditions, &mut values);
+    (conditions.join(" AND "), values)
+}
+
+/// The search text of a query whose mode can't be expressed in SQL and is matched in Rust.
+fn search_outside_sql(query: &SliceQuery) -> Option<&str> {
+    let search = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty())?;
+    matches!(query.search_mode, SearchMode::Fuzzy | SearchMode::Regex).then_some(search)
+}
 
-use super::models::{Recording, Transcript, RecordingWithTranscript, Stats, YearCount, AudioLengthBucket, Slice, Label};
+/// ORDER BY terms for a `SliceQuery`: pinned first, then (optionally) starred, then the sort
+/// column with missing values last, ties broken by ID.
+fn slice_query_order(query: &SliceQuery) -> String {
+    let column = sort_column(query.sort_by);
+    let direction = if query.descending { "DESC" } else { "ASC" };
+    let starred_clause = if query.starred_first { "starred DESC, " } else { "" };
+    format!(
+        "pinned DESC, {}{} IS NULL, {} {}, id {}",
+        starred_clause, column, column, direction, direction
+    )
+}
 
 pub struct Database {
     conn: Connection,
@@ -27,12 +244,26 @@ pub struct Database {
 
 impl Database {
     pub fn new<P: AsRef<Path>>(db_path: P) -> Result<Self> {
-        let conn = Connection::open(db_path)?;
-        let db = Database { conn };
+        let db = Self::connect(db_path)?;
         db.init_schema()?;
         Ok(db)
     }
 
+    /// Open another connection to a database whose schema is already set up.
+    pub fn connect<P: AsRef<Path>>(db_path: P) -> Result<Self> {
+        let conn = Connection::open(&db_path)?;
+        // The key has to be the first thing set on an encrypted database
+        if let Some(key) = encryption_key(&db_path) {
+            conn.pragma_update(None, "key", key)?;
+        }
+        // WAL lets UI reads run while a migration or transcription writes; NORMAL sync is
+        // safe under WAL and avoids an fsync per transaction
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        Ok(Database { conn })
+    }
+
     fn init_schema(&self) -> Result<()> {
         // Create recordings table
         self.conn.execute(
@@ -104,6 +335,78 @@ impl Database {
             [],
         );
 
+        // Migration: Add source column (which migration source/importer produced the slice)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN source TEXT",
+            [],
+        );
+
+        // Migration: Add starred column (carried over from Apple's favorite flag)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN starred INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Migration: Add was_edited column (recording was trimmed/edited in Voice Memos)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN was_edited INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Migration: Add content_hash column (SHA-256 of the copied audio, verified at migration time)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN content_hash TEXT",
+            [],
+        );
+
+        let _ = self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_slices_content_hash ON slices(content_hash)",
+            [],
+        );
+
+        // Migration: Add source_relative_path column (where a migrated file sat under its source
+        // root, telling apart same-named recordings from different subfolders)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN source_relative_path TEXT",
+            [],
+        );
+
+        // Migration: Add notes column (the user's own annotation on a slice)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN notes TEXT",
+            [],
+        );
+
+        // Migration: Add archived column (hidden from the library without being deleted)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN archived INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Migration: Add pinned column (kept at the top of the library listing)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Migration: Add summary column (written by the local LLM)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN summary TEXT",
+            [],
+        );
+
+        // Migration: Add last_opened_at column (when the slice's audio or transcript was last fetched)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN last_opened_at INTEGER",
+            [],
+        );
+
+        // Migration: Add deleted_at column (when the slice was moved to the trash; NULL = live)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN deleted_at INTEGER",
+            [],
+        );
+
         // Create labels table for label definitions
         self.conn.execute(
             r#"
@@ -130,368 +433,566 @@ impl Database {
             [],
         )?;
 
-        // Add title column to existing slices tables (migration)
-        let _ = self.conn.execute(
-            "ALTER TABLE slices ADD COLUMN title TEXT",
+        // Files already handled per migration source root, so each source is only scanned
+        // for what is new to it (filenames alone are not unique across sources).
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS source_files (
+                source_root   TEXT NOT NULL,
+                relative_path TEXT NOT NULL,
+                slice_id      INTEGER,
+                status        TEXT NOT NULL,  -- 'imported' or 'duplicate'
+                recorded_at   INTEGER NOT NULL,
+                PRIMARY KEY (source_root, relative_path)
+            )
+            "#,
             [],
-        ); // Ignore error if column already exists
+        )?;
 
-        // Add audio_time_length_seconds column to existing slices tables (migration)
-        let _ = self.conn.execute(
-            "ALTER TABLE slices ADD COLUMN audio_time_length_seconds REAL",
+        // Migration batches: files handled so far by a run, so an interrupted migration
+        // picks up where it stopped. Rows are cleared once the batch completes.
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS migration_batches (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind        TEXT NOT NULL,  -- primary source, e.g. 'voice_memos' or 'ios_backup'
+                status      TEXT NOT NULL,  -- 'running', 'completed' or 'rolled_back'
+                started_at  INTEGER NOT NULL,
+                finished_at INTEGER
+            )
+            "#,
             [],
-        ); // Ignore error if column already exists
+        )?;
 
-        // Create indexes
         self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_transcripts_recording ON transcripts(recording_id)",
+            r#"
+            CREATE TABLE IF NOT EXISTS migration_batch_files (
+                batch_id     INTEGER NOT NULL REFERENCES migration_batches(id) ON DELETE CASCADE,
+                file_path    TEXT NOT NULL,
+                result       TEXT NOT NULL,
+                size_bytes   INTEGER,
+                duplicate_of TEXT,
+                PRIMARY KEY (batch_id, file_path)
+            )
+            "#,
             [],
         )?;
 
+        // Slices each batch created, kept after it completes so the batch can be rolled back
         self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_slices_filename ON slices(original_audio_file_name)",
+            r#"
+            CREATE TABLE IF NOT EXISTS migration_batch_slices (
+                batch_id  INTEGER NOT NULL REFERENCES migration_batches(id) ON DELETE CASCADE,
+                slice_id  INTEGER NOT NULL,
+                file_name TEXT NOT NULL,  -- library file name as migrated
+                PRIMARY KEY (batch_id, slice_id)
+            )
+            "#,
             [],
         )?;
 
+        // Previous values of edited slice fields, so an edit can be reviewed and undone
         self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_slice_labels_slice ON slice_labels(slice_id)",
+            r#"
+            CREATE TABLE IF NOT EXISTS slice_history (
+                id         INTEGER PRIMARY KEY,
+                slice_id   INTEGER NOT NULL,
+                field      TEXT NOT NULL,  -- 'name', 'title', 'transcription' or 'notes'
+                old_value  TEXT,
+                new_value  TEXT,
+                changed_at INTEGER NOT NULL
+            )
+            "#,
             [],
         )?;
 
-        Ok(())
-    }
-
-    #[allow(dead_code)]
-    pub fn insert_recording(&self, recording: &Recording) -> Result<i64> {
-        let _rows = self.conn.execute(
+        // Free-form key/value pairs attached to a slice, e.g. project=alpha
+        self.conn.execute(
             r#"
-            INSERT INTO recordings (
-                apple_id, created_at, duration_sec, title, original_path, 
-                copied_path, file_size, mime_type, year
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            CREATE TABLE IF NOT EXISTS slice_metadata (
+                slice_id INTEGER NOT NULL,
+                key      TEXT NOT NULL,
+                value    TEXT NOT NULL,
+                PRIMARY KEY (slice_id, key)
+            )
             "#,
-            params![
-                recording.apple_id,
-                recording.created_at,
-                recording.duration_sec,
-                recording.title,
-                recording.original_path,
-                recording.copied_path,
-                recording.file_size,
-                recording.mime_type,
-                recording.year,
-            ],
+            [],
         )?;
-        Ok(self.conn.last_insert_rowid())
-    }
 
-    #[allow(dead_code)]
-    pub fn get_recording_by_apple_id(&self, apple_id: i64) -> Result<Option<Recording>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, apple_id, created_at, duration_sec, title, original_path, copied_path, file_size, mime_type, year FROM recordings WHERE apple_id = ?1"
+        // Collections: nested folders for organizing slices, e.g. Project/2025
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS collections (
+                id        INTEGER PRIMARY KEY,
+                name      TEXT NOT NULL,
+                parent_id INTEGER  -- NULL = top level
+            )
+            "#,
+            [],
         )?;
 
-        let recording = stmt.query_row(params![apple_id], |row| {
-            Ok(Recording {
-                id: Some(row.get(0)?),
-                apple_id: row.get(1)?,
-                created_at: row.get(2)?,
-                duration_sec: row.get(3)?,
-                title: row.get(4)?,
-                original_path: row.get(5)?,
-                copied_path: row.get(6)?,
-                file_size: row.get(7)?,
-                mime_type: row.get(8)?,
-                year: row.get(9)?,
-            })
-        });
-
-        match recording {
-            Ok(r) => Ok(Some(r)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
-    }
-
-    pub fn list_recordings(&self, limit: Option<u32>, offset: Option<u32>) -> Result<Vec<RecordingWithTranscript>> {
-        let limit_clause = limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default();
-        let offset_clause = offset.map(|o| format!("OFFSET {}", o)).unwrap_or_default();
-
-        let query = format!(
+        // The collection each slice is filed in; a slice sits in at most one
+        self.conn.execute(
             r#"
-            SELECT 
-                r.id, r.apple_id, r.created_at, r.duration_sec, r.title, 
-                r.original_path, r.copied_path, r.file_size, r.mime_type, r.year,
-                COUNT(t.id) as transcript_count,
-                CASE WHEN COUNT(CASE WHEN t.success = 1 THEN 1 END) > 0 THEN 1 ELSE 0 END as has_successful_transcript,
-                (SELECT text_path FROM transcripts WHERE recording_id = r.id AND success = 1 ORDER BY finished_at DESC LIMIT 1) as latest_transcript_path
-            FROM recordings r
-            LEFT JOIN transcripts t ON r.id = t.recording_id
-            GROUP BY r.id
-            ORDER BY r.created_at DESC
-            {} {}
+            CREATE TABLE IF NOT EXISTS slice_collections (
+                slice_id      INTEGER PRIMARY KEY,
+                collection_id INTEGER NOT NULL
+            )
             "#,
-            limit_clause, offset_clause
-        );
-
-        let mut stmt = self.conn.prepare(&query)?;
-        let rows = stmt.query_map([], |row| {
-            let transcript_path: Option<String> = row.get(12)?;
-            let latest_transcript_text = transcript_path
-                .and_then(|path| std::fs::read_to_string(path).ok());
-
-            Ok(RecordingWithTranscript {
-                recording: Recording {
-                    id: Some(row.get(0)?),
-                    apple_id: row.get(1)?,
-                    created_at: row.get(2)?,
-                    duration_sec: row.get(3)?,
-                    title: row.get(4)?,
-                    original_path: row.get(5)?,
-                    copied_path: row.get(6)?,
-                    file_size: row.get(7)?,
-                    mime_type: row.get(8)?,
-                    year: row.get(9)?,
-                },
-                transcript_count: row.get(10)?,
-                has_successful_transcript: row.get::<_, i32>(11)? == 1,
-                latest_transcript_text,
-            })
-        })?;
+            [],
+        )?;
 
-        let mut recordings = Vec::new();
-        for row in rows {
-            recordings.push(row?);
-        }
-        Ok(recordings)
-    }
+        // Links between related slices, e.g. part 1 -> part 2 of a session; one link per pair
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS slice_links (
+                from_id  INTEGER NOT NULL,
+                to_id    INTEGER NOT NULL,
+                relation TEXT NOT NULL,
+                PRIMARY KEY (from_id, to_id)
+            )
+            "#,
+            [],
+        )?;
 
-    pub fn insert_transcript(&self, transcript: &Transcript) -> Result<i64> {
+        // Recent search text, for recall in the search box
         self.conn.execute(
             r#"
-            INSERT INTO transcripts (
-                recording_id, model, started_at, finished_at, 
-                word_count, text_path, success, error_message
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            CREATE TABLE IF NOT EXISTS search_history (
+                query            TEXT PRIMARY KEY COLLATE NOCASE,
+                last_searched_at INTEGER NOT NULL,
+                times_searched   INTEGER NOT NULL DEFAULT 1
+            )
             "#,
-            params![
-                transcript.recording_id,
-                transcript.model,
-                transcript.started_at,
-                transcript.finished_at,
-                transcript.word_count,
-                transcript.text_path,
-                transcript.success as i32,
-                transcript.error_message,
-            ],
+            [],
         )?;
-        Ok(self.conn.last_insert_rowid())
-    }
 
-    pub fn update_transcript(&self, id: i64, transcript: &Transcript) -> Result<()> {
+        // NotebookLM batch uploads, one row per item, so an interrupted batch can resume
         self.conn.execute(
             r#"
-            UPDATE transcripts SET
-                finished_at = ?1,
-                word_count = ?2,
-                text_path = ?3,
-                success = ?4,
-                error_message = ?5
-            WHERE id = ?6
+            CREATE TABLE IF NOT EXISTS nlm_batch_items (
+                batch_id    TEXT NOT NULL,
+                position    INTEGER NOT NULL,
+                slice_id    INTEGER NOT NULL,
+                notebook_id TEXT NOT NULL,
+                kind        TEXT NOT NULL,
+                status      TEXT NOT NULL DEFAULT 'pending',
+                error       TEXT,
+                updated_at  INTEGER NOT NULL,
+                PRIMARY KEY (batch_id, position)
+            )
             "#,
-            params![
-                transcript.finished_at,
-                transcript.word_count,
-                transcript.text_path,
-                transcript.success as i32,
-                transcript.error_message,
-                id,
-            ],
+            [],
         )?;
-        Ok(())
-    }
+        // Migration: Add retry bookkeeping for uploads queued in the background, which are
+        // tried again after a failure instead of waiting for the user to resume them
+        let _ = self.conn.execute(
+            "ALTER TABLE nlm_batch_items ADD COLUMN queued INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE nlm_batch_items ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE nlm_batch_items ADD COLUMN retry_at INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
 
-    pub fn get_stats(&self) -> Result<Stats> {
-        // Total files from slices table
-        let total_files: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM slices",
+        // Sources CiderPress added to NotebookLM notebooks, so they can be found and removed
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS nlm_sources (
+                notebook_id TEXT NOT NULL,
+                source_id   TEXT NOT NULL,
+                slice_id    INTEGER,
+                kind        TEXT NOT NULL,
+                title       TEXT,
+                added_at    INTEGER NOT NULL,
+                PRIMARY KEY (notebook_id, source_id)
+            )
+            "#,
             [],
-            |row| row.get(0),
         )?;
 
-        // Total transcribed from slices table
-        let total_transcribed: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM slices WHERE transcribed = 1",
+        // Migration: hash of each source's content, to skip uploading it to the same notebook twice
+        let _ = self.conn.execute("ALTER TABLE nlm_sources ADD COLUMN content_hash TEXT", []);
+
+        // Which stretch of a long transcript each part uploaded as its own source holds
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS nlm_source_parts (
+                notebook_id TEXT NOT NULL,
+                source_id   TEXT NOT NULL,
+                slice_id    INTEGER NOT NULL,
+                part        INTEGER NOT NULL,
+                part_count  INTEGER NOT NULL,
+                start_char  INTEGER NOT NULL,
+                end_char    INTEGER NOT NULL,
+                PRIMARY KEY (notebook_id, source_id)
+            )
+            "#,
             [],
-            |row| row.get(0),
         )?;
 
-        // Average transcription time per 10 minutes of audio from slices table
-        let avg_transcribe_sec_10m: Option<f64> = self.conn.query_row(
+        // Every upload of a slice to NotebookLM, kept after the source itself is removed
+        self.conn.execute(
             r#"
-            SELECT AVG(transcription_time_taken / (audio_file_size / 1048576.0 / 60.0) * 10.0) 
-            FROM slices 
-            WHERE transcribed = 1 AND transcription_time_taken IS NOT NULL AND audio_file_size > 0
+            CREATE TABLE IF NOT EXISTS nlm_uploads (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                slice_id    INTEGER NOT NULL,
+                notebook_id TEXT NOT NULL,
+                source_type TEXT NOT NULL,
+                source_id   TEXT,
+                uploaded_at INTEGER NOT NULL
+            )
             "#,
             [],
-            |row| row.get(0),
-        ).unwrap_or(None);
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_nlm_uploads_slice ON nlm_uploads(slice_id)",
+            [],
+        )?;
+        // Migration: Add account column (which saved NLM account made the upload)
+        let _ = self.conn.execute(
+            "ALTER TABLE nlm_uploads ADD COLUMN account TEXT",
+            [],
+        );
 
-        // Total audio bytes from slices table
-        let total_audio_bytes: i64 = self.conn.query_row(
-            "SELECT COALESCE(SUM(audio_file_size), 0) FROM slices",
+        // Every run of the nlm binary, newest kept, to audit failures after the fact
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS nlm_invocations (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                command     TEXT NOT NULL,
+                args        TEXT NOT NULL,
+                started_at  INTEGER NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                success     INTEGER NOT NULL,
+                error_kind  TEXT,
+                output      TEXT,
+                account     TEXT
+            )
+            "#,
             [],
-            |row| row.get(0),
         )?;
 
-        // Largest file bytes from slices table
-        let largest_file_bytes: i64 = self.conn.query_row(
-            "SELECT COALESCE(MAX(audio_file_size), 0) FROM slices",
+        // Transcripts and audio uploaded to Google Drive, to skip unchanged ones and resume
+        // interrupted uploads
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS drive_files (
+                slice_id     INTEGER NOT NULL,
+                kind         TEXT NOT NULL,
+                file_id      TEXT,
+                status       TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                session_uri  TEXT,
+                updated_at   INTEGER NOT NULL,
+                PRIMARY KEY (slice_id, kind)
+            )
+            "#,
             [],
-            |row| row.get(0),
         )?;
 
-        // Average file bytes from slices table
-        let avg_file_bytes: f64 = self.conn.query_row(
-            "SELECT COALESCE(AVG(audio_file_size), 0.0) FROM slices",
+        // Sentiment of each analysed slice, for mood over time
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS slice_sentiment (
+                slice_id    INTEGER PRIMARY KEY,
+                score       REAL NOT NULL,
+                label       TEXT NOT NULL,
+                model       TEXT NOT NULL,
+                analyzed_at INTEGER NOT NULL
+            )
+            "#,
             [],
-            |row| row.get(0),
         )?;
 
-        // Count by year - extract from Apple's ZCLOUDRECORDING table if available
-        let count_by_year = self.get_count_by_year_from_apple_db().unwrap_or_else(|_| Vec::new());
+        // Prompts run on newly transcribed slices by label, and the documents they produced
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS label_prompts (
+                label_id   INTEGER PRIMARY KEY,
+                prompt     TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            )
+            "#,
+            [],
+        )?;
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS derived_documents (
+                id         INTEGER PRIMARY KEY AUTOINCREMENT,
+                slice_id   INTEGER NOT NULL,
+                label_id   INTEGER NOT NULL,
+                label_name TEXT NOT NULL,
+                prompt     TEXT NOT NULL,
+                content    TEXT NOT NULL,
+                model      TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                UNIQUE (slice_id, label_id)
+            )
+            "#,
+            [],
+        )?;
 
-        // Count by audio length
-        let count_by_audio_length = self.get_count_by_audio_length().unwrap_or_else(|_| Vec::new());
+        // People, places and organizations transcripts mention, and which slices have been
+        // scanned for them (a slice can mention none)
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS slice_entities (
+                slice_id   INTEGER NOT NULL,
+                kind       TEXT NOT NULL,
+                name       TEXT NOT NULL,
+                normalized TEXT NOT NULL,
+                mentions   INTEGER NOT NULL DEFAULT 1,
+                PRIMARY KEY (slice_id, kind, normalized)
+            )
+            "#,
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_slice_entities_normalized ON slice_entities(normalized)",
+            [],
+        )?;
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS entity_extractions (
+                slice_id     INTEGER PRIMARY KEY,
+                model        TEXT NOT NULL,
+                source_hash  TEXT NOT NULL,
+                extracted_at INTEGER NOT NULL
+            )
+            "#,
+            [],
+        )?;
 
-        Ok(Stats {
-            total_files,
-            total_transcribed,
-            avg_transcribe_sec_10m,
-            total_audio_bytes,
-            largest_file_bytes,
-            avg_file_bytes,
-            count_by_year,
-            count_by_audio_length,
-        })
-    }
+        // Transcripts translated by the local LLM, one per slice and target language
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS slice_translations (
+                slice_id      INTEGER NOT NULL,
+                language      TEXT NOT NULL,
+                text          TEXT NOT NULL,
+                model         TEXT NOT NULL,
+                source_hash   TEXT NOT NULL,
+                translated_at INTEGER NOT NULL,
+                PRIMARY KEY (slice_id, language)
+            )
+            "#,
+            [],
+        )?;
 
-    /// Get the average transcription speed in bytes per second of processing time.
-    /// This is calculated from historical transcription data.
-    /// Returns bytes_per_second (how many bytes of audio file can be transcribed per second of processing time).
-    pub fn get_transcription_speed(&self) -> Result<f64> {
-        // Calculate: SUM(audio_file_size) / SUM(transcription_time_taken)
-        // This gives us bytes per second of processing time
-        let result: Option<f64> = self.conn.query_row(
+        // Transcript passages and their embeddings, for question answering over the library
+        self.conn.execute(
             r#"
-            SELECT
-                CASE
-                    WHEN SUM(transcription_time_taken) > 0
-                    THEN CAST(SUM(audio_file_size) AS REAL) / CAST(SUM(transcription_time_taken) AS REAL)
-                    ELSE NULL
-                END
-            FROM slices
-            WHERE transcribed = 1
-              AND transcription_time_taken IS NOT NULL
-              AND transcription_time_taken > 0
-              AND audio_file_size > 0
+            CREATE TABLE IF NOT EXISTS transcript_chunks (
+                slice_id     INTEGER NOT NULL,
+                chunk_index  INTEGER NOT NULL,
+                start_char   INTEGER NOT NULL,
+                end_char     INTEGER NOT NULL,
+                text         TEXT NOT NULL,
+                embedding    BLOB NOT NULL,
+                model        TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                PRIMARY KEY (slice_id, chunk_index)
+            )
             "#,
             [],
-            |row| row.get(0),
-        ).unwrap_or(None);
+        )?;
 
-        // Default to a reasonable estimate if no historical data:
-        // Assume ~1MB per 30 seconds of processing = ~34000 bytes/second
-        Ok(result.unwrap_or(34000.0))
-    }
+        // Label suggestions the user accepted or turned down, so rejected ones aren't offered again
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS label_suggestion_feedback (
+                slice_id   INTEGER NOT NULL,
+                label_id   INTEGER NOT NULL,
+                accepted   INTEGER NOT NULL,
+                decided_at INTEGER NOT NULL,
+                PRIMARY KEY (slice_id, label_id)
+            )
+            "#,
+            [],
+        )?;
 
-    /// Compute this machine's *measured* realtime factor for a specific model:
-    /// how many seconds of audio it transcribes per second of processing time,
-    /// derived from past successful transcriptions with that exact model.
-    ///
-    /// `factor = SUM(audio_time_length_seconds) / SUM(transcription_time_taken)`.
-    ///
-    /// Returns `None` when there is too little signal to trust (fewer than 3
-    /// samples or under 60s of total audio), so callers can fall back to a
-    /// static per-family default.
-    pub fn measured_realtime_factor(&self, model: &str) -> Option<f64> {
-        let (total_audio, total_time, count): (f64, f64, i64) = self.conn.query_row(
+        // Labels whose slices are uploaded to a NotebookLM notebook once transcribed
+        self.conn.execute(
             r#"
-            SELECT
-                CAST(COALESCE(SUM(audio_time_length_seconds), 0) AS REAL),
-                CAST(COALESCE(SUM(transcription_time_taken), 0) AS REAL),
-                COUNT(*)
-            FROM slices
-            WHERE transcribed = 1
-              AND transcription_model = ?1
-              AND transcription_time_taken > 0
-              AND audio_time_length_seconds > 0
+            CREATE TABLE IF NOT EXISTS label_notebooks (
+                label_id    INTEGER PRIMARY KEY,
+                notebook_id TEXT NOT NULL
+            )
             "#,
-            params![model],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        ).ok()?;
+            [],
+        )?;
 
-        if count < 3 || total_audio < 60.0 || total_time <= 0.0 {
-            return None;
-        }
+        // What the sync-folder export last wrote for each slice, to only rewrite changed files
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS sync_exports (
+                slice_id     INTEGER PRIMARY KEY,
+                file_name    TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                exported_at  INTEGER NOT NULL
+            )
+            "#,
+            [],
+        )?;
 
-        Some(total_audio / total_time)
-    }
+        // Add title column to existing slices tables (migration)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN title TEXT",
+            [],
+        ); // Ignore error if column already exists
 
-    /// Cheaply refresh the cached `estimated_time_to_transcribe` (seconds) for a
-    /// slice, e.g. after computing a model-aware estimate.
-    pub fn update_slice_estimated_time(&self, slice_id: i64, seconds: i32) -> Result<()> {
-        let rows_affected = self.conn.execute(
-            "UPDATE slices SET estimated_time_to_transcribe = ?1 WHERE id = ?2",
-            params![seconds, slice_id],
+        // Add audio_time_length_seconds column to existing slices tables (migration)
+        let _ = self.conn.execute(
+            "ALTER TABLE slices ADD COLUMN audio_time_length_seconds REAL",
+            [],
+        ); // Ignore error if column already exists
+
+        // Create indexes
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_transcripts_recording ON transcripts(recording_id)",
+            [],
         )?;
 
-        if rows_affected == 0 {
-            return Err(anyhow::anyhow!("Slice with ID {} not found", slice_id));
-        }
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_slices_filename ON slices(original_audio_file_name)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_slice_labels_slice ON slice_labels(slice_id)",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_slice_history_slice ON slice_history(slice_id)",
+            [],
+        )?;
 
         Ok(())
     }
 
-    pub fn search_recordings(&self, query: &str, limit: Option<u32>, offset: Option<u32>) -> Result<Vec<RecordingWithTranscript>> {
-        let limit_clause = limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default();
-        let offset_clause = offset.map(|o| format!("OFFSET {}", o)).unwrap_or_default();
+    /// Problems found by SQLite's `PRAGMA integrity_check`; empty when the file is sound.
+    pub fn integrity_check(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let messages = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(messages.into_iter().filter(|m| m != "ok").collect())
+    }
 
-        let sql = format!(
+    /// Rows referring to a missing parent, per `PRAGMA foreign_key_check`.
+    pub fn foreign_key_check(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA foreign_key_check")?;
+        let violations = stmt
+            .query_map([], |row| {
+                let table: String = row.get(0)?;
+                let rowid: Option<i64> = row.get(1)?;
+                let parent: String = row.get(2)?;
+                Ok(format!("{} row {} refers to a missing {} row", table, rowid.unwrap_or_default(), parent))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(violations)
+    }
+
+    /// Slice-label associations whose slice or label is gone (the table has no foreign keys).
+    pub fn count_dangling_label_links(&self) -> Result<u32> {
+        let count: i64 = self.conn.query_row(
             r#"
-            SELECT DISTINCT
-                r.id, r.apple_id, r.created_at, r.duration_sec, r.title, 
-                r.original_path, r.copied_path, r.file_size, r.mime_type, r.year,
-                COUNT(t.id) as transcript_count,
-                CASE WHEN COUNT(CASE WHEN t.success = 1 THEN 1 END) > 0 THEN 1 ELSE 0 END as has_successful_transcript,
-                (SELECT text_path FROM transcripts WHERE recording_id = r.id AND success = 1 ORDER BY finished_at DESC LIMIT 1) as latest_transcript_path
-            FROM recordings r
-            LEFT JOIN transcripts t ON r.id = t.recording_id
-            WHERE EXISTS (
-                SELECT 1 FROM transcripts t2 
-                WHERE t2.recording_id = r.id 
-                AND t2.text_path IS NOT NULL 
-                AND EXISTS (
-                    SELECT 1 FROM transcripts t3 
-                    WHERE t3.id = t2.id 
-                    AND (
-                        SELECT CASE 
-                            WHEN t3.text_path IS NOT NULL 
-                            THEN (
-                                SELECT CASE 
-                                    WHEN LENGTH(TRIM(COALESCE((
-                                        SELECT substr(hex(RANDOMBLOB(4)), 1, 8) -- This is a placeholder for file content reading
-                                    ), ''))) > 0 
-                                    THEN 1 
-                                    ELSE 0 
-                                END
-                            )
-                            ELSE 0 
-                        END
-                    ) = 1
-                )
-            )
-            OR r.title LIKE ?1
+            SELECT COUNT(*) FROM slice_labels
+            WHERE slice_id NOT IN (SELECT id FROM slices)
+               OR label_id NOT IN (SELECT id FROM labels)
+            "#,
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as u32)
+    }
+
+    /// Rebuild the database file without its free pages, then fold the write-ahead log back
+    /// in so the space actually comes off the disk.
+    pub fn vacuum(&self) -> Result<()> {
+        self.conn.execute_batch("VACUUM")?;
+        self.conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+
+    /// Write a consistent copy of the whole database to `dest`, which must not exist yet.
+    pub fn backup_to(&self, dest: &Path) -> Result<()> {
+        self.conn.execute("VACUUM INTO ?1", params![dest.to_string_lossy()])?;
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn insert_recording(&self, recording: &Recording) -> Result<i64> {
+        let _rows = self.conn.execute(
+            r#"
+            INSERT INTO recordings (
+                apple_id, created_at, duration_sec, title, original_path, 
+                copied_path, file_size, mime_type, year
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+            params![
+                recording.apple_id,
+                recording.created_at,
+                recording.duration_sec,
+                recording.title,
+                recording.original_path,
+                recording.copied_path,
+                recording.file_size,
+                recording.mime_type,
+                recording.year,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    #[allow(dead_code)]
+    pub fn get_recording_by_apple_id(&self, apple_id: i64) -> Result<Option<Recording>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, apple_id, created_at, duration_sec, title, original_path, copied_path, file_size, mime_type, year FROM recordings WHERE apple_id = ?1"
+        )?;
+
+        let recording = stmt.query_row(params![apple_id], |row| {
+            Ok(Recording {
+                id: Some(row.get(0)?),
+                apple_id: row.get(1)?,
+                created_at: row.get(2)?,
+                duration_sec: row.get(3)?,
+                title: row.get(4)?,
+                original_path: row.get(5)?,
+                copied_path: row.get(6)?,
+                file_size: row.get(7)?,
+                mime_type: row.get(8)?,
+                year: row.get(9)?,
+            })
+        });
+
+        match recording {
+            Ok(r) => Ok(Some(r)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn list_recordings(&self, limit: Option<u32>, offset: Option<u32>) -> Result<Vec<RecordingWithTranscript>> {
+        let limit_clause = limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default();
+        let offset_clause = offset.map(|o| format!("OFFSET {}", o)).unwrap_or_default();
+
+        let query = format!(
+            r#"
+            SELECT 
+                r.id, r.apple_id, r.created_at, r.duration_sec, r.title, 
+                r.original_path, r.copied_path, r.file_size, r.mime_type, r.year,
+                COUNT(t.id) as transcript_count,
+                CASE WHEN COUNT(CASE WHEN t.success = 1 THEN 1 END) > 0 THEN 1 ELSE 0 END as has_successful_transcript,
+                (SELECT text_path FROM transcripts WHERE recording_id = r.id AND success = 1 ORDER BY finished_at DESC LIMIT 1) as latest_transcript_path
+            FROM recordings r
+            LEFT JOIN transcripts t ON r.id = t.recording_id
             GROUP BY r.id
             ORDER BY r.created_at DESC
             {} {}
@@ -499,9 +1000,8 @@ impl Database {
             limit_clause, offset_clause
         );
 
-        let search_pattern = format!("%{}%", query);
-        let mut stmt = self.conn.prepare(&sql)?;
-        let rows = stmt.query_map([&search_pattern], |row| {
+        let mut stmt = self.conn.prepare(&query)?;
+        let rows = stmt.query_map([], |row| {
             let transcript_path: Option<String> = row.get(12)?;
             let latest_transcript_text = transcript_path
                 .and_then(|path| std::fs::read_to_string(path).ok());
@@ -532,679 +1032,3352 @@ impl Database {
         Ok(recordings)
     }
 
-    pub fn insert_slice(&self, slice: &Slice) -> Result<i64> {
+    pub fn insert_transcript(&self, transcript: &Transcript) -> Result<i64> {
         self.conn.execute(
             r#"
-            INSERT OR IGNORE INTO slices (
-                original_audio_file_name, title, transcribed, audio_file_size, audio_file_type,
-                estimated_time_to_transcribe, audio_time_length_seconds, transcription, transcription_time_taken,
-                transcription_word_count, transcription_model, recording_date
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            INSERT INTO transcripts (
+                recording_id, model, started_at, finished_at, 
+                word_count, text_path, success, error_message
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
             "#,
             params![
-                slice.original_audio_file_name,
-                slice.title,
-                slice.transcribed as i32,
-                slice.audio_file_size,
-                slice.audio_file_type,
-                slice.estimated_time_to_transcribe,
-                slice.audio_time_length_seconds,
-                slice.transcription,
-                slice.transcription_time_taken,
-                slice.transcription_word_count,
-                slice.transcription_model,
-                slice.recording_date,
+                transcript.recording_id,
+                transcript.model,
+                transcript.started_at,
+                transcript.finished_at,
+                transcript.word_count,
+                transcript.text_path,
+                transcript.success as i32,
+                transcript.error_message,
             ],
         )?;
         Ok(self.conn.last_insert_rowid())
     }
 
-    pub fn slice_exists(&self, filename: &str) -> Result<bool> {
-        let count: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM slices WHERE original_audio_file_name = ?1",
-            params![filename],
-            |row| row.get(0),
+    pub fn update_transcript(&self, id: i64, transcript: &Transcript) -> Result<()> {
+        self.conn.execute(
+            r#"
+            UPDATE transcripts SET
+                finished_at = ?1,
+                word_count = ?2,
+                text_path = ?3,
+                success = ?4,
+                error_message = ?5
+            WHERE id = ?6
+            "#,
+            params![
+                transcript.finished_at,
+                transcript.word_count,
+                transcript.text_path,
+                transcript.success as i32,
+                transcript.error_message,
+                id,
+            ],
         )?;
-        Ok(count > 0)
+        Ok(())
     }
 
-    // Copy ZCLOUDRECORDING table from Apple's database to CiderPress-db
-    pub fn copy_zcloudrecording_table(&self, apple_db_path: &str) -> Result<u32> {
-        // Attach the Apple database
-        self.conn.execute(
-            &format!("ATTACH DATABASE '{}' AS apple_db", apple_db_path),
+    pub fn get_stats(&self) -> Result<Stats> {
+        // Total files from slices table
+        let total_files: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM slices WHERE deleted_at IS NULL",
             [],
+            |row| row.get(0),
         )?;
 
-        // Copy table structure if it doesn't exist
-        self.conn.execute(
-            r#"
-            CREATE TABLE IF NOT EXISTS ZCLOUDRECORDING AS 
-            SELECT * FROM apple_db.ZCLOUDRECORDING WHERE 0
-            "#,
+        // Of which archived
+        let archived_files: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM slices WHERE archived = 1 AND deleted_at IS NULL",
             [],
+            |row| row.get(0),
         )?;
 
-        // Insert new rows that don't already exist
-        let rows_copied = self.conn.execute(
+        // Total transcribed from slices table
+        let total_transcribed: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM slices WHERE transcribed = 1 AND deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        // Average transcription time per 10 minutes of audio from slices table
+        let avg_transcribe_sec_10m: Option<f64> = self.conn.query_row(
             r#"
-            INSERT OR IGNORE INTO ZCLOUDRECORDING 
-            SELECT * FROM apple_db.ZCLOUDRECORDING 
-            WHERE Z_PK NOT IN (SELECT Z_PK FROM ZCLOUDRECORDING)
+            SELECT AVG(transcription_time_taken / (audio_file_size / 1048576.0 / 60.0) * 10.0) 
+            FROM slices 
+            WHERE transcribed = 1 AND transcription_time_taken IS NOT NULL AND audio_file_size > 0
             "#,
             [],
+            |row| row.get(0),
+        ).unwrap_or(None);
+
+        // Total audio bytes from slices table
+        let total_audio_bytes: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(audio_file_size), 0) FROM slices WHERE deleted_at IS NULL",
+            [],
+            |row| row.get(0),
         )?;
 
-        // Detach the Apple database
-        self.conn.execute("DETACH DATABASE apple_db", [])?;
+        // Largest file bytes from slices table
+        let largest_file_bytes: i64 = self.conn.query_row(
+            "SELECT COALESCE(MAX(audio_file_size), 0) FROM slices WHERE deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
 
-        Ok(rows_copied as u32)
-    }
+        // Average file bytes from slices table
+        let avg_file_bytes: f64 = self.conn.query_row(
+            "SELECT COALESCE(AVG(audio_file_size), 0.0) FROM slices WHERE deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
 
-    /// Get the recording date (as Unix timestamp) for a given filename from ZCLOUDRECORDING
-    /// The ZPATH column contains the relative path including the filename
-    /// Apple's ZDATE is seconds since Jan 1, 2001 - we convert to Unix timestamp
-    pub fn get_recording_date_for_filename(&self, filename: &str) -> Result<Option<i64>> {
-        // Apple epoch offset: seconds from Unix epoch (1970-01-01) to Apple epoch (2001-01-01)
-        const APPLE_EPOCH_OFFSET: i64 = 978307200;
+        // Count by year - extract from Apple's ZCLOUDRECORDING table if available
+        let count_by_year = self.get_count_by_year_from_apple_db().unwrap_or_else(|_| Vec::new());
 
-        // Try to find the recording with matching filename in ZPATH
-        let result: Result<i64, _> = self.conn.query_row(
+        // Count by audio length
+        let count_by_audio_length = self.get_count_by_audio_length().unwrap_or_else(|_| Vec::new());
+
+        // Mood by month, from the slices with a sentiment score
+        let mood_by_month = self.get_mood_by_month().unwrap_or_else(|_| Vec::new());
+
+        Ok(Stats {
+            total_files,
+            archived_files,
+            total_transcribed,
+            avg_transcribe_sec_10m,
+            total_audio_bytes,
+            largest_file_bytes,
+            avg_file_bytes,
+            count_by_year,
+            count_by_audio_length,
+            mood_by_month,
+        })
+    }
+
+    /// Get the average transcription speed in bytes per second of processing time.
+    /// This is calculated from historical transcription data.
+    /// Returns bytes_per_second (how many bytes of audio file can be transcribed per second of processing time).
+    pub fn get_transcription_speed(&self) -> Result<f64> {
+        // Calculate: SUM(audio_file_size) / SUM(transcription_time_taken)
+        // This gives us bytes per second of processing time
+        let result: Option<f64> = self.conn.query_row(
             r#"
-            SELECT CAST(ZDATE + ? AS INTEGER) as unix_timestamp
-            FROM ZCLOUDRECORDING
-            WHERE ZPATH LIKE '%' || ?
-            LIMIT 1
-            "#,
-            params![APPLE_EPOCH_OFFSET, filename],
-            |row| row.get(0),
-        );
+            SELECT
+                CASE
+                    WHEN SUM(transcription_time_taken) > 0
+                    THEN CAST(SUM(audio_file_size) AS REAL) / CAST(SUM(transcription_time_taken) AS REAL)
+                    ELSE NULL
+                END
+            FROM slices
+            WHERE transcribed = 1
+              AND transcription_time_taken IS NOT NULL
+              AND transcription_time_taken > 0
+              AND audio_file_size > 0
+            "#,
+            [],
+            |row| row.get(0),
+        ).unwrap_or(None);
+
+        // Default to a reasonable estimate if no historical data:
+        // Assume ~1MB per 30 seconds of processing = ~34000 bytes/second
+        Ok(result.unwrap_or(34000.0))
+    }
+
+    /// Compute this machine's *measured* realtime factor for a specific model:
+    /// how many seconds of audio it transcribes per second of processing time,
+    /// derived from past successful transcriptions with that exact model.
+    ///
+    /// `factor = SUM(audio_time_length_seconds) / SUM(transcription_time_taken)`.
+    ///
+    /// Returns `None` when there is too little signal to trust (fewer than 3
+    /// samples or under 60s of total audio), so callers can fall back to a
+    /// static per-family default.
+    pub fn measured_realtime_factor(&self, model: &str) -> Option<f64> {
+        let (total_audio, total_time, count): (f64, f64, i64) = self.conn.query_row(
+            r#"
+            SELECT
+                CAST(COALESCE(SUM(audio_time_length_seconds), 0) AS REAL),
+                CAST(COALESCE(SUM(transcription_time_taken), 0) AS REAL),
+                COUNT(*)
+            FROM slices
+            WHERE transcribed = 1
+              AND transcription_model = ?1
+              AND transcription_time_taken > 0
+              AND audio_time_length_seconds > 0
+            "#,
+            params![model],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).ok()?;
+
+        if count < 3 || total_audio < 60.0 || total_time <= 0.0 {
+            return None;
+        }
+
+        Some(total_audio / total_time)
+    }
+
+    /// Cheaply refresh the cached `estimated_time_to_transcribe` (seconds) for a
+    /// slice, e.g. after computing a model-aware estimate.
+    pub fn update_slice_estimated_time(&self, slice_id: i64, seconds: i32) -> Result<()> {
+        let rows_affected = self.conn.execute(
+            "UPDATE slices SET estimated_time_to_transcribe = ?1 WHERE id = ?2",
+            params![seconds, slice_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Slice with ID {} not found", slice_id));
+        }
+
+        Ok(())
+    }
+
+    pub fn search_recordings(&self, query: &str, limit: Option<u32>, offset: Option<u32>) -> Result<Vec<RecordingWithTranscript>> {
+        let limit_clause = limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default();
+        let offset_clause = offset.map(|o| format!("OFFSET {}", o)).unwrap_or_default();
+
+        let sql = format!(
+            r#"
+            SELECT DISTINCT
+                r.id, r.apple_id, r.created_at, r.duration_sec, r.title, 
+                r.original_path, r.copied_path, r.file_size, r.mime_type, r.year,
+                COUNT(t.id) as transcript_count,
+                CASE WHEN COUNT(CASE WHEN t.success = 1 THEN 1 END) > 0 THEN 1 ELSE 0 END as has_successful_transcript,
+                (SELECT text_path FROM transcripts WHERE recording_id = r.id AND success = 1 ORDER BY finished_at DESC LIMIT 1) as latest_transcript_path
+            FROM recordings r
+            LEFT JOIN transcripts t ON r.id = t.recording_id
+            WHERE EXISTS (
+                SELECT 1 FROM transcripts t2 
+                WHERE t2.recording_id = r.id 
+                AND t2.text_path IS NOT NULL 
+                AND EXISTS (
+                    SELECT 1 FROM transcripts t3 
+                    WHERE t3.id = t2.id 
+                    AND (
+                        SELECT CASE 
+                            WHEN t3.text_path IS NOT NULL 
+                            THEN (
+                                SELECT CASE 
+                                    WHEN LENGTH(TRIM(COALESCE((
+                                        SELECT substr(hex(RANDOMBLOB(4)), 1, 8) -- This is a placeholder for file content reading
+                                    ), ''))) > 0 
+                                    THEN 1 
+                                    ELSE 0 
+                                END
+                            )
+                            ELSE 0 
+                        END
+                    ) = 1
+                )
+            )
+            OR r.title LIKE ?1
+            GROUP BY r.id
+            ORDER BY r.created_at DESC
+            {} {}
+            "#,
+            limit_clause, offset_clause
+        );
+
+        let search_pattern = format!("%{}%", query);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([&search_pattern], |row| {
+            let transcript_path: Option<String> = row.get(12)?;
+            let latest_transcript_text = transcript_path
+                .and_then(|path| std::fs::read_to_string(path).ok());
+
+            Ok(RecordingWithTranscript {
+                recording: Recording {
+                    id: Some(row.get(0)?),
+                    apple_id: row.get(1)?,
+                    created_at: row.get(2)?,
+                    duration_sec: row.get(3)?,
+                    title: row.get(4)?,
+                    original_path: row.get(5)?,
+                    copied_path: row.get(6)?,
+                    file_size: row.get(7)?,
+                    mime_type: row.get(8)?,
+                    year: row.get(9)?,
+                },
+                transcript_count: row.get(10)?,
+                has_successful_transcript: row.get::<_, i32>(11)? == 1,
+                latest_transcript_text,
+            })
+        })?;
+
+        let mut recordings = Vec::new();
+        for row in rows {
+            recordings.push(row?);
+        }
+        Ok(recordings)
+    }
+
+    pub fn insert_slice(&self, slice: &Slice) -> Result<i64> {
+        let title = stored_text(slice.title.as_deref());
+        let transcription = stored_text(slice.transcription.as_deref());
+        let notes = stored_text(slice.notes.as_deref());
+        self.conn.execute(
+            r#"
+            INSERT OR IGNORE INTO slices (
+                original_audio_file_name, title, transcribed, audio_file_size, audio_file_type,
+                estimated_time_to_transcribe, audio_time_length_seconds, transcription, transcription_time_taken,
+                transcription_word_count, transcription_model, recording_date, source, starred, was_edited, content_hash,
+                source_relative_path, notes
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+            "#,
+            params![
+                slice.original_audio_file_name,
+                title,
+                slice.transcribed as i32,
+                slice.audio_file_size,
+                slice.audio_file_type,
+                slice.estimated_time_to_transcribe,
+                slice.audio_time_length_seconds,
+                transcription,
+                slice.transcription_time_taken,
+                slice.transcription_word_count,
+                slice.transcription_model,
+                slice.recording_date,
+                slice.source,
+                slice.starred,
+                slice.was_edited,
+                slice.content_hash,
+                slice.source_relative_path,
+                notes,
+            ],
+        )?;
+        let slice_id = self.conn.last_insert_rowid();
+        self.index_phonetics(slice_id, transcription.as_deref())?;
+        Ok(slice_id)
+    }
+
+    pub fn slice_exists(&self, filename: &str) -> Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM slices WHERE original_audio_file_name = ?1",
+            params![filename],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// The recorded source path of the slice stored under `filename`: `None` if there is no
+    /// such slice, `Some(None)` if it predates source path tracking.
+    pub fn get_slice_source_path(&self, filename: &str) -> Result<Option<Option<String>>> {
+        let result = self.conn.query_row(
+            "SELECT source_relative_path FROM slices WHERE original_audio_file_name = ?1",
+            params![filename],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(path) => Ok(Some(path)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn set_slice_source_path(&self, filename: &str, relative_path: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE slices SET source_relative_path = ?1 WHERE original_audio_file_name = ?2",
+            params![relative_path, filename],
+        )?;
+        Ok(())
+    }
+
+    /// Filename of the slice whose audio has this SHA-256, if any.
+    pub fn find_slice_by_content_hash(&self, content_hash: &str) -> Result<Option<String>> {
+        let result = self.conn.query_row(
+            "SELECT original_audio_file_name FROM slices WHERE content_hash = ?1 LIMIT 1",
+            params![content_hash],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(filename) => Ok(Some(filename)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// (id, filename) of slices that have no content hash yet.
+    pub fn get_slices_without_content_hash(&self) -> Result<Vec<(i64, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, original_audio_file_name FROM slices WHERE content_hash IS NULL ORDER BY id"
+        )?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn set_slice_content_hash(&self, slice_id: i64, content_hash: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE slices SET content_hash = ?1 WHERE id = ?2",
+            params![content_hash, slice_id],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a file from this source root was already imported or skipped as a duplicate.
+    pub fn source_file_seen(&self, source_root: &str, relative_path: &str) -> Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM source_files WHERE source_root = ?1 AND relative_path = ?2",
+            params![source_root, relative_path],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// The unfinished migration batch of this kind, if a previous run was interrupted.
+    pub fn active_migration_batch(&self, kind: &str) -> Result<Option<i64>> {
+        let result = self.conn.query_row(
+            "SELECT id FROM migration_batches WHERE kind = ?1 AND status = 'running' ORDER BY id DESC LIMIT 1",
+            params![kind],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(id) => Ok(Some(id)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn start_migration_batch(&self, kind: &str) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO migration_batches (kind, status, started_at) VALUES (?1, 'running', ?2)",
+            params![kind, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn record_migration_batch_file(&self, batch_id: i64, file: &MigrationBatchFile) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO migration_batch_files (batch_id, file_path, result, size_bytes, duplicate_of)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![batch_id, &file.file_path, &file.result, file.size_bytes, &file.duplicate_of],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_migration_batch_files(&self, batch_id: i64) -> Result<Vec<MigrationBatchFile>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, result, size_bytes, duplicate_of FROM migration_batch_files WHERE batch_id = ?1",
+        )?;
+        let files = stmt
+            .query_map(params![batch_id], |row| {
+                Ok(MigrationBatchFile {
+                    file_path: row.get(0)?,
+                    result: row.get(1)?,
+                    size_bytes: row.get(2)?,
+                    duplicate_of: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(files)
+    }
+
+    /// Mark a batch completed and drop its per-file rows.
+    pub fn finish_migration_batch(&self, batch_id: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE migration_batches SET status = 'completed', finished_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().timestamp(), batch_id],
+        )?;
+        self.conn.execute("DELETE FROM migration_batch_files WHERE batch_id = ?1", params![batch_id])?;
+        Ok(())
+    }
+
+    pub fn add_migration_batch_slice(&self, batch_id: i64, slice_id: i64, file_name: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO migration_batch_slices (batch_id, slice_id, file_name) VALUES (?1, ?2, ?3)",
+            params![batch_id, slice_id, file_name],
+        )?;
+        Ok(())
+    }
+
+    /// (id, status) of the most recent migration batch that created slices and hasn't been
+    /// rolled back. Runs that found nothing new are passed over.
+    pub fn last_migration_batch(&self) -> Result<Option<(i64, String)>> {
+        let result = self.conn.query_row(
+            r#"
+            SELECT id, status FROM migration_batches b
+            WHERE status != 'rolled_back'
+              AND EXISTS (SELECT 1 FROM migration_batch_slices s WHERE s.batch_id = b.id)
+            ORDER BY id DESC LIMIT 1
+            "#,
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        );
+
+        match result {
+            Ok(batch) => Ok(Some(batch)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// (slice id, file name as migrated) for every slice a batch created.
+    pub fn get_migration_batch_slices(&self, batch_id: i64) -> Result<Vec<(i64, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT slice_id, file_name FROM migration_batch_slices WHERE batch_id = ?1 ORDER BY slice_id",
+        )?;
+        let slices = stmt
+            .query_map(params![batch_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(slices)
+    }
+
+    pub fn mark_migration_batch_rolled_back(&self, batch_id: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE migration_batches SET status = 'rolled_back', finished_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().timestamp(), batch_id],
+        )?;
+        self.conn.execute("DELETE FROM migration_batch_files WHERE batch_id = ?1", params![batch_id])?;
+        Ok(())
+    }
+
+    pub fn record_source_file(
+        &self,
+        source_root: &str,
+        relative_path: &str,
+        slice_id: Option<i64>,
+        status: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO source_files (source_root, relative_path, slice_id, status, recorded_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![source_root, relative_path, slice_id, status, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Whether the slice for this filename was already migrated as an edited version.
+    /// Returns None if no slice exists for the filename.
+    pub fn slice_was_edited(&self, filename: &str) -> Result<Option<bool>> {
+        let result: Result<i32, _> = self.conn.query_row(
+            "SELECT was_edited FROM slices WHERE original_audio_file_name = ?1",
+            params![filename],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(flag) => Ok(Some(flag != 0)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Record that a previously migrated slice now holds the edited version of its
+    /// recording, updating the audio facts that changed with the re-copied file. The old
+    /// transcript no longer matches the audio, so it is cleared for transcribing again.
+    pub fn mark_slice_edited(
+        &self,
+        filename: &str,
+        file_size: i64,
+        duration_seconds: Option<f64>,
+        content_hash: &str,
+    ) -> Result<()> {
+        let slice_id: i64 = match self.conn.query_row(
+            "SELECT id FROM slices WHERE original_audio_file_name = ?1",
+            params![filename],
+            |row| row.get(0),
+        ) {
+            Ok(id) => id,
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                return Err(anyhow::anyhow!("No slice found for file: {}", filename));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        self.conn.execute(
+            r#"
+            UPDATE slices
+            SET was_edited = 1, audio_file_size = ?1,
+                audio_time_length_seconds = COALESCE(?2, audio_time_length_seconds),
+                content_hash = ?3,
+                transcribed = 0, transcription = NULL, transcription_time_taken = NULL,
+                transcription_word_count = NULL, transcription_model = NULL
+            WHERE id = ?4
+            "#,
+            params![file_size, duration_seconds, content_hash, slice_id],
+        )?;
+        self.index_phonetics(slice_id, None)?;
+        Ok(())
+    }
+
+    // Copy ZCLOUDRECORDING table from Apple's database to CiderPress-db
+    pub fn copy_zcloudrecording_table(&self, apple_db_path: &str) -> Result<u32> {
+        // Attach the Apple database. The empty key keeps SQLCipher from opening Apple's
+        // plaintext file with the library's own key once the library is encrypted.
+        self.conn.execute("ATTACH DATABASE ?1 AS apple_db KEY ''", params![apple_db_path])?;
+
+        // Copy table structure if it doesn't exist
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS ZCLOUDRECORDING AS 
+            SELECT * FROM apple_db.ZCLOUDRECORDING WHERE 0
+            "#,
+            [],
+        )?;
+
+        // Insert new rows that don't already exist
+        let rows_copied = self.conn.execute(
+            r#"
+            INSERT OR IGNORE INTO ZCLOUDRECORDING 
+            SELECT * FROM apple_db.ZCLOUDRECORDING 
+            WHERE Z_PK NOT IN (SELECT Z_PK FROM ZCLOUDRECORDING)
+            "#,
+            [],
+        )?;
+
+        // Detach the Apple database
+        self.conn.execute("DETACH DATABASE apple_db", [])?;
+
+        Ok(rows_copied as u32)
+    }
+
+    /// Copy Apple's ZFOLDER table (user-created Voice Memos folders) and make sure a label
+    /// exists for every folder name. Returns the number of labels created.
+    /// Older Voice Memos databases have no folders, which is not an error.
+    pub fn import_apple_folders(&self, apple_db_path: &str) -> Result<u32> {
+        self.conn.execute("ATTACH DATABASE ?1 AS apple_db KEY ''", params![apple_db_path])?;
+
+        let has_folders: bool = self.conn.query_row(
+            "SELECT COUNT(*) > 0 FROM apple_db.sqlite_master WHERE type = 'table' AND name = 'ZFOLDER'",
+            [],
+            |row| row.get(0),
+        )?;
+
+        if has_folders {
+            // Folders are few and can be renamed in Voice Memos, so refresh the whole table
+            let copy_result = self.conn.execute_batch(
+                r#"
+                CREATE TABLE IF NOT EXISTS ZFOLDER AS SELECT * FROM apple_db.ZFOLDER WHERE 0;
+                DELETE FROM ZFOLDER;
+                INSERT INTO ZFOLDER SELECT * FROM apple_db.ZFOLDER;
+                "#,
+            );
+            if let Err(e) = copy_result {
+                self.conn.execute("DETACH DATABASE apple_db", [])?;
+                return Err(e.into());
+            }
+        }
+
+        self.conn.execute("DETACH DATABASE apple_db", [])?;
+
+        if !has_folders {
+            return Ok(0);
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT ZENCRYPTEDNAME FROM ZFOLDER WHERE ZENCRYPTEDNAME IS NOT NULL AND TRIM(ZENCRYPTEDNAME) != ''"
+        )?;
+        let names = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut created = 0;
+        for name in names {
+            if self.find_label_by_name(&name)?.is_none() {
+                self.create_label(&Label {
+                    id: None,
+                    name,
+                    color: "#228be6".to_string(),
+                    keywords: String::new(),
+                })?;
+                created += 1;
+            }
+        }
+        Ok(created)
+    }
+
+    /// Assign each slice the label matching its Apple Voice Memos folder.
+    /// Uses the ZCLOUDRECORDING/ZFOLDER copies, so call after `import_apple_folders`.
+    /// Only adds associations; returns the number of new slice-label rows.
+    pub fn assign_apple_folder_labels(&self) -> Result<u32> {
+        let result = self.conn.execute(
+            r#"
+            INSERT OR IGNORE INTO slice_labels (slice_id, label_id)
+            SELECT s.id, l.id
+            FROM slices s
+            JOIN ZCLOUDRECORDING r ON r.ZPATH LIKE '%' || s.original_audio_file_name
+            JOIN ZFOLDER f ON f.Z_PK = r.ZFOLDER
+            JOIN labels l ON l.name = f.ZENCRYPTEDNAME COLLATE NOCASE
+            "#,
+            [],
+        );
+
+        match result {
+            Ok(count) => Ok(count as u32),
+            // No folders copied yet (or an older Apple schema without ZFOLDER)
+            Err(e) if e.to_string().contains("no such") => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get the recording date (as Unix timestamp) for a given filename from ZCLOUDRECORDING
+    /// The ZPATH column contains the relative path including the filename
+    /// Apple's ZDATE is seconds since Jan 1, 2001 - we convert to Unix timestamp
+    pub fn get_recording_date_for_filename(&self, filename: &str) -> Result<Option<i64>> {
+        // Apple epoch offset: seconds from Unix epoch (1970-01-01) to Apple epoch (2001-01-01)
+        const APPLE_EPOCH_OFFSET: i64 = 978307200;
+
+        // Try to find the recording with matching filename in ZPATH
+        let result: Result<i64, _> = self.conn.query_row(
+            r#"
+            SELECT CAST(ZDATE + ? AS INTEGER) as unix_timestamp
+            FROM ZCLOUDRECORDING
+            WHERE ZPATH LIKE '%' || ?
+            LIMIT 1
+            "#,
+            params![APPLE_EPOCH_OFFSET, filename],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(timestamp) => Ok(Some(timestamp)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => {
+                // Table might not exist yet, return None
+                if e.to_string().contains("no such table") {
+                    Ok(None)
+                } else {
+                    Err(e.into())
+                }
+            }
+        }
+    }
+
+    /// Whether Apple marked the recording with this filename as a favorite.
+    /// Voice Memos versions without a favorites column report false.
+    pub fn get_favorite_for_filename(&self, filename: &str) -> Result<bool> {
+        let result: Result<Option<i64>, _> = self.conn.query_row(
+            "SELECT ZFAVORITE FROM ZCLOUDRECORDING WHERE ZPATH LIKE '%' || ? LIMIT 1",
+            params![filename],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(flag) => Ok(flag.unwrap_or(0) != 0),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(false),
+            Err(e) if e.to_string().contains("no such") => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn get_slice(&self, slice_id: i64) -> Result<Option<Slice>> {
+        let result = self.conn.query_row(
+            &format!("SELECT {} FROM slices WHERE id = ?1", SLICE_COLUMNS),
+            params![slice_id],
+            slice_from_row,
+        );
+
+        match result {
+            Ok(slice) => Ok(Some(slice)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn list_all_slices(&self) -> Result<Vec<Slice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM slices ORDER BY id",
+            SLICE_COLUMNS
+        ))?;
+
+        let slice_iter = stmt.query_map([], slice_from_row)?;
+
+        let mut slices = Vec::new();
+        for slice in slice_iter {
+            slices.push(slice?);
+        }
+        Ok(slices)
+    }
+
+    /// Slices not in the trash, archived or not.
+    pub fn list_active_slices(&self) -> Result<Vec<Slice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM slices WHERE deleted_at IS NULL ORDER BY id",
+            SLICE_COLUMNS
+        ))?;
+
+        let slice_iter = stmt.query_map([], slice_from_row)?;
+
+        let mut slices = Vec::new();
+        for slice in slice_iter {
+            slices.push(slice?);
+        }
+        Ok(slices)
+    }
+
+    /// Slices shown in the library: those outside the trash and the archive, or with
+    /// `archived` the archived ones instead.
+    pub fn list_library_slices(&self, archived: bool) -> Result<Vec<Slice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM slices WHERE deleted_at IS NULL AND archived = ?1 ORDER BY id",
+            SLICE_COLUMNS
+        ))?;
+        let slices = stmt
+            .query_map(params![archived], slice_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(slices)
+    }
+
+    /// A page of slices outside the trash, filtered and sorted in SQL.
+    /// Pinned slices always come first; slices missing the sort value come last in either direction.
+    pub fn query_slices(&self, query: &SliceQuery) -> Result<SlicePage> {
+        self.check_search_mode(query)?;
+        let (where_clause, values) = slice_query_where(query);
+        if let Some(text) = search_outside_sql(query) {
+            return match query.search_mode {
+                SearchMode::Regex => {
+                    let regex = search::compile_regex(text)?;
+                    let budget = search::SearchBudget::for_regex();
+                    self.query_slices_matching(query, &where_clause, &values, Some(&budget), |slice| {
+                        regex.is_match(&search::searchable_text(slice))
+                    })
+                }
+                _ => self.query_slices_matching(query, &where_clause, &values, None, |slice| {
+                    search::fuzzy_matches(text, &search::searchable_text(slice))
+                }),
+            };
+        }
+
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM slices WHERE {}", where_clause),
+            rusqlite::params_from_iter(&values),
+            |row| row.get(0),
+        )?;
+
+        let limit_clause = query.limit.map(|l| format!("LIMIT {}", l)).unwrap_or_else(|| "LIMIT -1".to_string());
+        let offset_clause = query.offset.map(|o| format!("OFFSET {}", o)).unwrap_or_default();
+        let columns = if query.without_transcription { SLICE_LIST_COLUMNS } else { SLICE_COLUMNS };
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM slices WHERE {} ORDER BY {} {} {}",
+            columns, where_clause, slice_query_order(query), limit_clause, offset_clause
+        ))?;
+        let slice_iter = stmt.query_map(rusqlite::params_from_iter(&values), slice_from_row)?;
+
+        let mut slices = Vec::new();
+        for slice in slice_iter {
+            slices.push(slice?);
+        }
+        Ok(SlicePage { slices, total: total as u32, truncated: false })
+    }
+
+    /// `query_slices` for searches matched in Rust: every slice passing the SQL conditions is
+    /// loaded in order and checked with `matches`, then the requested page is cut out. With a
+    /// `budget` the scan stops early once it is used up, and the page is marked truncated.
+    fn query_slices_matching(
+        &self,
+        query: &SliceQuery,
+        where_clause: &str,
+        values: &[rusqlite::types::Value],
+        budget: Option<&search::SearchBudget>,
+        matches: impl Fn(&Slice) -> bool,
+    ) -> Result<SlicePage> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM slices WHERE {} ORDER BY {}",
+            SLICE_COLUMNS, where_clause, slice_query_order(query)
+        ))?;
+        let mut matched = Vec::new();
+        let mut truncated = false;
+        for slice in stmt.query_map(rusqlite::params_from_iter(values), slice_from_row)? {
+            if budget.is_some_and(|budget| budget.exhausted(matched.len())) {
+                truncated = true;
+                break;
+            }
+            let slice = slice?;
+            if matches(&slice) {
+                matched.push(slice);
+            }
+        }
+
+        let total = matched.len() as u32;
+        let slices = matched
+            .into_iter()
+            .skip(query.offset.unwrap_or(0) as usize)
+            .take(query.limit.map_or(usize::MAX, |l| l as usize))
+            .map(|mut slice| {
+                if query.without_transcription {
+                    slice.transcription = None;
+                }
+                slice
+            })
+            .collect();
+        Ok(SlicePage { slices, total, truncated })
+    }
+
+    /// `query_slices` with where the search matched in each slice: the matched field,
+    /// character offsets and a snippet per match. Without search text there is nothing to
+    /// highlight and every slice comes back with no matches.
+    pub fn search_slices(&self, query: &SliceQuery) -> Result<SearchResultPage> {
+        let with_text = SliceQuery { without_transcription: false, ..query.clone() };
+        let page = self.query_slices(&with_text)?;
+        let matcher = match query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(text) => Some(search::Matcher::new(text, query.search_mode)?),
+            None => None,
+        };
+
+        let results = page.slices
+            .into_iter()
+            .map(|mut slice| {
+                let matches = matcher.as_ref().map(|m| m.find(&slice)).unwrap_or_default();
+                if query.without_transcription {
+                    slice.transcription = None;
+                }
+                SearchResult { score: search::relevance(&matches), slice, matches }
+            })
+            .collect();
+        Ok(SearchResultPage { results, total: page.total, truncated: page.truncated })
+    }
+
+    /// One search over titles, file names, transcripts, notes and label names, best matches
+    /// first. A slice is found if the search text matches any of them (in fuzzy mode, any
+    /// one search word is enough); the query's other filters still apply, and its sort order
+    /// only breaks ties in relevance. Without search text this is `search_slices`.
+    pub fn ranked_search(&self, query: &SliceQuery) -> Result<SearchResultPage> {
+        let Some(text) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) else {
+            return self.search_slices(query);
+        };
+        let matcher = search::Matcher::new(text, query.search_mode)?;
+        let budget = (query.search_mode == SearchMode::Regex).then(search::SearchBudget::for_regex);
+        let labels = self.get_labels_for_all_slices()?;
+
+        let candidates = SliceQuery { search: None, ..query.clone() };
+        let (where_clause, values) = slice_query_where(&candidates);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM slices WHERE {} ORDER BY {}",
+            SLICE_COLUMNS, where_clause, slice_query_order(&candidates)
+        ))?;
+        let mut results = Vec::new();
+        let mut truncated = false;
+        for slice in stmt.query_map(rusqlite::params_from_iter(&values), slice_from_row)? {
+            if budget.as_ref().is_some_and(|budget| budget.exhausted(results.len())) {
+                truncated = true;
+                break;
+            }
+            let slice = slice?;
+            let mut matches = matcher.find(&slice);
+            for label in slice.id.and_then(|id| labels.get(&id)).into_iter().flatten() {
+                matches.extend(matcher.find_in("label", &label.name));
+            }
+            if !matches.is_empty() {
+                results.push(SearchResult { score: search::relevance(&matches), slice, matches });
+            }
+        }
+        // Stable, so equally relevant slices keep the query's order
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let total = results.len() as u32;
+        let results = results
+            .into_iter()
+            .skip(query.offset.unwrap_or(0) as usize)
+            .take(query.limit.map_or(usize::MAX, |l| l as usize))
+            .map(|mut result| {
+                if query.without_transcription {
+                    result.slice.transcription = None;
+                }
+                result
+            })
+            .collect();
+        Ok(SearchResultPage { results, total, truncated })
+    }
+
+    /// IDs of every slice matching `query`, in its sort order, ignoring `limit` and `offset`.
+    /// Lets exports work from a filter without the frontend sending each ID.
+    pub fn query_slice_ids(&self, query: &SliceQuery) -> Result<Vec<i64>> {
+        if search_outside_sql(query).is_some() {
+            let everything = SliceQuery { limit: None, offset: None, ..query.clone() };
+            let page = self.query_slices(&everything)?;
+            // Acting on only the matches found before the search gave up would silently miss the rest
+            if page.truncated {
+                return Err(anyhow::anyhow!("The search stopped before checking every slice; narrow it and try again"));
+            }
+            return Ok(page.slices.into_iter().filter_map(|s| s.id).collect());
+        }
+        self.check_search_mode(query)?;
+        let (where_clause, values) = slice_query_where(query);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id FROM slices WHERE {} ORDER BY {}",
+            where_clause, slice_query_order(query)
+        ))?;
+        let ids = stmt
+            .query_map(rusqlite::params_from_iter(&values), |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Phonetic search reads the phonetic index, which only exists once turned on.
+    fn check_search_mode(&self, query: &SliceQuery) -> Result<()> {
+        if query.search_mode == SearchMode::Phonetic && !self.phonetic_index_enabled()? {
+            return Err(anyhow::anyhow!("Turn on the phonetic index to search by sound"));
+        }
+        Ok(())
+    }
+
+    /// Whether the optional phonetic index is on and kept up to date with transcripts.
+    pub fn phonetic_index_enabled(&self) -> Result<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'phonetic_index'",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Turn the phonetic index on (building it from every transcript) or off (dropping it).
+    /// Returns how many slices were indexed.
+    pub fn set_phonetic_index(&self, enabled: bool) -> Result<u32> {
+        if !enabled {
+            self.conn.execute("DROP TABLE IF EXISTS phonetic_index", [])?;
+            return Ok(0);
+        }
+
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS phonetic_index (
+                code     TEXT NOT NULL,
+                slice_id INTEGER NOT NULL,
+                PRIMARY KEY (code, slice_id)
+            ) WITHOUT ROWID
+            "#,
+            [],
+        )?;
+        let tx = self.conn.unchecked_transaction()?;
+        self.conn.execute("DELETE FROM phonetic_index", [])?;
+        let mut stmt = self.conn.prepare("SELECT id, transcription FROM slices WHERE transcription IS NOT NULL")?;
+        let transcripts = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (slice_id, text) in &transcripts {
+            self.insert_phonetic_codes(*slice_id, text)?;
+        }
+        tx.commit()?;
+        Ok(transcripts.len() as u32)
+    }
+
+    /// Bring a slice's entries in the phonetic index in line with its transcription, when
+    /// the index is on.
+    fn index_phonetics(&self, slice_id: i64, transcription: Option<&str>) -> Result<()> {
+        if !self.phonetic_index_enabled()? {
+            return Ok(());
+        }
+        self.conn.execute("DELETE FROM phonetic_index WHERE slice_id = ?1", params![slice_id])?;
+        if let Some(text) = transcription {
+            self.insert_phonetic_codes(slice_id, text)?;
+        }
+        Ok(())
+    }
+
+    fn insert_phonetic_codes(&self, slice_id: i64, text: &str) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached("INSERT OR IGNORE INTO phonetic_index (code, slice_id) VALUES (?1, ?2)")?;
+        for code in search::phonetic_codes(text) {
+            stmt.execute(params![code, slice_id])?;
+        }
+        Ok(())
+    }
+
+    /// Just the transcription of one slice, for views that list slices without it.
+    pub fn get_slice_transcription(&self, slice_id: i64) -> Result<Option<String>> {
+        let result = self.conn.query_row(
+            "SELECT transcription FROM slices WHERE id = ?1",
+            params![slice_id],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(transcription) => Ok(transcription),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Err(anyhow::anyhow!("No slice found with ID: {}", slice_id)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Record that a slice's audio or transcript was just opened.
+    pub fn mark_slice_opened(&self, slice_id: i64, opened_at: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE slices SET last_opened_at = ?1 WHERE id = ?2",
+            params![opened_at, slice_id],
+        )?;
+        Ok(())
+    }
+
+    /// Remember a search, moving it to the top of the history if it was run before.
+    pub fn record_search(&self, query: &str, searched_at: i64) -> Result<()> {
+        let query = query.trim();
+        // Searches for personal information aren't kept while redaction mode is on
+        if query.is_empty() || matches!(pii::redact_if_enabled(query), Cow::Owned(_)) {
+            return Ok(());
+        }
+        self.conn.execute(
+            r#"
+            INSERT INTO search_history (query, last_searched_at) VALUES (?1, ?2)
+            ON CONFLICT(query) DO UPDATE SET
+                query = excluded.query,
+                last_searched_at = excluded.last_searched_at,
+                times_searched = times_searched + 1
+            "#,
+            params![query, searched_at],
+        )?;
+        self.conn.execute(
+            r#"
+            DELETE FROM search_history WHERE query NOT IN (
+                SELECT query FROM search_history ORDER BY last_searched_at DESC LIMIT ?1
+            )
+            "#,
+            params![SEARCH_HISTORY_LIMIT],
+        )?;
+        Ok(())
+    }
+
+    /// Past searches, most recent first; with a `prefix`, only those starting with it
+    /// (ignoring case), for suggestions while typing.
+    pub fn list_search_history(&self, prefix: Option<&str>, limit: u32) -> Result<Vec<SearchHistoryEntry>> {
+        let pattern = format!("{}%", prefix.unwrap_or("").trim().replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT query, last_searched_at, times_searched FROM search_history
+            WHERE query LIKE ?1 ESCAPE '\'
+            ORDER BY last_searched_at DESC, rowid DESC
+            LIMIT ?2
+            "#,
+        )?;
+        let entries = stmt
+            .query_map(params![pattern, limit], |row| {
+                Ok(SearchHistoryEntry {
+                    query: row.get(0)?,
+                    last_searched_at: row.get(1)?,
+                    times_searched: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Forget every recorded search. Returns how many were removed.
+    pub fn clear_search_history(&self) -> Result<usize> {
+        Ok(self.conn.execute("DELETE FROM search_history", [])?)
+    }
+
+    /// The `limit` most recently opened slices outside the trash, newest first.
+    pub fn list_recent_slices(&self, limit: u32) -> Result<Vec<RecentSlice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            SELECT {}, last_opened_at FROM slices
+            WHERE last_opened_at IS NOT NULL AND deleted_at IS NULL
+            ORDER BY last_opened_at DESC, id DESC
+            LIMIT ?1
+            "#,
+            SLICE_LIST_COLUMNS
+        ))?;
+        let slices = stmt
+            .query_map(params![limit], |row| {
+                Ok(RecentSlice {
+                    slice: slice_from_row(row)?,
+                    last_opened_at: row.get("last_opened_at")?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(slices)
+    }
+
+    /// Slices in the trash, most recently deleted first.
+    pub fn list_trashed_slices(&self) -> Result<Vec<TrashedSlice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, deleted_at FROM slices WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id",
+            SLICE_COLUMNS
+        ))?;
+
+        let slice_iter = stmt.query_map([], |row| {
+            Ok(TrashedSlice {
+                slice: slice_from_row(row)?,
+                deleted_at: row.get("deleted_at")?,
+            })
+        })?;
+
+        let mut slices = Vec::new();
+        for slice in slice_iter {
+            slices.push(slice?);
+        }
+        Ok(slices)
+    }
+
+    /// Move slices to the trash, keeping their rows, labels and audio so they can be restored.
+    /// Slices already in the trash keep their original deletion time. Returns the number moved.
+    pub fn trash_slices(&self, slice_ids: &[i64], deleted_at: i64) -> Result<u32> {
+        let mut moved = 0;
+        for slice_id in slice_ids {
+            moved += self.conn.execute(
+                "UPDATE slices SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+                params![deleted_at, slice_id],
+            )?;
+        }
+        Ok(moved as u32)
+    }
+
+    /// Archive (or with `archived` false, unarchive) slices outside the trash.
+    /// Returns the number whose state changed.
+    pub fn set_slices_archived(&self, slice_ids: &[i64], archived: bool) -> Result<u32> {
+        let mut changed = 0;
+        for slice_id in slice_ids {
+            changed += self.conn.execute(
+                "UPDATE slices SET archived = ?1 WHERE id = ?2 AND archived != ?1 AND deleted_at IS NULL",
+                params![archived, slice_id],
+            )?;
+        }
+        Ok(changed as u32)
+    }
+
+    /// Take slices back out of the trash. Returns the number restored.
+    pub fn restore_slices(&self, slice_ids: &[i64]) -> Result<u32> {
+        let mut restored = 0;
+        for slice_id in slice_ids {
+            restored += self.conn.execute(
+                "UPDATE slices SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+                params![slice_id],
+            )?;
+        }
+        Ok(restored as u32)
+    }
+
+    /// IDs of slices moved to the trash before `cutoff` (a Unix timestamp).
+    pub fn get_slices_trashed_before(&self, cutoff: i64) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM slices WHERE deleted_at IS NOT NULL AND deleted_at < ?1 ORDER BY id"
+        )?;
+        let ids = stmt
+            .query_map(params![cutoff], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Delete a slice along with its label associations. Its source_files rows are dropped
+    /// too, so the original recording can be imported again. One transaction, so a failure
+    /// leaves the slice whole.
+    pub fn delete_slice(&self, slice_id: i64) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.conn.execute("DELETE FROM slice_labels WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM slice_history WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM slice_metadata WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM slice_collections WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM slice_links WHERE from_id = ?1 OR to_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM source_files WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM migration_batch_slices WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM nlm_uploads WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM drive_files WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM slice_sentiment WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM transcript_chunks WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM label_suggestion_feedback WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM slice_translations WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM slice_entities WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM entity_extractions WHERE slice_id = ?1", params![slice_id])?;
+        self.conn.execute("DELETE FROM derived_documents WHERE slice_id = ?1", params![slice_id])?;
+        self.index_phonetics(slice_id, None)?;
+        let rows_affected = self.conn.execute("DELETE FROM slices WHERE id = ?1", params![slice_id])?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("No slice found with ID: {}", slice_id));
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn clear_all_slices(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.conn.execute("DELETE FROM slice_history", [])?;
+        self.conn.execute("DELETE FROM slice_metadata", [])?;
+        self.conn.execute("DELETE FROM slice_collections", [])?;
+        self.conn.execute("DELETE FROM slice_links", [])?;
+        self.conn.execute("DELETE FROM nlm_uploads", [])?;
+        self.conn.execute("DELETE FROM drive_files", [])?;
+        self.conn.execute("DELETE FROM slice_sentiment", [])?;
+        self.conn.execute("DELETE FROM transcript_chunks", [])?;
+        self.conn.execute("DELETE FROM label_suggestion_feedback", [])?;
+        self.conn.execute("DELETE FROM slice_translations", [])?;
+        self.conn.execute("DELETE FROM slice_entities", [])?;
+        self.conn.execute("DELETE FROM entity_extractions", [])?;
+        self.conn.execute("DELETE FROM derived_documents", [])?;
+        if self.phonetic_index_enabled()? {
+            self.conn.execute("DELETE FROM phonetic_index", [])?;
+        }
+        self.conn.execute("DELETE FROM slices", [])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn update_slice_transcription(
+        &self,
+        slice_id: i64,
+        transcription: &str,
+        transcription_time_taken: i32,
+        word_count: i32,
+        model_name: &str,
+    ) -> Result<()> {
+        let transcription = &*pii::redact_if_enabled(transcription);
+        // A first transcription isn't an edit; replacing an existing one is
+        let previous = self.get_slice(slice_id)?.and_then(|s| s.transcription);
+        if previous.is_some() {
+            self.record_change(slice_id, HISTORY_TRANSCRIPTION, previous.as_deref(), Some(transcription))?;
+        }
+
+        self.conn.execute(
+            r#"
+            UPDATE slices SET
+                transcribed = 1,
+                transcription = ?1,
+                transcription_time_taken = ?2,
+                transcription_word_count = ?3,
+                transcription_model = ?4
+            WHERE id = ?5
+            "#,
+            params![
+                transcription,
+                transcription_time_taken,
+                word_count,
+                model_name,
+                slice_id,
+            ],
+        )?;
+
+        self.index_phonetics(slice_id, Some(transcription))?;
+
+        // Auto-apply labels whose keywords match the freshly-transcribed text.
+        self.apply_auto_labels(slice_id, transcription)?;
+        Ok(())
+    }
+
+    pub fn update_slice_name(&self, slice_id: i64, new_name: &str) -> Result<()> {
+        // Check if the new name already exists (excluding the current slice)
+        let existing_count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM slices WHERE original_audio_file_name = ?1 AND id != ?2",
+            params![new_name, slice_id],
+            |row| row.get(0),
+        )?;
+        
+        if existing_count > 0 {
+            return Err(anyhow::anyhow!("A slice with the filename '{}' already exists", new_name));
+        }
+        
+        // Check if the slice exists
+        let current = self.get_slice(slice_id)?
+            .ok_or_else(|| anyhow::anyhow!("Slice with ID {} not found", slice_id))?;
+        self.record_change(slice_id, HISTORY_NAME, Some(&current.original_audio_file_name), Some(new_name))?;
+        
+        // Perform the update
+        let rows_affected = self.conn.execute(
+            "UPDATE slices SET original_audio_file_name = ?1 WHERE id = ?2",
+            params![new_name, slice_id],
+        )?;
+        
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Failed to update slice name: no rows affected"));
+        }
+        
+        Ok(())
+    }
+
+    pub fn update_slice(&self, slice_id: i64, slice: &Slice) -> Result<()> {
+        // Check if the new name already exists (excluding the current slice)
+        let existing_count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM slices WHERE original_audio_file_name = ?1 AND id != ?2",
+            params![slice.original_audio_file_name, slice_id],
+            |row| row.get(0),
+        )?;
+        
+        if existing_count > 0 {
+            return Err(anyhow::anyhow!("A slice with the filename '{}' already exists", slice.original_audio_file_name));
+        }
+        
+        // Check if the slice exists
+        let current = self.get_slice(slice_id)?
+            .ok_or_else(|| anyhow::anyhow!("Slice with ID {} not found", slice_id))?;
+        let title = stored_text(slice.title.as_deref());
+        let transcription = stored_text(slice.transcription.as_deref());
+        self.record_change(slice_id, HISTORY_NAME, Some(&current.original_audio_file_name), Some(&slice.original_audio_file_name))?;
+        self.record_change(slice_id, HISTORY_TITLE, current.title.as_deref(), title.as_deref())?;
+        self.record_change(slice_id, HISTORY_TRANSCRIPTION, current.transcription.as_deref(), transcription.as_deref())?;
+        
+        // Perform the update
+        let rows_affected = self.conn.execute(
+            r#"
+            UPDATE slices SET
+                original_audio_file_name = ?1,
+                title = ?2,
+                transcribed = ?3,
+                audio_file_size = ?4,
+                audio_file_type = ?5,
+                estimated_time_to_transcribe = ?6,
+                audio_time_length_seconds = ?7,
+                transcription = ?8,
+                transcription_time_taken = ?9,
+                transcription_word_count = ?10,
+                transcription_model = ?11,
+                recording_date = ?12
+            WHERE id = ?13
+            "#,
+            params![
+                slice.original_audio_file_name,
+                title,
+                slice.transcribed as i32,
+                slice.audio_file_size,
+                slice.audio_file_type,
+                slice.estimated_time_to_transcribe,
+                slice.audio_time_length_seconds,
+                transcription,
+                slice.transcription_time_taken,
+                slice.transcription_word_count,
+                slice.transcription_model,
+                slice.recording_date,
+                slice_id,
+            ],
+        )?;
+        
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Failed to update slice: no rows affected"));
+        }
+        self.index_phonetics(slice_id, transcription.as_deref())?;
+
+        // Auto-apply labels when a slice's transcription is viewed/edited and saved.
+        if let Some(text) = transcription.as_deref() {
+            self.apply_auto_labels(slice_id, text)?;
+        }
+
+        Ok(())
+    }
+
+    /// Flip a slice's starred flag, returning the new value.
+    pub fn toggle_slice_starred(&self, slice_id: i64) -> Result<bool> {
+        let rows_affected = self.conn.execute(
+            "UPDATE slices SET starred = 1 - starred WHERE id = ?1",
+            params![slice_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("No slice found with ID: {}", slice_id));
+        }
+        let starred: i32 = self.conn.query_row(
+            "SELECT starred FROM slices WHERE id = ?1",
+            params![slice_id],
+            |row| row.get(0),
+        )?;
+        Ok(starred != 0)
+    }
+
+    /// Transcribed slices without a title, oldest first.
+    pub fn list_untitled_slice_ids(&self) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id FROM slices
+            WHERE deleted_at IS NULL AND (title IS NULL OR TRIM(title) = '')
+              AND transcription IS NOT NULL AND TRIM(transcription) != ''
+            ORDER BY id
+            "#,
+        )?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    pub fn save_slice_sentiment(&self, sentiment: &SliceSentiment) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO slice_sentiment (slice_id, score, label, model, analyzed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![sentiment.slice_id, sentiment.score, sentiment.label, sentiment.model, sentiment.analyzed_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_slice_sentiment(&self, slice_id: i64) -> Result<Option<SliceSentiment>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT slice_id, score, label, model, analyzed_at FROM slice_sentiment WHERE slice_id = ?1",
+        )?;
+        let sentiment = stmt
+            .query_map(params![slice_id], |row| {
+                Ok(SliceSentiment {
+                    slice_id: row.get(0)?,
+                    score: row.get(1)?,
+                    label: row.get(2)?,
+                    model: row.get(3)?,
+                    analyzed_at: row.get(4)?,
+                })
+            })?
+            .next()
+            .transpose()?;
+        Ok(sentiment)
+    }
+
+    /// Transcribed slices not analysed for sentiment yet, oldest first.
+    pub fn list_slices_without_sentiment(&self) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id FROM slices
+            WHERE deleted_at IS NULL AND transcription IS NOT NULL AND TRIM(transcription) != ''
+              AND id NOT IN (SELECT slice_id FROM slice_sentiment)
+            ORDER BY id
+            "#,
+        )?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Average sentiment per month the slices were recorded in (when analysed if the
+    /// recording date is unknown), oldest month first.
+    pub fn get_mood_by_month(&self) -> Result<Vec<MoodPoint>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT strftime('%Y-%m', COALESCE(s.recording_date, ss.analyzed_at), 'unixepoch', 'localtime') AS month,
+                   AVG(ss.score), COUNT(*)
+            FROM slice_sentiment ss
+            JOIN slices s ON s.id = ss.slice_id
+            WHERE s.deleted_at IS NULL
+            GROUP BY month
+            ORDER BY month
+            "#,
+        )?;
+        let points = stmt
+            .query_map([], |row| {
+                Ok(MoodPoint {
+                    month: row.get(0)?,
+                    average_score: row.get(1)?,
+                    count: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(points)
+    }
+
+    /// Replace the entities found in a slice's transcript. `source_hash` is the hash of the
+    /// transcript they were found in, to tell when it has changed since.
+    pub fn replace_slice_entities(&self, slice_id: i64, model: &str, source_hash: &str, entities: &[SliceEntity]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.conn.execute("DELETE FROM slice_entities WHERE slice_id = ?1", params![slice_id])?;
+        // Names that are personal information aren't kept while redaction mode is on
+        for entity in entities.iter().filter(|e| matches!(stored_text(Some(&e.name)), Some(Cow::Borrowed(_)))) {
+            self.conn.execute(
+                r#"
+                INSERT INTO slice_entities (slice_id, kind, name, normalized, mentions)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                ON CONFLICT(slice_id, kind, normalized) DO UPDATE SET mentions = MAX(mentions, excluded.mentions)
+                "#,
+                params![slice_id, entity.kind.as_str(), entity.name, normalize_entity_name(&entity.name), entity.mentions],
+            )?;
+        }
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO entity_extractions (slice_id, model, source_hash, extracted_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+            params![slice_id, model, source_hash, chrono::Utc::now().timestamp()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The entities a slice mentions, most mentioned first.
+    pub fn get_slice_entities(&self, slice_id: i64) -> Result<Vec<SliceEntity>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT slice_id, kind, name, mentions FROM slice_entities
+            WHERE slice_id = ?1 ORDER BY mentions DESC, name
+            "#,
+        )?;
+        let entities = stmt
+            .query_map(params![slice_id], |row| {
+                let kind: String = row.get(1)?;
+                Ok(SliceEntity {
+                    slice_id: row.get(0)?,
+                    kind: EntityKind::parse(&kind).unwrap_or(EntityKind::Person),
+                    name: row.get(2)?,
+                    mentions: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entities)
+    }
+
+    /// For each slice scanned for entities, the hash of the transcript it was scanned in.
+    pub fn entity_extraction_hashes(&self) -> Result<HashMap<i64, String>> {
+        let mut stmt = self.conn.prepare("SELECT slice_id, source_hash FROM entity_extractions")?;
+        let hashes = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(hashes)
+    }
+
+    /// Entities mentioned by slices outside the trash, optionally of one `kind` and with
+    /// `query` in their name, in the most slices first.
+    pub fn list_entities(&self, kind: Option<EntityKind>, query: Option<&str>, limit: u32) -> Result<Vec<EntityCount>> {
+        let query = query.map(normalize_entity_name).unwrap_or_default();
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT e.kind, MIN(e.name), COUNT(DISTINCT e.slice_id), SUM(e.mentions)
+            FROM slice_entities e
+            JOIN slices s ON s.id = e.slice_id
+            WHERE s.deleted_at IS NULL
+              AND (?1 IS NULL OR e.kind = ?1)
+              AND (?2 = '' OR instr(e.normalized, ?2) > 0)
+            GROUP BY e.kind, e.normalized
+            ORDER BY COUNT(DISTINCT e.slice_id) DESC, SUM(e.mentions) DESC, MIN(e.name)
+            LIMIT ?3
+            "#,
+        )?;
+        let entities = stmt
+            .query_map(params![kind.map(EntityKind::as_str), query, limit], |row| {
+                let kind: String = row.get(0)?;
+                Ok(EntityCount {
+                    kind: EntityKind::parse(&kind).unwrap_or(EntityKind::Person),
+                    name: row.get(1)?,
+                    slice_count: row.get(2)?,
+                    mentions: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entities)
+    }
+
+    /// Slices outside the trash mentioning an entity whose name contains `name` (ignoring
+    /// case, spacing and surrounding punctuation), optionally only of one `kind`.
+    pub fn find_slices_by_entity(&self, name: &str, kind: Option<EntityKind>) -> Result<Vec<Slice>> {
+        let name = normalize_entity_name(name);
+        if name.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            SELECT {} FROM slices
+            WHERE deleted_at IS NULL AND id IN (
+                SELECT slice_id FROM slice_entities
+                WHERE instr(normalized, ?1) > 0 AND (?2 IS NULL OR kind = ?2)
+            )
+            ORDER BY COALESCE(recording_date, 0) DESC, id DESC
+            "#,
+            SLICE_COLUMNS
+        ))?;
+        let slices = stmt
+            .query_map(params![name, kind.map(EntityKind::as_str)], slice_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(slices)
+    }
+
+    /// Store a translation, replacing any earlier one into the same language.
+    pub fn save_slice_translation(&self, translation: &SliceTranslation) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO slice_translations (slice_id, language, text, model, source_hash, translated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![
+                translation.slice_id,
+                translation.language,
+                stored_text(Some(&translation.text)),
+                translation.model,
+                translation.source_hash,
+                translation.translated_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// A slice's translations, by language.
+    pub fn list_slice_translations(&self, slice_id: i64) -> Result<Vec<SliceTranslation>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT slice_id, language, text, model, source_hash, translated_at
+            FROM slice_translations WHERE slice_id = ?1 ORDER BY language
+            "#,
+        )?;
+        let translations = stmt
+            .query_map(params![slice_id], |row| {
+                Ok(SliceTranslation {
+                    slice_id: row.get(0)?,
+                    language: row.get(1)?,
+                    text: row.get(2)?,
+                    model: row.get(3)?,
+                    source_hash: row.get(4)?,
+                    translated_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(translations)
+    }
+
+    /// Remove a slice's translation into `language`. Returns whether there was one.
+    pub fn delete_slice_translation(&self, slice_id: i64, language: &str) -> Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM slice_translations WHERE slice_id = ?1 AND language = ?2",
+            params![slice_id, language],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Replace a slice's passages in the transcript index. `content_hash` is the hash of the
+    /// transcript they were cut from, to tell when it has changed since.
+    pub fn replace_transcript_chunks(&self, slice_id: i64, model: &str, content_hash: &str, chunks: &[TranscriptChunk]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.conn.execute("DELETE FROM transcript_chunks WHERE slice_id = ?1", params![slice_id])?;
+        for (index, chunk) in chunks.iter().enumerate() {
+            let embedding: Vec<u8> = chunk.embedding.iter().flat_map(|v| v.to_le_bytes()).collect();
+            self.conn.execute(
+                r#"
+                INSERT INTO transcript_chunks (slice_id, chunk_index, start_char, end_char, text, embedding, model, content_hash)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#,
+                params![slice_id, index as i64, chunk.start_char as i64, chunk.end_char as i64, chunk.text, embedding, model, content_hash],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// For each indexed slice, the hash of the transcript its passages were embedded from
+    /// with `model`.
+    pub fn transcript_index_hashes(&self, model: &str) -> Result<HashMap<i64, String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT slice_id, content_hash FROM transcript_chunks WHERE model = ?1 GROUP BY slice_id",
+        )?;
+        let hashes = stmt
+            .query_map(params![model], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(hashes)
+    }
+
+    /// Every indexed passage embedded with `model`, leaving out slices in the trash.
+    pub fn load_transcript_chunks(&self, model: &str) -> Result<Vec<TranscriptChunk>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT c.slice_id, c.start_char, c.end_char, c.text, c.embedding
+            FROM transcript_chunks c
+            JOIN slices s ON s.id = c.slice_id
+            WHERE c.model = ?1 AND s.deleted_at IS NULL
+            ORDER BY c.slice_id, c.chunk_index
+            "#,
+        )?;
+        let chunks = stmt
+            .query_map(params![model], |row| {
+                let bytes: Vec<u8> = row.get(4)?;
+                Ok(TranscriptChunk {
+                    slice_id: row.get(0)?,
+                    start_char: row.get::<_, i64>(1)? as usize,
+                    end_char: row.get::<_, i64>(2)? as usize,
+                    text: row.get(3)?,
+                    embedding: bytes
+                        .chunks_exact(4)
+                        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                        .collect(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(chunks)
+    }
+
+    /// Drop indexed passages of slices that no longer have a transcript. Returns how many
+    /// were removed.
+    pub fn prune_transcript_chunks(&self) -> Result<usize> {
+        Ok(self.conn.execute(
+            r#"
+            DELETE FROM transcript_chunks WHERE slice_id NOT IN (
+                SELECT id FROM slices WHERE transcription IS NOT NULL AND TRIM(transcription) != ''
+            )
+            "#,
+            [],
+        )?)
+    }
+
+    /// Note that the user accepted or rejected the suggestion of `label_id` for `slice_id`,
+    /// replacing an earlier decision.
+    pub fn record_label_feedback(&self, slice_id: i64, label_id: i64, accepted: bool) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO label_suggestion_feedback (slice_id, label_id, accepted, decided_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+            params![slice_id, label_id, accepted, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Suggestions the user rejected, as `(slice_id, label_id)` pairs.
+    pub fn list_rejected_label_suggestions(&self) -> Result<Vec<(i64, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT slice_id, label_id FROM label_suggestion_feedback WHERE accepted = 0 ORDER BY slice_id, label_id",
+        )?;
+        let pairs = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(pairs)
+    }
+
+    /// IDs of slices with a transcript, outside the trash, oldest first.
+    pub fn list_transcribed_slice_ids(&self) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id FROM slices
+            WHERE deleted_at IS NULL AND transcription IS NOT NULL AND TRIM(transcription) != ''
+            ORDER BY id
+            "#,
+        )?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Store (or with `None`, clear) a slice's summary.
+    pub fn set_slice_summary(&self, slice_id: i64, summary: Option<&str>) -> Result<()> {
+        let summary = stored_text(summary);
+        let rows_affected = self.conn.execute(
+            "UPDATE slices SET summary = ?1 WHERE id = ?2",
+            params![summary, slice_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("No slice found with ID: {}", slice_id));
+        }
+        Ok(())
+    }
+
+    /// Pin or unpin a slice.
+    pub fn set_slice_pinned(&self, slice_id: i64, pinned: bool) -> Result<()> {
+        let rows_affected = self.conn.execute(
+            "UPDATE slices SET pinned = ?1 WHERE id = ?2",
+            params![pinned, slice_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("No slice found with ID: {}", slice_id));
+        }
+        Ok(())
+    }
+
+    pub fn update_slice_audio_duration(&self, slice_id: i64, duration_seconds: f64) -> Result<()> {
+        let rows_affected = self.conn.execute(
+            "UPDATE slices SET audio_time_length_seconds = ?1 WHERE id = ?2",
+            params![duration_seconds, slice_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("Slice with ID {} not found", slice_id));
+        }
+
+        Ok(())
+    }
+
+    /// Clear audio durations that are obviously corrupted (> 24 hours).
+    /// These were caused by a unit conversion bug in get_audio_duration.
+    pub fn clear_corrupt_audio_durations(&self) -> Result<u32> {
+        let rows_affected = self.conn.execute(
+            "UPDATE slices SET audio_time_length_seconds = NULL WHERE audio_time_length_seconds > 86400",
+            [],
+        )?;
+        Ok(rows_affected as u32)
+    }
+
+    pub fn get_slices_without_duration(&self) -> Result<Vec<Slice>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM slices WHERE audio_time_length_seconds IS NULL ORDER BY id",
+            SLICE_COLUMNS
+        ))?;
+
+        let slice_iter = stmt.query_map([], slice_from_row)?;
+
+        let mut slices = Vec::new();
+        for slice in slice_iter {
+            slices.push(slice?);
+        }
+        Ok(slices)
+    }
+
+    /// Backfill recording_date for slices that have NULL recording_date
+    /// by looking up the date from ZCLOUDRECORDING table
+    pub fn backfill_recording_dates(&self) -> Result<u32> {
+        // Get all slices with NULL recording_date
+        let mut stmt = self.conn.prepare(
+            "SELECT id, original_audio_file_name FROM slices WHERE recording_date IS NULL"
+        )?;
+
+        let slices_to_update: Vec<(i64, String)> = stmt.query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?.filter_map(|r| r.ok()).collect();
+
+        let mut updated_count = 0u32;
+
+        for (slice_id, filename) in slices_to_update {
+            if let Ok(Some(recording_date)) = self.get_recording_date_for_filename(&filename) {
+                let rows_affected = self.conn.execute(
+                    "UPDATE slices SET recording_date = ?1 WHERE id = ?2",
+                    params![recording_date, slice_id],
+                )?;
+                if rows_affected > 0 {
+                    updated_count += 1;
+                }
+            }
+        }
+
+        Ok(updated_count)
+    }
+
+    fn get_count_by_year_from_apple_db(&self) -> Result<Vec<YearCount>> {
+        // Try to get year data from the ZCLOUDRECORDING table if it exists
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT
+                CAST(strftime('%Y', datetime(ZDATE + 978307200, 'unixepoch')) AS INTEGER) as year,
+                COUNT(*) as count
+            FROM ZCLOUDRECORDING
+            WHERE ZDATE IS NOT NULL
+            GROUP BY year
+            ORDER BY year
+            "#
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(YearCount {
+                year: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })?;
+
+        let mut count_by_year = Vec::new();
+        for row in rows {
+            count_by_year.push(row?);
+        }
+
+        Ok(count_by_year)
+    }
+
+    fn get_count_by_audio_length(&self) -> Result<Vec<AudioLengthBucket>> {
+        // Group audio files by duration buckets using the audio_time_length_seconds field
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT
+                CASE
+                    WHEN audio_time_length_seconds IS NULL THEN 'Unknown'
+                    WHEN audio_time_length_seconds < 30 THEN '< 30s'
+                    WHEN audio_time_length_seconds < 60 THEN '30s-1m'
+                    WHEN audio_time_length_seconds < 300 THEN '1-5m'
+                    WHEN audio_time_length_seconds < 900 THEN '5-15m'
+                    WHEN audio_time_length_seconds < 1800 THEN '15-30m'
+                    WHEN audio_time_length_seconds < 3600 THEN '30m-1h'
+                    ELSE '1h+'
+                END as bucket,
+                COUNT(*) as count
+            FROM slices
+            WHERE deleted_at IS NULL
+            GROUP BY bucket
+            ORDER BY
+                CASE bucket
+                    WHEN '< 30s' THEN 1
+                    WHEN '30s-1m' THEN 2
+                    WHEN '1-5m' THEN 3
+                    WHEN '5-15m' THEN 4
+                    WHEN '15-30m' THEN 5
+                    WHEN '30m-1h' THEN 6
+                    WHEN '1h+' THEN 7
+                    ELSE 8
+                END
+            "#
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(AudioLengthBucket {
+                label: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })?;
+
+        let mut buckets = Vec::new();
+        for row in rows {
+            buckets.push(row?);
+        }
+
+        Ok(buckets)
+    }
+
+    pub fn update_recording_title_by_slice(&self, slice_id: i64, new_title: &str) -> Result<()> {
+        self.set_slice_title(slice_id, Some(new_title))
+    }
+
+    /// Set (or with `None` clear) a slice's title, recording the old one in its history.
+    fn set_slice_title(&self, slice_id: i64, new_title: Option<&str>) -> Result<()> {
+        let new_title = stored_text(new_title);
+        let new_title = new_title.as_deref();
+        let current = self.get_slice(slice_id)?
+            .ok_or_else(|| anyhow::anyhow!("No slice found with ID: {}", slice_id))?;
+        self.record_change(slice_id, HISTORY_TITLE, current.title.as_deref(), new_title)?;
+
+        // Update the title directly in the slices table
+        self.conn.execute(
+            "UPDATE slices SET title = ?1 WHERE id = ?2",
+            params![new_title, slice_id],
+        )?;
+        Ok(())
+    }
+
+    /// Save a hand-edited (or reverted) transcription, recording the old text in the history.
+    /// `None` returns the slice to untranscribed.
+    fn set_slice_transcription_text(&self, slice_id: i64, text: Option<&str>) -> Result<()> {
+        let text = stored_text(text);
+        let text = text.as_deref();
+        let current = self.get_slice(slice_id)?
+            .ok_or_else(|| anyhow::anyhow!("No slice found with ID: {}", slice_id))?;
+        self.record_change(slice_id, HISTORY_TRANSCRIPTION, current.transcription.as_deref(), text)?;
+
+        let word_count = text.map(|t| t.split_whitespace().count() as i32);
+        self.conn.execute(
+            r#"
+            UPDATE slices SET
+                transcription = ?1,
+                transcribed = ?1 IS NOT NULL,
+                transcription_word_count = ?2
+            WHERE id = ?3
+            "#,
+            params![text, word_count, slice_id],
+        )?;
+        self.index_phonetics(slice_id, text)?;
+        Ok(())
+    }
+
+    /// Find/replace across stored transcriptions, each changed one recorded in its history
+    /// so it can be reverted. With `dry_run` nothing is written; the report shows what would change.
+    pub fn replace_in_transcriptions(&self, request: &TranscriptReplacement, dry_run: bool) -> Result<ReplaceReport> {
+        let regex = search::replacement_regex(&request.find, request.case_sensitive, request.within_words)?;
+        let slices = match &request.slice_ids {
+            Some(ids) => ids.iter()
+                .map(|&id| self.get_slice(id)?.ok_or_else(|| anyhow::anyhow!("No slice found with ID: {}", id)))
+                .collect::<Result<Vec<_>>>()?,
+            None => self.list_active_slices()?,
+        };
+
+        let mut report = ReplaceReport { slices: Vec::new(), total_replacements: 0, applied: !dry_run };
+        for slice in slices {
+            let (Some(slice_id), Some(text)) = (slice.id, slice.transcription.as_deref()) else { continue };
+            let replacements = regex.find_iter(text).count() as u32;
+            if replacements == 0 {
+                continue;
+            }
+            if !dry_run {
+                let replaced = regex.replace_all(text, regex::NoExpand(&request.replace));
+                self.set_slice_transcription_text(slice_id, Some(&replaced))?;
+            }
+            report.total_replacements += replacements;
+            report.slices.push(SliceReplaceCount {
+                slice_id,
+                name: slice.title.clone().filter(|t| !t.is_empty()).unwrap_or_else(|| slice.original_audio_file_name.clone()),
+                replacements,
+                examples: search::replacement_examples(&regex, text),
+            });
+        }
+        Ok(report)
+    }
+
+    /// Rewrite every stored copy of a slice's text through `mask`: its transcript, title, notes
+    /// and summary, the old and new values in its history, its translations and derived
+    /// documents. Entities whose names `mask` changes are dropped. Unlike an edit this leaves
+    /// no trace in the history, and the slice's passages are dropped from the transcript
+    /// index until it is next updated. Returns how many values changed.
+    pub fn scrub_slice_text(&self, slice_id: i64, mask: &dyn Fn(&str) -> String) -> Result<u32> {
+        let slice = self.get_slice(slice_id)?
+            .ok_or_else(|| anyhow::anyhow!("No slice found with ID: {}", slice_id))?;
+        let masked = |value: &Option<String>| value.as_deref().map(mask);
+        let mut changed = 0;
+        let tx = self.conn.unchecked_transaction()?;
+
+        let fields = [
+            (&slice.transcription, masked(&slice.transcription)),
+            (&slice.title, masked(&slice.title)),
+            (&slice.notes, masked(&slice.notes)),
+            (&slice.summary, masked(&slice.summary)),
+        ];
+        let slice_changes = fields.iter().filter(|(old, new)| *old != new).count() as u32;
+        if slice_changes > 0 {
+            let [transcription, title, notes, summary] = fields.map(|(_, new)| new);
+            self.conn.execute(
+                r#"
+                UPDATE slices SET
+                    transcription = ?1,
+                    transcription_word_count = COALESCE(?2, transcription_word_count),
+                    title = ?3,
+                    notes = ?4,
+                    summary = ?5
+                WHERE id = ?6
+                "#,
+                params![
+                    transcription,
+                    transcription.as_deref().map(|t| t.split_whitespace().count() as i32),
+                    title,
+                    notes,
+                    summary,
+                    slice_id
+                ],
+            )?;
+            self.index_phonetics(slice_id, transcription.as_deref())?;
+            self.conn.execute("DELETE FROM transcript_chunks WHERE slice_id = ?1", params![slice_id])?;
+            changed += slice_changes;
+        }
+
+        for change in self.get_slice_history(slice_id)? {
+            let (id, old_value, new_value) = (change.id, change.old_value, change.new_value);
+            let (old_masked, new_masked) = (masked(&old_value), masked(&new_value));
+            if old_masked != old_value || new_masked != new_value {
+                self.conn.execute(
+                    "UPDATE slice_history SET old_value = ?1, new_value = ?2 WHERE id = ?3",
+                    params![old_masked, new_masked, id],
+                )?;
+                changed += 1;
+            }
+        }
+
+        for translation in self.list_slice_translations(slice_id)? {
+            let text = mask(&translation.text);
+            if text != translation.text {
+                self.conn.execute(
+                    "UPDATE slice_translations SET text = ?1 WHERE slice_id = ?2 AND language = ?3",
+                    params![text, slice_id, translation.language],
+                )?;
+                changed += 1;
+            }
+        }
+        for document in self.list_derived_documents(slice_id)? {
+            let content = mask(&document.content);
+            if content != document.content {
+                self.conn.execute(
+                    "UPDATE derived_documents SET content = ?1 WHERE id = ?2",
+                    params![content, document.id],
+                )?;
+                changed += 1;
+            }
+        }
+        for entity in self.get_slice_entities(slice_id)? {
+            if mask(&entity.name) != entity.name {
+                self.conn.execute(
+                    "DELETE FROM slice_entities WHERE slice_id = ?1 AND kind = ?2 AND normalized = ?3",
+                    params![slice_id, entity.kind.as_str(), normalize_entity_name(&entity.name)],
+                )?;
+                changed += 1;
+            }
+        }
+
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Rewrite the text kept outside slices through `mask`: the arguments and output of
+    /// recorded nlm runs (which can carry a transcript), and past searches, which are
+    /// dropped rather than kept masked. Returns how many values changed.
+    pub fn scrub_logged_text(&self, mask: &dyn Fn(&str) -> String) -> Result<u32> {
+        let mut changed = 0;
+        let tx = self.conn.unchecked_transaction()?;
+
+        let invocations = {
+            let mut stmt = self.conn.prepare("SELECT id, args, output FROM nlm_invocations")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        for (id, args, output) in invocations {
+            let (masked_args, masked_output) = (mask(&args), output.as_deref().map(mask));
+            if masked_args != args || masked_output != output {
+                self.conn.execute(
+                    "UPDATE nlm_invocations SET args = ?1, output = ?2 WHERE id = ?3",
+                    params![masked_args, masked_output, id],
+                )?;
+                changed += 1;
+            }
+        }
+
+        let queries = {
+            let mut stmt = self.conn.prepare("SELECT query FROM search_history")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        for query in queries.into_iter().filter(|q| mask(q) != *q) {
+            self.conn.execute("DELETE FROM search_history WHERE query = ?1", params![query])?;
+            changed += 1;
+        }
+
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Set a slice's notes; blank notes are cleared.
+    pub fn update_slice_notes(&self, slice_id: i64, notes: Option<&str>) -> Result<()> {
+        let notes = stored_text(notes.map(str::trim).filter(|n| !n.is_empty()));
+        let notes = notes.as_deref();
+        let current = self.get_slice(slice_id)?
+            .ok_or_else(|| anyhow::anyhow!("No slice found with ID: {}", slice_id))?;
+        self.record_change(slice_id, HISTORY_NOTES, current.notes.as_deref(), notes)?;
+
+        self.conn.execute(
+            "UPDATE slices SET notes = ?1 WHERE id = ?2",
+            params![notes, slice_id],
+        )?;
+        Ok(())
+    }
+
+    /// Add a history entry for `field` of a slice changing from `old` to `new`; no-op when equal.
+    fn record_change(&self, slice_id: i64, field: &str, old: Option<&str>, new: Option<&str>) -> Result<()> {
+        if old == new {
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT INTO slice_history (slice_id, field, old_value, new_value, changed_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![slice_id, field, old, new, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Edits made to a slice, newest first.
+    pub fn get_slice_history(&self, slice_id: i64) -> Result<Vec<SliceChange>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, slice_id, field, old_value, new_value, changed_at FROM slice_history
+            WHERE slice_id = ?1
+            ORDER BY changed_at DESC, id DESC
+            "#,
+        )?;
+        let changes = stmt
+            .query_map(params![slice_id], |row| {
+                Ok(SliceChange {
+                    id: row.get(0)?,
+                    slice_id: row.get(1)?,
+                    field: row.get(2)?,
+                    old_value: row.get(3)?,
+                    new_value: row.get(4)?,
+                    changed_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(changes)
+    }
+
+    /// Put back the value a slice field had before the history entry `change_id`. The revert
+    /// is itself recorded, so it can be undone too.
+    pub fn revert_slice_change(&self, change_id: i64) -> Result<()> {
+        let result = self.conn.query_row(
+            "SELECT slice_id, field, old_value FROM slice_history WHERE id = ?1",
+            params![change_id],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)),
+        );
+        let (slice_id, field, old_value) = match result {
+            Ok(change) => change,
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                return Err(anyhow::anyhow!("No history entry found with ID: {}", change_id));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        match field.as_str() {
+            HISTORY_NAME => {
+                let name = old_value.ok_or_else(|| anyhow::anyhow!("History entry {} has no previous name", change_id))?;
+                self.update_slice_name(slice_id, &name)
+            }
+            HISTORY_TITLE => self.set_slice_title(slice_id, old_value.as_deref()),
+            HISTORY_TRANSCRIPTION => self.set_slice_transcription_text(slice_id, old_value.as_deref()),
+            HISTORY_NOTES => self.update_slice_notes(slice_id, old_value.as_deref()),
+            other => Err(anyhow::anyhow!("Unknown history field: {}", other)),
+        }
+    }
+
+    pub fn auto_populate_titles(&self) -> Result<u32> {
+        use std::collections::HashMap;
+        use regex::Regex;
+
+        // Get all slices with their current titles
+        let slices = self.list_all_slices()?;
+
+        // Track titles to handle duplicates
+        let mut title_counts: HashMap<String, u32> = HashMap::new();
+        let mut updated_count = 0u32;
+
+        // Regex to extract dates like "20251117" from filenames
+        let date_pattern = Regex::new(r"(\d{8})").unwrap();
+
+        for slice in slices {
+            // Skip if title is already set
+            if slice.title.is_some() && !slice.title.as_ref().unwrap().trim().is_empty() {
+                // Count existing titles for deduplication
+                let title = slice.title.as_ref().unwrap().clone();
+                *title_counts.entry(title).or_insert(0) += 1;
+                continue;
+            }
+
+            // Extract title from filename
+            let filename = &slice.original_audio_file_name;
+
+            // Try to extract date from filename
+            let mut title = if let Some(captures) = date_pattern.captures(filename) {
+                if let Some(date_str) = captures.get(1) {
+                    let date = date_str.as_str();
+                    if date.len() == 8 {
+                        // Format as YYYY-MM-DD
+                        format!("{}-{}-{}", &date[0..4], &date[4..6], &date[6..8])
+                    } else {
+                        // Fallback to filename without extension
+                        filename.trim_end_matches(".m4a")
+                            .trim_end_matches(".wav")
+                            .trim_end_matches(".mp3")
+                            .to_string()
+                    }
+                } else {
+                    filename.trim_end_matches(".m4a")
+                        .trim_end_matches(".wav")
+                        .trim_end_matches(".mp3")
+                        .to_string()
+                }
+            } else {
+                // No date found, use filename without extension
+                filename.trim_end_matches(".m4a")
+                    .trim_end_matches(".wav")
+                    .trim_end_matches(".mp3")
+                    .to_string()
+            };
+
+            // Handle duplicates by appending (2), (3), etc.
+            let base_title = title.clone();
+            let mut counter = 2;
+            while title_counts.contains_key(&title) {
+                title = format!("{} ({})", base_title, counter);
+                counter += 1;
+            }
+
+            // Mark this title as used
+            *title_counts.entry(title.clone()).or_insert(0) += 1;
+
+            // Update the slice title directly if we have a slice ID
+            if let Some(slice_id) = slice.id {
+                let rows_affected = self.conn.execute(
+                    "UPDATE slices SET title = ?1 WHERE id = ?2",
+                    params![&title, slice_id],
+                )?;
+
+                if rows_affected > 0 {
+                    updated_count += 1;
+                } else {
+                    tracing::warn!(
+                        "Failed to auto-populate title for slice {}: no rows affected",
+                        slice_id
+                    );
+                }
+            }
+        }
+
+        Ok(updated_count)
+    }
+
+    // ==================== Label CRUD operations ====================
+
+    pub fn list_labels(&self) -> Result<Vec<Label>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, color, keywords FROM labels ORDER BY id"
+        )?;
+
+        let label_iter = stmt.query_map([], |row| {
+            Ok(Label {
+                id: Some(row.get("id")?),
+                name: row.get("name")?,
+                color: row.get("color")?,
+                keywords: row.get("keywords")?,
+            })
+        })?;
+
+        let mut labels = Vec::new();
+        for label in label_iter {
+            labels.push(label?);
+        }
+        Ok(labels)
+    }
+
+    pub fn create_label(&self, label: &Label) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO labels (name, color, keywords) VALUES (?1, ?2, ?3)",
+            params![&label.name, &label.color, &label.keywords],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Look up a label by name, case-insensitively.
+    pub fn find_label_by_name(&self, name: &str) -> Result<Option<Label>> {
+        let result = self.conn.query_row(
+            "SELECT id, name, color, keywords FROM labels WHERE name = ?1 COLLATE NOCASE LIMIT 1",
+            params![name],
+            |row| {
+                Ok(Label {
+                    id: Some(row.get("id")?),
+                    name: row.get("name")?,
+                    color: row.get("color")?,
+                    keywords: row.get("keywords")?,
+                })
+            },
+        );
+
+        match result {
+            Ok(label) => Ok(Some(label)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// ID of the label with this name, creating it (with no keywords) if it doesn't exist.
+    pub fn get_or_create_label(&self, name: &str, color: &str) -> Result<i64> {
+        if let Some(id) = self.find_label_by_name(name)?.and_then(|label| label.id) {
+            return Ok(id);
+        }
+        self.create_label(&Label {
+            id: None,
+            name: name.to_string(),
+            color: color.to_string(),
+            keywords: String::new(),
+        })
+    }
+
+    /// Attach a label to each of `slice_ids`. Returns the number of new associations.
+    pub fn assign_label(&self, label_id: i64, slice_ids: &[i64]) -> Result<u32> {
+        let label_exists: bool = self.conn.query_row(
+            "SELECT COUNT(*) FROM labels WHERE id = ?1",
+            params![label_id],
+            |row| row.get::<_, i64>(0).map(|n| n > 0),
+        )?;
+        if !label_exists {
+            return Err(anyhow::anyhow!("No label found with ID: {}", label_id));
+        }
+
+        let mut added = 0;
+        for slice_id in slice_ids {
+            added += self.conn.execute(
+                "INSERT OR IGNORE INTO slice_labels (slice_id, label_id) SELECT id, ?1 FROM slices WHERE id = ?2",
+                params![label_id, slice_id],
+            )?;
+        }
+        Ok(added as u32)
+    }
+
+    /// Detach a label from each of `slice_ids`. Returns the number of associations removed.
+    pub fn remove_label(&self, label_id: i64, slice_ids: &[i64]) -> Result<u32> {
+        let mut removed = 0;
+        for slice_id in slice_ids {
+            removed += self.conn.execute(
+                "DELETE FROM slice_labels WHERE slice_id = ?1 AND label_id = ?2",
+                params![slice_id, label_id],
+            )?;
+        }
+        Ok(removed as u32)
+    }
+
+    /// Slices outside the trash carrying any of `label_ids`, or with `match_all` every one of them.
+    pub fn list_slices_with_labels(&self, label_ids: &[i64], match_all: bool) -> Result<Vec<Slice>> {
+        if label_ids.is_empty() {
+            return self.list_active_slices();
+        }
+
+        let placeholders = vec!["?"; label_ids.len()].join(", ");
+        let required = if match_all { label_ids.len() } else { 1 };
+        let mut stmt = self.conn.prepare(&format!(
+            r#"
+            SELECT {} FROM slices
+            WHERE deleted_at IS NULL AND id IN (
+                SELECT slice_id FROM slice_labels
+                WHERE label_id IN ({})
+                GROUP BY slice_id
+                HAVING COUNT(DISTINCT label_id) >= {}
+            )
+            ORDER BY id
+            "#,
+            SLICE_COLUMNS, placeholders, required
+        ))?;
+
+        let slice_iter = stmt.query_map(rusqlite::params_from_iter(label_ids), slice_from_row)?;
+
+        let mut slices = Vec::new();
+        for slice in slice_iter {
+            slices.push(slice?);
+        }
+        Ok(slices)
+    }
+
+    pub fn update_label(&self, id: i64, label: &Label) -> Result<()> {
+        let rows_affected = self.conn.execute(
+            "UPDATE labels SET name = ?1, color = ?2, keywords = ?3 WHERE id = ?4",
+            params![&label.name, &label.color, &label.keywords, id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("No label found with ID: {}", id));
+        }
+        Ok(())
+    }
+
+    pub fn delete_label(&self, id: i64) -> Result<()> {
+        // Remove any slice associations for this label first so no orphan rows remain.
+        self.conn.execute(
+            "DELETE FROM slice_labels WHERE label_id = ?1",
+            params![id],
+        )?;
+        self.conn.execute("DELETE FROM label_notebooks WHERE label_id = ?1", params![id])?;
+        self.conn.execute("DELETE FROM label_suggestion_feedback WHERE label_id = ?1", params![id])?;
+        self.conn.execute("DELETE FROM label_prompts WHERE label_id = ?1", params![id])?;
+
+        let rows_affected = self.conn.execute(
+            "DELETE FROM labels WHERE id = ?1",
+            params![id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(anyhow::anyhow!("No label found with ID: {}", id));
+        }
+        Ok(())
+    }
+
+    /// Auto-apply labels to a slice by matching each label's keywords against the given text.
+    ///
+    /// Matching semantics: a label's `keywords` string is split on commas, each phrase is
+    /// trimmed, and empty phrases are ignored. Each phrase is matched case-insensitively under
+    /// the configured `keyword_match_mode`. If ANY phrase of a label matches, that label is
+    /// applied to the slice.
+    ///
+    /// Reconciliation: this only ever ADDS associations (INSERT OR IGNORE). It never removes
+    /// labels, so re-transcribing or re-saving a slice reconciles by adding any newly matching
+    /// labels while preserving previously applied ones.
+    pub fn apply_auto_labels(&self, slice_id: i64, text: &str) -> Result<()> {
+        let mode = labeling::match_mode();
+        let labels = self.list_labels()?;
+
+        for label in labels {
+            let label_id = match label.id {
+                Some(id) => id,
+                None => continue,
+            };
+
+            if labeling::keywords_match(&label.keywords, text, mode) {
+                self.conn.execute(
+                    "INSERT OR IGNORE INTO slice_labels (slice_id, label_id) VALUES (?1, ?2)",
+                    params![slice_id, label_id],
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetch all slice -> labels associations as a map keyed by slice_id.
+    /// Returned in a single query so the Slices screen can render label badges with one round trip.
+    pub fn get_labels_for_all_slices(&self) -> Result<HashMap<i64, Vec<Label>>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT sl.slice_id, l.id, l.name, l.color, l.keywords
+            FROM slice_labels sl
+            JOIN labels l ON l.id = sl.label_id
+            ORDER BY sl.slice_id, l.id
+            "#,
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                Label {
+                    id: Some(row.get(1)?),
+                    name: row.get(2)?,
+                    color: row.get(3)?,
+                    keywords: row.get(4)?,
+                },
+            ))
+        })?;
 
-        match result {
-            Ok(timestamp) => Ok(Some(timestamp)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => {
-                // Table might not exist yet, return None
-                if e.to_string().contains("no such table") {
-                    Ok(None)
-                } else {
-                    Err(e.into())
-                }
-            }
+        let mut map: HashMap<i64, Vec<Label>> = HashMap::new();
+        for row in rows {
+            let (slice_id, label) = row?;
+            map.entry(slice_id).or_default().push(label);
         }
+        Ok(map)
     }
 
-    pub fn list_all_slices(&self) -> Result<Vec<Slice>> {
+    /// Every collection, grouped by parent and sorted by name within each group.
+    pub fn list_collections(&self) -> Result<Vec<Collection>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, original_audio_file_name, title, transcribed, audio_file_size, audio_file_type,
-                    estimated_time_to_transcribe, audio_time_length_seconds, transcription, transcription_time_taken,
-                    transcription_word_count, transcription_model, recording_date
-             FROM slices
-             ORDER BY id"
+            "SELECT id, name, parent_id FROM collections ORDER BY parent_id, name COLLATE NOCASE, id"
         )?;
+        let collections = stmt
+            .query_map([], |row| {
+                Ok(Collection { id: Some(row.get(0)?), name: row.get(1)?, parent_id: row.get(2)? })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(collections)
+    }
 
-        let slice_iter = stmt.query_map([], |row| {
-            Ok(Slice {
-                id: Some(row.get("id")?),
-                original_audio_file_name: row.get("original_audio_file_name")?,
-                title: row.get("title")?,
-                transcribed: row.get::<_, i32>("transcribed")? != 0,
-                audio_file_size: row.get("audio_file_size")?,
-                audio_file_type: row.get("audio_file_type")?,
-                estimated_time_to_transcribe: row.get("estimated_time_to_transcribe")?,
-                audio_time_length_seconds: row.get("audio_time_length_seconds")?,
-                transcription: row.get("transcription")?,
-                transcription_time_taken: row.get("transcription_time_taken")?,
-                transcription_word_count: row.get("transcription_word_count")?,
-                transcription_model: row.get("transcription_model")?,
-                recording_date: row.get("recording_date")?,
-            })
-        })?;
+    fn get_collection(&self, id: i64) -> Result<Collection> {
+        let result = self.conn.query_row(
+            "SELECT id, name, parent_id FROM collections WHERE id = ?1",
+            params![id],
+            |row| Ok(Collection { id: Some(row.get(0)?), name: row.get(1)?, parent_id: row.get(2)? }),
+        );
 
-        let mut slices = Vec::new();
-        for slice in slice_iter {
-            slices.push(slice?);
+        match result {
+            Ok(collection) => Ok(collection),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Err(anyhow::anyhow!("No collection found with ID: {}", id)),
+            Err(e) => Err(e.into()),
         }
-        Ok(slices)
     }
 
-    pub fn clear_all_slices(&self) -> Result<()> {
-        self.conn.execute("DELETE FROM slices", [])?;
+    /// Fail if another collection under `parent_id` already has `name` (case-insensitively).
+    fn check_collection_name_free(&self, name: &str, parent_id: Option<i64>, except_id: Option<i64>) -> Result<()> {
+        let taken: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM collections WHERE name = ?1 COLLATE NOCASE AND parent_id IS ?2 AND id IS NOT ?3",
+            params![name, parent_id, except_id],
+            |row| row.get(0),
+        )?;
+        if taken > 0 {
+            return Err(anyhow::anyhow!("A collection named '{}' already exists there", name));
+        }
         Ok(())
     }
 
-    pub fn update_slice_transcription(
-        &self,
-        slice_id: i64,
-        transcription: &str,
-        transcription_time_taken: i32,
-        word_count: i32,
-        model_name: &str,
-    ) -> Result<()> {
+    /// Create a collection at the top level or inside `parent_id`; returns its ID.
+    pub fn create_collection(&self, name: &str, parent_id: Option<i64>) -> Result<i64> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Collection name must not be empty"));
+        }
+        if let Some(parent_id) = parent_id {
+            self.get_collection(parent_id)?;
+        }
+        self.check_collection_name_free(name, parent_id, None)?;
+
         self.conn.execute(
-            r#"
-            UPDATE slices SET
-                transcribed = 1,
-                transcription = ?1,
-                transcription_time_taken = ?2,
-                transcription_word_count = ?3,
-                transcription_model = ?4
-            WHERE id = ?5
-            "#,
-            params![
-                transcription,
-                transcription_time_taken,
-                word_count,
-                model_name,
-                slice_id,
-            ],
+            "INSERT INTO collections (name, parent_id) VALUES (?1, ?2)",
+            params![name, parent_id],
         )?;
+        Ok(self.conn.last_insert_rowid())
+    }
 
-        // Auto-apply labels whose keywords match the freshly-transcribed text.
-        self.apply_auto_labels(slice_id, transcription)?;
+    /// Move a collection, with everything in it, under `new_parent_id` (None = top level).
+    pub fn move_collection(&self, id: i64, new_parent_id: Option<i64>) -> Result<()> {
+        let collection = self.get_collection(id)?;
+
+        // Walk up from the new parent so a collection never ends up inside itself
+        let mut ancestor = new_parent_id;
+        while let Some(ancestor_id) = ancestor {
+            if ancestor_id == id {
+                return Err(anyhow::anyhow!("Cannot move collection '{}' inside itself", collection.name));
+            }
+            ancestor = self.get_collection(ancestor_id)?.parent_id;
+        }
+        self.check_collection_name_free(&collection.name, new_parent_id, Some(id))?;
+
+        self.conn.execute(
+            "UPDATE collections SET parent_id = ?1 WHERE id = ?2",
+            params![new_parent_id, id],
+        )?;
         Ok(())
     }
 
-    pub fn update_slice_name(&self, slice_id: i64, new_name: &str) -> Result<()> {
-        // Check if the new name already exists (excluding the current slice)
-        let existing_count: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM slices WHERE original_audio_file_name = ?1 AND id != ?2",
-            params![new_name, slice_id],
-            |row| row.get(0),
+    /// Delete a collection. Its subcollections and slices move up to its parent rather than
+    /// being deleted along with it.
+    pub fn delete_collection(&self, id: i64) -> Result<()> {
+        let collection = self.get_collection(id)?;
+
+        self.conn.execute(
+            "UPDATE collections SET parent_id = ?1 WHERE parent_id = ?2",
+            params![collection.parent_id, id],
         )?;
-        
-        if existing_count > 0 {
-            return Err(anyhow::anyhow!("A slice with the filename '{}' already exists", new_name));
+        match collection.parent_id {
+            Some(parent_id) => self.conn.execute(
+                "UPDATE slice_collections SET collection_id = ?1 WHERE collection_id = ?2",
+                params![parent_id, id],
+            )?,
+            None => self.conn.execute("DELETE FROM slice_collections WHERE collection_id = ?1", params![id])?,
+        };
+        self.conn.execute("DELETE FROM collections WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// File slices in a collection, taking them out of any other; `None` unfiles them.
+    /// Returns the number of existing slices moved.
+    pub fn move_slices_to_collection(&self, slice_ids: &[i64], collection_id: Option<i64>) -> Result<u32> {
+        if let Some(collection_id) = collection_id {
+            self.get_collection(collection_id)?;
         }
-        
-        // Check if the slice exists
-        let slice_exists: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM slices WHERE id = ?1",
-            params![slice_id],
-            |row| row.get(0),
-        )?;
-        
-        if slice_exists == 0 {
-            return Err(anyhow::anyhow!("Slice with ID {} not found", slice_id));
+
+        let mut moved = 0;
+        for slice_id in slice_ids {
+            moved += match collection_id {
+                Some(collection_id) => self.conn.execute(
+                    r#"
+                    INSERT OR REPLACE INTO slice_collections (slice_id, collection_id)
+                    SELECT id, ?2 FROM slices WHERE id = ?1
+                    "#,
+                    params![slice_id, collection_id],
+                )?,
+                None => self.conn.execute("DELETE FROM slice_collections WHERE slice_id = ?1", params![slice_id])?,
+            };
         }
-        
-        // Perform the update
-        let rows_affected = self.conn.execute(
-            "UPDATE slices SET original_audio_file_name = ?1 WHERE id = ?2",
-            params![new_name, slice_id],
-        )?;
-        
-        if rows_affected == 0 {
-            return Err(anyhow::anyhow!("Failed to update slice name: no rows affected"));
+        Ok(moved as u32)
+    }
+
+    /// Link `from_id` to `to_id`, replacing any link already between the two in either direction.
+    pub fn link_slices(&self, from_id: i64, to_id: i64, relation: &str) -> Result<()> {
+        let relation = relation.trim();
+        if relation.is_empty() {
+            return Err(anyhow::anyhow!("Link relation must not be empty"));
         }
-        
+        if from_id == to_id {
+            return Err(anyhow::anyhow!("Cannot link a slice to itself"));
+        }
+        for slice_id in [from_id, to_id] {
+            if self.get_slice(slice_id)?.is_none() {
+                return Err(anyhow::anyhow!("No slice found with ID: {}", slice_id));
+            }
+        }
+
+        self.unlink_slices(from_id, to_id)?;
+        self.conn.execute(
+            "INSERT INTO slice_links (from_id, to_id, relation) VALUES (?1, ?2, ?3)",
+            params![from_id, to_id, relation],
+        )?;
         Ok(())
     }
 
-    pub fn update_slice(&self, slice_id: i64, slice: &Slice) -> Result<()> {
-        // Check if the new name already exists (excluding the current slice)
-        let existing_count: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM slices WHERE original_audio_file_name = ?1 AND id != ?2",
-            params![slice.original_audio_file_name, slice_id],
-            |row| row.get(0),
+    /// Remove the link between two slices, whichever way it points; returns whether there was one.
+    pub fn unlink_slices(&self, a: i64, b: i64) -> Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM slice_links WHERE (from_id = ?1 AND to_id = ?2) OR (from_id = ?2 AND to_id = ?1)",
+            params![a, b],
         )?;
-        
-        if existing_count > 0 {
-            return Err(anyhow::anyhow!("A slice with the filename '{}' already exists", slice.original_audio_file_name));
-        }
-        
-        // Check if the slice exists
-        let slice_exists: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM slices WHERE id = ?1",
-            params![slice_id],
-            |row| row.get(0),
-        )?;
-        
-        if slice_exists == 0 {
-            return Err(anyhow::anyhow!("Slice with ID {} not found", slice_id));
-        }
-        
-        // Perform the update
-        let rows_affected = self.conn.execute(
+        Ok(removed > 0)
+    }
+
+    /// Slices linked to `slice_id` in either direction, oldest recording first. Trashed slices
+    /// keep their links but are left out.
+    pub fn get_related_slices(&self, slice_id: i64) -> Result<Vec<RelatedSlice>> {
+        let mut stmt = self.conn.prepare(&format!(
             r#"
-            UPDATE slices SET
-                original_audio_file_name = ?1,
-                title = ?2,
-                transcribed = ?3,
-                audio_file_size = ?4,
-                audio_file_type = ?5,
-                estimated_time_to_transcribe = ?6,
-                audio_time_length_seconds = ?7,
-                transcription = ?8,
-                transcription_time_taken = ?9,
-                transcription_word_count = ?10,
-                transcription_model = ?11,
-                recording_date = ?12
-            WHERE id = ?13
+            SELECT {}, links.relation, links.outgoing FROM slices
+            JOIN (
+                SELECT to_id AS other_id, relation, 1 AS outgoing FROM slice_links WHERE from_id = ?1
+                UNION ALL
+                SELECT from_id AS other_id, relation, 0 AS outgoing FROM slice_links WHERE to_id = ?1
+            ) AS links ON slices.id = links.other_id
+            WHERE deleted_at IS NULL
+            ORDER BY recording_date IS NULL, recording_date, id
             "#,
-            params![
-                slice.original_audio_file_name,
-                slice.title,
-                slice.transcribed as i32,
-                slice.audio_file_size,
-                slice.audio_file_type,
-                slice.estimated_time_to_transcribe,
-                slice.audio_time_length_seconds,
-                slice.transcription,
-                slice.transcription_time_taken,
-                slice.transcription_word_count,
-                slice.transcription_model,
-                slice.recording_date,
-                slice_id,
-            ],
-        )?;
-        
-        if rows_affected == 0 {
-            return Err(anyhow::anyhow!("Failed to update slice: no rows affected"));
-        }
+            SLICE_COLUMNS
+        ))?;
+        let related = stmt
+            .query_map(params![slice_id], |row| {
+                Ok(RelatedSlice {
+                    slice: slice_from_row(row)?,
+                    relation: row.get("relation")?,
+                    outgoing: row.get::<_, i32>("outgoing")? != 0,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(related)
+    }
 
-        // Auto-apply labels when a slice's transcription is viewed/edited and saved.
-        if let Some(text) = slice.transcription.as_deref() {
-            self.apply_auto_labels(slice_id, text)?;
-        }
+    /// File name and content hash the sync export last wrote, by slice ID.
+    pub fn get_sync_exports(&self) -> Result<HashMap<i64, (String, String)>> {
+        let mut stmt = self.conn.prepare("SELECT slice_id, file_name, content_hash FROM sync_exports")?;
+        let exports = stmt
+            .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(exports)
+    }
 
+    pub fn record_sync_export(&self, slice_id: i64, file_name: &str, content_hash: &str, exported_at: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO sync_exports (slice_id, file_name, content_hash, exported_at) VALUES (?1, ?2, ?3, ?4)",
+            params![slice_id, file_name, content_hash, exported_at],
+        )?;
         Ok(())
     }
 
-    pub fn update_slice_audio_duration(&self, slice_id: i64, duration_seconds: f64) -> Result<()> {
-        let rows_affected = self.conn.execute(
-            "UPDATE slices SET audio_time_length_seconds = ?1 WHERE id = ?2",
-            params![duration_seconds, slice_id],
-        )?;
+    pub fn remove_sync_export(&self, slice_id: i64) -> Result<()> {
+        self.conn.execute("DELETE FROM sync_exports WHERE slice_id = ?1", params![slice_id])?;
+        Ok(())
+    }
 
-        if rows_affected == 0 {
-            return Err(anyhow::anyhow!("Slice with ID {} not found", slice_id));
+    /// Queue a NotebookLM batch: one pending item per entry of `items`, in order.
+    pub fn create_nlm_batch(&self, batch_id: &str, notebook_id: &str, items: &[(i64, NlmSourceKind)], queued: bool) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let tx = self.conn.unchecked_transaction()?;
+        for (position, (slice_id, kind)) in items.iter().enumerate() {
+            self.conn.execute(
+                r#"
+                INSERT INTO nlm_batch_items (batch_id, position, slice_id, notebook_id, kind, updated_at, queued)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+                params![batch_id, position as i64, slice_id, notebook_id, kind.as_str(), now, queued],
+            )?;
         }
-
+        tx.commit()?;
         Ok(())
     }
 
-    /// Clear audio durations that are obviously corrupted (> 24 hours).
-    /// These were caused by a unit conversion bug in get_audio_duration.
-    pub fn clear_corrupt_audio_durations(&self) -> Result<u32> {
-        let rows_affected = self.conn.execute(
-            "UPDATE slices SET audio_time_length_seconds = NULL WHERE audio_time_length_seconds > 86400",
-            [],
+    /// IDs of queued batches with items waiting to be tried or retried by now, oldest first.
+    /// Batches the user started are left to them to resume.
+    pub fn pending_nlm_batches(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT batch_id FROM nlm_batch_items WHERE status = 'pending' AND queued = 1 AND retry_at <= ?1
+            GROUP BY batch_id
+            ORDER BY MIN(updated_at), batch_id
+            "#,
         )?;
-        Ok(rows_affected as u32)
+        let batches = stmt
+            .query_map(params![chrono::Utc::now().timestamp()], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(batches)
     }
 
-    pub fn get_slices_without_duration(&self) -> Result<Vec<Slice>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, original_audio_file_name, title, transcribed, audio_file_size, audio_file_type,
-                    estimated_time_to_transcribe, audio_time_length_seconds, transcription, transcription_time_taken,
-                    transcription_word_count, transcription_model, recording_date
-             FROM slices
-             WHERE audio_time_length_seconds IS NULL
-             ORDER BY id"
+    /// When the next queued upload waiting on a retry is due, if any is.
+    pub fn next_nlm_retry_at(&self) -> Result<Option<i64>> {
+        let next = self.conn.query_row(
+            "SELECT MIN(retry_at) FROM nlm_batch_items WHERE status = 'pending' AND queued = 1 AND retry_at > ?1",
+            params![chrono::Utc::now().timestamp()],
+            |row| row.get(0),
         )?;
+        Ok(next)
+    }
 
-        let slice_iter = stmt.query_map([], |row| {
-            Ok(Slice {
-                id: Some(row.get("id")?),
-                original_audio_file_name: row.get("original_audio_file_name")?,
-                title: row.get("title")?,
-                transcribed: row.get::<_, i32>("transcribed")? != 0,
-                audio_file_size: row.get("audio_file_size")?,
-                audio_file_type: row.get("audio_file_type")?,
-                estimated_time_to_transcribe: row.get("estimated_time_to_transcribe")?,
-                audio_time_length_seconds: row.get("audio_time_length_seconds")?,
-                transcription: row.get("transcription")?,
-                transcription_time_taken: row.get("transcription_time_taken")?,
-                transcription_word_count: row.get("transcription_word_count")?,
-                transcription_model: row.get("transcription_model")?,
-                recording_date: row.get("recording_date")?,
-            })
-        })?;
+    /// Put a failed queued upload back in line to be tried again at `retry_at`.
+    pub fn schedule_nlm_batch_retry(&self, batch_id: &str, position: u32, error: &str, retry_at: i64) -> Result<()> {
+        self.conn.execute(
+            r#"
+            UPDATE nlm_batch_items
+            SET status = 'pending', error = ?1, attempts = attempts + 1, retry_at = ?2, updated_at = ?3
+            WHERE batch_id = ?4 AND position = ?5
+            "#,
+            params![error, retry_at, chrono::Utc::now().timestamp(), batch_id, position],
+        )?;
+        Ok(())
+    }
 
-        let mut slices = Vec::new();
-        for slice in slice_iter {
-            slices.push(slice?);
-        }
-        Ok(slices)
+    pub fn record_nlm_source(&self, source: &NlmSource) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO nlm_sources (notebook_id, source_id, slice_id, kind, title, added_at, content_hash)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            params![source.notebook_id, source.source_id, source.slice_id, source.kind.as_str(), source.title, source.added_at, source.content_hash],
+        )?;
+        Ok(())
     }
 
-    /// Backfill recording_date for slices that have NULL recording_date
-    /// by looking up the date from ZCLOUDRECORDING table
-    pub fn backfill_recording_dates(&self) -> Result<u32> {
-        // Get all slices with NULL recording_date
+    /// The newest source of `notebook_id` holding the same content: one with `content_hash`,
+    /// or for sources recorded before hashing, one with the same kind, title and slice.
+    pub fn find_nlm_source(&self, notebook_id: &str, kind: NlmSourceKind, content_hash: &str, title: &str, slice_id: Option<i64>) -> Result<Option<NlmSource>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, original_audio_file_name FROM slices WHERE recording_date IS NULL"
+            r#"
+            SELECT notebook_id, source_id, slice_id, kind, title, added_at, content_hash FROM nlm_sources
+            WHERE notebook_id = ?1 AND kind = ?2
+              AND (content_hash = ?3 OR (content_hash IS NULL AND title = ?4 AND slice_id IS ?5))
+            ORDER BY added_at DESC
+            LIMIT 1
+            "#,
         )?;
+        let source = stmt
+            .query_map(params![notebook_id, kind.as_str(), content_hash, title, slice_id], nlm_source_from_row)?
+            .next()
+            .transpose()?;
+        Ok(source)
+    }
 
-        let slices_to_update: Vec<(i64, String)> = stmt.query_map([], |row| {
-            Ok((row.get(0)?, row.get(1)?))
-        })?.filter_map(|r| r.ok()).collect();
-
-        let mut updated_count = 0u32;
-
-        for (slice_id, filename) in slices_to_update {
-            if let Ok(Some(recording_date)) = self.get_recording_date_for_filename(&filename) {
-                let rows_affected = self.conn.execute(
-                    "UPDATE slices SET recording_date = ?1 WHERE id = ?2",
-                    params![recording_date, slice_id],
-                )?;
-                if rows_affected > 0 {
-                    updated_count += 1;
-                }
-            }
-        }
+    /// Forget a source removed from its notebook. Returns whether it was known.
+    pub fn remove_nlm_source(&self, notebook_id: &str, source_id: &str) -> Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM nlm_sources WHERE notebook_id = ?1 AND source_id = ?2",
+            params![notebook_id, source_id],
+        )?;
+        self.conn.execute(
+            "DELETE FROM nlm_source_parts WHERE notebook_id = ?1 AND source_id = ?2",
+            params![notebook_id, source_id],
+        )?;
+        Ok(removed > 0)
+    }
 
-        Ok(updated_count)
+    /// Note which part of a split transcript a source holds.
+    pub fn record_nlm_source_part(&self, part: &NlmSourcePart) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO nlm_source_parts (notebook_id, source_id, slice_id, part, part_count, start_char, end_char)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            params![part.notebook_id, part.source_id, part.slice_id, part.part, part.part_count, part.start_char as i64, part.end_char as i64],
+        )?;
+        Ok(())
     }
 
-    fn get_count_by_year_from_apple_db(&self) -> Result<Vec<YearCount>> {
-        // Try to get year data from the ZCLOUDRECORDING table if it exists
+    /// Parts a slice's transcript was split into, by notebook and then part number.
+    pub fn get_slice_nlm_source_parts(&self, slice_id: i64) -> Result<Vec<NlmSourcePart>> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT
-                CAST(strftime('%Y', datetime(ZDATE + 978307200, 'unixepoch')) AS INTEGER) as year,
-                COUNT(*) as count
-            FROM ZCLOUDRECORDING
-            WHERE ZDATE IS NOT NULL
-            GROUP BY year
-            ORDER BY year
-            "#
+            SELECT notebook_id, source_id, slice_id, part, part_count, start_char, end_char
+            FROM nlm_source_parts
+            WHERE slice_id = ?1
+            ORDER BY notebook_id, part
+            "#,
         )?;
+        let parts = stmt
+            .query_map(params![slice_id], |row| {
+                Ok(NlmSourcePart {
+                    notebook_id: row.get(0)?,
+                    source_id: row.get(1)?,
+                    slice_id: row.get(2)?,
+                    part: row.get(3)?,
+                    part_count: row.get(4)?,
+                    start_char: row.get::<_, i64>(5)? as usize,
+                    end_char: row.get::<_, i64>(6)? as usize,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(parts)
+    }
 
-        let rows = stmt.query_map([], |row| {
-            Ok(YearCount {
-                year: row.get(0)?,
-                count: row.get(1)?,
-            })
-        })?;
-
-        let mut count_by_year = Vec::new();
-        for row in rows {
-            count_by_year.push(row?);
-        }
-
-        Ok(count_by_year)
+    /// Drop everything recorded about a deleted notebook: its sources and label mappings.
+    pub fn forget_nlm_notebook(&self, notebook_id: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM nlm_sources WHERE notebook_id = ?1", params![notebook_id])?;
+        self.conn.execute("DELETE FROM nlm_source_parts WHERE notebook_id = ?1", params![notebook_id])?;
+        self.conn.execute("DELETE FROM label_notebooks WHERE notebook_id = ?1", params![notebook_id])?;
+        Ok(())
     }
 
-    fn get_count_by_audio_length(&self) -> Result<Vec<AudioLengthBucket>> {
-        // Group audio files by duration buckets using the audio_time_length_seconds field
+    /// Sources made from a slice that are still in their notebooks, newest first.
+    pub fn get_slice_nlm_sources(&self, slice_id: i64) -> Result<Vec<NlmSource>> {
         let mut stmt = self.conn.prepare(
             r#"
-            SELECT
-                CASE
-                    WHEN audio_time_length_seconds IS NULL THEN 'Unknown'
-                    WHEN audio_time_length_seconds < 30 THEN '< 30s'
-                    WHEN audio_time_length_seconds < 60 THEN '30s-1m'
-                    WHEN audio_time_length_seconds < 300 THEN '1-5m'
-                    WHEN audio_time_length_seconds < 900 THEN '5-15m'
-                    WHEN audio_time_length_seconds < 1800 THEN '15-30m'
-                    WHEN audio_time_length_seconds < 3600 THEN '30m-1h'
-                    ELSE '1h+'
-                END as bucket,
-                COUNT(*) as count
-            FROM slices
-            GROUP BY bucket
-            ORDER BY
-                CASE bucket
-                    WHEN '< 30s' THEN 1
-                    WHEN '30s-1m' THEN 2
-                    WHEN '1-5m' THEN 3
-                    WHEN '5-15m' THEN 4
-                    WHEN '15-30m' THEN 5
-                    WHEN '30m-1h' THEN 6
-                    WHEN '1h+' THEN 7
-                    ELSE 8
-                END
-            "#
+            SELECT notebook_id, source_id, slice_id, kind, title, added_at, content_hash FROM nlm_sources
+            WHERE slice_id = ?1
+            ORDER BY added_at DESC, source_id
+            "#,
         )?;
+        let sources = stmt
+            .query_map(params![slice_id], nlm_source_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(sources)
+    }
 
-        let rows = stmt.query_map([], |row| {
-            Ok(AudioLengthBucket {
-                label: row.get(0)?,
-                count: row.get(1)?,
-            })
-        })?;
-
-        let mut buckets = Vec::new();
-        for row in rows {
-            buckets.push(row?);
-        }
+    /// Note that a slice was uploaded to a notebook, and with which NLM account; returns the
+    /// new record's ID.
+    pub fn record_nlm_upload(
+        &self,
+        slice_id: i64,
+        notebook_id: &str,
+        kind: NlmSourceKind,
+        source_id: Option<&str>,
+        account: Option<&str>,
+    ) -> Result<i64> {
+        self.conn.execute(
+            r#"
+            INSERT INTO nlm_uploads (slice_id, notebook_id, source_type, source_id, uploaded_at, account)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![slice_id, notebook_id, kind.as_str(), source_id, chrono::Utc::now().timestamp(), account],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
 
-        Ok(buckets)
+    /// Everything ever uploaded from a slice, newest first.
+    pub fn get_slice_nlm_uploads(&self, slice_id: i64) -> Result<Vec<NlmUpload>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, slice_id, notebook_id, source_type, source_id, uploaded_at, account FROM nlm_uploads
+            WHERE slice_id = ?1
+            ORDER BY uploaded_at DESC, id DESC
+            "#,
+        )?;
+        let uploads = stmt
+            .query_map(params![slice_id], |row| {
+                let kind: String = row.get(3)?;
+                Ok(NlmUpload {
+                    id: row.get(0)?,
+                    slice_id: row.get(1)?,
+                    notebook_id: row.get(2)?,
+                    source_type: NlmSourceKind::parse(&kind).unwrap_or(NlmSourceKind::Text),
+                    source_id: row.get(4)?,
+                    uploaded_at: row.get(5)?,
+                    account: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(uploads)
     }
 
-    pub fn update_recording_title_by_slice(&self, slice_id: i64, new_title: &str) -> Result<()> {
-        // Update the title directly in the slices table
-        let rows_affected = self.conn.execute(
-            "UPDATE slices SET title = ?1 WHERE id = ?2",
-            params![new_title, slice_id],
+    /// Record a run of nlm, dropping the oldest records beyond `keep`.
+    pub fn record_nlm_invocation(&self, invocation: &NlmInvocation, keep: u32) -> Result<i64> {
+        self.conn.execute(
+            r#"
+            INSERT INTO nlm_invocations (command, args, started_at, duration_ms, success, error_kind, output, account)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            params![
+                invocation.command,
+                pii::redact_if_enabled(&serde_json::to_string(&invocation.args)?),
+                invocation.started_at,
+                invocation.duration_ms,
+                invocation.success,
+                invocation.error_kind,
+                stored_text(invocation.output.as_deref()),
+                invocation.account,
+            ],
         )?;
+        let id = self.conn.last_insert_rowid();
+        self.conn.execute("DELETE FROM nlm_invocations WHERE id <= ?1", params![id - i64::from(keep)])?;
+        Ok(id)
+    }
 
-        if rows_affected == 0 {
-            return Err(anyhow::anyhow!(
-                "No slice found with ID: {}",
-                slice_id
-            ));
-        }
+    /// Recorded nlm runs, newest first; with `failed_only` just the failures, and with
+    /// `command` just runs of that subcommand.
+    pub fn list_nlm_invocations(&self, failed_only: bool, command: Option<&str>, limit: u32, offset: u32) -> Result<Vec<NlmInvocation>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, command, args, started_at, duration_ms, success, error_kind, output, account
+            FROM nlm_invocations
+            WHERE (?1 = 0 OR success = 0) AND (?2 IS NULL OR command = ?2)
+            ORDER BY id DESC
+            LIMIT ?3 OFFSET ?4
+            "#,
+        )?;
+        let invocations = stmt
+            .query_map(params![failed_only, command, limit, offset], |row| {
+                let args: String = row.get(2)?;
+                Ok(NlmInvocation {
+                    id: row.get(0)?,
+                    command: row.get(1)?,
+                    args: serde_json::from_str(&args).unwrap_or_default(),
+                    started_at: row.get(3)?,
+                    duration_ms: row.get(4)?,
+                    success: row.get(5)?,
+                    error_kind: row.get(6)?,
+                    output: row.get(7)?,
+                    account: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(invocations)
+    }
 
+    /// Record where a slice's upload to Drive stands, replacing what was there.
+    pub fn save_drive_file(&self, file: &DriveFile) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT OR REPLACE INTO drive_files (slice_id, kind, file_id, status, content_hash, session_uri, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            params![file.slice_id, file.kind.as_str(), file.file_id, file.status, file.content_hash, file.session_uri, file.updated_at],
+        )?;
         Ok(())
     }
 
-    pub fn auto_populate_titles(&self) -> Result<u32> {
-        use std::collections::HashMap;
-        use regex::Regex;
-
-        // Get all slices with their current titles
-        let slices = self.list_all_slices()?;
-
-        // Track titles to handle duplicates
-        let mut title_counts: HashMap<String, u32> = HashMap::new();
-        let mut updated_count = 0u32;
-
-        // Regex to extract dates like "20251117" from filenames
-        let date_pattern = Regex::new(r"(\d{8})").unwrap();
+    pub fn get_drive_file(&self, slice_id: i64, kind: NlmSourceKind) -> Result<Option<DriveFile>> {
+        Ok(self.query_drive_files("WHERE slice_id = ?1 AND kind = ?2", params![slice_id, kind.as_str()])?.pop())
+    }
 
-        for slice in slices {
-            // Skip if title is already set
-            if slice.title.is_some() && !slice.title.as_ref().unwrap().trim().is_empty() {
-                // Count existing titles for deduplication
-                let title = slice.title.as_ref().unwrap().clone();
-                *title_counts.entry(title).or_insert(0) += 1;
-                continue;
-            }
+    /// A slice's uploads to Drive: its transcript and/or audio.
+    pub fn get_slice_drive_files(&self, slice_id: i64) -> Result<Vec<DriveFile>> {
+        self.query_drive_files("WHERE slice_id = ?1", params![slice_id])
+    }
 
-            // Extract title from filename
-            let filename = &slice.original_audio_file_name;
+    fn query_drive_files(&self, filter: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<DriveFile>> {
+        let sql = format!(
+            "SELECT slice_id, kind, file_id, status, content_hash, session_uri, updated_at FROM drive_files {} ORDER BY kind",
+            filter
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let files = stmt
+            .query_map(params, |row| {
+                let kind: String = row.get(1)?;
+                Ok(DriveFile {
+                    slice_id: row.get(0)?,
+                    kind: NlmSourceKind::parse(&kind).unwrap_or(NlmSourceKind::Text),
+                    file_id: row.get(2)?,
+                    status: row.get(3)?,
+                    content_hash: row.get(4)?,
+                    session_uri: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(files)
+    }
 
-            // Try to extract date from filename
-            let mut title = if let Some(captures) = date_pattern.captures(filename) {
-                if let Some(date_str) = captures.get(1) {
-                    let date = date_str.as_str();
-                    if date.len() == 8 {
-                        // Format as YYYY-MM-DD
-                        format!("{}-{}-{}", &date[0..4], &date[4..6], &date[6..8])
-                    } else {
-                        // Fallback to filename without extension
-                        filename.trim_end_matches(".m4a")
-                            .trim_end_matches(".wav")
-                            .trim_end_matches(".mp3")
-                            .to_string()
-                    }
-                } else {
-                    filename.trim_end_matches(".m4a")
-                        .trim_end_matches(".wav")
-                        .trim_end_matches(".mp3")
-                        .to_string()
+    /// Map a label to a NotebookLM notebook, or with `None` remove its mapping.
+    pub fn set_label_notebook(&self, label_id: i64, notebook_id: Option<&str>) -> Result<()> {
+        match notebook_id.map(str::trim).filter(|id| !id.is_empty()) {
+            Some(notebook_id) => {
+                let exists: i64 = self.conn.query_row(
+                    "SELECT COUNT(*) FROM labels WHERE id = ?1",
+                    params![label_id],
+                    |row| row.get(0),
+                )?;
+                if exists == 0 {
+                    return Err(anyhow::anyhow!("No label found with ID: {}", label_id));
                 }
-            } else {
-                // No date found, use filename without extension
-                filename.trim_end_matches(".m4a")
-                    .trim_end_matches(".wav")
-                    .trim_end_matches(".mp3")
-                    .to_string()
-            };
-
-            // Handle duplicates by appending (2), (3), etc.
-            let base_title = title.clone();
-            let mut counter = 2;
-            while title_counts.contains_key(&title) {
-                title = format!("{} ({})", base_title, counter);
-                counter += 1;
+                self.conn.execute(
+                    "INSERT OR REPLACE INTO label_notebooks (label_id, notebook_id) VALUES (?1, ?2)",
+                    params![label_id, notebook_id],
+                )?;
             }
+            None => {
+                self.conn.execute("DELETE FROM label_notebooks WHERE label_id = ?1", params![label_id])?;
+            }
+        }
+        Ok(())
+    }
 
-            // Mark this title as used
-            *title_counts.entry(title.clone()).or_insert(0) += 1;
+    pub fn list_label_notebooks(&self) -> Result<Vec<LabelNotebook>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT ln.label_id, l.name, ln.notebook_id
+            FROM label_notebooks ln JOIN labels l ON l.id = ln.label_id
+            ORDER BY l.name COLLATE NOCASE
+            "#,
+        )?;
+        let mappings = stmt
+            .query_map([], |row| {
+                Ok(LabelNotebook { label_id: row.get(0)?, label_name: row.get(1)?, notebook_id: row.get(2)? })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(mappings)
+    }
 
-            // Update the slice title directly if we have a slice ID
-            if let Some(slice_id) = slice.id {
-                let rows_affected = self.conn.execute(
-                    "UPDATE slices SET title = ?1 WHERE id = ?2",
-                    params![&title, slice_id],
-                )?;
+    /// Notebooks a slice's labels are mapped to, each once.
+    pub fn get_slice_notebooks(&self, slice_id: i64) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT DISTINCT ln.notebook_id
+            FROM slice_labels sl JOIN label_notebooks ln ON ln.label_id = sl.label_id
+            WHERE sl.slice_id = ?1
+            ORDER BY ln.notebook_id
+            "#,
+        )?;
+        let notebooks = stmt.query_map(params![slice_id], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+        Ok(notebooks)
+    }
 
-                if rows_affected > 0 {
-                    updated_count += 1;
-                } else {
-                    tracing::warn!(
-                        "Failed to auto-populate title for slice {}: no rows affected",
-                        slice_id
-                    );
+    /// Set a label's prompt, or with `None` (or a blank one) remove it.
+    pub fn set_label_prompt(&self, label_id: i64, prompt: Option<&str>) -> Result<()> {
+        match prompt.map(str::trim).filter(|p| !p.is_empty()) {
+            Some(prompt) => {
+                let exists: i64 = self.conn.query_row(
+                    "SELECT COUNT(*) FROM labels WHERE id = ?1",
+                    params![label_id],
+                    |row| row.get(0),
+                )?;
+                if exists == 0 {
+                    return Err(anyhow::anyhow!("No label found with ID: {}", label_id));
                 }
+                self.conn.execute(
+                    "INSERT OR REPLACE INTO label_prompts (label_id, prompt, updated_at) VALUES (?1, ?2, ?3)",
+                    params![label_id, prompt, chrono::Utc::now().timestamp()],
+                )?;
+            }
+            None => {
+                self.conn.execute("DELETE FROM label_prompts WHERE label_id = ?1", params![label_id])?;
             }
         }
-
-        Ok(updated_count)
+        Ok(())
     }
 
-    // ==================== Label CRUD operations ====================
-
-    pub fn list_labels(&self) -> Result<Vec<Label>> {
+    pub fn list_label_prompts(&self) -> Result<Vec<LabelPrompt>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, color, keywords FROM labels ORDER BY id"
+            r#"
+            SELECT lp.label_id, l.name, lp.prompt
+            FROM label_prompts lp JOIN labels l ON l.id = lp.label_id
+            ORDER BY l.name COLLATE NOCASE
+            "#,
         )?;
+        let prompts = stmt
+            .query_map([], |row| Ok(LabelPrompt { label_id: row.get(0)?, label_name: row.get(1)?, prompt: row.get(2)? }))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(prompts)
+    }
 
-        let label_iter = stmt.query_map([], |row| {
-            Ok(Label {
-                id: Some(row.get("id")?),
-                name: row.get("name")?,
-                color: row.get("color")?,
-                keywords: row.get("keywords")?,
-            })
-        })?;
-
-        let mut labels = Vec::new();
-        for label in label_iter {
-            labels.push(label?);
-        }
-        Ok(labels)
+    /// Prompts of the labels a slice has.
+    pub fn get_slice_label_prompts(&self, slice_id: i64) -> Result<Vec<LabelPrompt>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT lp.label_id, l.name, lp.prompt
+            FROM slice_labels sl
+            JOIN label_prompts lp ON lp.label_id = sl.label_id
+            JOIN labels l ON l.id = sl.label_id
+            WHERE sl.slice_id = ?1
+            ORDER BY l.name COLLATE NOCASE
+            "#,
+        )?;
+        let prompts = stmt
+            .query_map(params![slice_id], |row| {
+                Ok(LabelPrompt { label_id: row.get(0)?, label_name: row.get(1)?, prompt: row.get(2)? })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(prompts)
     }
 
-    pub fn create_label(&self, label: &Label) -> Result<i64> {
+    /// Store what a label's prompt made of a slice, replacing the document from an earlier
+    /// run of that label's prompt. Returns its ID.
+    pub fn save_derived_document(&self, document: &DerivedDocument) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO labels (name, color, keywords) VALUES (?1, ?2, ?3)",
-            params![&label.name, &label.color, &label.keywords],
+            r#"
+            INSERT INTO derived_documents (slice_id, label_id, label_name, prompt, content, model, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(slice_id, label_id) DO UPDATE SET
+                label_name = excluded.label_name,
+                prompt = excluded.prompt,
+                content = excluded.content,
+                model = excluded.model,
+                created_at = excluded.created_at
+            "#,
+            params![
+                document.slice_id,
+                document.label_id,
+                document.label_name,
+                document.prompt,
+                stored_text(Some(&document.content)),
+                document.model,
+                document.created_at
+            ],
         )?;
-        Ok(self.conn.last_insert_rowid())
+        let id = self.conn.query_row(
+            "SELECT id FROM derived_documents WHERE slice_id = ?1 AND label_id = ?2",
+            params![document.slice_id, document.label_id],
+            |row| row.get(0),
+        )?;
+        Ok(id)
     }
 
-    pub fn update_label(&self, id: i64, label: &Label) -> Result<()> {
-        let rows_affected = self.conn.execute(
-            "UPDATE labels SET name = ?1, color = ?2, keywords = ?3 WHERE id = ?4",
-            params![&label.name, &label.color, &label.keywords, id],
+    /// The documents derived from a slice, newest first.
+    pub fn list_derived_documents(&self, slice_id: i64) -> Result<Vec<DerivedDocument>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, slice_id, label_id, label_name, prompt, content, model, created_at
+            FROM derived_documents WHERE slice_id = ?1
+            ORDER BY created_at DESC, id DESC
+            "#,
         )?;
+        let documents = stmt
+            .query_map(params![slice_id], |row| {
+                Ok(DerivedDocument {
+                    id: row.get(0)?,
+                    slice_id: row.get(1)?,
+                    label_id: row.get(2)?,
+                    label_name: row.get(3)?,
+                    prompt: row.get(4)?,
+                    content: row.get(5)?,
+                    model: row.get(6)?,
+                    created_at: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(documents)
+    }
 
+    pub fn delete_derived_document(&self, id: i64) -> Result<()> {
+        let rows_affected = self.conn.execute("DELETE FROM derived_documents WHERE id = ?1", params![id])?;
         if rows_affected == 0 {
-            return Err(anyhow::anyhow!("No label found with ID: {}", id));
+            return Err(anyhow::anyhow!("No derived document found with ID: {}", id));
         }
         Ok(())
     }
 
-    pub fn delete_label(&self, id: i64) -> Result<()> {
-        // Remove any slice associations for this label first so no orphan rows remain.
-        self.conn.execute(
-            "DELETE FROM slice_labels WHERE label_id = ?1",
-            params![id],
+    /// Every item of a NotebookLM batch, in upload order.
+    pub fn get_nlm_batch_items(&self, batch_id: &str) -> Result<Vec<NlmBatchItem>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT batch_id, position, slice_id, notebook_id, kind, status, error, queued, attempts
+            FROM nlm_batch_items WHERE batch_id = ?1
+            ORDER BY position
+            "#,
         )?;
+        let items = stmt
+            .query_map(params![batch_id], |row| {
+                let kind: String = row.get(4)?;
+                Ok(NlmBatchItem {
+                    batch_id: row.get(0)?,
+                    position: row.get(1)?,
+                    slice_id: row.get(2)?,
+                    notebook_id: row.get(3)?,
+                    kind: NlmSourceKind::parse(&kind).unwrap_or(NlmSourceKind::Text),
+                    status: row.get(5)?,
+                    error: row.get(6)?,
+                    queued: row.get(7)?,
+                    attempts: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
 
-        let rows_affected = self.conn.execute(
-            "DELETE FROM labels WHERE id = ?1",
-            params![id],
+    pub fn set_nlm_batch_item_status(&self, batch_id: &str, position: u32, status: &str, error: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE nlm_batch_items SET status = ?1, error = ?2, updated_at = ?3 WHERE batch_id = ?4 AND position = ?5",
+            params![status, error, chrono::Utc::now().timestamp(), batch_id, position],
         )?;
-
-        if rows_affected == 0 {
-            return Err(anyhow::anyhow!("No label found with ID: {}", id));
-        }
         Ok(())
     }
 
-    /// Auto-apply labels to a slice by matching each label's keywords against the given text.
-    ///
-    /// Matching semantics: a label's `keywords` string is split on commas, each phrase is
-    /// trimmed, and empty phrases are ignored. Each phrase is matched case-insensitively as a
-    /// substring of `text`. If ANY phrase of a label matches, that label is applied to the slice.
-    ///
-    /// Reconciliation: this only ever ADDS associations (INSERT OR IGNORE). It never removes
-    /// labels, so re-transcribing or re-saving a slice reconciles by adding any newly matching
-    /// labels while preserving previously applied ones.
-    pub fn apply_auto_labels(&self, slice_id: i64, text: &str) -> Result<()> {
-        let text_lower = text.to_lowercase();
-        let labels = self.list_labels()?;
-
-        for label in labels {
-            let label_id = match label.id {
-                Some(id) => id,
-                None => continue,
-            };
-
-            let matched = label
-                .keywords
-                .split(',')
-                .map(|phrase| phrase.trim())
-                .filter(|phrase| !phrase.is_empty())
-                .any(|phrase| text_lower.contains(&phrase.to_lowercase()));
+    /// A slice's metadata, sorted by key.
+    pub fn get_slice_metadata(&self, slice_id: i64) -> Result<BTreeMap<String, String>> {
+        let mut stmt = self.conn.prepare("SELECT key, value FROM slice_metadata WHERE slice_id = ?1")?;
+        let entries = stmt
+            .query_map(params![slice_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        Ok(entries)
+    }
 
-            if matched {
-                self.conn.execute(
-                    "INSERT OR IGNORE INTO slice_labels (slice_id, label_id) VALUES (?1, ?2)",
-                    params![slice_id, label_id],
-                )?;
-            }
+    /// Set one metadata entry on a slice, replacing any value already under `key`.
+    pub fn set_slice_metadata(&self, slice_id: i64, key: &str, value: &str) -> Result<()> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(anyhow::anyhow!("Metadata key must not be empty"));
         }
-
+        if self.get_slice(slice_id)?.is_none() {
+            return Err(anyhow::anyhow!("No slice found with ID: {}", slice_id));
+        }
+        self.conn.execute(
+            "INSERT OR REPLACE INTO slice_metadata (slice_id, key, value) VALUES (?1, ?2, ?3)",
+            params![slice_id, key, value],
+        )?;
         Ok(())
     }
 
-    /// Fetch all slice -> labels associations as a map keyed by slice_id.
-    /// Returned in a single query so the Slices screen can render label badges with one round trip.
-    pub fn get_labels_for_all_slices(&self) -> Result<HashMap<i64, Vec<Label>>> {
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT sl.slice_id, l.id, l.name, l.color, l.keywords
-            FROM slice_labels sl
-            JOIN labels l ON l.id = sl.label_id
-            ORDER BY sl.slice_id, l.id
-            "#,
+    /// Remove one metadata entry from a slice; returns whether it was there.
+    pub fn delete_slice_metadata(&self, slice_id: i64, key: &str) -> Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM slice_metadata WHERE slice_id = ?1 AND key = ?2",
+            params![slice_id, key.trim()],
         )?;
+        Ok(removed > 0)
+    }
+}
 
-        let rows = stmt.query_map([], |row| {
-            Ok((
-                row.get::<_, i64>(0)?,
-                Label {
-                    id: Some(row.get(1)?),
-                    name: row.get(2)?,
-                    color: row.get(3)?,
-                    keywords: row.get(4)?,
-                },
-            ))
-        })?;
-
-        let mut map: HashMap<i64, Vec<Label>> = HashMap::new();
-        for row in rows {
-            let (slice_id, label) = row?;
-            map.entry(slice_id).or_default().push(label);
-        }
-        Ok(map)
+/// An untranscribed slice named `name`, for tests to build on with `..create_test_slice(name)`.
+#[cfg(test)]
+pub(crate) fn create_test_slice(name: &str) -> Slice {
+    Slice {
+        id: None,
+        original_audio_file_name: name.to_string(),
+        title: None,
+        transcribed: false,
+        audio_file_size: 1024,
+        audio_file_type: "m4a".to_string(),
+        estimated_time_to_transcribe: 30,
+        audio_time_length_seconds: None,
+        transcription: None,
+        transcription_time_taken: None,
+        transcription_word_count: None,
+        transcription_model: None,
+        recording_date: None,
+        source: None,
+        starred: false,
+        was_edited: false,
+        content_hash: None,
+        source_relative_path: None,
+        notes: None,
+        pinned: false,
+        summary: None,
     }
 }
 
@@ -1220,22 +4393,15 @@ mod tests {
         (db, temp_dir)
     }
 
-    fn create_test_slice(name: &str) -> Slice {
-        Slice {
-            id: None,
-            original_audio_file_name: name.to_string(),
-            title: None,
-            transcribed: false,
-            audio_file_size: 1024,
-            audio_file_type: "m4a".to_string(),
-            estimated_time_to_transcribe: 30,
-            audio_time_length_seconds: None,
-            transcription: None,
-            transcription_time_taken: None,
-            transcription_word_count: None,
-            transcription_model: None,
-            recording_date: None,
-        }
+    #[test]
+    fn test_connections_use_wal_with_busy_timeout() {
+        let (db, _temp_dir) = create_test_database();
+        let journal_mode: String = db.conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(journal_mode, "wal");
+        let busy_timeout: i64 = db.conn.query_row("PRAGMA busy_timeout", [], |row| row.get(0)).unwrap();
+        assert_eq!(busy_timeout, BUSY_TIMEOUT.as_millis() as i64);
+        let synchronous: i64 = db.conn.query_row("PRAGMA synchronous", [], |row| row.get(0)).unwrap();
+        assert_eq!(synchronous, 1); // NORMAL
     }
 
     #[test]
@@ -1297,6 +4463,40 @@ mod tests {
         assert_eq!(updated_slice.original_audio_file_name, new_name);
     }
 
+    #[test]
+    fn test_slice_history_records_and_reverts() {
+        let (db, _temp_dir) = create_test_database();
+        let slice_id = db.insert_slice(&create_test_slice("memo.m4a")).unwrap();
+
+        // The first transcription isn't an edit; re-transcribing and renaming are
+        db.update_slice_transcription(slice_id, "first draft", 1, 2, "base.en").unwrap();
+        db.update_slice_transcription(slice_id, "second draft here", 1, 3, "large-v3").unwrap();
+        db.update_slice_name(slice_id, "renamed.m4a").unwrap();
+        db.update_recording_title_by_slice(slice_id, "Groceries").unwrap();
+        db.update_recording_title_by_slice(slice_id, "Groceries").unwrap();
+
+        let history = db.get_slice_history(slice_id).unwrap();
+        let fields: Vec<_> = history.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["title", "name", "transcription"], "newest first, unchanged values skipped");
+        assert_eq!(history[2].old_value.as_deref(), Some("first draft"));
+
+        db.revert_slice_change(history[2].id).unwrap();
+        db.revert_slice_change(history[1].id).unwrap();
+        db.revert_slice_change(history[0].id).unwrap();
+        let slice = db.get_slice(slice_id).unwrap().unwrap();
+        assert_eq!(slice.transcription.as_deref(), Some("first draft"));
+        assert_eq!(slice.transcription_word_count, Some(2));
+        assert_eq!(slice.original_audio_file_name, "memo.m4a");
+        assert_eq!(slice.title, None);
+
+        // Reverts are history entries of their own
+        assert_eq!(db.get_slice_history(slice_id).unwrap().len(), 6);
+        assert!(db.revert_slice_change(9999).is_err());
+
+        db.delete_slice(slice_id).unwrap();
+        assert!(db.get_slice_history(slice_id).unwrap().is_empty());
+    }
+
     #[test]
     fn test_update_slice_name_duplicate_filename() {
         let (db, _temp_dir) = create_test_database();
@@ -1474,4 +4674,921 @@ mod tests {
         assert_eq!(updated.transcription_time_taken, Some(60));
         assert_eq!(updated.original_audio_file_name, "test_slice.m4a"); // Should remain unchanged
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_apple_folders_become_labels() {
+        let (db, temp_dir) = create_test_database();
+
+        // Mock Apple database with one foldered and one unfoldered recording
+        let apple_db_path = temp_dir.path().join("CloudRecordings.db");
+        let apple = Connection::open(&apple_db_path).unwrap();
+        apple.execute_batch(
+            r#"
+            CREATE TABLE ZFOLDER (Z_PK INTEGER PRIMARY KEY, ZENCRYPTEDNAME TEXT);
+            CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT, ZFOLDER INTEGER);
+            INSERT INTO ZFOLDER (Z_PK, ZENCRYPTEDNAME) VALUES (1, 'Work');
+            INSERT INTO ZCLOUDRECORDING (ZPATH, ZFOLDER) VALUES ('work.m4a', 1);
+            INSERT INTO ZCLOUDRECORDING (ZPATH, ZFOLDER) VALUES ('loose.m4a', NULL);
+            "#,
+        ).unwrap();
+        drop(apple);
+
+        let apple_db = apple_db_path.to_str().unwrap();
+        db.copy_zcloudrecording_table(apple_db).unwrap();
+        let work_id = db.insert_slice(&create_test_slice("work.m4a")).unwrap();
+        let loose_id = db.insert_slice(&create_test_slice("loose.m4a")).unwrap();
+
+        assert_eq!(db.import_apple_folders(apple_db).unwrap(), 1);
+        assert_eq!(db.assign_apple_folder_labels().unwrap(), 1);
+
+        // Re-running is idempotent
+        assert_eq!(db.import_apple_folders(apple_db).unwrap(), 0);
+        assert_eq!(db.assign_apple_folder_labels().unwrap(), 0);
+
+        let labels = db.get_labels_for_all_slices().unwrap();
+        assert_eq!(labels[&work_id].len(), 1);
+        assert_eq!(labels[&work_id][0].name, "Work");
+        assert!(!labels.contains_key(&loose_id));
+    }
+
+    #[test]
+    fn test_assign_remove_and_filter_by_label() {
+        let (db, _temp_dir) = create_test_database();
+        let work = db.get_or_create_label("Work", "#228be6").unwrap();
+        let ideas = db.get_or_create_label("Ideas", "#40c057").unwrap();
+        let a = db.insert_slice(&create_test_slice("a.m4a")).unwrap();
+        let b = db.insert_slice(&create_test_slice("b.m4a")).unwrap();
+        let c = db.insert_slice(&create_test_slice("c.m4a")).unwrap();
+
+        assert_eq!(db.assign_label(work, &[a, b, 999]).unwrap(), 2);
+        assert_eq!(db.assign_label(work, &[a]).unwrap(), 0);
+        assert_eq!(db.assign_label(ideas, &[b, c]).unwrap(), 2);
+        assert!(db.assign_label(12345, &[a]).is_err());
+
+        let ids = |slices: Vec<Slice>| slices.into_iter().filter_map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(db.list_slices_with_labels(&[work], false).unwrap()), vec![a, b]);
+        assert_eq!(ids(db.list_slices_with_labels(&[work, ideas], false).unwrap()), vec![a, b, c]);
+        assert_eq!(ids(db.list_slices_with_labels(&[work, ideas], true).unwrap()), vec![b]);
+
+        assert_eq!(db.remove_label(work, &[b]).unwrap(), 1);
+        assert_eq!(ids(db.list_slices_with_labels(&[work, ideas], true).unwrap()), Vec::<i64>::new());
+
+        // Trashed slices drop out of the filtered listing
+        db.trash_slices(&[a], 0).unwrap();
+        assert!(db.list_slices_with_labels(&[work], false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_slices_pages_filters_and_sorts() {
+        let (db, _temp_dir) = create_test_database();
+        let mut ids = Vec::new();
+        for (name, date, text) in [
+            ("a.m4a", Some(300), "budget meeting"),
+            ("b.m4a", None, "shopping list"),
+            ("c.m4a", Some(100), "Budget follow-up"),
+            ("d.m4a", Some(200), "call mum"),
+        ] {
+            let mut slice = create_test_slice(name);
+            slice.recording_date = date;
+            slice.transcription = Some(text.to_string());
+            ids.push(db.insert_slice(&slice).unwrap());
+        }
+        db.trash_slices(&[ids[3]], 0).unwrap();
+
+        let page_ids = |page: SlicePage| page.slices.into_iter().filter_map(|s| s.id).collect::<Vec<_>>();
+
+        let page = db.query_slices(&SliceQuery { limit: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page_ids(page), vec![ids[0], ids[1]]);
+        let page = db.query_slices(&SliceQuery { limit: Some(2), offset: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(page_ids(page), vec![ids[2]]);
+
+        // Undated slices sort last either way
+        let by_date = |descending| SliceQuery { sort_by: SliceSortField::RecordingDate, descending, ..Default::default() };
+        assert_eq!(page_ids(db.query_slices(&by_date(false)).unwrap()), vec![ids[2], ids[0], ids[1]]);
+        assert_eq!(page_ids(db.query_slices(&by_date(true)).unwrap()), vec![ids[0], ids[2], ids[1]]);
+
+        let search = SliceQuery { search: Some("budget".to_string()), ..Default::default() };
+        assert_eq!(page_ids(db.query_slices(&search).unwrap()), vec![ids[0], ids[2]]);
+
+        let label_id = db.get_or_create_label("Work", "#228be6").unwrap();
+        db.assign_label(label_id, &[ids[2]]).unwrap();
+        let labelled = SliceQuery { search: Some("budget".to_string()), label_id: Some(label_id), ..Default::default() };
+        let page = db.query_slices(&labelled).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page_ids(page), vec![ids[2]]);
+    }
+
+    #[test]
+    fn test_query_slices_sort_fields() {
+        let (db, _temp_dir) = create_test_database();
+        let mut ids = Vec::new();
+        for (name, title, size, words, duration) in [
+            ("a.m4a", Some("banana"), 300, Some(50), Some(12.0)),
+            ("b.m4a", Some("Apple"), 100, None, Some(90.0)),
+            ("c.m4a", None, 200, Some(10), None),
+        ] {
+            let mut slice = create_test_slice(name);
+            slice.title = title.map(str::to_string);
+            slice.audio_file_size = size;
+            slice.transcription_word_count = words;
+            slice.transcribed = words.is_some();
+            slice.audio_time_length_seconds = duration;
+            ids.push(db.insert_slice(&slice).unwrap());
+        }
+
+        let sorted = |sort_by, descending| {
+            db.query_slices(&SliceQuery { sort_by, descending, ..Default::default() }).unwrap()
+                .slices.into_iter().filter_map(|s| s.id).collect::<Vec<_>>()
+        };
+        assert_eq!(sorted(SliceSortField::Title, false), vec![ids[1], ids[0], ids[2]]);
+        assert_eq!(sorted(SliceSortField::Size, true), vec![ids[0], ids[2], ids[1]]);
+        assert_eq!(sorted(SliceSortField::WordCount, false), vec![ids[2], ids[0], ids[1]]);
+        assert_eq!(sorted(SliceSortField::Duration, true), vec![ids[1], ids[0], ids[2]]);
+        assert_eq!(sorted(SliceSortField::Transcribed, false), vec![ids[1], ids[0], ids[2]]);
+    }
+
+    #[test]
+    fn test_query_slices_structured_filter() {
+        let (db, _temp_dir) = create_test_database();
+        let mut ids = Vec::new();
+        for (name, date, duration, model) in [
+            ("a.m4a", Some(1_000), Some(30.0), Some("base.en")),
+            ("b.m4a", Some(2_000), Some(900.0), Some("large-v3")),
+            ("c.m4a", Some(3_000), Some(600.0), None),
+            ("d.m4a", None, None, None),
+        ] {
+            let mut slice = create_test_slice(name);
+            slice.recording_date = date;
+            slice.audio_time_length_seconds = duration;
+            slice.transcribed = model.is_some();
+            slice.transcription_model = model.map(str::to_string);
+            ids.push(db.insert_slice(&slice).unwrap());
+        }
+
+        let matching = |filter: SliceFilter| {
+            db.query_slices(&SliceQuery { filter, ..Default::default() }).unwrap()
+                .slices.into_iter().filter_map(|s| s.id).collect::<Vec<_>>()
+        };
+        assert_eq!(matching(SliceFilter { recorded_after: Some(2_000), recorded_before: Some(3_000), ..Default::default() }), vec![ids[1]]);
+        assert_eq!(matching(SliceFilter { min_duration_seconds: Some(5.0 * 60.0), ..Default::default() }), vec![ids[1], ids[2]]);
+        assert_eq!(
+            matching(SliceFilter { min_duration_seconds: Some(300.0), transcribed: Some(false), ..Default::default() }),
+            vec![ids[2]]
+        );
+        assert_eq!(matching(SliceFilter { transcription_model: Some("base.en".to_string()), ..Default::default() }), vec![ids[0]]);
+        assert_eq!(matching(SliceFilter::default()).len(), 4);
+    }
+
+    #[test]
+    fn test_fuzzy_search_pages_after_matching() {
+        let (db, _temp_dir) = create_test_database();
+        let texts = ["please receive the parcel", "nothing relevant", "received two parcels", "Henderson call"];
+        let ids: Vec<i64> = texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let id = db.insert_slice(&create_test_slice(&format!("{}.m4a", i))).unwrap();
+                db.update_slice_transcription(id, text, 1, 1, "base.en").unwrap();
+                id
+            })
+            .collect();
+
+        let fuzzy = |search: &str, limit| SliceQuery {
+            search: Some(search.to_string()),
+            search_mode: SearchMode::Fuzzy,
+            limit,
+            without_transcription: true,
+            ..Default::default()
+        };
+        let page = db.query_slices(&fuzzy("recieve parcel", Some(1))).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.slices.iter().map(|s| s.id.unwrap()).collect::<Vec<_>>(), vec![ids[0]]);
+        assert_eq!(page.slices[0].transcription, None);
+        assert_eq!(db.query_slice_ids(&fuzzy("hender", Some(1))).unwrap(), vec![ids[3]]);
+
+        let substring = SliceQuery { search: Some("recieve".to_string()), ..Default::default() };
+        assert_eq!(db.query_slices(&substring).unwrap().total, 0);
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let (db, _temp_dir) = create_test_database();
+        let ids: Vec<i64> = ["call 555-0142 back", "ticket ABC-12 is done", "no numbers here"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let id = db.insert_slice(&create_test_slice(&format!("memo{}.m4a", i))).unwrap();
+                db.update_slice_transcription(id, text, 1, 1, "base.en").unwrap();
+                id
+            })
+            .collect();
+
+        let regex = |pattern: &str| SliceQuery {
+            search: Some(pattern.to_string()),
+            search_mode: SearchMode::Regex,
+            ..Default::default()
+        };
+        let page = db.query_slices(&regex(r"\d{3}-\d{4}")).unwrap();
+        assert_eq!(page.slices.iter().map(|s| s.id.unwrap()).collect::<Vec<_>>(), vec![ids[0]]);
+        assert!(!page.truncated);
+        assert_eq!(db.query_slice_ids(&regex(r"abc-\d+")).unwrap(), vec![ids[1]]);
+        assert!(db.query_slices(&regex("[unclosed")).is_err());
+    }
+
+    #[test]
+    fn test_search_slices_reports_matches() {
+        let (db, _temp_dir) = create_test_database();
+        let id = db.insert_slice(&create_test_slice("memo.m4a")).unwrap();
+        db.update_slice_transcription(id, "Budget review, then the budget vote", 6, 1, "base.en").unwrap();
+        db.insert_slice(&create_test_slice("other.m4a")).unwrap();
+
+        let query = SliceQuery {
+            search: Some("budget".to_string()),
+            without_transcription: true,
+            ..Default::default()
+        };
+        let page = db.search_slices(&query).unwrap();
+        assert_eq!(page.total, 1);
+        let result = &page.results[0];
+        assert_eq!(result.slice.id, Some(id));
+        assert!(result.slice.transcription.is_none());
+        let offsets: Vec<_> = result.matches.iter().map(|m| (m.field.as_str(), m.start, m.end)).collect();
+        assert_eq!(offsets, vec![("transcription", 0, 6), ("transcription", 24, 30)]);
+
+        let everything = db.search_slices(&SliceQuery::default()).unwrap();
+        assert_eq!(everything.total, 2);
+        assert!(everything.results.iter().all(|r| r.matches.is_empty()));
+    }
+
+    #[test]
+    fn test_ranked_search_weights_fields_and_labels() {
+        let (db, _temp_dir) = create_test_database();
+        let in_transcript = db.insert_slice(&create_test_slice("a.m4a")).unwrap();
+        db.update_slice_transcription(in_transcript, "we talked about the garden", 5, 1, "base.en").unwrap();
+        let in_title = db
+            .insert_slice(&Slice { title: Some("Garden plans".to_string()), ..create_test_slice("b.m4a") })
+            .unwrap();
+        let labelled = db.insert_slice(&create_test_slice("c.m4a")).unwrap();
+        let label_id = db
+            .create_label(&Label { id: None, name: "Garden".to_string(), color: "#00ff00".to_string(), keywords: String::new() })
+            .unwrap();
+        db.assign_label(label_id, &[labelled]).unwrap();
+        db.insert_slice(&create_test_slice("d.m4a")).unwrap();
+
+        let query = SliceQuery { search: Some("garden".to_string()), ..Default::default() };
+        let page = db.ranked_search(&query).unwrap();
+        let ranked: Vec<_> = page.results.iter().map(|r| r.slice.id.unwrap()).collect();
+        assert_eq!(ranked, vec![in_title, labelled, in_transcript]);
+        assert_eq!(page.total, 3);
+        assert_eq!(page.results[1].matches[0].field, "label");
+
+        let second = db.ranked_search(&SliceQuery { offset: Some(1), limit: Some(1), ..query }).unwrap();
+        assert_eq!(second.results.iter().map(|r| r.slice.id.unwrap()).collect::<Vec<_>>(), vec![labelled]);
+    }
+
+    #[test]
+    fn test_replace_in_transcriptions() {
+        let (db, _temp_dir) = create_test_database();
+        let first = db.insert_slice(&create_test_slice("a.m4a")).unwrap();
+        db.update_slice_transcription(first, "call Katherine, then katherine again", 5, 1, "base.en").unwrap();
+        let second = db.insert_slice(&create_test_slice("b.m4a")).unwrap();
+        db.update_slice_transcription(second, "Katherines are here", 3, 1, "base.en").unwrap();
+
+        let request = TranscriptReplacement {
+            find: "katherine".to_string(),
+            replace: "Kathryn".to_string(),
+            case_sensitive: false,
+            within_words: false,
+            slice_ids: None,
+        };
+        let preview = db.replace_in_transcriptions(&request, true).unwrap();
+        assert!(!preview.applied);
+        assert_eq!(preview.total_replacements, 2);
+        assert_eq!(preview.slices.len(), 1);
+        assert_eq!(preview.slices[0].examples[0].start, 5);
+        assert!(db.get_slice_history(first).unwrap().is_empty());
+
+        let applied = db.replace_in_transcriptions(&request, false).unwrap();
+        assert_eq!(applied.total_replacements, 2);
+        let text = db.get_slice(first).unwrap().unwrap().transcription.unwrap();
+        assert_eq!(text, "call Kathryn, then Kathryn again");
+        assert_eq!(db.get_slice(second).unwrap().unwrap().transcription.as_deref(), Some("Katherines are here"));
+
+        let history = db.get_slice_history(first).unwrap();
+        assert_eq!(history[0].old_value.as_deref(), Some("call Katherine, then katherine again"));
+        db.revert_slice_change(history[0].id).unwrap();
+        assert_eq!(
+            db.get_slice(first).unwrap().unwrap().transcription.as_deref(),
+            Some("call Katherine, then katherine again")
+        );
+    }
+
+    #[test]
+    fn test_phonetic_search() {
+        let (db, _temp_dir) = create_test_database();
+        let before = db.insert_slice(&create_test_slice("a.m4a")).unwrap();
+        db.update_slice_transcription(before, "lunch with Catherine", 3, 1, "base.en").unwrap();
+
+        let sounds_like = |text: &str| SliceQuery {
+            search: Some(text.to_string()),
+            search_mode: SearchMode::Phonetic,
+            ..Default::default()
+        };
+        assert!(db.query_slices(&sounds_like("Kathryn")).is_err());
+
+        assert_eq!(db.set_phonetic_index(true).unwrap(), 1);
+        let after = db.insert_slice(&create_test_slice("b.m4a")).unwrap();
+        db.update_slice_transcription(after, "Katherine called about lunch", 4, 1, "base.en").unwrap();
+        db.insert_slice(&create_test_slice("c.m4a")).unwrap();
+
+        assert_eq!(db.query_slice_ids(&sounds_like("Kathryn")).unwrap(), vec![before, after]);
+        assert_eq!(db.query_slice_ids(&sounds_like("kathryn lunch")).unwrap(), vec![before, after]);
+        assert_eq!(db.query_slice_ids(&sounds_like("kathryn called")).unwrap(), vec![after]);
+
+        db.delete_slice(after).unwrap();
+        assert_eq!(db.query_slice_ids(&sounds_like("Kathryn")).unwrap(), vec![before]);
+
+        db.set_phonetic_index(false).unwrap();
+        assert!(!db.phonetic_index_enabled().unwrap());
+    }
+
+    #[test]
+    fn test_search_history() {
+        let (db, _temp_dir) = create_test_database();
+        db.record_search("budget", 100).unwrap();
+        db.record_search("  ", 150).unwrap();
+        db.record_search("Dentist", 200).unwrap();
+        db.record_search("Budget", 300).unwrap();
+
+        let history = db.list_search_history(None, 10).unwrap();
+        let queries: Vec<_> = history.iter().map(|e| (e.query.as_str(), e.times_searched)).collect();
+        assert_eq!(queries, vec![("Budget", 2), ("Dentist", 1)]);
+        assert_eq!(db.list_search_history(Some("de"), 10).unwrap()[0].query, "Dentist");
+        assert!(db.list_search_history(Some("%"), 10).unwrap().is_empty());
+
+        for i in 0..SEARCH_HISTORY_LIMIT {
+            db.record_search(&format!("query {}", i), 1000 + i).unwrap();
+        }
+        assert_eq!(db.list_search_history(None, 500).unwrap().len() as i64, SEARCH_HISTORY_LIMIT);
+        assert!(db.list_search_history(Some("budget"), 10).unwrap().is_empty());
+
+        assert_eq!(db.clear_search_history().unwrap() as i64, SEARCH_HISTORY_LIMIT);
+        assert!(db.list_search_history(None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_starred_toggle_filter_and_ordering() {
+        let (db, _temp_dir) = create_test_database();
+        let ids: Vec<i64> = ["a.m4a", "b.m4a", "c.m4a"]
+            .iter()
+            .map(|name| db.insert_slice(&create_test_slice(name)).unwrap())
+            .collect();
+
+        assert!(db.toggle_slice_starred(ids[2]).unwrap());
+        assert!(db.toggle_slice_starred(ids[1]).unwrap());
+        assert!(!db.toggle_slice_starred(ids[1]).unwrap());
+        assert!(db.toggle_slice_starred(999).is_err());
+
+        let listed = |query: SliceQuery| {
+            db.query_slices(&query).unwrap().slices.into_iter().filter_map(|s| s.id).collect::<Vec<_>>()
+        };
+        assert_eq!(listed(SliceQuery { starred_first: true, ..Default::default() }), vec![ids[2], ids[0], ids[1]]);
+        assert_eq!(
+            listed(SliceQuery { filter: SliceFilter { starred: Some(false), ..Default::default() }, ..Default::default() }),
+            vec![ids[0], ids[1]]
+        );
+    }
+
+    #[test]
+    fn test_slice_notes_update_and_search() {
+        let (db, _temp_dir) = create_test_database();
+        let slice_id = db.insert_slice(&create_test_slice("memo.m4a")).unwrap();
+        db.insert_slice(&create_test_slice("other.m4a")).unwrap();
+
+        db.update_slice_notes(slice_id, Some("  follow up with Sam ")).unwrap();
+        assert_eq!(db.get_slice(slice_id).unwrap().unwrap().notes.as_deref(), Some("follow up with Sam"));
+        let found = db.query_slices(&SliceQuery { search: Some("sam".to_string()), ..Default::default() }).unwrap();
+        assert_eq!(found.slices.iter().filter_map(|s| s.id).collect::<Vec<_>>(), vec![slice_id]);
+
+        db.update_slice_notes(slice_id, Some(" ")).unwrap();
+        assert_eq!(db.get_slice(slice_id).unwrap().unwrap().notes, None);
+        assert_eq!(db.get_slice_history(slice_id).unwrap().len(), 2);
+        assert!(db.update_slice_notes(999, Some("x")).is_err());
+    }
+
+    #[test]
+    fn test_slice_metadata_crud() {
+        let (db, _temp_dir) = create_test_database();
+        let slice_id = db.insert_slice(&create_test_slice("memo.m4a")).unwrap();
+
+        db.set_slice_metadata(slice_id, "project", "alpha").unwrap();
+        db.set_slice_metadata(slice_id, " location ", "car").unwrap();
+        db.set_slice_metadata(slice_id, "project", "beta").unwrap();
+        assert!(db.set_slice_metadata(slice_id, "  ", "x").is_err());
+        assert!(db.set_slice_metadata(999, "project", "alpha").is_err());
+
+        let entries: Vec<_> = db.get_slice_metadata(slice_id).unwrap().into_iter().collect();
+        assert_eq!(entries, vec![("location".to_string(), "car".to_string()), ("project".to_string(), "beta".to_string())]);
+
+        assert!(db.delete_slice_metadata(slice_id, "location").unwrap());
+        assert!(!db.delete_slice_metadata(slice_id, "location").unwrap());
+        db.delete_slice(slice_id).unwrap();
+        assert!(db.get_slice_metadata(slice_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_archive_hides_slices_from_library() {
+        let (db, _temp_dir) = create_test_database();
+        let ids: Vec<i64> = ["a.m4a", "b.m4a", "c.m4a"]
+            .iter()
+            .map(|name| db.insert_slice(&create_test_slice(name)).unwrap())
+            .collect();
+        db.trash_slices(&[ids[2]], 1_000).unwrap();
+
+        assert_eq!(db.set_slices_archived(&[ids[0], ids[2]], true).unwrap(), 1, "trashed slices are left alone");
+        assert_eq!(db.set_slices_archived(&[ids[0]], true).unwrap(), 0);
+
+        let library: Vec<_> = db.list_library_slices(false).unwrap().into_iter().filter_map(|s| s.id).collect();
+        assert_eq!(library, vec![ids[1]]);
+        let archive = db.query_slices(&SliceQuery { archived: true, ..Default::default() }).unwrap();
+        assert_eq!(archive.slices.iter().filter_map(|s| s.id).collect::<Vec<_>>(), vec![ids[0]]);
+        assert_eq!(db.query_slices(&SliceQuery::default()).unwrap().total, 1);
+
+        let stats = db.get_stats().unwrap();
+        assert_eq!((stats.total_files, stats.archived_files), (2, 1));
+
+        assert_eq!(db.set_slices_archived(&[ids[0]], false).unwrap(), 1);
+        assert_eq!(db.list_library_slices(false).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_collections_nest_move_and_file_slices() {
+        let (db, _temp_dir) = create_test_database();
+        let alpha = db.create_collection("Project Alpha", None).unwrap();
+        let y2024 = db.create_collection("2024", Some(alpha)).unwrap();
+        let y2025 = db.create_collection("2025", Some(alpha)).unwrap();
+        assert!(db.create_collection("project alpha", None).is_err());
+        assert!(db.create_collection(" ", None).is_err());
+        assert!(db.create_collection("Orphan", Some(999)).is_err());
+
+        // No cycles, no clashing names
+        assert!(db.move_collection(alpha, Some(y2024)).is_err());
+        assert!(db.move_collection(y2025, Some(alpha)).is_ok());
+        db.move_collection(y2025, Some(y2024)).unwrap();
+        assert_eq!(db.get_collection(y2025).unwrap().parent_id, Some(y2024));
+
+        let ids: Vec<i64> = ["a.m4a", "b.m4a"]
+            .iter()
+            .map(|name| db.insert_slice(&create_test_slice(name)).unwrap())
+            .collect();
+        assert_eq!(db.move_slices_to_collection(&[ids[0], ids[1], 999], Some(y2025)).unwrap(), 2);
+        assert_eq!(db.move_slices_to_collection(&[ids[1]], None).unwrap(), 1);
+        let filed = db.query_slices(&SliceQuery { collection_id: Some(y2025), ..Default::default() }).unwrap();
+        assert_eq!(filed.slices.iter().filter_map(|s| s.id).collect::<Vec<_>>(), vec![ids[0]]);
+
+        // Deleting a collection hands its contents to the parent
+        db.delete_collection(y2025).unwrap();
+        let filed = db.query_slices(&SliceQuery { collection_id: Some(y2024), ..Default::default() }).unwrap();
+        assert_eq!(filed.total, 1);
+        let names: Vec<_> = db.list_collections().unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, vec!["Project Alpha", "2024"]);
+    }
+
+    #[test]
+    fn test_slice_summary_is_stored_and_listed() {
+        let (db, _temp_dir) = create_test_database();
+        let id = db.insert_slice(&create_test_slice("memo.m4a")).unwrap();
+        assert_eq!(db.get_slice(id).unwrap().unwrap().summary, None);
+
+        db.set_slice_summary(id, Some("Planned the offsite.")).unwrap();
+        assert_eq!(db.get_slice(id).unwrap().unwrap().summary.as_deref(), Some("Planned the offsite."));
+        let listed = db.query_slices(&SliceQuery::default()).unwrap().slices;
+        assert_eq!(listed[0].summary.as_deref(), Some("Planned the offsite."));
+
+        db.set_slice_summary(id, None).unwrap();
+        assert_eq!(db.get_slice(id).unwrap().unwrap().summary, None);
+        assert!(db.set_slice_summary(999, Some("x")).is_err());
+    }
+
+    #[test]
+    fn test_untitled_slices_are_listed_for_titling() {
+        let (db, _temp_dir) = create_test_database();
+        let slice = |name: &str, title: Option<&str>, transcription: Option<&str>| Slice {
+            title: title.map(str::to_string),
+            transcription: transcription.map(str::to_string),
+            ..create_test_slice(name)
+        };
+        let untitled = db.insert_slice(&slice("a.m4a", None, Some("we talked about the roadmap"))).unwrap();
+        let blank = db.insert_slice(&slice("b.m4a", Some("  "), Some("groceries"))).unwrap();
+        db.insert_slice(&slice("c.m4a", Some("Roadmap"), Some("roadmap"))).unwrap();
+        db.insert_slice(&slice("d.m4a", None, None)).unwrap();
+
+        assert_eq!(db.list_untitled_slice_ids().unwrap(), vec![untitled, blank]);
+    }
+
+    #[test]
+    fn test_mood_is_averaged_by_month() {
+        let (db, _temp_dir) = create_test_database();
+        let recorded = |name: &str, date: i64| Slice {
+            recording_date: Some(date),
+            transcription: Some("text".to_string()),
+            ..create_test_slice(name)
+        };
+        // Mid-month, so the local time zone can't move them across a month boundary
+        let january = db.insert_slice(&recorded("a.m4a", 1_736_899_200)).unwrap(); // 2025-01-15
+        let january_too = db.insert_slice(&recorded("b.m4a", 1_737_072_000)).unwrap(); // 2025-01-17
+        let march = db.insert_slice(&recorded("c.m4a", 1_741_996_800)).unwrap(); // 2025-03-15
+        let unrated = db.insert_slice(&recorded("d.m4a", 1_741_996_800)).unwrap();
+        for (slice_id, score) in [(january, 0.8), (january_too, 0.2), (march, -0.6)] {
+            db.save_slice_sentiment(&SliceSentiment {
+                slice_id,
+                score,
+                label: "neutral".to_string(),
+                model: "llama3.2".to_string(),
+                analyzed_at: 1_750_000_000,
+            })
+            .unwrap();
+        }
+
+        let mood = db.get_mood_by_month().unwrap();
+        assert_eq!(mood.iter().map(|p| (p.month.as_str(), p.count)).collect::<Vec<_>>(), vec![("2025-01", 2), ("2025-03", 1)]);
+        assert!((mood[0].average_score - 0.5).abs() < 1e-9);
+        assert_eq!(db.list_slices_without_sentiment().unwrap(), vec![unrated]);
+
+        db.delete_slice(march).unwrap();
+        assert_eq!(db.get_slice_sentiment(march).unwrap(), None);
+        assert_eq!(db.get_mood_by_month().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_transcript_chunks_round_trip() {
+        let (db, _temp_dir) = create_test_database();
+        let transcribed = |name: &str| Slice {
+            transcription: Some("some words".to_string()),
+            ..create_test_slice(name)
+        };
+        let kept = db.insert_slice(&transcribed("a.m4a")).unwrap();
+        let trashed = db.insert_slice(&transcribed("b.m4a")).unwrap();
+        let chunk = |slice_id: i64, embedding: Vec<f32>| TranscriptChunk {
+            slice_id,
+            start_char: 0,
+            end_char: 10,
+            text: "some words".to_string(),
+            embedding,
+        };
+        db.replace_transcript_chunks(kept, "nomic", "h1", &[chunk(kept, vec![0.5, -1.25]), chunk(kept, vec![1.0, 0.0])]).unwrap();
+        db.replace_transcript_chunks(trashed, "nomic", "h2", &[chunk(trashed, vec![0.0, 1.0])]).unwrap();
+        db.replace_transcript_chunks(kept, "other", "h1", &[]).unwrap();
+
+        assert_eq!(db.transcript_index_hashes("nomic").unwrap(), HashMap::from([(trashed, "h2".to_string())]));
+        db.replace_transcript_chunks(kept, "nomic", "h3", &[chunk(kept, vec![0.5, -1.25])]).unwrap();
+        assert_eq!(db.load_transcript_chunks("nomic").unwrap().len(), 2);
+
+        db.trash_slices(&[trashed], 1_750_000_000).unwrap();
+        assert_eq!(db.load_transcript_chunks("nomic").unwrap(), vec![chunk(kept, vec![0.5, -1.25])]);
+        assert_eq!(db.list_transcribed_slice_ids().unwrap(), vec![kept]);
+
+        db.update_slice_transcription(kept, "", 0, 0, "model").unwrap();
+        assert_eq!(db.prune_transcript_chunks().unwrap(), 1);
+        assert!(db.load_transcript_chunks("nomic").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_slice_translations_per_language() {
+        let (db, _temp_dir) = create_test_database();
+        let slice_id = db.insert_slice(&create_test_slice("a.m4a")).unwrap();
+        let translation = |language: &str, text: &str| SliceTranslation {
+            slice_id,
+            language: language.to_string(),
+            text: text.to_string(),
+            model: "llama3.2".to_string(),
+            source_hash: "hash".to_string(),
+            translated_at: 1_750_000_000,
+        };
+        db.save_slice_translation(&translation("es", "hola")).unwrap();
+        db.save_slice_translation(&translation("de", "hallo")).unwrap();
+        db.save_slice_translation(&translation("es", "buenas")).unwrap();
+
+        assert_eq!(db.list_slice_translations(slice_id).unwrap(), vec![translation("de", "hallo"), translation("es", "buenas")]);
+        assert!(db.delete_slice_translation(slice_id, "de").unwrap());
+        assert!(!db.delete_slice_translation(slice_id, "de").unwrap());
+
+        db.delete_slice(slice_id).unwrap();
+        assert!(db.list_slice_translations(slice_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_entities_are_queryable_across_slices() {
+        let (db, _temp_dir) = create_test_database();
+        let checkup = db.insert_slice(&create_test_slice("checkup.m4a")).unwrap();
+        let followup = db.insert_slice(&create_test_slice("followup.m4a")).unwrap();
+        let trashed = db.insert_slice(&create_test_slice("old.m4a")).unwrap();
+        let entity = |slice_id: i64, kind: EntityKind, name: &str, mentions: u32| SliceEntity {
+            slice_id,
+            kind,
+            name: name.to_string(),
+            mentions,
+        };
+        db.replace_slice_entities(checkup, "llama3.2", "h1", &[
+            entity(checkup, EntityKind::Person, "Dr. Alvarez", 2),
+            entity(checkup, EntityKind::Place, "Mercy Hospital", 1),
+        ])
+        .unwrap();
+        db.replace_slice_entities(followup, "llama3.2", "h2", &[
+            entity(followup, EntityKind::Person, "dr.  alvarez,", 1),
+            entity(followup, EntityKind::Person, "Dr. Alvarez", 1),
+        ])
+        .unwrap();
+        db.replace_slice_entities(trashed, "llama3.2", "h3", &[entity(trashed, EntityKind::Person, "Dr. Alvarez", 1)]).unwrap();
+        db.trash_slices(&[trashed], 1_750_000_000).unwrap();
+
+        assert_eq!(db.get_slice_entities(followup).unwrap(), vec![entity(followup, EntityKind::Person, "dr.  alvarez,", 1)]);
+        let ids = |slices: Vec<Slice>| -> Vec<i64> {
+            let mut ids: Vec<i64> = slices.iter().filter_map(|s| s.id).collect();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(ids(db.find_slices_by_entity("ALVAREZ", None).unwrap()), vec![checkup, followup]);
+        assert_eq!(ids(db.find_slices_by_entity("alvarez", Some(EntityKind::Place)).unwrap()), Vec::<i64>::new());
+        assert_eq!(ids(db.find_slices_by_entity("mercy", Some(EntityKind::Place)).unwrap()), vec![checkup]);
+
+        let people = db.list_entities(Some(EntityKind::Person), None, 10).unwrap();
+        assert_eq!(people.len(), 1);
+        assert_eq!((people[0].slice_count, people[0].mentions), (2, 3));
+        assert_eq!(db.list_entities(None, Some("hosp"), 10).unwrap()[0].name, "Mercy Hospital");
+        assert_eq!(db.entity_extraction_hashes().unwrap().len(), 3);
+
+        db.delete_slice(checkup).unwrap();
+        assert!(db.get_slice_entities(checkup).unwrap().is_empty());
+        assert_eq!(db.entity_extraction_hashes().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_label_prompts_and_derived_documents() {
+        let (db, _temp_dir) = create_test_database();
+        let label = |name: &str| Label { id: None, name: name.to_string(), color: "#228be6".to_string(), keywords: String::new() };
+        let meeting = db.create_label(&label("Meeting")).unwrap();
+        let idea = db.create_label(&label("Idea")).unwrap();
+        let slice_id = db.insert_slice(&create_test_slice("a.m4a")).unwrap();
+        db.assign_label(meeting, &[slice_id]).unwrap();
+
+        assert!(db.set_label_prompt(999, Some("Minutes")).is_err());
+        db.set_label_prompt(meeting, Some("  Write minutes ")).unwrap();
+        db.set_label_prompt(idea, Some("One-paragraph pitch")).unwrap();
+        assert_eq!(db.list_label_prompts().unwrap().len(), 2);
+        let prompts = db.get_slice_label_prompts(slice_id).unwrap();
+        assert_eq!(prompts, vec![LabelPrompt { label_id: meeting, label_name: "Meeting".to_string(), prompt: "Write minutes".to_string() }]);
+
+        let document = |content: &str, created_at: i64| DerivedDocument {
+            id: 0,
+            slice_id,
+            label_id: meeting,
+            label_name: "Meeting".to_string(),
+            prompt: "Write minutes".to_string(),
+            content: content.to_string(),
+            model: "llama3.2".to_string(),
+            created_at,
+        };
+        let first = db.save_derived_document(&document("Draft", 1)).unwrap();
+        let second = db.save_derived_document(&document("Final", 2)).unwrap();
+        assert_eq!(first, second, "a rerun replaces the label's document");
+        assert_eq!(db.list_derived_documents(slice_id).unwrap(), vec![DerivedDocument { id: first, ..document("Final", 2) }]);
+
+        db.set_label_prompt(idea, None).unwrap();
+        db.delete_label(meeting).unwrap();
+        assert!(db.list_label_prompts().unwrap().is_empty());
+        db.delete_derived_document(first).unwrap();
+        assert!(db.delete_derived_document(first).is_err());
+    }
+
+    #[test]
+    fn test_scrub_slice_text_leaves_no_trace() {
+        let (db, _temp_dir) = create_test_database();
+        let slice_id = db.insert_slice(&Slice {
+            title: Some("Call 555-123-4567".to_string()),
+            ..create_test_slice("a.m4a")
+        })
+        .unwrap();
+        db.update_slice_transcription(slice_id, "first, call 555-123-4567", 1, 3, "base.en").unwrap();
+        db.update_slice_transcription(slice_id, "then mail a@b.io", 1, 3, "base.en").unwrap();
+        db.save_slice_translation(&SliceTranslation {
+            slice_id,
+            language: "es".to_string(),
+            text: "luego escribe a a@b.io".to_string(),
+            model: "llama3.2".to_string(),
+            source_hash: "hash".to_string(),
+            translated_at: 1,
+        })
+        .unwrap();
+        let mask = |text: &str| crate::backend::pii::redact(text).into_owned();
+
+        assert_eq!(db.scrub_slice_text(slice_id, &mask).unwrap(), 4, "transcript, title, history entry, translation");
+        let slice = db.get_slice(slice_id).unwrap().unwrap();
+        assert_eq!(slice.transcription.as_deref(), Some("then mail [redacted email]"));
+        assert_eq!(slice.title.as_deref(), Some("Call [redacted phone]"));
+        let history = db.get_slice_history(slice_id).unwrap();
+        assert_eq!(history.len(), 1, "scrubbing isn't recorded as an edit");
+        assert_eq!(history[0].old_value.as_deref(), Some("first, call [redacted phone]"));
+        assert_eq!(db.list_slice_translations(slice_id).unwrap()[0].text, "luego escribe a [redacted email]");
+        assert_eq!(db.scrub_slice_text(slice_id, &mask).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_scrub_logged_text() {
+        let (db, _temp_dir) = create_test_database();
+        db.record_nlm_invocation(&NlmInvocation {
+            id: 0,
+            command: "add".to_string(),
+            args: vec!["add".to_string(), "call 555-123-4567".to_string()],
+            started_at: 100,
+            duration_ms: 250,
+            success: true,
+            error_kind: None,
+            output: Some("added".to_string()),
+            account: None,
+        }, 10).unwrap();
+        db.record_search("a@b.io", 1).unwrap();
+        db.record_search("garden", 2).unwrap();
+        let mask = |text: &str| crate::backend::pii::redact(text).into_owned();
+
+        assert_eq!(db.scrub_logged_text(&mask).unwrap(), 2, "invocation args, search");
+        let recorded = &db.list_nlm_invocations(false, None, 10, 0).unwrap()[0];
+        assert_eq!(recorded.args, vec!["add".to_string(), "call [redacted phone]".to_string()]);
+        let searches: Vec<_> = db.list_search_history(None, 10).unwrap().into_iter().map(|e| e.query).collect();
+        assert_eq!(searches, vec!["garden".to_string()]);
+        assert_eq!(db.scrub_logged_text(&mask).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_pinned_slices_listed_first() {
+        let (db, _temp_dir) = create_test_database();
+        let ids: Vec<i64> = ["a.m4a", "b.m4a", "c.m4a"]
+            .iter()
+            .map(|name| db.insert_slice(&create_test_slice(name)).unwrap())
+            .collect();
+
+        db.set_slice_pinned(ids[2], true).unwrap();
+        db.toggle_slice_starred(ids[1]).unwrap();
+        assert!(db.set_slice_pinned(999, true).is_err());
+
+        let listed = |query: SliceQuery| {
+            db.query_slices(&query).unwrap().slices.into_iter().filter_map(|s| s.id).collect::<Vec<_>>()
+        };
+        assert_eq!(listed(SliceQuery::default()), vec![ids[2], ids[0], ids[1]]);
+        assert_eq!(listed(SliceQuery { starred_first: true, ..Default::default() }), vec![ids[2], ids[1], ids[0]]);
+        assert_eq!(listed(SliceQuery { descending: true, ..Default::default() }), vec![ids[2], ids[1], ids[0]]);
+
+        db.set_slice_pinned(ids[2], false).unwrap();
+        assert!(!db.get_slice(ids[2]).unwrap().unwrap().pinned);
+        assert_eq!(listed(SliceQuery::default()), vec![ids[0], ids[1], ids[2]]);
+    }
+
+    #[test]
+    fn test_slice_links_both_directions() {
+        let (db, _temp_dir) = create_test_database();
+        let ids: Vec<i64> = ["part1.m4a", "part2.m4a", "followup.m4a"]
+            .iter()
+            .map(|name| db.insert_slice(&create_test_slice(name)).unwrap())
+            .collect();
+
+        db.link_slices(ids[0], ids[1], "part").unwrap();
+        db.link_slices(ids[2], ids[0], "related").unwrap();
+        db.link_slices(ids[0], ids[2], " follow_up ").unwrap();
+        assert!(db.link_slices(ids[0], ids[0], "part").is_err());
+        assert!(db.link_slices(ids[0], 999, "part").is_err());
+        assert!(db.link_slices(ids[0], ids[1], " ").is_err());
+
+        let related = db.get_related_slices(ids[0]).unwrap();
+        let summary: Vec<_> = related.iter().map(|r| (r.slice.id.unwrap(), r.relation.as_str(), r.outgoing)).collect();
+        assert_eq!(summary, vec![(ids[1], "part", true), (ids[2], "follow_up", true)]);
+        let back = db.get_related_slices(ids[1]).unwrap();
+        assert_eq!((back[0].slice.id, back[0].outgoing), (Some(ids[0]), false));
+
+        db.trash_slices(&[ids[1]], 1_700_000_000).unwrap();
+        assert_eq!(db.get_related_slices(ids[0]).unwrap().len(), 1);
+        assert!(db.unlink_slices(ids[2], ids[0]).unwrap());
+        assert!(!db.unlink_slices(ids[2], ids[0]).unwrap());
+        db.delete_slice(ids[1]).unwrap();
+        assert!(db.get_related_slices(ids[0]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_listing_without_transcription() {
+        let (db, _temp_dir) = create_test_database();
+        let mut slice = create_test_slice("memo.m4a");
+        slice.transcribed = true;
+        slice.transcription = Some("a long transcript".to_string());
+        let slice_id = db.insert_slice(&slice).unwrap();
+
+        let light = db.query_slices(&SliceQuery { without_transcription: true, ..Default::default() }).unwrap();
+        assert!(light.slices[0].transcribed);
+        assert_eq!(light.slices[0].transcription, None);
+        let full = db.query_slices(&SliceQuery::default()).unwrap();
+        assert_eq!(full.slices[0].transcription.as_deref(), Some("a long transcript"));
+
+        assert_eq!(db.get_slice_transcription(slice_id).unwrap().as_deref(), Some("a long transcript"));
+        assert!(db.get_slice_transcription(999).is_err());
+    }
+
+    #[test]
+    fn test_recent_slices_newest_first() {
+        let (db, _temp_dir) = create_test_database();
+        let ids: Vec<i64> = ["a.m4a", "b.m4a", "c.m4a", "d.m4a"]
+            .iter()
+            .map(|name| db.insert_slice(&create_test_slice(name)).unwrap())
+            .collect();
+
+        db.mark_slice_opened(ids[0], 100).unwrap();
+        db.mark_slice_opened(ids[1], 200).unwrap();
+        db.mark_slice_opened(ids[2], 300).unwrap();
+        db.mark_slice_opened(ids[0], 400).unwrap();
+        db.trash_slices(&[ids[2]], 500).unwrap();
+
+        let recent = db.list_recent_slices(10).unwrap();
+        let summary: Vec<_> = recent.iter().map(|r| (r.slice.id.unwrap(), r.last_opened_at)).collect();
+        assert_eq!(summary, vec![(ids[0], 400), (ids[1], 200)]);
+        assert_eq!(db.list_recent_slices(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_query_slice_ids_ignores_paging() {
+        let (db, _temp_dir) = create_test_database();
+        let ids: Vec<i64> = ["a.m4a", "b.m4a", "c.m4a"]
+            .iter()
+            .map(|name| db.insert_slice(&create_test_slice(name)).unwrap())
+            .collect();
+        db.update_slice_transcription(ids[1], "hello", 1, 1, "base.en").unwrap();
+        db.update_slice_transcription(ids[2], "world", 1, 1, "base.en").unwrap();
+
+        let transcribed = SliceQuery {
+            filter: SliceFilter { transcribed: Some(true), ..Default::default() },
+            descending: true,
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(db.query_slice_ids(&transcribed).unwrap(), vec![ids[2], ids[1]]);
+        assert_eq!(db.query_slices(&transcribed).unwrap().slices.len(), 1);
+    }
+
+    #[test]
+    fn test_apple_favorite_maps_to_starred() {
+        let (db, temp_dir) = create_test_database();
+
+        let apple_db_path = temp_dir.path().join("CloudRecordings.db");
+        let apple = Connection::open(&apple_db_path).unwrap();
+        apple.execute_batch(
+            r#"
+            CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT, ZFAVORITE INTEGER);
+            INSERT INTO ZCLOUDRECORDING (ZPATH, ZFAVORITE) VALUES ('fav.m4a', 1);
+            INSERT INTO ZCLOUDRECORDING (ZPATH, ZFAVORITE) VALUES ('plain.m4a', 0);
+            "#,
+        ).unwrap();
+        drop(apple);
+
+        // Before the Apple table is copied there is nothing to look up
+        assert!(!db.get_favorite_for_filename("fav.m4a").unwrap());
+
+        db.copy_zcloudrecording_table(apple_db_path.to_str().unwrap()).unwrap();
+        assert!(db.get_favorite_for_filename("fav.m4a").unwrap());
+        assert!(!db.get_favorite_for_filename("plain.m4a").unwrap());
+        assert!(!db.get_favorite_for_filename("missing.m4a").unwrap());
+
+        let mut slice = create_test_slice("fav.m4a");
+        slice.starred = true;
+        db.insert_slice(&slice).unwrap();
+        assert!(db.list_all_slices().unwrap()[0].starred);
+    }
+
+    #[test]
+    fn test_invocation_history_filters_and_prunes() {
+        let (db, _temp_dir) = create_test_database();
+        let invocation = |command: &str, success: bool| NlmInvocation {
+            id: 0,
+            command: command.to_string(),
+            args: vec![command.to_string(), "nb".to_string()],
+            started_at: 100,
+            duration_ms: 250,
+            success,
+            error_kind: (!success).then(|| "NlmTimeout".to_string()),
+            output: Some("output".to_string()),
+            account: None,
+        };
+        db.record_nlm_invocation(&invocation("list", true), 3).unwrap();
+        let failed = db.record_nlm_invocation(&invocation("add", false), 3).unwrap();
+        let latest = db.record_nlm_invocation(&invocation("add", true), 3).unwrap();
+
+        let ids = |failed_only, command| {
+            db.list_nlm_invocations(failed_only, command, 10, 0).unwrap().iter().map(|i| i.id).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(true, None), vec![failed]);
+        assert_eq!(ids(false, Some("add")), vec![latest, failed]);
+        let recorded = &db.list_nlm_invocations(true, None, 10, 0).unwrap()[0];
+        assert_eq!(recorded.args, vec!["add".to_string(), "nb".to_string()]);
+        assert_eq!(recorded.error_kind.as_deref(), Some("NlmTimeout"));
+
+        db.record_nlm_invocation(&invocation("sources", true), 3).unwrap();
+        assert_eq!(ids(false, None).len(), 3, "only the newest are kept");
+        assert!(ids(false, Some("list")).is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Dates written into search text ("budget last week", "march 2023", "yesterday"),
+//! turned into a recording date range so they filter instead of being matched as words.
+
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, TimeZone};
+
+use super::models::SliceQuery;
+
+const MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+];
+
+/// Short forms of `MONTHS`, in the same order; "sept" is also accepted.
+const MONTH_ABBREVIATIONS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun",
+    "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Words that only introduce a date ("from last week") and go with it.
+const DATE_PREPOSITIONS: [&str; 4] = ["in", "on", "from", "during"];
+
+/// A date phrase found in search text and the text left once it's taken out.
+#[derive(Debug, PartialEq)]
+pub struct DatePhrase {
+    pub text: String,
+    pub start: NaiveDate, // first day, inclusive
+    pub end: Option<NaiveDate>, // day after the last, exclusive; None = up to now
+}
+
+fn month_number(word: &str) -> Option<u32> {
+    let word = if word == "sept" { "sep" } else { word };
+    MONTHS.iter()
+        .position(|m| *m == word)
+        .or_else(|| MONTH_ABBREVIATIONS.iter().position(|m| *m == word))
+        .map(|i| i as u32 + 1)
+}
+
+fn year_number(word: &str) -> Option<i32> {
+    (word.len() == 4).then(|| word.parse().ok()).flatten().filter(|y| (1970..=2100).contains(y))
+}
+
+fn month_start(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1)
+}
+
+/// The unit in "last 3 months" and so on, singular or plural.
+fn unit(word: &str) -> Option<&'static str> {
+    match word.strip_suffix('s').unwrap_or(word) {
+        "day" => Some("day"),
+        "week" => Some("week"),
+        "month" => Some("month"),
+        "year" => Some("year"),
+        _ => None,
+    }
+}
+
+/// The date range for a phrase starting at `words[0]`, and how many words it took.
+fn phrase_at(words: &[&str], today: NaiveDate) -> Option<(usize, NaiveDate, NaiveDate)> {
+    let tomorrow = today + Duration::days(1);
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let this_month = month_start(today.year(), today.month())?;
+    let this_year = month_start(today.year(), 1)?;
+
+    match words {
+        ["today", ..] => Some((1, today, tomorrow)),
+        ["yesterday", ..] => Some((1, today - Duration::days(1), today)),
+        [which @ ("this" | "last"), unit_word @ ("day" | "week" | "month" | "year"), ..] => {
+            let (this_start, this_end) = match *unit_word {
+                "day" => (today, tomorrow),
+                "week" => (week_start, week_start + Duration::days(7)),
+                "month" => (this_month, this_month.checked_add_months(Months::new(1))?),
+                _ => (this_year, this_year.checked_add_months(Months::new(12))?),
+            };
+            if *which == "this" {
+                return Some((2, this_start, this_end));
+            }
+            let previous_start = match *unit_word {
+                "day" => today - Duration::days(1),
+                "week" => week_start - Duration::days(7),
+                "month" => this_month.checked_sub_months(Months::new(1))?,
+                _ => this_year.checked_sub_months(Months::new(12))?,
+            };
+            Some((2, previous_start, this_start))
+        }
+        [("last" | "past"), count, unit_word, ..] => {
+            let count: u32 = count.parse().ok().filter(|n| *n > 0)?;
+            // A count too large for a date is no date at all
+            let start = match unit(unit_word)? {
+                "day" => tomorrow.checked_sub_signed(Duration::days(count as i64))?,
+                "week" => tomorrow.checked_sub_signed(Duration::weeks(count as i64))?,
+                "month" => tomorrow.checked_sub_months(Months::new(count))?,
+                _ => tomorrow.checked_sub_months(Months::new(count.checked_mul(12)?))?,
+            };
+            Some((3, start, tomorrow))
+        }
+        [month, year, ..] if month_number(month).is_some() && year_number(year).is_some() => {
+            let start = month_start(year_number(year)?, month_number(month)?)?;
+            Some((2, start, start.checked_add_months(Months::new(1))?))
+        }
+        // "may" alone is too common a word to take as a month
+        [month, ..] if month_number(month).is_some() && *month != "may" => {
+            let month = month_number(month)?;
+            // The latest one that has started, so in February "march" means last March
+            let year = if month > today.month() { today.year() - 1 } else { today.year() };
+            let start = month_start(year, month)?;
+            Some((1, start, start.checked_add_months(Months::new(1))?))
+        }
+        [year, ..] if year_number(year).is_some() => {
+            let start = month_start(year_number(year)?, 1)?;
+            Some((1, start, start.checked_add_months(Months::new(12))?))
+        }
+        _ => None,
+    }
+}
+
+/// The first date phrase in `text`, read relative to `today`. "since" before a phrase
+/// leaves the range open up to now.
+pub fn find_date_phrase(text: &str, today: NaiveDate) -> Option<DatePhrase> {
+    let original: Vec<&str> = text.split_whitespace().collect();
+    let lower: Vec<String> = original.iter()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .collect();
+    let lower: Vec<&str> = lower.iter().map(String::as_str).collect();
+
+    for i in 0..lower.len() {
+        let Some((taken, start, end)) = phrase_at(&lower[i..], today) else { continue };
+        let since = i > 0 && lower[i - 1] == "since";
+        let first = if i > 0 && (since || DATE_PREPOSITIONS.contains(&lower[i - 1])) { i - 1 } else { i };
+        let rest: Vec<&str> = original[..first].iter().chain(&original[i + taken..]).copied().collect();
+        return Some(DatePhrase {
+            text: rest.join(" "),
+            start,
+            end: (!since).then_some(end),
+        });
+    }
+    None
+}
+
+/// Midnight at the start of `date` in local time, as a Unix timestamp.
+fn local_midnight(date: NaiveDate) -> Option<i64> {
+    Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest().map(|d| d.timestamp())
+}
+
+/// `query` with a date phrase in its search text (when `natural_dates` is set) moved into
+/// its recording date filter, narrowing any range already set. Queries without one come
+/// back unchanged.
+pub fn resolve(query: &SliceQuery, today: NaiveDate) -> SliceQuery {
+    let mut resolved = query.clone();
+    if !query.natural_dates {
+        return resolved;
+    }
+    let Some(phrase) = query.search.as_deref().and_then(|text| find_date_phrase(text, today)) else {
+        return resolved;
+    };
+
+    resolved.search = Some(phrase.text).filter(|text| !text.trim().is_empty());
+    let filter = &mut resolved.filter;
+    if let Some(after) = local_midnight(phrase.start) {
+        filter.recorded_after = Some(filter.recorded_after.map_or(after, |existing| existing.max(after)));
+    }
+    if let Some(before) = phrase.end.and_then(local_midnight) {
+        filter.recorded_before = Some(filter.recorded_before.map_or(before, |existing| existing.min(before)));
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_find_date_phrase() {
+        let today = date(2024, 2, 14); // a Wednesday
+        let found = |text: &str| find_date_phrase(text, today).map(|p| (p.text, p.start, p.end));
+
+        assert_eq!(found("yesterday"), Some((String::new(), date(2024, 2, 13), Some(today))));
+        assert_eq!(
+            found("budget from last week"),
+            Some(("budget".to_string(), date(2024, 2, 5), Some(date(2024, 2, 12))))
+        );
+        assert_eq!(found("this month standup"), Some(("standup".to_string(), date(2024, 2, 1), Some(date(2024, 3, 1)))));
+        assert_eq!(found("notes March 2023"), Some(("notes".to_string(), date(2023, 3, 1), Some(date(2023, 4, 1)))));
+        assert_eq!(found("march"), Some((String::new(), date(2023, 3, 1), Some(date(2023, 4, 1)))));
+        assert_eq!(found("in jan"), Some((String::new(), date(2024, 1, 1), Some(date(2024, 2, 1)))));
+        assert_eq!(found("last 7 days"), Some((String::new(), date(2024, 2, 8), Some(date(2024, 2, 15)))));
+        assert_eq!(found("last year"), Some((String::new(), date(2023, 1, 1), Some(date(2024, 1, 1)))));
+        assert_eq!(found("since 2022 ideas"), Some(("ideas".to_string(), date(2022, 1, 1), None)));
+        assert_eq!(found("sept 2023"), Some((String::new(), date(2023, 9, 1), Some(date(2023, 10, 1)))));
+        assert_eq!(found("what may happen"), None);
+        assert_eq!(found("marc said"), None);
+        assert_eq!(found("janet's notes"), None);
+        assert_eq!(found("last 4000000000 days"), None);
+        assert_eq!(found("past 400000000 years"), None);
+        assert_eq!(found("call bob"), None);
+    }
+
+    #[test]
+    fn test_resolve_only_when_asked() {
+        let today = date(2024, 2, 14);
+        let query = SliceQuery { search: Some("budget yesterday".to_string()), ..Default::default() };
+        assert_eq!(resolve(&query, today).search.as_deref(), Some("budget yesterday"));
+
+        let mut query = SliceQuery { natural_dates: true, ..query };
+        query.filter.recorded_before = Some(0);
+        let resolved = resolve(&query, today);
+        assert_eq!(resolved.search.as_deref(), Some("budget"));
+        assert_eq!(resolved.filter.recorded_after, local_midnight(date(2024, 2, 13)));
+        assert_eq!(resolved.filter.recorded_before, Some(0)); // the narrower bound wins
+    }
+}
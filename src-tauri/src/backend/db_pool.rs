@@ -0,0 +1,223 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::database::Database;
+
+/// Connections kept open per database once returned; extra ones are closed.
+const MAX_IDLE_CONNECTIONS: usize = 4;
+
+/// How often `close` checks whether the checked-out connections have come back.
+const CLOSE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// One pool per database file, shared by commands and background tasks
+lazy_static::lazy_static! {
+    static ref POOLS: Mutex<HashMap<PathBuf, DbPool>> = Mutex::new(HashMap::new());
+}
+
+struct PoolInner {
+    path: PathBuf,
+    idle: Mutex<Vec<Database>>,
+    closed: AtomicBool,
+    checked_out: AtomicUsize,
+}
+
+/// Reusable connections to one database file, so concurrent commands and background
+/// tasks each get their own connection instead of queueing on a single shared one.
+#[derive(Clone)]
+pub struct DbPool {
+    inner: Arc<PoolInner>,
+}
+
+/// A connection checked out of a `DbPool`; returned to the pool when dropped.
+pub struct PooledDatabase {
+    db: Option<Database>,
+    pool: DbPool,
+}
+
+impl DbPool {
+    /// Open a pool for `db_path`, creating the schema with its first connection.
+    pub fn open<P: AsRef<Path>>(db_path: P) -> Result<Self> {
+        let path = db_path.as_ref().to_path_buf();
+        let first = Database::new(&path)?;
+        Ok(Self::with_idle(path, vec![first], false))
+    }
+
+    fn with_idle(path: PathBuf, idle: Vec<Database>, closed: bool) -> Self {
+        DbPool {
+            inner: Arc::new(PoolInner {
+                path,
+                idle: Mutex::new(idle),
+                closed: AtomicBool::new(closed),
+                checked_out: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Check out an idle connection, or open a new one if all are in use.
+    pub fn get(&self) -> Result<PooledDatabase> {
+        // Counted before the closed check, so `close` can't miss a connection on its way out
+        self.inner.checked_out.fetch_add(1, Ordering::SeqCst);
+        let mut pooled = PooledDatabase {
+            db: None,
+            pool: self.clone(),
+        };
+        if self.inner.closed.load(Ordering::SeqCst) {
+            return Err(anyhow::anyhow!("Database {:?} has been closed", self.inner.path));
+        }
+        let idle = self.inner.idle.lock().unwrap().pop();
+        pooled.db = Some(match idle {
+            Some(db) => db,
+            None => Database::connect(&self.inner.path)?,
+        });
+        Ok(pooled)
+    }
+
+    #[cfg(test)]
+    fn idle_count(&self) -> usize {
+        self.inner.idle.lock().unwrap().len()
+    }
+}
+
+impl Deref for PooledDatabase {
+    type Target = Database;
+
+    fn deref(&self) -> &Database {
+        self.db.as_ref().expect("pooled connection already returned")
+    }
+}
+
+impl Drop for PooledDatabase {
+    fn drop(&mut self) {
+        if let Some(db) = self.db.take() {
+            if !self.pool.inner.closed.load(Ordering::SeqCst) {
+                let mut idle = self.pool.inner.idle.lock().unwrap();
+                if idle.len() < MAX_IDLE_CONNECTIONS {
+                    idle.push(db);
+                }
+            }
+        }
+        self.pool.inner.checked_out.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The shared pool for the database at `db_path`, opened on first use.
+pub fn pool_for<P: AsRef<Path>>(db_path: P) -> Result<DbPool> {
+    let path = db_path.as_ref().to_path_buf();
+    let mut pools = POOLS.lock().unwrap();
+    if let Some(pool) = pools.get(&path) {
+        return Ok(pool.clone());
+    }
+    let pool = DbPool::open(&path)?;
+    pools.insert(path, pool.clone());
+    Ok(pool)
+}
+
+/// Take the database at `db_path` out of use, e.g. before the file is replaced: idle
+/// connections close, and no more are handed out, by this pool or a new one, until
+/// `reopen`. Waits up to `timeout` for the checked-out connections to be returned; if some
+/// are still out by then the pool is put back in use and this fails.
+pub fn close<P: AsRef<Path>>(db_path: P, timeout: Duration) -> Result<()> {
+    let path = db_path.as_ref().to_path_buf();
+    let pool = POOLS.lock().unwrap()
+        .entry(path.clone())
+        .or_insert_with(|| DbPool::with_idle(path.clone(), Vec::new(), true))
+        .clone();
+    pool.inner.closed.store(true, Ordering::SeqCst);
+    pool.inner.idle.lock().unwrap().clear();
+
+    let deadline = Instant::now() + timeout;
+    while pool.inner.checked_out.load(Ordering::SeqCst) > 0 {
+        if Instant::now() >= deadline {
+            pool.inner.closed.store(false, Ordering::SeqCst);
+            return Err(anyhow::anyhow!("Database {:?} is still in use; try again in a moment", path));
+        }
+        std::thread::sleep(CLOSE_POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+/// Open a fresh shared pool for `db_path` after `close`, running any schema migrations.
+pub fn reopen<P: AsRef<Path>>(db_path: P) -> Result<DbPool> {
+    POOLS.lock().unwrap().remove(db_path.as_ref());
+    pool_for(db_path)
+}
+
+/// Check out a connection to the database at `db_path` from its shared pool.
+pub fn connect<P: AsRef<Path>>(db_path: P) -> Result<PooledDatabase> {
+    pool_for(db_path)?.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_connections_are_reused_and_shared() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("test.db");
+        let pool = pool_for(&db_path)?;
+        assert_eq!(pool.idle_count(), 1);
+
+        // Two checked out at once: the idle one plus a new connection
+        let first = pool.get()?;
+        let second = connect(&db_path)?;
+        assert_eq!(pool.idle_count(), 0);
+
+        let label_id = first.get_or_create_label("shared", "#000000")?;
+        assert_eq!(second.get_or_create_label("shared", "#000000")?, label_id);
+
+        drop(first);
+        drop(second);
+        assert_eq!(pool.idle_count(), 2);
+        assert_eq!(pool_for(&db_path)?.idle_count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_closed_pool_is_replaced() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("test.db");
+        let pool = pool_for(&db_path)?;
+        let checked_out = pool.get()?;
+
+        // Not while a connection is out; the pool stays usable
+        assert!(close(&db_path, Duration::from_millis(100)).is_err());
+        drop(pool.get()?);
+
+        let returned = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            drop(checked_out);
+        });
+        close(&db_path, Duration::from_secs(5))?;
+        returned.join().unwrap();
+        assert_eq!(pool.idle_count(), 0, "returned connections are closed, not kept");
+        assert!(pool.get().is_err());
+        assert!(connect(&db_path).is_err(), "no fresh pool until reopened");
+
+        assert_eq!(reopen(&db_path)?.idle_count(), 1);
+        assert!(connect(&db_path).is_ok());
+        Ok(())
+    }
+}
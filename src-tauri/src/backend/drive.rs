@@ -0,0 +1,608 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Google Drive as an upload target besides NotebookLM: sign in through the browser
+//! (OAuth with a loopback redirect and PKCE), then send transcripts and audio up with
+//! Drive's resumable uploads. What was sent is tracked per slice, so a changed transcript
+//! replaces its file, an unchanged one is skipped, and an interrupted upload resumes.
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::header::{CONTENT_RANGE, CONTENT_TYPE, LOCATION, RANGE};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::export::percent_encode;
+use super::migrate::sha256_file;
+use super::models::{DriveFile, DriveSyncFailure, DriveSyncReport, FolderImportProgress, NlmSourceKind, Slice};
+use super::nlm_upload::source_title;
+use super::ollama::open_db;
+use super::pii;
+
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const UPLOAD_URL: &str = "https://www.googleapis.com/upload/drive/v3/files";
+
+/// Only files CiderPress itself creates are visible to it.
+const SCOPE: &str = "https://www.googleapis.com/auth/drive.file";
+
+/// How long to wait for the user to finish signing in in the browser.
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Bytes sent per request of a resumable upload; Drive wants a multiple of 256 KiB.
+const CHUNK_SIZE: u64 = 32 * 256 * 1024;
+
+/// Signed-in Drive credentials, kept in the CiderPress home.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DriveToken {
+    access_token: String,
+    refresh_token: String,
+    expires_at: i64, // Unix timestamp
+}
+
+/// Google's token endpoint response.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: i64,
+}
+
+fn token_path(config: &Config) -> PathBuf {
+    config.ciderpress_home_path().join("google-drive-token.json")
+}
+
+/// Whether Drive has been signed in to.
+pub fn is_connected(config: &Config) -> bool {
+    token_path(config).exists()
+}
+
+/// Forget the Drive sign-in. What was uploaded stays tracked.
+pub fn disconnect(config: &Config) -> Result<()> {
+    let path = token_path(config);
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    Ok(())
+}
+
+fn save_token(config: &Config, token: &DriveToken) -> Result<()> {
+    let path = token_path(config);
+    std::fs::write(&path, serde_json::to_string_pretty(token)?)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+fn client_credentials(config: &Config) -> Result<(String, String)> {
+    let client_id = config.drive_client_id.as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| anyhow!("Set a Google OAuth client ID for Drive first"))?;
+    Ok((client_id.to_string(), config.drive_client_secret.clone().unwrap_or_default()))
+}
+
+/// Undo `percent_encode` (and form encoding's `+` for space).
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+fn form_encode(pairs: &[(&str, &str)]) -> String {
+    pairs.iter()
+        .map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Unpadded base64url, as PKCE wants its challenge.
+fn base64url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (u32::from(b) << (16 - 8 * i)));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+        }
+    }
+    out
+}
+
+/// The query parameters of the OAuth redirect, from the first line of its HTTP request
+/// (`GET /?code=...&state=... HTTP/1.1`).
+fn parse_redirect(request_line: &str) -> Vec<(String, String)> {
+    let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let query = target.split_once('?').map(|(_, q)| q).unwrap_or_default();
+    query.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (percent_decode(k), percent_decode(v)))
+        .collect()
+}
+
+/// Exchange a form with Google's token endpoint for tokens.
+async fn request_token(client: &reqwest::Client, form: &[(&str, &str)]) -> Result<TokenResponse> {
+    let response = client.post(TOKEN_URL)
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(form_encode(form))
+        .send()
+        .await
+        .context("Failed to reach Google's token endpoint")?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!("Google sign-in failed ({}): {}", status, body));
+    }
+    serde_json::from_str(&body).context("Unexpected token response from Google")
+}
+
+/// Sign in to Drive: open Google's consent page in the browser and wait for it to
+/// redirect back to a one-off local server with the authorization code.
+pub async fn authorize(config: &Config) -> Result<()> {
+    let (client_id, client_secret) = client_credentials(config)?;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let redirect_uri = format!("http://127.0.0.1:{}", listener.local_addr()?.port());
+
+    let verifier = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    let challenge = base64url(&Sha256::digest(verifier.as_bytes()));
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let auth_url = format!(
+        "{}?{}",
+        AUTH_URL,
+        form_encode(&[
+            ("client_id", &client_id),
+            ("redirect_uri", &redirect_uri),
+            ("response_type", "code"),
+            ("scope", SCOPE),
+            ("access_type", "offline"),
+            ("prompt", "consent"),
+            ("code_challenge", &challenge),
+            ("code_challenge_method", "S256"),
+            ("state", &state),
+        ])
+    );
+    std::process::Command::new("open")
+        .arg(&auth_url)
+        .spawn()
+        .context("Failed to open the browser for Google sign-in")?;
+
+    let code = tokio::time::timeout(AUTH_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| anyhow!("Google sign-in wasn't finished within {} minutes", AUTH_TIMEOUT.as_secs() / 60))??;
+
+    let client = reqwest::Client::new();
+    let token = request_token(&client, &[
+        ("code", &code),
+        ("client_id", &client_id),
+        ("client_secret", &client_secret),
+        ("redirect_uri", &redirect_uri),
+        ("grant_type", "authorization_code"),
+        ("code_verifier", &verifier),
+    ]).await?;
+    let refresh_token = token.refresh_token
+        .ok_or_else(|| anyhow!("Google didn't return a refresh token"))?;
+    save_token(config, &DriveToken {
+        access_token: token.access_token,
+        refresh_token,
+        expires_at: chrono::Utc::now().timestamp() + token.expires_in,
+    })?;
+    info!("Connected to Google Drive");
+    Ok(())
+}
+
+/// Serve redirects until one carries the authorization code (or an error) for `state`.
+async fn wait_for_code(listener: &tokio::net::TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let mut buffer = vec![0u8; 8192];
+        let read = stream.read(&mut buffer).await?;
+        let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+        let params = parse_redirect(request.lines().next().unwrap_or_default());
+        let param = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+
+        let (result, page) = match (param("code"), param("error")) {
+            (Some(code), _) if param("state").as_deref() == Some(state) => (Some(Ok(code)), "Signed in to Google Drive. You can close this tab."),
+            (_, Some(error)) => (Some(Err(anyhow!("Google sign-in was refused: {}", error))), "Sign-in failed. You can close this tab."),
+            _ => (None, "Waiting for Google sign-in…"), // e.g. the browser asking for a favicon
+        };
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            page.len(),
+            page
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        if let Some(result) = result {
+            return result;
+        }
+    }
+}
+
+/// A valid access token, refreshed first when it has (nearly) expired.
+async fn access_token(config: &Config, client: &reqwest::Client) -> Result<String> {
+    let content = std::fs::read_to_string(token_path(config))
+        .map_err(|_| anyhow!("Not signed in to Google Drive"))?;
+    let mut token: DriveToken = serde_json::from_str(&content).context("Unreadable Drive token; sign in again")?;
+    if token.expires_at > chrono::Utc::now().timestamp() + 60 {
+        return Ok(token.access_token);
+    }
+
+    let (client_id, client_secret) = client_credentials(config)?;
+    let refreshed = request_token(client, &[
+        ("client_id", &client_id),
+        ("client_secret", &client_secret),
+        ("refresh_token", &token.refresh_token),
+        ("grant_type", "refresh_token"),
+    ]).await?;
+    token.access_token = refreshed.access_token;
+    token.expires_at = chrono::Utc::now().timestamp() + refreshed.expires_in;
+    if let Some(refresh_token) = refreshed.refresh_token {
+        token.refresh_token = refresh_token;
+    }
+    save_token(config, &token)?;
+    Ok(token.access_token)
+}
+
+/// What one upload sends: a transcript held in memory or an audio file on disk.
+enum Content {
+    Text(Vec<u8>),
+    File(PathBuf),
+}
+
+impl Content {
+    async fn read_chunk(&self, offset: u64, len: u64) -> Result<Vec<u8>> {
+        match self {
+            Content::Text(bytes) => Ok(bytes[offset as usize..(offset + len) as usize].to_vec()),
+            Content::File(path) => {
+                let mut file = tokio::fs::File::open(path).await?;
+                file.seek(std::io::SeekFrom::Start(offset)).await?;
+                let mut buffer = vec![0u8; len as usize];
+                file.read_exact(&mut buffer).await?;
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("m4a") => "audio/mp4",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        Some("aac") => "audio/aac",
+        Some("flac") => "audio/flac",
+        Some("ogg") | Some("oga") | Some("opus") => "audio/ogg",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Start a resumable upload session: a new file, or new content for `file_id`. Returns the
+/// session URI the content goes to.
+async fn start_session(
+    config: &Config,
+    client: &reqwest::Client,
+    token: &str,
+    file_id: Option<&str>,
+    name: &str,
+    mime: &str,
+    size: u64,
+) -> Result<String> {
+    let request = match file_id {
+        Some(id) => client.patch(format!("{}/{}?uploadType=resumable", UPLOAD_URL, id))
+            .body(serde_json::json!({ "name": name }).to_string()),
+        None => {
+            let mut metadata = serde_json::json!({ "name": name });
+            if let Some(folder) = config.drive_folder_id.as_deref().filter(|f| !f.trim().is_empty()) {
+                metadata["parents"] = serde_json::json!([folder.trim()]);
+            }
+            client.post(format!("{}?uploadType=resumable", UPLOAD_URL)).body(metadata.to_string())
+        }
+    };
+    let response = request
+        .bearer_auth(token)
+        .header(CONTENT_TYPE, "application/json; charset=UTF-8")
+        .header("X-Upload-Content-Type", mime)
+        .header("X-Upload-Content-Length", size.to_string())
+        .send()
+        .await
+        .context("Failed to reach Google Drive")?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("Drive refused the upload ({}): {}", status, response.text().await.unwrap_or_default()));
+    }
+    response.headers().get(LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Drive gave no upload session URI"))
+}
+
+/// Where an upload session stands.
+enum SessionState {
+    Offset(u64),     // bytes Drive has so far
+    Done(String),    // finished; the file's ID
+    Expired,         // gone; start a new session
+}
+
+/// The file ID in a finished upload's response body.
+async fn finished_file_id(response: reqwest::Response) -> Result<String> {
+    #[derive(Deserialize)]
+    struct FileResource {
+        id: String,
+    }
+    let body = response.text().await?;
+    Ok(serde_json::from_str::<FileResource>(&body).context("Unexpected response from Drive")?.id)
+}
+
+/// Bytes Drive has confirmed, from a `Range: bytes=0-N` header (none means nothing yet).
+fn confirmed_bytes(response: &reqwest::Response) -> u64 {
+    response.headers().get(RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|r| r.rsplit('-').next())
+        .and_then(|end| end.parse::<u64>().ok())
+        .map_or(0, |end| end + 1)
+}
+
+/// Ask Drive how much of an interrupted upload it has.
+async fn session_state(client: &reqwest::Client, session_uri: &str, size: u64) -> Result<SessionState> {
+    let response = client.put(session_uri)
+        .header(CONTENT_RANGE, format!("bytes */{}", size))
+        .header(reqwest::header::CONTENT_LENGTH, "0")
+        .send()
+        .await?;
+    match response.status().as_u16() {
+        308 => Ok(SessionState::Offset(confirmed_bytes(&response))),
+        200 | 201 => Ok(SessionState::Done(finished_file_id(response).await?)),
+        404 | 410 => Ok(SessionState::Expired),
+        status => Err(anyhow!("Drive upload status check failed ({})", status)),
+    }
+}
+
+/// Send `content` to a session from `offset` on, chunk by chunk. Returns the file's ID.
+async fn send_content(client: &reqwest::Client, session_uri: &str, content: &Content, size: u64, mut offset: u64) -> Result<String> {
+    loop {
+        let len = CHUNK_SIZE.min(size - offset);
+        let chunk = content.read_chunk(offset, len).await?;
+        let range = if size == 0 {
+            "bytes */0".to_string()
+        } else {
+            format!("bytes {}-{}/{}", offset, offset + len - 1, size)
+        };
+        let response = client.put(session_uri)
+            .header(CONTENT_RANGE, range)
+            .body(chunk)
+            .send()
+            .await
+            .context("Drive upload interrupted")?;
+        match response.status().as_u16() {
+            200 | 201 => return finished_file_id(response).await,
+            308 => offset = confirmed_bytes(&response),
+            status => return Err(anyhow!("Drive upload failed ({}): {}", status, response.text().await.unwrap_or_default())),
+        }
+    }
+}
+
+/// Upload one slice's transcript or audio unless Drive already has this exact content.
+/// Returns whether anything was sent.
+async fn sync_item(config: &Config, client: &reqwest::Client, slice: &Slice, kind: NlmSourceKind) -> Result<bool> {
+    let slice_id = slice.id.ok_or_else(|| anyhow!("Slice has no ID"))?;
+    let (name, content, mime, hash) = match kind {
+        NlmSourceKind::Text => {
+            let text = slice.transcription.as_deref()
+                .filter(|t| !t.trim().is_empty())
+                .ok_or_else(|| anyhow!("Slice {} has no transcription", slice_id))?;
+            let hash = format!("{:x}", Sha256::digest(text.as_bytes()));
+            (source_title(slice), Content::Text(text.as_bytes().to_vec()), "text/plain", hash)
+        }
+        NlmSourceKind::Audio => {
+            let path = config.audio_dir().join(&slice.original_audio_file_name);
+            if !path.exists() {
+                return Err(anyhow!("Audio file not found: {}", path.display()));
+            }
+            let hash = match slice.content_hash.clone() {
+                Some(hash) => hash,
+                None => {
+                    let path = path.clone();
+                    tokio::task::spawn_blocking(move || sha256_file(&path)).await??
+                }
+            };
+            let mime = mime_type(&path);
+            (slice.original_audio_file_name.clone(), Content::File(path), mime, hash)
+        }
+    };
+    let size = match &content {
+        Content::Text(bytes) => bytes.len() as u64,
+        Content::File(path) => tokio::fs::metadata(path).await?.len(),
+    };
+
+    let previous = open_db(config)?.get_drive_file(slice_id, kind)?;
+    if previous.as_ref().is_some_and(|p| p.status == "synced" && p.content_hash == hash) {
+        return Ok(false);
+    }
+    let token = access_token(config, client).await?;
+
+    // Pick an interrupted upload of the same content back up where it stopped
+    let mut resume = None;
+    if let Some(DriveFile { status, content_hash, session_uri: Some(uri), .. }) = &previous {
+        if status == "uploading" && *content_hash == hash {
+            match session_state(client, uri, size).await {
+                Ok(SessionState::Offset(offset)) => resume = Some((uri.clone(), offset)),
+                Ok(SessionState::Done(file_id)) => {
+                    save_synced(config, slice_id, kind, &file_id, &hash)?;
+                    return Ok(true);
+                }
+                Ok(SessionState::Expired) => {}
+                Err(e) => warn!("Couldn't resume Drive upload of slice {}: {}", slice_id, e),
+            }
+        }
+    }
+    let (session_uri, offset) = match resume {
+        Some(resume) => resume,
+        None => {
+            let file_id = previous.as_ref().and_then(|p| p.file_id.as_deref());
+            let uri = start_session(config, client, &token, file_id, &name, mime, size).await?;
+            open_db(config)?.save_drive_file(&DriveFile {
+                slice_id,
+                kind,
+                file_id: file_id.map(str::to_string),
+                status: "uploading".to_string(),
+                content_hash: hash.clone(),
+                updated_at: chrono::Utc::now().timestamp(),
+                session_uri: Some(uri.clone()),
+            })?;
+            (uri, 0)
+        }
+    };
+
+    let file_id = send_content(client, &session_uri, &content, size, offset).await?;
+    save_synced(config, slice_id, kind, &file_id, &hash)?;
+    Ok(true)
+}
+
+fn save_synced(config: &Config, slice_id: i64, kind: NlmSourceKind, file_id: &str, hash: &str) -> Result<()> {
+    open_db(config)?.save_drive_file(&DriveFile {
+        slice_id,
+        kind,
+        file_id: Some(file_id.to_string()),
+        status: "synced".to_string(),
+        content_hash: hash.to_string(),
+        updated_at: chrono::Utc::now().timestamp(),
+        session_uri: None,
+    })
+}
+
+/// Send the chosen slices' transcripts and/or audio to Drive, one after another. Content
+/// already there unchanged is skipped; a failure is recorded and the rest carry on.
+pub async fn sync_slices(
+    config: &Config,
+    slice_ids: &[i64],
+    kinds: &[NlmSourceKind],
+    mut on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<DriveSyncReport> {
+    if !is_connected(config) {
+        return Err(anyhow!("Not signed in to Google Drive"));
+    }
+    let client = reqwest::Client::new();
+    let mut report = DriveSyncReport::default();
+    let mut progress = FolderImportProgress {
+        total_files: (slice_ids.len() * kinds.len()) as u32,
+        processed_files: 0,
+        current_file: None,
+    };
+    on_progress(&progress);
+
+    for &slice_id in slice_ids {
+        let slice = open_db(config)?.get_slice(slice_id)?.map(pii::for_export);
+        for &kind in kinds {
+            let result = match &slice {
+                Some(slice) => {
+                    progress.current_file = Some(slice.title.clone().unwrap_or_else(|| slice.original_audio_file_name.clone()));
+                    sync_item(config, &client, slice, kind).await
+                }
+                None => Err(anyhow!("No slice found with ID: {}", slice_id)),
+            };
+            match result {
+                Ok(true) => report.uploaded += 1,
+                Ok(false) => report.unchanged += 1,
+                Err(e) => {
+                    warn!("Drive upload of slice {} failed: {}", slice_id, e);
+                    report.failed.push(DriveSyncFailure { slice_id, kind, message: e.to_string() });
+                }
+            }
+            progress.processed_files += 1;
+            on_progress(&progress);
+        }
+    }
+    info!("Drive sync: {} uploaded, {} unchanged, {} failed", report.uploaded, report.unchanged, report.failed.len());
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::Database;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_url_encoding_round_trips() {
+        assert_eq!(percent_encode("a b/c?d=é"), "a%20b%2Fc%3Fd%3D%C3%A9");
+        assert_eq!(percent_decode("a%20b+c%2Fd%C3%A9"), "a b c/dé");
+        assert_eq!(form_encode(&[("scope", SCOPE), ("x", "1")]), "scope=https%3A%2F%2Fwww.googleapis.com%2Fauth%2Fdrive.file&x=1");
+    }
+
+    #[test]
+    fn test_base64url_matches_rfc_7636_example() {
+        let challenge = base64url(&Sha256::digest(b"dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"));
+        assert_eq!(challenge, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+        assert_eq!(base64url(b"f"), "Zg");
+        assert_eq!(base64url(b"fo"), "Zm8");
+    }
+
+    #[test]
+    fn test_parse_redirect() {
+        let params = parse_redirect("GET /?state=abc&code=4%2F0Ab HTTP/1.1");
+        assert_eq!(params, vec![
+            ("state".to_string(), "abc".to_string()),
+            ("code".to_string(), "4/0Ab".to_string()),
+        ]);
+        assert!(parse_redirect("GET /favicon.ico HTTP/1.1").is_empty());
+    }
+
+    #[test]
+    fn test_drive_files_are_tracked_per_slice_and_kind() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        let file = |kind, status: &str| DriveFile {
+            slice_id: 3,
+            kind,
+            file_id: Some("file-1".to_string()),
+            status: status.to_string(),
+            content_hash: "abc".to_string(),
+            updated_at: 100,
+            session_uri: None,
+        };
+        db.save_drive_file(&file(NlmSourceKind::Text, "uploading")).unwrap();
+        db.save_drive_file(&file(NlmSourceKind::Text, "synced")).unwrap();
+        db.save_drive_file(&file(NlmSourceKind::Audio, "synced")).unwrap();
+
+        assert_eq!(db.get_drive_file(3, NlmSourceKind::Text).unwrap(), Some(file(NlmSourceKind::Text, "synced")));
+        assert_eq!(db.get_slice_drive_files(3).unwrap().len(), 2);
+        assert!(db.get_drive_file(4, NlmSourceKind::Text).unwrap().is_none());
+    }
+}
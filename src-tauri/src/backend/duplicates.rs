@@ -0,0 +1,174 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use std::collections::{BTreeMap, HashSet};
+use tracing::info;
+
+use super::database::Database;
+use super::labeling::words;
+use super::models::{DuplicateGroup, DuplicateReason, Slice};
+
+/// Durations further apart than this are different recordings, whatever they say.
+const DURATION_TOLERANCE_SECONDS: f64 = 1.0;
+
+/// Share of distinct words two transcripts must have in common to count as the same recording;
+/// re-transcribing with another model changes a few words, not most of them.
+const MIN_TRANSCRIPT_SIMILARITY: f64 = 0.9;
+
+/// Distinct words two transcripts share, as a fraction of the words in either (Jaccard index).
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Root of `i` in the union-find `parent` table, flattening the path as it goes.
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut node = i;
+    while parent[node] != root {
+        let next = parent[node];
+        parent[node] = root;
+        node = next;
+    }
+    root
+}
+
+/// Slices with the same duration and nearly the same transcript. Sorting by duration keeps
+/// the comparisons to neighbours within tolerance.
+fn similar_transcript_groups(slices: Vec<&Slice>) -> Vec<Vec<Slice>> {
+    let mut candidates: Vec<(&Slice, f64, HashSet<String>)> = slices
+        .into_iter()
+        .filter_map(|slice| {
+            let duration = slice.audio_time_length_seconds?;
+            let text_words: HashSet<String> = words(slice.transcription.as_deref()?).into_iter().collect();
+            (!text_words.is_empty()).then_some((slice, duration, text_words))
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let mut parent: Vec<usize> = (0..candidates.len()).collect();
+    for i in 0..candidates.len() {
+        for j in i + 1..candidates.len() {
+            if candidates[j].1 - candidates[i].1 > DURATION_TOLERANCE_SECONDS {
+                break;
+            }
+            if similarity(&candidates[i].2, &candidates[j].2) >= MIN_TRANSCRIPT_SIMILARITY {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<Slice>> = BTreeMap::new();
+    for (i, (slice, _, _)) in candidates.iter().enumerate() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push((*slice).clone());
+    }
+    groups.into_values().filter(|group| group.len() > 1).collect()
+}
+
+/// Group the slices outside the trash that are likely copies of each other: identical audio
+/// by content hash, or the same duration with a near-identical transcript (the same memo
+/// imported twice from different sources, or re-encoded on the way). A slice can be in one
+/// group of each kind.
+pub fn find_duplicates(db: &Database) -> Result<Vec<DuplicateGroup>> {
+    let slices = db.list_active_slices()?;
+
+    let mut by_hash: BTreeMap<&str, Vec<&Slice>> = BTreeMap::new();
+    for slice in &slices {
+        if let Some(hash) = slice.content_hash.as_deref() {
+            by_hash.entry(hash).or_default().push(slice);
+        }
+    }
+
+    // Identical copies only need their transcripts compared once, through the first of them
+    let mut groups = Vec::new();
+    let mut grouped: HashSet<i64> = HashSet::new();
+    for group in by_hash.into_values().filter(|group| group.len() > 1) {
+        grouped.extend(group.iter().skip(1).filter_map(|slice| slice.id));
+        groups.push(DuplicateGroup {
+            reason: DuplicateReason::IdenticalAudio,
+            slices: group.into_iter().cloned().collect(),
+        });
+    }
+
+    let compared: Vec<&Slice> = slices
+        .iter()
+        .filter(|slice| slice.id.is_some_and(|id| !grouped.contains(&id)))
+        .collect();
+    for group in similar_transcript_groups(compared) {
+        groups.push(DuplicateGroup { reason: DuplicateReason::SimilarTranscript, slices: group });
+    }
+
+    for group in &mut groups {
+        group.slices.sort_by_key(|slice| slice.id);
+    }
+    groups.sort_by_key(|group| group.slices[0].id);
+
+    info!("Found {} group(s) of likely duplicate slices", groups.len());
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+    use tempfile::TempDir;
+
+    fn slice(name: &str, hash: Option<&str>, duration: Option<f64>, transcription: Option<&str>) -> Slice {
+        Slice {
+            transcribed: transcription.is_some(),
+            audio_time_length_seconds: duration,
+            transcription: transcription.map(str::to_string),
+            content_hash: hash.map(str::to_string),
+            ..create_test_slice(name)
+        }
+    }
+
+    #[test]
+    fn test_find_duplicates_by_hash_and_transcript() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        let text = "remember to call the plumber about the kitchen sink on monday morning before work";
+        let original = db.insert_slice(&slice("a.m4a", Some("aaa"), Some(62.0), Some(text)))?;
+        let copy = db.insert_slice(&slice("a 2.m4a", Some("aaa"), Some(62.0), Some(text)))?;
+        let reencoded = db.insert_slice(&slice("a.opus", Some("bbb"), Some(62.4), Some(text)))?;
+        // Same words but minutes longer, and same length but a different memo
+        db.insert_slice(&slice("long.m4a", Some("ccc"), Some(300.0), Some(text)))?;
+        db.insert_slice(&slice("other.m4a", Some("ddd"), Some(62.0), Some("buy milk eggs and bread")))?;
+        let trashed = db.insert_slice(&slice("trashed.m4a", Some("aaa"), Some(62.0), Some(text)))?;
+        db.trash_slices(&[trashed], 1_000)?;
+
+        let groups = find_duplicates(&db)?;
+        let summary: Vec<_> = groups
+            .iter()
+            .map(|g| (g.reason, g.slices.iter().filter_map(|s| s.id).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(summary, vec![
+            (DuplicateReason::IdenticalAudio, vec![original, copy]),
+            (DuplicateReason::SimilarTranscript, vec![original, reencoded]),
+        ]);
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Hands transcripts to the user's mail client as a ready-to-send draft. Plain drafts go
+//! through a `mailto:` link to whatever mail app is the default; drafts with audio attached
+//! are built in Apple Mail over AppleScript, as `mailto:` can't carry attachments.
+
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone};
+use std::path::PathBuf;
+use std::process::Command;
+use tracing::info;
+
+use super::apple_notes::{applescript_string, run_applescript};
+use super::config::Config;
+use super::database::Database;
+use super::export::{percent_encode, strip_html_tags};
+use super::models::Slice;
+use super::pii;
+
+struct EmailDraft {
+    to: Option<String>,
+    subject: String,
+    body: String,
+    attachments: Vec<PathBuf>,
+}
+
+fn slice_title(slice: &Slice) -> &str {
+    slice.title.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or(&slice.original_audio_file_name)
+}
+
+/// Subject and body for the given slices, laid out like the plain-text transcript export.
+fn compose(slices: &[Slice]) -> (String, String) {
+    let subject = match slices {
+        [slice] => slice_title(slice).to_string(),
+        _ => format!("{} voice memo transcripts", slices.len()),
+    };
+
+    let mut body = String::new();
+    for (i, slice) in slices.iter().enumerate() {
+        if i > 0 {
+            body.push_str("\n-------\n\n");
+        }
+        body.push_str(&format!("Title: {}\n", slice_title(slice)));
+        if let Some(date) = slice.recording_date.and_then(|ts| Local.timestamp_opt(ts, 0).single()) {
+            body.push_str(&format!("Recorded: {}\n", date.format("%Y-%m-%d %H:%M")));
+        }
+        body.push('\n');
+        match &slice.transcription {
+            Some(transcription) => body.push_str(&strip_html_tags(transcription)),
+            None => body.push_str("(not transcribed)"),
+        }
+        body.push('\n');
+    }
+    (subject, body)
+}
+
+fn mailto_url(draft: &EmailDraft) -> String {
+    format!(
+        "mailto:{}?subject={}&body={}",
+        draft.to.as_deref().map(percent_encode).unwrap_or_default(),
+        percent_encode(&draft.subject),
+        percent_encode(&draft.body)
+    )
+}
+
+/// Script opening a new Apple Mail message window with the draft filled in.
+fn mail_script(draft: &EmailDraft) -> String {
+    let mut script = format!(
+        "tell application \"Mail\"\n    set newMessage to make new outgoing message with properties {{subject:{}, content:{}, visible:true}}\n    tell newMessage\n",
+        applescript_string(&draft.subject),
+        applescript_string(&draft.body)
+    );
+    if let Some(to) = &draft.to {
+        script.push_str(&format!(
+            "        make new to recipient at end of to recipients with properties {{address:{}}}\n",
+            applescript_string(to)
+        ));
+    }
+    for path in &draft.attachments {
+        script.push_str(&format!(
+            "        make new attachment with properties {{file name:(POSIX file {})}} at after the last paragraph\n",
+            applescript_string(&path.to_string_lossy())
+        ));
+    }
+    script.push_str("    end tell\n    activate\nend tell\n");
+    script
+}
+
+/// Open a draft email with the given slices' transcripts in the body, addressed to `to` if
+/// set. With `attach_audio` their recordings are attached too, which needs Apple Mail.
+/// Unknown IDs are skipped; slices without a transcript are only included for their audio.
+pub fn compose_email(
+    config: &Config,
+    db: &Database,
+    slice_ids: &[i64],
+    to: Option<&str>,
+    attach_audio: bool,
+) -> Result<()> {
+    let mut slices = Vec::new();
+    let mut attachments = Vec::new();
+    for id in slice_ids {
+        let Some(slice) = db.get_slice(*id)?.map(pii::for_export) else { continue };
+        let audio = config.audio_dir().join(&slice.original_audio_file_name);
+        let has_audio = attach_audio && slice.audio_file_type != "text" && audio.exists();
+        if slice.transcription.is_none() && !has_audio {
+            continue;
+        }
+        if has_audio {
+            attachments.push(audio);
+        }
+        slices.push(slice);
+    }
+    if slices.is_empty() {
+        return Err(anyhow::anyhow!("No transcribed slices found in selection"));
+    }
+
+    let (subject, body) = compose(&slices);
+    let draft = EmailDraft {
+        to: to.map(str::trim).filter(|t| !t.is_empty()).map(str::to_string),
+        subject,
+        body,
+        attachments,
+    };
+
+    if attach_audio {
+        run_applescript(&mail_script(&draft)).context("Failed to create the message in Mail")?;
+    } else {
+        Command::new("open")
+            .arg(mailto_url(&draft))
+            .spawn()
+            .context("Failed to open the mail client")?;
+    }
+    info!("Composed email with {} slice(s) and {} attachment(s)", slices.len(), draft.attachments.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft() -> EmailDraft {
+        EmailDraft {
+            to: Some("me@example.com".to_string()),
+            subject: "Ideas & plans".to_string(),
+            body: "Line one\nSay \"hi\"".to_string(),
+            attachments: vec![PathBuf::from("/tmp/audio/a b.m4a")],
+        }
+    }
+
+    #[test]
+    fn test_mailto_url_encodes_fields() {
+        assert_eq!(
+            mailto_url(&draft()),
+            "mailto:me%40example.com?subject=Ideas%20%26%20plans&body=Line%20one%0ASay%20%22hi%22"
+        );
+    }
+
+    #[test]
+    fn test_mail_script_adds_recipient_and_attachments() {
+        let script = mail_script(&draft());
+        assert!(script.contains(r#"{subject:"Ideas & plans", content:"Line one
+Say \"hi\"", visible:true}"#));
+        assert!(script.contains(r#"{address:"me@example.com"}"#));
+        assert!(script.contains(r#"{file name:(POSIX file "/tmp/audio/a b.m4a")}"#));
+    }
+}
@@ -0,0 +1,185 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The transcript index: transcripts cut into short overlapping passages, each embedded by
+//! the local embedding model and kept in `transcript_chunks`, so passages can be found by
+//! meaning rather than by the words they share with a query.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use tracing::info;
+
+use super::config::Config;
+use super::models::{FolderImportProgress, LlmBatchReport, TranscriptChunk, TranscriptPassage};
+use super::nlm_upload::{split_ranges, text_hash};
+use super::ollama::{open_db, run_over_slices, transcript, Ollama};
+
+/// Passage length and how much consecutive passages share, so a sentence cut at one
+/// boundary is whole in the neighbouring passage.
+const PASSAGE_CHARS: usize = 1_500;
+const PASSAGE_OVERLAP_CHARS: usize = 200;
+
+/// How many passages go to the model per embedding request.
+const EMBED_BATCH: usize = 16;
+
+/// Cosine similarity of two embeddings; 0 when their lengths differ or one is all zeros.
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Cut a transcript into passages, as `(start, end)` character offsets and text.
+pub fn passages(text: &str) -> Vec<(usize, usize, String)> {
+    let chars: Vec<char> = text.chars().collect();
+    split_ranges(&chars, PASSAGE_CHARS, PASSAGE_OVERLAP_CHARS)
+        .into_iter()
+        .map(|(start, end)| (start, end, chars[start..end].iter().collect::<String>()))
+        .filter(|(_, _, passage)| !passage.trim().is_empty())
+        .collect()
+}
+
+/// Embed one slice's transcript and store its passages, replacing any earlier ones.
+/// Returns how many passages were stored.
+pub async fn index_slice(config: &Config, ollama: &Ollama, slice_id: i64) -> Result<usize> {
+    let slice = open_db(config)?
+        .get_slice(slice_id)?
+        .ok_or_else(|| anyhow!("No slice found with ID: {}", slice_id))?;
+    let text = transcript(&slice).ok_or_else(|| anyhow!("Slice {} has no transcription", slice_id))?;
+
+    let mut chunks = Vec::new();
+    let passages = passages(text);
+    for batch in passages.chunks(EMBED_BATCH) {
+        let inputs: Vec<String> = batch.iter().map(|(_, _, passage)| passage.clone()).collect();
+        let embeddings = ollama.embed(&inputs).await?;
+        for ((start, end, passage), embedding) in batch.iter().zip(embeddings) {
+            chunks.push(TranscriptChunk {
+                slice_id,
+                start_char: *start,
+                end_char: *end,
+                text: passage.clone(),
+                embedding,
+            });
+        }
+    }
+    open_db(config)?.replace_transcript_chunks(slice_id, ollama.embedding_model(), &text_hash(text), &chunks)?;
+    Ok(chunks.len())
+}
+
+/// Bring the index up to date, reporting each slice through `on_progress`: transcripts
+/// that are new or have changed since they were embedded (or were embedded with another
+/// model) are embedded again, and passages of slices that lost their transcript are dropped.
+pub async fn update_index(config: &Config, on_progress: impl FnMut(&FolderImportProgress)) -> Result<LlmBatchReport> {
+    let ollama = Ollama::new(config)?;
+    let ollama = &ollama;
+    let db = open_db(config)?;
+    let pruned = db.prune_transcript_chunks()?;
+    if pruned > 0 {
+        info!("Dropped {} indexed passages of slices without a transcript", pruned);
+    }
+    let slice_ids = db.list_transcribed_slice_ids()?;
+    let indexed = db.transcript_index_hashes(ollama.embedding_model())?;
+    drop(db);
+
+    run_over_slices(
+        config,
+        "Transcript indexing",
+        &slice_ids,
+        |slice| match (transcript(slice), slice.id.and_then(|id| indexed.get(&id))) {
+            (None, _) => true,
+            (Some(text), Some(hash)) => *hash == text_hash(text),
+            (Some(_), None) => false,
+        },
+        move |slice| async move { index_slice(config, ollama, slice.id.unwrap_or_default()).await.map(|_| ()) },
+        on_progress,
+    )
+    .await
+}
+
+/// The `limit` indexed passages closest in meaning to `query`, most similar first.
+pub async fn search(config: &Config, ollama: &Ollama, query: &str, limit: usize) -> Result<Vec<TranscriptPassage>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let embedding = ollama
+        .embed(&[query.to_string()])
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("{} returned no embedding", ollama.embedding_model()))?;
+
+    let db = open_db(config)?;
+    let chunks = db.load_transcript_chunks(ollama.embedding_model())?;
+    if chunks.is_empty() {
+        return Err(anyhow!("The transcript index is empty; update it first"));
+    }
+    let mut scored: Vec<(f32, TranscriptChunk)> =
+        chunks.into_iter().map(|chunk| (cosine(&embedding, &chunk.embedding), chunk)).collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(limit);
+
+    let mut slices = HashMap::new();
+    let mut results = Vec::with_capacity(scored.len());
+    for (score, chunk) in scored {
+        if !slices.contains_key(&chunk.slice_id) {
+            slices.insert(chunk.slice_id, db.get_slice(chunk.slice_id)?);
+        }
+        let slice = slices.get(&chunk.slice_id).and_then(|s| s.as_ref());
+        results.push(TranscriptPassage {
+            slice_id: chunk.slice_id,
+            title: slice.and_then(|s| s.title.clone()),
+            recording_date: slice.and_then(|s| s.recording_date),
+            text: chunk.text,
+            score,
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine() {
+        assert!((cosine(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine(&[1.0, 0.0], &[0.0, 3.0]).abs() < 1e-6);
+        assert!((cosine(&[1.0, 1.0], &[-1.0, -1.0]) + 1.0).abs() < 1e-6);
+        assert_eq!(cosine(&[1.0, 2.0], &[1.0]), 0.0);
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+
+    #[test]
+    fn test_passages_cover_the_transcript() {
+        let text = "word ".repeat(1_000);
+        let parts = passages(&text);
+        assert!(parts.len() > 1);
+        assert_eq!(parts[0].0, 0);
+        assert_eq!(parts.last().unwrap().1, text.chars().count());
+        assert!(parts.iter().all(|(start, end, p)| end - start <= PASSAGE_CHARS && p.chars().count() == end - start));
+        assert_eq!(passages("short memo"), vec![(0, 10, "short memo".to_string())]);
+    }
+}
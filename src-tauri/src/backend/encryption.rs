@@ -0,0 +1,259 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tracing::{error, info, warn};
+
+use super::backup;
+use super::config::Config;
+use super::database;
+use super::db_pool::DbPool;
+
+/// Keychain item holding the database passphrase, so it never sits in the config file.
+const KEYCHAIN_SERVICE: &str = "CiderPress Database";
+const KEYCHAIN_ACCOUNT: &str = "CiderPress-db.sqlite";
+
+/// The database passphrase saved in the login keychain, if any.
+pub fn load_passphrase() -> Option<String> {
+    let output = Command::new("/usr/bin/security")
+        .args(["find-generic-password", "-s", KEYCHAIN_SERVICE, "-a", KEYCHAIN_ACCOUNT, "-w"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let passphrase = String::from_utf8(output.stdout).ok()?;
+    Some(passphrase.trim_end_matches('\n').to_string())
+}
+
+/// Save the database passphrase in the login keychain, replacing any earlier one.
+/// Sent on stdin rather than as an argument, where other processes could read it.
+fn store_passphrase(passphrase: &str) -> Result<()> {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut child = Command::new("/usr/bin/security")
+        .arg("-i")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .context("Failed to run the security tool")?;
+    writeln!(
+        child.stdin.take().context("security tool has no stdin")?,
+        "add-generic-password -U -s {} -a {} -w {}",
+        quote(KEYCHAIN_SERVICE), quote(KEYCHAIN_ACCOUNT), quote(passphrase)
+    )?;
+    if !child.wait()?.success() {
+        return Err(anyhow::anyhow!("Failed to save the database passphrase to the keychain"));
+    }
+    Ok(())
+}
+
+/// Whether the file at `db_path` is SQLCipher-encrypted (or otherwise not readable as plain SQLite).
+pub fn is_encrypted(db_path: &Path) -> bool {
+    Connection::open(db_path)
+        .and_then(|conn| conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0)))
+        .is_err()
+}
+
+/// Reject passphrases the keychain wouldn't give back as typed; its tool is line-based.
+fn check_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.is_empty() {
+        return Err(anyhow::anyhow!("The passphrase must not be empty"));
+    }
+    if passphrase.chars().any(char::is_control) {
+        return Err(anyhow::anyhow!("The passphrase must not contain line breaks or other control characters"));
+    }
+    Ok(())
+}
+
+/// Write an encrypted copy of the plaintext database at `db_path` to `dest`.
+fn export_encrypted(db_path: &Path, dest: &Path, passphrase: &str) -> Result<()> {
+    if dest.exists() {
+        fs::remove_file(dest)?;
+    }
+    let conn = Connection::open(db_path)?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+        params![dest.to_string_lossy(), passphrase],
+    )?;
+    conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+    conn.execute("DETACH DATABASE encrypted", [])?;
+    Ok(())
+}
+
+/// Encrypt the plaintext database at `db_path` in place with `passphrase`, and open
+/// connections to it with the passphrase from now on. The encrypted copy is written beside
+/// the original and only then swapped in, so a failure leaves the plaintext database intact.
+pub fn encrypt_database(db_path: &Path, passphrase: &str) -> Result<DbPool> {
+    check_passphrase(passphrase)?;
+    if is_encrypted(db_path) {
+        return Err(anyhow::anyhow!("The database is already encrypted"));
+    }
+
+    let staged = db_path.with_extension("sqlite.encrypting");
+    export_encrypted(db_path, &staged, passphrase)?;
+    database::set_encryption_key(db_path, Some(passphrase.to_string()));
+    match backup::replace_database(db_path, &staged) {
+        Ok(pool) => {
+            info!("Database encrypted");
+            Ok(pool)
+        }
+        Err(e) => {
+            database::set_encryption_key(db_path, None);
+            let _ = fs::remove_file(&staged);
+            Err(e)
+        }
+    }
+}
+
+/// Encrypt the plaintext database copies in `backups_dir` (saved before a restore or a
+/// redaction) with `passphrase`, so no plaintext copy of the library outlives the switch
+/// and the copies stay restorable. A copy that can't be encrypted is deleted. Returns how
+/// many were encrypted.
+fn encrypt_backups(backups_dir: &Path, passphrase: &str) -> Result<u32> {
+    if !backups_dir.is_dir() {
+        return Ok(0);
+    }
+    let mut encrypted = 0;
+    for path in fs::read_dir(backups_dir)?.flatten().map(|e| e.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some("sqlite") || is_encrypted(&path) {
+            continue;
+        }
+        let staged = path.with_extension("sqlite.encrypting");
+        match export_encrypted(&path, &staged, passphrase).and_then(|()| fs::rename(&staged, &path).map_err(Into::into)) {
+            Ok(()) => encrypted += 1,
+            Err(e) => {
+                warn!("Failed to encrypt backup {:?}, deleting it: {}", path, e);
+                let _ = fs::remove_file(&staged);
+                fs::remove_file(&path)?;
+            }
+        }
+    }
+    Ok(encrypted)
+}
+
+/// One-time switch of the library database to SQLCipher: saves the passphrase to the
+/// keychain and checks it reads back, encrypts the existing plaintext file, then the
+/// plaintext copies in the backups folder.
+pub fn enable_encryption(config: &Config, passphrase: &str) -> Result<DbPool> {
+    let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+    check_passphrase(passphrase)?;
+    store_passphrase(passphrase)?;
+    // Only encrypt with a passphrase the next launch will read back unchanged
+    if load_passphrase().as_deref() != Some(passphrase) {
+        return Err(anyhow::anyhow!("The passphrase could not be read back from the keychain; the database was left unencrypted"));
+    }
+    let pool = encrypt_database(&db_path, passphrase)?;
+    // The library itself is encrypted by now, so this can't undo the switch
+    match encrypt_backups(&config.backups_dir(), passphrase) {
+        Ok(count) => info!("Encrypted {} database backup(s)", count),
+        Err(e) => error!("Failed to encrypt the database backups: {}", e),
+    }
+    Ok(pool)
+}
+
+/// Make the keychain passphrase known to the database layer when the library is encrypted.
+/// Call before the first connection is opened.
+pub fn apply_config(config: &Config) {
+    if !config.database_encrypted {
+        return;
+    }
+    let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+    match load_passphrase() {
+        Some(passphrase) => database::set_encryption_key(&db_path, Some(passphrase)),
+        None => warn!("Database is encrypted but no passphrase was found in the keychain"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::Database;
+    use crate::backend::db_pool;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_encrypt_database_in_place() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("CiderPress-db.sqlite");
+        let label_id = db_pool::connect(&db_path)?.get_or_create_label("Journal", "#228be6")?;
+        assert!(!is_encrypted(&db_path));
+
+        assert!(encrypt_database(&db_path, "").is_err());
+        assert!(encrypt_database(&db_path, "correct\nhorse").is_err());
+        let pool = encrypt_database(&db_path, "correct horse")?;
+        assert!(is_encrypted(&db_path));
+        assert!(encrypt_database(&db_path, "correct horse").is_err());
+
+        // Existing data carries over, and new connections use the passphrase
+        assert_eq!(pool.get()?.get_or_create_label("Journal", "#228be6")?, label_id);
+        let wrong = Connection::open(&db_path)?;
+        wrong.pragma_update(None, "key", "wrong")?;
+        assert!(wrong.query_row("SELECT COUNT(*) FROM labels", [], |row| row.get::<_, i64>(0)).is_err());
+
+        database::set_encryption_key(&db_path, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypted_library_reads_plaintext_apple_database() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("CiderPress-db.sqlite");
+        db_pool::connect(&db_path)?;
+        let pool = encrypt_database(&db_path, "correct horse")?;
+
+        // Apple's database stays plaintext, and its path may contain a quote
+        let apple_db_path = temp_dir.path().join("Bob's Recordings.db");
+        Connection::open(&apple_db_path)?.execute_batch(
+            r#"
+            CREATE TABLE ZFOLDER (Z_PK INTEGER PRIMARY KEY, ZENCRYPTEDNAME TEXT);
+            CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT, ZFOLDER INTEGER);
+            INSERT INTO ZFOLDER (Z_PK, ZENCRYPTEDNAME) VALUES (1, 'Work');
+            INSERT INTO ZCLOUDRECORDING (ZPATH, ZFOLDER) VALUES ('work.m4a', 1);
+            "#,
+        )?;
+
+        let db = pool.get()?;
+        let apple_db = apple_db_path.to_str().unwrap();
+        assert_eq!(db.copy_zcloudrecording_table(apple_db)?, 1);
+        assert_eq!(db.import_apple_folders(apple_db)?, 1);
+        assert!(!is_encrypted(&apple_db_path));
+
+        database::set_encryption_key(&db_path, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_plaintext_backups_are_encrypted() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let backups_dir = temp_dir.path().join("backups");
+        fs::create_dir_all(&backups_dir)?;
+        let backup = backups_dir.join("CiderPress-db-before-restore-20260101-120000.sqlite");
+        Database::new(&backup)?.get_or_create_label("Journal", "#228be6")?;
+        fs::write(backups_dir.join("notes.txt"), "not a database")?;
+
+        assert_eq!(encrypt_backups(&backups_dir, "correct horse")?, 1);
+        assert!(is_encrypted(&backup));
+        assert!(backups_dir.join("notes.txt").exists());
+        assert_eq!(backup::validate_backup(&backup, Some("correct horse"))?, 0);
+        assert_eq!(encrypt_backups(&backups_dir, "correct horse")?, 0, "already encrypted");
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! People, places and organizations named in transcripts, found by the local LLM and kept
+//! in `slice_entities` so the library can be searched by who or what a memo mentions.
+
+use anyhow::{anyhow, Result};
+
+use super::config::Config;
+use super::database::normalize_entity_name;
+use super::models::{EntityKind, FolderImportProgress, LlmBatchReport, SliceEntity};
+use super::nlm_upload::{split_ranges, text_hash};
+use super::ollama::{open_db, run_over_slices, transcript, Ollama};
+use super::search::replacement_regex;
+
+/// Most of a transcript scanned in one request; names cut at a boundary turn up whole in
+/// the overlap.
+const CHUNK_CHARS: usize = 8_000;
+const CHUNK_OVERLAP_CHARS: usize = 200;
+
+const ENTITY_INSTRUCTIONS: &str = "You find the named entities in a transcript of a voice recording. Reply with \
+JSON only, in the form {\"people\": [string], \"places\": [string], \"organizations\": [string]}, listing each \
+person, place and organization mentioned by name once, spelled as in the transcript. Leave out pronouns, \
+generic words like \"the doctor\" and anything not named.";
+
+/// Read the entities out of the model's reply, each kind's names once, in reply order.
+pub fn parse_entities(reply: &str) -> Vec<(EntityKind, String)> {
+    // Models sometimes wrap the JSON in prose or a code fence
+    let value: serde_json::Value = match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str(&reply[start..=end]).unwrap_or_default(),
+        _ => return Vec::new(),
+    };
+    let mut entities: Vec<(EntityKind, String)> = Vec::new();
+    for (key, kind) in [
+        ("people", EntityKind::Person),
+        ("places", EntityKind::Place),
+        ("organizations", EntityKind::Organization),
+    ] {
+        for name in value[key].as_array().into_iter().flatten().filter_map(|v| v.as_str()) {
+            let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+            let normalized = normalize_entity_name(&name);
+            let seen = entities
+                .iter()
+                .any(|(k, n)| *k == kind && normalize_entity_name(n) == normalized);
+            if !normalized.is_empty() && !seen {
+                entities.push((kind, name));
+            }
+        }
+    }
+    entities
+}
+
+/// How many times `name` occurs in `text` as whole words, ignoring case; at least 1, since
+/// the model saw it.
+pub fn count_mentions(text: &str, name: &str) -> u32 {
+    let count = replacement_regex(&normalize_entity_name(name), false, false)
+        .map(|regex| regex.find_iter(text).count())
+        .unwrap_or(0);
+    (count as u32).max(1)
+}
+
+/// Find the entities in a transcript, part by part if it is long.
+pub async fn extract_from_text(ollama: &Ollama, text: &str) -> Result<Vec<(EntityKind, String)>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut entities: Vec<(EntityKind, String)> = Vec::new();
+    for (start, end) in split_ranges(&chars, CHUNK_CHARS, CHUNK_OVERLAP_CHARS) {
+        let part: String = chars[start..end].iter().collect();
+        let reply = ollama.generate_json(Some(ENTITY_INSTRUCTIONS), &part).await?;
+        for (kind, name) in parse_entities(&reply) {
+            let normalized = normalize_entity_name(&name);
+            if !entities.iter().any(|(k, n)| *k == kind && normalize_entity_name(n) == normalized) {
+                entities.push((kind, name));
+            }
+        }
+    }
+    Ok(entities)
+}
+
+/// Find the entities in one slice's transcript and store them, replacing earlier ones.
+pub async fn extract_slice(config: &Config, ollama: &Ollama, slice_id: i64) -> Result<Vec<SliceEntity>> {
+    let slice = open_db(config)?
+        .get_slice(slice_id)?
+        .ok_or_else(|| anyhow!("No slice found with ID: {}", slice_id))?;
+    let text = transcript(&slice).ok_or_else(|| anyhow!("Slice {} has no transcription", slice_id))?;
+
+    let entities: Vec<SliceEntity> = extract_from_text(ollama, text)
+        .await?
+        .into_iter()
+        .map(|(kind, name)| SliceEntity { slice_id, kind, mentions: count_mentions(text, &name), name })
+        .collect();
+    let db = open_db(config)?;
+    db.replace_slice_entities(slice_id, ollama.model(), &text_hash(text), &entities)?;
+    db.get_slice_entities(slice_id)
+}
+
+/// Find entities in slices one after another, reporting each through `on_progress`: the
+/// given ones, or with `None` every transcribed slice. Slices scanned since their
+/// transcript last changed are skipped unless `overwrite`.
+pub async fn extract_slices(
+    config: &Config,
+    slice_ids: Option<Vec<i64>>,
+    overwrite: bool,
+    on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<LlmBatchReport> {
+    let ollama = Ollama::new(config)?;
+    let ollama = &ollama;
+    let db = open_db(config)?;
+    let slice_ids = match slice_ids {
+        Some(ids) => ids,
+        None => db.list_transcribed_slice_ids()?,
+    };
+    let scanned = if overwrite { Default::default() } else { db.entity_extraction_hashes()? };
+    drop(db);
+
+    run_over_slices(
+        config,
+        "Entity extraction",
+        &slice_ids,
+        |slice| match (transcript(slice), slice.id.and_then(|id| scanned.get(&id))) {
+            (None, _) => true,
+            (Some(text), Some(hash)) => *hash == text_hash(text),
+            (Some(_), None) => false,
+        },
+        move |slice| async move { extract_slice(config, ollama, slice.id.unwrap_or_default()).await.map(|_| ()) },
+        on_progress,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entities() {
+        let reply = "Sure:\n```json\n{\"people\": [\"Dr. Alvarez\", \"dr.  alvarez\", \"\"], \"places\": [\"Lisbon\"], \"organizations\": null}\n```";
+        assert_eq!(
+            parse_entities(reply),
+            vec![(EntityKind::Person, "Dr. Alvarez".to_string()), (EntityKind::Place, "Lisbon".to_string())]
+        );
+        assert!(parse_entities("No entities here.").is_empty());
+        assert!(parse_entities("{not json}").is_empty());
+    }
+
+    #[test]
+    fn test_count_mentions() {
+        let text = "Called Dr. Alvarez today. DR. ALVAREZ says rest.";
+        assert_eq!(count_mentions(text, "Dr. Alvarez"), 2);
+        assert_eq!(count_mentions(text, "Alvarez's office"), 1);
+        assert_eq!(count_mentions("Ana and ANA met Anabel.", "Ana"), 2);
+    }
+}
@@ -0,0 +1,810 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use chrono::{Local, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::convert::{remux_with_metadata, transcode_to_m4a};
+use super::database::Database;
+use super::models::{ImportFailure, LibraryExportReport, Slice, SliceFilter, SliceQuery, SliceSortField};
+use super::pii;
+
+/// Longest title kept in an exported filename, in characters.
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Make a title safe to use in a filename on macOS, Windows and Linux.
+pub fn sanitize_title(title: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { ' ' } else { c })
+        .collect();
+    let collapsed = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    collapsed.chars().take(MAX_TITLE_CHARS).collect::<String>().trim_end_matches(['.', ' ']).to_string()
+}
+
+/// Simple HTML tag stripping helper
+pub fn strip_html_tags(html: &str) -> String {
+    let mut result = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => {
+                in_tag = false;
+                // Add space after closing tags that typically end blocks
+            }
+            _ if !in_tag => result.push(c),
+            _ => {}
+        }
+    }
+
+    // Clean up multiple whitespace and trim
+    result
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters, for a URL component
+/// (a `mailto:` field, a query value, a path segment).
+pub fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Escape text for use in HTML element content and quoted attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Voice Memos-style name for a slice: `20230415 102231-Title.m4a`, the recording date
+/// as local time followed by the title. Slices without a date or title keep what they have.
+pub fn voice_memos_file_name(slice: &Slice) -> String {
+    let stem = Path::new(&slice.original_audio_file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("recording");
+    let title = slice.title.as_deref()
+        .map(sanitize_title)
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| sanitize_title(stem));
+    let date = slice.recording_date.and_then(|ts| Local.timestamp_opt(ts, 0).single());
+
+    match date {
+        Some(date) => format!("{}-{}.m4a", date.format("%Y%m%d %H%M%S"), title),
+        None => format!("{}.m4a", title),
+    }
+}
+
+/// `name`, or `name 2`, `name 3`, ... if an earlier export already took it.
+fn unique_file_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let (stem, ext) = name.rsplit_once('.').unwrap_or((name, ""));
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while !taken.insert(candidate.to_lowercase()) {
+        candidate = format!("{} {}.{}", stem, n, ext);
+        n += 1;
+    }
+    candidate
+}
+
+/// Longest transcript excerpt written into an exported file's comment tag, in characters.
+const MAX_EXCERPT_CHARS: usize = 250;
+
+/// The start of a transcript as plain text, cut at a word boundary with an ellipsis if longer
+/// than `max_chars`.
+fn transcript_excerpt(transcription: &str, max_chars: usize) -> String {
+    let text = strip_html_tags(transcription);
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > 0 => &cut[..space],
+        _ => &cut,
+    };
+    format!("{}…", cut.trim_end_matches([',', '.', ';', ':']))
+}
+
+/// Container tags carrying the slice's title and recording date into the exported file, and
+/// with `with_transcript` the start of its transcript as a comment. ffmpeg maps these to the
+/// MP4 atoms or ID3 frames of the output format.
+fn embedded_metadata(slice: &Slice, with_transcript: bool) -> Vec<(&'static str, String)> {
+    let mut metadata = Vec::new();
+    if let Some(title) = &slice.title {
+        metadata.push(("title", title.clone()));
+    }
+    if let Some(date) = slice.recording_date.and_then(|ts| Utc.timestamp_opt(ts, 0).single()) {
+        metadata.push(("creation_time", date.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string()));
+        metadata.push(("date", date.format("%Y-%m-%d").to_string()));
+    }
+    if let Some(transcription) = slice.transcription.as_deref().filter(|_| with_transcript) {
+        let excerpt = transcript_excerpt(transcription, MAX_EXCERPT_CHARS);
+        if !excerpt.is_empty() {
+            metadata.push(("comment", excerpt));
+        }
+    }
+    metadata
+}
+
+/// Write one slice's audio to `dest` as an .m4a with its title and date (and with
+/// `with_transcript` a transcript excerpt) embedded, and the file's modification time set to
+/// the recording date.
+fn export_recording(config: &Config, slice: &Slice, dest: &Path, with_transcript: bool) -> Result<()> {
+    let source = config.audio_dir().join(&slice.original_audio_file_name);
+    if !source.exists() {
+        return Err(anyhow::anyhow!("Audio file not found: {:?}", source));
+    }
+
+    let is_m4a = source.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("m4a"));
+    let staging_dir = config.ciderpress_home_path().join("conversion_staging");
+    let staged = staging_dir.join(format!("export-{}", dest.file_name().unwrap_or_default().to_string_lossy()));
+    let audio = if is_m4a {
+        source.clone()
+    } else {
+        fs::create_dir_all(&staging_dir)?;
+        transcode_to_m4a(&source, &staged)?;
+        staged.clone()
+    };
+
+    // A file ffmpeg can't remux still gets exported, just without the embedded tags
+    let remuxed = remux_with_metadata(&audio, dest, &embedded_metadata(slice, with_transcript));
+    let result = match remuxed {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            warn!("Could not embed metadata in {:?} ({}), copying as is", dest, e);
+            fs::copy(&audio, dest).map(|_| ()).map_err(anyhow::Error::from)
+        }
+    };
+    if staged.exists() {
+        let _ = fs::remove_file(&staged);
+    }
+    result?;
+
+    if let Some(ts) = slice.recording_date.filter(|ts| *ts >= 0) {
+        let modified = UNIX_EPOCH + Duration::from_secs(ts as u64);
+        fs::File::options().write(true).open(dest)?.set_modified(modified)?;
+    }
+    Ok(())
+}
+
+/// Rebuild a flat folder of `.m4a` recordings, named and dated the way Voice Memos names
+/// them, for taking the library elsewhere. `slice_ids` limits the export; text slices
+/// have no audio and are left out. `embed_transcript` also tags each file with the start
+/// of its transcript.
+pub fn export_voice_memos_layout(
+    config: &Config,
+    db: &Database,
+    dest_dir: &Path,
+    slice_ids: Option<&[i64]>,
+    embed_transcript: bool,
+) -> Result<LibraryExportReport> {
+    fs::create_dir_all(dest_dir)?;
+
+    let slices: Vec<Slice> = db.list_all_slices()?
+        .into_iter()
+        .filter(|s| s.audio_file_type != "text")
+        .filter(|s| slice_ids.map_or(true, |ids| s.id.is_some_and(|id| ids.contains(&id))))
+        .map(pii::for_export)
+        .collect();
+
+    let mut report = LibraryExportReport {
+        destination: dest_dir.to_string_lossy().to_string(),
+        ..LibraryExportReport::default()
+    };
+    let mut taken: HashSet<String> = HashSet::new();
+
+    for slice in &slices {
+        let file_name = unique_file_name(&voice_memos_file_name(slice), &mut taken);
+        let dest = dest_dir.join(&file_name);
+        match export_recording(config, slice, &dest, embed_transcript) {
+            Ok(()) => report.exported_files.push(file_name),
+            Err(e) => {
+                warn!("Failed to export {}: {}", slice.original_audio_file_name, e);
+                report.errors.push(ImportFailure {
+                    file_path: slice.original_audio_file_name.clone(),
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+
+    info!(
+        "Exported {} recordings to {:?} ({} failed)",
+        report.exported_files.len(),
+        dest_dir,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+const HTML_STYLE: &str = "body { font-family: -apple-system, sans-serif; max-width: 50em; margin: 2em auto; padding: 0 1em; }
+section { border-top: 1px solid #ddd; padding: 1em 0; }
+.meta { color: #666; font-size: 0.9em; }
+audio { width: 100%; }
+p.transcript { line-height: 1.5; }";
+
+/// Write a self-contained review page: `index.html` in `dest_dir` with each slice's title,
+/// date, transcript and an `<audio>` player for its recording, copied into `audio/` next to
+/// it so the folder can be opened anywhere. Unknown IDs are skipped; text slices get no
+/// player. The report lists the audio files copied.
+pub fn export_html(config: &Config, db: &Database, slice_ids: &[i64], dest_dir: &Path) -> Result<LibraryExportReport> {
+    let audio_dir = dest_dir.join("audio");
+    fs::create_dir_all(&audio_dir)?;
+
+    let mut report = LibraryExportReport {
+        destination: dest_dir.to_string_lossy().to_string(),
+        ..LibraryExportReport::default()
+    };
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut sections = String::new();
+
+    for id in slice_ids {
+        let Some(slice) = db.get_slice(*id)?.map(pii::for_export) else { continue };
+        let title = slice.title.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or(&slice.original_audio_file_name);
+
+        sections.push_str(&format!("<section>\n<h2>{}</h2>\n", escape_html(title)));
+        if let Some(date) = slice.recording_date.and_then(|ts| Local.timestamp_opt(ts, 0).single()) {
+            sections.push_str(&format!("<p class=\"meta\">{}</p>\n", date.format("%Y-%m-%d %H:%M")));
+        }
+
+        if slice.audio_file_type != "text" {
+            let source = config.audio_dir().join(&slice.original_audio_file_name);
+            let file_name = unique_file_name(&slice.original_audio_file_name, &mut taken);
+            match fs::copy(&source, audio_dir.join(&file_name)) {
+                Ok(_) => {
+                    sections.push_str(&format!(
+                        "<audio controls preload=\"none\" src=\"audio/{}\"></audio>\n",
+                        percent_encode(&file_name)
+                    ));
+                    report.exported_files.push(format!("audio/{}", file_name));
+                }
+                Err(e) => {
+                    warn!("Failed to copy {:?} for HTML export: {}", source, e);
+                    report.errors.push(ImportFailure {
+                        file_path: slice.original_audio_file_name.clone(),
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        match slice.transcription.as_deref().map(strip_html_tags).filter(|t| !t.is_empty()) {
+            Some(text) => sections.push_str(&format!("<p class=\"transcript\">{}</p>\n", escape_html(&text))),
+            None => sections.push_str("<p class=\"meta\">No transcription</p>\n"),
+        }
+        sections.push_str("</section>\n");
+    }
+
+    let page = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>CiderPress export</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<h1>CiderPress export</h1>\n<p class=\"meta\">Exported {}</p>\n{}</body>\n</html>\n",
+        HTML_STYLE,
+        Local::now().format("%Y-%m-%d %H:%M"),
+        sections
+    );
+    fs::write(dest_dir.join("index.html"), page)?;
+
+    Ok(report)
+}
+
+/// Manifest entry for one slice in a zip export; paths are relative to the archive root.
+#[derive(Debug, Serialize)]
+struct ZipManifestEntry {
+    id: i64,
+    title: Option<String>,
+    recording_date: Option<i64>,
+    duration_seconds: Option<f64>,
+    labels: Vec<String>,
+    audio: Option<String>,
+    transcript: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ZipManifest {
+    app_version: String,
+    exported_at: i64,
+    slices: Vec<ZipManifestEntry>,
+}
+
+/// A transcript as a small Markdown document: title, recording date, then the text.
+pub fn transcript_markdown(slice: &Slice, transcription: &str) -> String {
+    let title = slice.title.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or(&slice.original_audio_file_name);
+    let mut markdown = format!("# {}\n\n", title);
+    if let Some(date) = slice.recording_date.and_then(|ts| Local.timestamp_opt(ts, 0).single()) {
+        markdown.push_str(&format!("Recorded {}\n\n", date.format("%Y-%m-%d %H:%M")));
+    }
+    markdown.push_str(&strip_html_tags(transcription));
+    markdown.push('\n');
+    markdown
+}
+
+/// Bundle the given slices into one zip at `dest`: their audio under `audio/`, transcripts as
+/// Markdown under `transcripts/`, and a `manifest.json` tying each slice to its files. Unknown
+/// IDs are skipped; missing audio is reported and the slice's transcript still included.
+/// With a `password` every entry, manifest included, is AES-256 encrypted.
+pub fn export_zip(
+    config: &Config,
+    db: &Database,
+    slice_ids: &[i64],
+    dest: &Path,
+    password: Option<&str>,
+) -> Result<LibraryExportReport> {
+    if password.is_some_and(str::is_empty) {
+        return Err(anyhow::anyhow!("The archive password must not be empty"));
+    }
+    let mut labels_by_slice = db.get_labels_for_all_slices()?;
+    let mut report = LibraryExportReport {
+        destination: dest.to_string_lossy().to_string(),
+        ..LibraryExportReport::default()
+    };
+
+    let mut zip = zip::ZipWriter::new(fs::File::create(dest)?);
+    // Audio is compressed already; deflating it again only costs time
+    let options = |method| {
+        let options = zip::write::SimpleFileOptions::default().compression_method(method);
+        match password {
+            Some(password) => options.with_aes_encryption(zip::AesMode::Aes256, password),
+            None => options,
+        }
+    };
+    let stored = options(zip::CompressionMethod::Stored);
+    let deflated = options(zip::CompressionMethod::Deflated);
+
+    let mut taken_audio: HashSet<String> = HashSet::new();
+    let mut taken_transcripts: HashSet<String> = HashSet::new();
+    let mut entries = Vec::new();
+
+    for id in slice_ids {
+        let Some(slice) = db.get_slice(*id)?.map(pii::for_export) else { continue };
+        let mut entry = ZipManifestEntry {
+            id: *id,
+            title: slice.title.clone(),
+            recording_date: slice.recording_date,
+            duration_seconds: slice.audio_time_length_seconds,
+            labels: labels_by_slice.remove(id).unwrap_or_default().into_iter().map(|l| l.name).collect(),
+            audio: None,
+            transcript: None,
+        };
+
+        if slice.audio_file_type != "text" {
+            let source = config.audio_dir().join(&slice.original_audio_file_name);
+            match fs::File::open(&source) {
+                Ok(mut audio) => {
+                    let name = format!("audio/{}", unique_file_name(&slice.original_audio_file_name, &mut taken_audio));
+                    zip.start_file(name.as_str(), stored)?;
+                    std::io::copy(&mut audio, &mut zip)?;
+                    report.exported_files.push(name.clone());
+                    entry.audio = Some(name);
+                }
+                Err(e) => {
+                    warn!("Failed to add {:?} to zip export: {}", source, e);
+                    report.errors.push(ImportFailure {
+                        file_path: slice.original_audio_file_name.clone(),
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        if let Some(transcription) = &slice.transcription {
+            let stem = Path::new(&slice.original_audio_file_name)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("transcript");
+            let name = format!("transcripts/{}", unique_file_name(&format!("{}.md", stem), &mut taken_transcripts));
+            zip.start_file(name.as_str(), deflated)?;
+            zip.write_all(transcript_markdown(&slice, transcription).as_bytes())?;
+            report.exported_files.push(name.clone());
+            entry.transcript = Some(name);
+        }
+        entries.push(entry);
+    }
+
+    let manifest = ZipManifest {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: Utc::now().timestamp(),
+        slices: entries,
+    };
+    zip.start_file("manifest.json", deflated)?;
+    serde_json::to_writer_pretty(&mut zip, &manifest)?;
+    zip.finish()?.sync_all()?;
+
+    info!("Exported {} slices to zip {:?} ({} failed)", manifest.slices.len(), dest, report.errors.len());
+    Ok(report)
+}
+
+/// Local-time bounds of `year` as a slice filter, for exporting one year of recordings.
+pub fn year_filter(year: i32) -> Result<SliceFilter> {
+    let start = |year| {
+        Local.with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+            .earliest()
+            .map(|date| date.timestamp())
+            .ok_or_else(|| anyhow::anyhow!("Invalid year: {}", year))
+    };
+    Ok(SliceFilter {
+        recorded_after: Some(start(year)?),
+        recorded_before: Some(start(year + 1)?),
+        ..SliceFilter::default()
+    })
+}
+
+/// Write the transcribed slices matching `query` as one Markdown "book" at `dest`: a table of
+/// contents, then a section per month in recording order, with undated recordings at the end.
+/// Returns the number of entries written.
+pub fn export_book(db: &Database, query: &SliceQuery, title: &str, dest: &Path) -> Result<usize> {
+    let query = SliceQuery {
+        sort_by: SliceSortField::RecordingDate,
+        descending: false,
+        limit: None,
+        offset: None,
+        filter: SliceFilter { transcribed: Some(true), ..query.filter.clone() },
+        ..query.clone()
+    };
+    let mut slices: Vec<Slice> = db.query_slices(&query)?.slices.into_iter().map(pii::for_export).collect();
+    // Pinned slices lead the listing; a book is strictly chronological
+    slices.sort_by_key(|s| (s.recording_date.is_none(), s.recording_date, s.id));
+
+    // (heading, anchor, entries) per month
+    let mut months: Vec<(String, String, Vec<(&Slice, String)>)> = Vec::new();
+    for slice in &slices {
+        let date = slice.recording_date.and_then(|ts| Local.timestamp_opt(ts, 0).single());
+        let (heading, anchor) = match date {
+            Some(date) => (date.format("%B %Y").to_string(), date.format("month-%Y-%m").to_string()),
+            None => ("Undated".to_string(), "month-undated".to_string()),
+        };
+        let entry_title = match (date, slice.title.as_deref().filter(|t| !t.trim().is_empty())) {
+            (Some(date), Some(title)) => format!("{} — {}", date.format("%-d %B, %H:%M"), title),
+            (Some(date), None) => date.format("%-d %B, %H:%M").to_string(),
+            (None, title) => title.unwrap_or(&slice.original_audio_file_name).to_string(),
+        };
+        match months.last_mut() {
+            Some((last, _, entries)) if *last == heading => entries.push((slice, entry_title)),
+            _ => months.push((heading, anchor, vec![(slice, entry_title)])),
+        }
+    }
+
+    let mut book = format!("# {}\n\n## Contents\n\n", title);
+    for (heading, anchor, entries) in &months {
+        book.push_str(&format!("- [{}](#{})\n", heading, anchor));
+        for (slice, entry_title) in entries {
+            book.push_str(&format!("  - [{}](#entry-{})\n", entry_title, slice.id.unwrap_or_default()));
+        }
+    }
+    for (heading, anchor, entries) in &months {
+        book.push_str(&format!("\n<a id=\"{}\"></a>\n\n## {}\n", anchor, heading));
+        for (slice, entry_title) in entries {
+            book.push_str(&format!(
+                "\n<a id=\"entry-{}\"></a>\n\n### {}\n\n{}\n",
+                slice.id.unwrap_or_default(),
+                entry_title,
+                strip_html_tags(slice.transcription.as_deref().unwrap_or_default())
+            ));
+        }
+    }
+    fs::write(dest, book)?;
+
+    info!("Exported a book of {} entries to {:?}", slices.len(), dest);
+    Ok(slices.len())
+}
+
+/// One slice as written by the JSON export: every slice field, plus its label names and
+/// metadata. Transcripts are stored as one text, so there are no timed segments to include.
+#[derive(Debug, Serialize)]
+struct SliceExportRecord {
+    #[serde(flatten)]
+    slice: Slice,
+    labels: Vec<String>,
+    metadata: BTreeMap<String, String>,
+}
+
+/// Write the given slices to `dest` for post-processing with other tools: one JSON array,
+/// or with `lines` one JSON object per line (JSONL). Unknown IDs are skipped; the order of
+/// `slice_ids` is kept. Returns the number of slices written.
+pub fn export_slices_json(db: &Database, slice_ids: &[i64], dest: &Path, lines: bool) -> Result<usize> {
+    let mut labels_by_slice = db.get_labels_for_all_slices()?;
+
+    let mut records = Vec::new();
+    for id in slice_ids {
+        let Some(slice) = db.get_slice(*id)?.map(pii::for_export) else { continue };
+        records.push(SliceExportRecord {
+            labels: labels_by_slice.remove(id).unwrap_or_default().into_iter().map(|l| l.name).collect(),
+            metadata: db.get_slice_metadata(*id)?,
+            slice,
+        });
+    }
+
+    let mut file = std::io::BufWriter::new(fs::File::create(dest)?);
+    if lines {
+        for record in &records {
+            serde_json::to_writer(&mut file, record)?;
+            file.write_all(b"\n")?;
+        }
+    } else {
+        serde_json::to_writer_pretty(&mut file, &records)?;
+    }
+    file.flush()?;
+
+    info!("Exported {} slices as JSON to {:?}", records.len(), dest);
+    Ok(records.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+    use tempfile::TempDir;
+
+    fn slice(filename: &str, title: Option<&str>, recording_date: Option<i64>) -> Slice {
+        Slice {
+            title: title.map(str::to_string),
+            recording_date,
+            ..create_test_slice(filename)
+        }
+    }
+
+    #[test]
+    fn test_voice_memos_file_name() {
+        let date = Local.with_ymd_and_hms(2023, 4, 15, 10, 22, 31).unwrap().timestamp();
+        assert_eq!(
+            voice_memos_file_name(&slice("20230415 102231-3F2504E0.m4a", Some("Grocery list: eggs/milk"), Some(date))),
+            "20230415 102231-Grocery list eggs milk.m4a"
+        );
+        assert_eq!(voice_memos_file_name(&slice("memo.m4a", None, None)), "memo.m4a");
+        assert_eq!(voice_memos_file_name(&slice("memo.m4a", Some("  ..  "), None)), "memo.m4a");
+
+        let mut taken = HashSet::new();
+        assert_eq!(unique_file_name("Idea.m4a", &mut taken), "Idea.m4a");
+        assert_eq!(unique_file_name("idea.m4a", &mut taken), "idea 2.m4a");
+        assert_eq!(unique_file_name("Idea.m4a", &mut taken), "Idea 3.m4a");
+    }
+
+    #[test]
+    fn test_embedded_metadata_transcript_excerpt() {
+        let mut memo = slice("memo.m4a", Some("Standup"), None);
+        memo.transcription = Some("<p>We shipped the build, then we argued about lunch</p>".to_string());
+        assert_eq!(embedded_metadata(&memo, false), vec![("title", "Standup".to_string())]);
+        assert_eq!(
+            embedded_metadata(&memo, true)[1],
+            ("comment", "We shipped the build, then we argued about lunch".to_string())
+        );
+
+        assert_eq!(transcript_excerpt("We shipped the build, then we argued", 22), "We shipped the build…");
+        assert_eq!(transcript_excerpt("Supercalifragilistic", 5), "Super…");
+    }
+
+    #[test]
+    fn test_export_voice_memos_layout() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        let date = Local.with_ymd_and_hms(2023, 4, 15, 10, 22, 31).unwrap().timestamp();
+        fs::write(config.audio_dir().join("a.m4a"), b"first")?;
+        fs::write(config.audio_dir().join("b.m4a"), b"second")?;
+        let a = db.insert_slice(&slice("a.m4a", Some("Standup"), Some(date)))?;
+        let b = db.insert_slice(&slice("b.m4a", Some("Standup"), Some(date)))?;
+        let missing = db.insert_slice(&slice("gone.m4a", None, None))?;
+        let mut text = slice("text_entry.txt", Some("Typed"), None);
+        text.audio_file_type = "text".to_string();
+        db.insert_slice(&text)?;
+
+        let dest = temp_dir.path().join("export");
+        let report = export_voice_memos_layout(&config, &db, &dest, Some(&[a, b, missing]), false)?;
+        assert_eq!(report.exported_files, vec!["20230415 102231-Standup.m4a", "20230415 102231-Standup 2.m4a"]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].file_path, "gone.m4a");
+
+        let exported = dest.join("20230415 102231-Standup.m4a");
+        let modified = fs::metadata(&exported)?.modified()?;
+        assert_eq!(modified.duration_since(UNIX_EPOCH)?.as_secs(), date as u64);
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_html() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        fs::write(config.audio_dir().join("a.m4a"), b"audio")?;
+        let mut memo = slice("a.m4a", Some("Tom & Jerry <draft>"), None);
+        memo.transcription = Some("<p>Call the <b>plumber</b></p>".to_string());
+        let a = db.insert_slice(&memo)?;
+        let missing = db.insert_slice(&slice("gone.m4a", None, None))?;
+
+        let dest = temp_dir.path().join("html");
+        let report = export_html(&config, &db, &[a, missing, 999], &dest)?;
+        assert_eq!(report.exported_files, vec!["audio/a.m4a"]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(fs::read(dest.join("audio/a.m4a"))?, b"audio");
+
+        let page = fs::read_to_string(dest.join("index.html"))?;
+        assert!(page.contains("<h2>Tom &amp; Jerry &lt;draft&gt;</h2>"));
+        assert!(page.contains("<audio controls preload=\"none\" src=\"audio/a.m4a\"></audio>"));
+        // Names with spaces or '#' still point at the copied file
+        assert_eq!(percent_encode("Team sync #2.m4a"), "Team%20sync%20%232.m4a");
+        assert!(page.contains("Call the plumber"));
+        assert!(page.contains("<h2>gone.m4a</h2>"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_zip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        fs::write(config.audio_dir().join("a.m4a"), b"audio")?;
+        let mut memo = slice("a.m4a", Some("Kickoff"), None);
+        memo.transcription = Some("Agenda first".to_string());
+        let a = db.insert_slice(&memo)?;
+        let mut gone = slice("gone.m4a", None, None);
+        gone.transcription = Some("Still here".to_string());
+        let missing = db.insert_slice(&gone)?;
+
+        let dest = temp_dir.path().join("project.zip");
+        let report = export_zip(&config, &db, &[a, missing, 999], &dest, None)?;
+        assert_eq!(report.exported_files, vec!["audio/a.m4a", "transcripts/a.md", "transcripts/gone.md"]);
+        assert_eq!(report.errors.len(), 1);
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&dest)?)?;
+        let mut transcript = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("transcripts/a.md")?, &mut transcript)?;
+        assert_eq!(transcript, "# Kickoff\n\nAgenda first\n");
+        let manifest: serde_json::Value = serde_json::from_reader(archive.by_name("manifest.json")?)?;
+        assert_eq!(manifest["slices"][0]["audio"], "audio/a.m4a");
+        assert_eq!(manifest["slices"][1]["audio"], serde_json::Value::Null);
+        assert_eq!(manifest["slices"][1]["transcript"], "transcripts/gone.md");
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_zip_with_password() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        fs::write(config.audio_dir().join("a.m4a"), b"secret audio")?;
+        let mut memo = slice("a.m4a", Some("Private"), None);
+        memo.transcription = Some("Confidential".to_string());
+        let a = db.insert_slice(&memo)?;
+
+        let dest = temp_dir.path().join("private.zip");
+        assert!(export_zip(&config, &db, &[a], &dest, Some("")).is_err());
+        export_zip(&config, &db, &[a], &dest, Some("hunter2"))?;
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&dest)?)?;
+        assert!(archive.by_name("audio/a.m4a").is_err());
+        assert!(archive.by_name_decrypt("audio/a.m4a", b"wrong").is_err());
+        let mut audio = Vec::new();
+        std::io::Read::read_to_end(&mut archive.by_name_decrypt("audio/a.m4a", b"hunter2")?, &mut audio)?;
+        assert_eq!(audio, b"secret audio");
+        assert!(archive.by_name("manifest.json").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_book() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+        let at = |month, day| Local.with_ymd_and_hms(2024, month, day, 9, 30, 0).unwrap().timestamp();
+        let entries = [
+            ("feb.m4a", Some("Ski trip"), Some(at(2, 3))),
+            ("jan2.m4a", None, Some(at(1, 20))),
+            ("jan1.m4a", Some("New year"), Some(at(1, 1))),
+            ("undated.m4a", Some("Loose thought"), None),
+            ("last_year.m4a", Some("Old"), Some(at(1, 1) - 86_400)),
+        ];
+        for (name, title, date) in entries {
+            let mut memo = slice(name, title, date);
+            memo.transcribed = true;
+            memo.transcription = Some(format!("Text of {}", name));
+            db.insert_slice(&memo)?;
+        }
+        db.insert_slice(&slice("untranscribed.m4a", Some("Silent"), Some(at(1, 5))))?;
+
+        let dest = temp_dir.path().join("book.md");
+        let query = SliceQuery { filter: year_filter(2024)?, ..SliceQuery::default() };
+        assert_eq!(export_book(&db, &query, "Journal 2024", &dest)?, 3);
+        let book = fs::read_to_string(&dest)?;
+        assert!(book.starts_with("# Journal 2024\n\n## Contents\n\n- [January 2024](#month-2024-01)\n"));
+        let order: Vec<_> = ["### 1 January, 09:30 — New year", "### 20 January, 09:30\n", "## February 2024", "Text of feb.m4a"]
+            .iter()
+            .map(|needle| book.find(needle).unwrap())
+            .collect();
+        assert!(order.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(!book.contains("Silent") && !book.contains("Old"));
+
+        let everything = export_book(&db, &SliceQuery::default(), "All", &dest)?;
+        assert_eq!(everything, 5);
+        assert!(fs::read_to_string(&dest)?.contains("## Undated"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_slices_json() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+        let mut memo = slice("memo.m4a", Some("Standup"), Some(1_700_000_000));
+        memo.transcription = Some("hello there".to_string());
+        let a = db.insert_slice(&memo)?;
+        let b = db.insert_slice(&slice("other.m4a", None, None))?;
+        let label_id = db.get_or_create_label("Work", "#ff0000")?;
+        db.assign_label(label_id, &[a])?;
+        db.set_slice_metadata(a, "project", "alpha")?;
+
+        let dest = temp_dir.path().join("export.jsonl");
+        assert_eq!(export_slices_json(&db, &[b, a, 999], &dest, true)?, 2);
+        let rows: Vec<serde_json::Value> = fs::read_to_string(&dest)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(rows[0]["original_audio_file_name"], "other.m4a");
+        assert_eq!(rows[1]["transcription"], "hello there");
+        assert_eq!(rows[1]["labels"], serde_json::json!(["Work"]));
+        assert_eq!(rows[1]["metadata"]["project"], "alpha");
+
+        let dest = temp_dir.path().join("export.json");
+        export_slices_json(&db, &[a], &dest, false)?;
+        let rows: serde_json::Value = serde_json::from_str(&fs::read_to_string(&dest)?)?;
+        assert_eq!(rows[0]["title"], "Standup");
+        Ok(())
+    }
+}
@@ -0,0 +1,423 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+use super::config::Config;
+use super::convert::transcode_to_m4a;
+use super::database::Database;
+use super::logging;
+use super::migrate::{get_audio_duration, place_file, sha256_file, verify_copy};
+use super::models::{DuplicateSkip, FolderImportProgress, FolderImportReport, ImportFailure, Slice};
+
+/// Audio formats accepted from arbitrary folders (recorder dumps, exports, etc.)
+pub const AUDIO_EXTENSIONS: &[&str] = &[
+    "m4a", "mp3", "wav", "aac", "flac", "ogg", "oga", "opus", "aiff", "aif", "caf", "wma",
+];
+
+/// What happened to a single file handed to the importer.
+pub enum ImportOutcome {
+    Imported(i64),     // ID of the new slice
+    Duplicate(String), // Filename of the slice that already holds the same audio
+    NameTaken,         // A different recording already uses this filename
+}
+
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Copy one audio file into the library and create its slice.
+/// `recording_date` defaults to now when not known.
+pub fn import_audio_file(
+    config: &Config,
+    db: &Database,
+    source_path: &Path,
+    title: Option<String>,
+    recording_date: Option<i64>,
+    source: Option<&str>,
+) -> Result<ImportOutcome> {
+    let filename = source_path.file_name()
+        .and_then(|n| n.to_str())
+        .context("Invalid filename")?;
+    import_audio_file_as(config, db, source_path, filename, title, recording_date, source)
+}
+
+/// Like `import_audio_file`, but stored in the library under `filename`, for sources
+/// whose own names (e.g. Zoom's `audio_only.m4a`) repeat from one recording to the next.
+pub fn import_audio_file_as(
+    config: &Config,
+    db: &Database,
+    source_path: &Path,
+    filename: &str,
+    title: Option<String>,
+    recording_date: Option<i64>,
+    source: Option<&str>,
+) -> Result<ImportOutcome> {
+    let filename = filename.to_string();
+
+    // Check the audio first so a re-import of the same file reads as a duplicate,
+    // not as a filename clash
+    let content_hash = sha256_file(source_path)?;
+    if let Some(existing) = db.find_slice_by_content_hash(&content_hash)? {
+        return Ok(ImportOutcome::Duplicate(existing));
+    }
+
+    if db.slice_exists(&filename)? {
+        return Ok(ImportOutcome::NameTaken);
+    }
+
+    let dest_dir = config.audio_dir();
+    fs::create_dir_all(&dest_dir)?;
+    let dest_path = dest_dir.join(&filename);
+    place_file(source_path, &dest_path, config.copy_mode)
+        .with_context(|| format!("Failed to copy audio file {:?}", source_path))?;
+    verify_copy(&content_hash, &dest_path)?;
+
+    let file_size = fs::metadata(&dest_path)?.len() as i64;
+    let ext = source_path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("unknown")
+        .to_lowercase();
+
+    let duration = get_audio_duration(&dest_path);
+
+    // Estimate transcription time (roughly 1 second per 34KB)
+    let estimated_time = (file_size / 34000).max(1) as i32;
+
+    let slice_title = title.unwrap_or_else(|| {
+        Path::new(&filename).file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Imported Audio")
+            .to_string()
+    });
+
+    let slice = Slice {
+        id: None,
+        original_audio_file_name: filename,
+        title: Some(slice_title),
+        transcribed: false,
+        audio_file_size: file_size,
+        audio_file_type: ext,
+        estimated_time_to_transcribe: estimated_time,
+        audio_time_length_seconds: duration,
+        transcription: None,
+        transcription_time_taken: None,
+        transcription_word_count: None,
+        transcription_model: None,
+        recording_date: Some(recording_date.unwrap_or_else(|| chrono::Utc::now().timestamp())),
+        source: source.map(|s| s.to_string()),
+        starred: false,
+        was_edited: false,
+        content_hash: Some(content_hash),
+        source_relative_path: None,
+        notes: None,
+        pinned: false,
+        summary: None,
+    };
+
+    let id = db.insert_slice(&slice)?;
+    Ok(ImportOutcome::Imported(id))
+}
+
+/// Import a recording as `<stem>.m4a`, transcoding it first when it is in another format
+/// (opus, ogg, mp4 video). The converted file is staged under the CiderPress home and removed after.
+pub fn import_converted_audio(
+    config: &Config,
+    db: &Database,
+    source_path: &Path,
+    stem: &str,
+    title: Option<String>,
+    recording_date: Option<i64>,
+    source: Option<&str>,
+) -> Result<ImportOutcome> {
+    let is_m4a = source_path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("m4a"));
+    if is_m4a {
+        let filename = format!("{}.m4a", stem);
+        return import_audio_file_as(config, db, source_path, &filename, title, recording_date, source);
+    }
+
+    let staging_dir = config.ciderpress_home_path().join("conversion_staging");
+    fs::create_dir_all(&staging_dir)?;
+    let staged_path = staging_dir.join(format!("{}.m4a", stem));
+
+    let result = transcode_to_m4a(source_path, &staged_path)
+        .and_then(|_| import_audio_file(config, db, &staged_path, title, recording_date, source));
+
+    if staged_path.exists() {
+        if let Err(e) = fs::remove_file(&staged_path) {
+            warn!("Failed to remove staged conversion {:?}: {}", staged_path, e);
+        }
+    }
+    result
+}
+
+/// `name`, or `name 2`, `name 3`, ... if a slice or a file in `audio_dir` already uses it.
+pub fn free_file_name(db: &Database, audio_dir: &Path, name: &str) -> Result<String> {
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) => (stem, format!(".{}", ext)),
+        None => (name, String::new()),
+    };
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while db.slice_exists(&candidate)? || audio_dir.join(&candidate).exists() {
+        candidate = format!("{} {}{}", stem, n, ext);
+        n += 1;
+    }
+    Ok(candidate)
+}
+
+/// All audio files below `folder`, sorted so imports run in a stable order.
+pub fn scan_audio_files(folder: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(folder)
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Skipping unreadable entry during folder scan: {}", e);
+                None
+            }
+        })
+        .filter(|entry| entry.file_type().is_file() && is_audio_file(entry.path()))
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// File modification time as a Unix timestamp; the best recording date a plain folder offers.
+pub fn file_modified_timestamp(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let secs = modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    Some(secs as i64)
+}
+
+/// Recursively import every audio file under `folder`. A failing file is recorded in the
+/// report and never stops the rest of the import. `on_progress` is called after each file.
+pub fn import_folder(
+    config: &Config,
+    db: &Database,
+    folder: &Path,
+    mut on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<FolderImportReport> {
+    if !folder.is_dir() {
+        return Err(anyhow::anyhow!("Folder not found: {:?}", folder));
+    }
+
+    let files = scan_audio_files(folder);
+    info!("Folder import: {} audio files found in {:?}", files.len(), folder);
+
+    let mut report = FolderImportReport::default();
+    let mut progress = FolderImportProgress {
+        total_files: files.len() as u32,
+        processed_files: 0,
+        current_file: None,
+    };
+    on_progress(&progress);
+
+    for path in &files {
+        let display_path = path.to_string_lossy().to_string();
+        progress.current_file = Some(display_path.clone());
+
+        match import_audio_file(config, db, path, None, file_modified_timestamp(path), Some("folder_import")) {
+            Ok(ImportOutcome::Imported(id)) => report.imported_slice_ids.push(id),
+            Ok(ImportOutcome::Duplicate(existing)) => report.duplicates.push(DuplicateSkip {
+                file_path: display_path,
+                duplicate_of: existing,
+            }),
+            Ok(ImportOutcome::NameTaken) => report.errors.push(ImportFailure {
+                file_path: display_path,
+                message: "A slice with this filename already exists".to_string(),
+            }),
+            Err(e) => report.errors.push(ImportFailure {
+                file_path: display_path,
+                message: e.to_string(),
+            }),
+        }
+
+        progress.processed_files += 1;
+        on_progress(&progress);
+    }
+
+    logging::log_info(
+        "import",
+        &format!("Folder import: {} imported, {} duplicates, {} errors", report.imported_slice_ids.len(), report.duplicates.len(), report.errors.len()),
+        Some(serde_json::json!({
+            "folder": folder.to_string_lossy(),
+            "total_files": files.len(),
+        })),
+    );
+
+    Ok(report)
+}
+
+/// How an importer wants one of its files to appear in the library.
+pub struct ImportDetails {
+    pub stem: String, // Library filename without the .m4a extension
+    pub title: String,
+    pub recording_date: Option<i64>,
+}
+
+/// Convert and import app recordings (voice notes, meetings) found under `folder`, for
+/// importers that know each file's name, title and date (`describe`). Files already imported
+/// from this folder are skipped; a failing file is recorded in the report and retried next run.
+pub fn import_tracked_files(
+    config: &Config,
+    db: &Database,
+    folder: &Path,
+    files: &[PathBuf],
+    source: &str,
+    describe: impl Fn(&Path) -> ImportDetails,
+    mut on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<FolderImportReport> {
+    let root_key = folder.to_string_lossy().to_string();
+    let mut report = FolderImportReport::default();
+    let mut progress = FolderImportProgress {
+        total_files: files.len() as u32,
+        processed_files: 0,
+        current_file: None,
+    };
+    on_progress(&progress);
+
+    for path in files {
+        let display_path = path.to_string_lossy().to_string();
+        progress.current_file = Some(display_path.clone());
+
+        let relative_path = path.strip_prefix(folder).unwrap_or(path).to_string_lossy().to_string();
+        let result = match db.source_file_seen(&root_key, &relative_path) {
+            Ok(true) => Ok(None), // Imported on an earlier run
+            Ok(false) => {
+                let details = describe(path);
+                import_converted_audio(config, db, path, &details.stem, Some(details.title), details.recording_date, Some(source))
+                    .map(Some)
+            }
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(Some(ImportOutcome::Imported(id))) => {
+                db.record_source_file(&root_key, &relative_path, Some(id), "imported")?;
+                report.imported_slice_ids.push(id);
+            }
+            Ok(Some(ImportOutcome::Duplicate(existing))) => {
+                db.record_source_file(&root_key, &relative_path, None, "duplicate")?;
+                report.duplicates.push(DuplicateSkip {
+                    file_path: display_path,
+                    duplicate_of: existing,
+                });
+            }
+            Ok(Some(ImportOutcome::NameTaken)) => report.errors.push(ImportFailure {
+                file_path: display_path,
+                message: "A slice with this filename already exists".to_string(),
+            }),
+            Ok(None) => {}
+            Err(e) => report.errors.push(ImportFailure {
+                file_path: display_path,
+                message: e.to_string(),
+            }),
+        }
+
+        progress.processed_files += 1;
+        on_progress(&progress);
+    }
+
+    logging::log_info(
+        "import",
+        &format!("{} import: {} imported, {} duplicates, {} errors", source, report.imported_slice_ids.len(), report.duplicates.len(), report.errors.len()),
+        Some(serde_json::json!({
+            "folder": folder.to_string_lossy(),
+            "total_files": files.len(),
+        })),
+    );
+
+    Ok(report)
+}
+
+/// Files below `folder` with one of `extensions` (lowercase), sorted.
+pub fn scan_files_with_extensions(folder: &Path, extensions: &[&str]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(folder)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry.file_type().is_file()
+                && entry.path().extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+        })
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_import_folder_reports_duplicates_and_errors() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let folder = temp_dir.path().join("dump");
+        let nested = folder.join("2019").join("march");
+        fs::create_dir_all(&nested)?;
+        fs::write(folder.join("a.mp3"), b"first")?;
+        fs::write(nested.join("b.wav"), b"second")?;
+        fs::write(nested.join("b copy.WAV"), b"second")?; // same audio, different name
+        fs::write(nested.join("notes.txt"), b"not audio")?;
+
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db_path = temp_dir.path().join("test.db");
+        let db = Database::new(&db_path)?;
+
+        let mut updates = Vec::new();
+        let report = import_folder(&config, &db, &folder, |p| updates.push(p.processed_files))?;
+
+        assert_eq!(report.imported_slice_ids.len(), 2);
+        assert_eq!(report.duplicates.len(), 1);
+        assert!(report.errors.is_empty());
+        assert_eq!(updates, vec![0, 1, 2, 3]);
+
+        let slices = db.list_all_slices()?;
+        assert!(slices.iter().all(|s| s.source.as_deref() == Some("folder_import")));
+
+        // A second run finds everything already imported
+        let report = import_folder(&config, &db, &folder, |_| {})?;
+        assert!(report.imported_slice_ids.is_empty());
+        assert_eq!(report.duplicates.len(), 3);
+
+        // A different recording reusing a library filename is a per-file error
+        let other = temp_dir.path().join("other");
+        fs::create_dir_all(&other)?;
+        fs::write(other.join("a.mp3"), b"different audio")?;
+        let report = import_folder(&config, &db, &other, |_| {})?;
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].file_path.ends_with("a.mp3"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use super::config::Config;
+use super::database::Database;
+use super::db_pool;
+use super::importer::{self, ImportOutcome};
+use super::models::{DuplicateSkip, FolderImportReport, ImportFailure};
+use super::transcribe::TranscriptionEngine;
+
+const SOURCE: &str = "inbox";
+
+/// How long the inbox must be quiet before it is imported; AirDrop and the
+/// Finder write a dropped file in several steps.
+const DEBOUNCE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Active inbox watcher; dropping it stops the watch and ends the worker thread
+lazy_static::lazy_static! {
+    static ref WATCHER: Arc<Mutex<Option<RecommendedWatcher>>> = Arc::new(Mutex::new(None));
+}
+
+/// Audio files waiting in the inbox. Hidden files are partial downloads or Finder metadata.
+fn pending_files(inbox: &Path) -> Vec<PathBuf> {
+    importer::scan_audio_files(inbox)
+        .into_iter()
+        .filter(|path| {
+            !path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| name.starts_with('.'))
+        })
+        .collect()
+}
+
+fn remove_from_inbox(path: &Path) {
+    if let Err(e) = fs::remove_file(path) {
+        warn!("Failed to remove {:?} from the inbox: {}", path, e);
+    }
+}
+
+/// `stem`, numbered if the library already has a `<stem>.m4a`, so a second "New Recording"
+/// dropped in the inbox is imported as "New Recording 2" instead of failing on every pass.
+fn free_stem(config: &Config, db: &Database, stem: &str) -> Result<String> {
+    let name = importer::free_file_name(db, &config.audio_dir(), &format!("{}.m4a", stem))?;
+    Ok(name.trim_end_matches(".m4a").to_string())
+}
+
+/// Import every audio file in `inbox` and remove it from there once the library holds it.
+/// Files that fail to import stay in the inbox, to be retried on the next change.
+pub fn process_inbox(config: &Config, db: &Database, inbox: &Path) -> Result<FolderImportReport> {
+    let mut report = FolderImportReport::default();
+
+    for path in pending_files(inbox) {
+        let display_path = path.to_string_lossy().to_string();
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording").to_string();
+
+        let imported = free_stem(config, db, &stem).and_then(|stem| {
+            importer::import_converted_audio(config, db, &path, &stem, None, importer::file_modified_timestamp(&path), Some(SOURCE))
+        });
+        match imported {
+            Ok(ImportOutcome::Imported(id)) => {
+                remove_from_inbox(&path);
+                report.imported_slice_ids.push(id);
+            }
+            Ok(ImportOutcome::Duplicate(existing)) => {
+                remove_from_inbox(&path);
+                report.duplicates.push(DuplicateSkip {
+                    file_path: display_path,
+                    duplicate_of: existing,
+                });
+            }
+            Ok(ImportOutcome::NameTaken) => report.errors.push(ImportFailure {
+                file_path: display_path,
+                message: "A slice with this filename already exists".to_string(),
+            }),
+            Err(e) => report.errors.push(ImportFailure {
+                file_path: display_path,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    if !report.imported_slice_ids.is_empty() {
+        info!("Inbox: imported {} recording(s) from {:?}", report.imported_slice_ids.len(), inbox);
+    }
+    Ok(report)
+}
+
+pub fn is_watching() -> bool {
+    WATCHER.lock().map(|w| w.is_some()).unwrap_or(false)
+}
+
+/// Start watching the configured inbox folder, replacing any previous inbox watcher.
+/// Files already waiting there are imported straight away.
+pub fn start_watcher(config: &Config) -> Result<()> {
+    stop_watcher();
+
+    let inbox = config.inbox_path().context("No inbox folder configured")?;
+    fs::create_dir_all(&inbox)
+        .with_context(|| format!("Failed to create inbox folder {:?}", inbox))?;
+
+    let (tx, rx) = mpsc::channel::<()>();
+    let initial = tx.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+            let _ = tx.send(());
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Inbox event error: {}", e),
+    })
+    .context("Failed to create filesystem watcher")?;
+
+    watcher
+        .watch(&inbox, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {:?}", inbox))?;
+    let _ = initial.send(());
+
+    let config = config.clone();
+    std::thread::spawn(move || run_worker(config, inbox, rx));
+
+    *WATCHER.lock().unwrap() = Some(watcher);
+    info!("Inbox watcher started");
+    Ok(())
+}
+
+pub fn stop_watcher() {
+    let previous = WATCHER.lock().unwrap().take();
+    if previous.is_some() {
+        info!("Inbox watcher stopped");
+    }
+}
+
+/// Start or stop the inbox watcher to match the `inbox_folder` setting.
+pub fn apply_config(config: &Config) {
+    if config.inbox_path().is_some() {
+        if let Err(e) = start_watcher(config) {
+            error!("Failed to start inbox watcher: {}", e);
+        }
+    } else {
+        stop_watcher();
+    }
+}
+
+/// Waits for change notifications, debounces them and imports the inbox.
+/// Exits once the watcher (and with it the sender) is dropped.
+fn run_worker(config: Config, inbox: PathBuf, rx: mpsc::Receiver<()>) {
+    while rx.recv().is_ok() {
+        loop {
+            match rx.recv_timeout(DEBOUNCE_INTERVAL) {
+                Ok(()) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let result = db_pool::connect(&db_path).and_then(|db| {
+            let report = process_inbox(&config, &db, &inbox)?;
+            // The files are imported either way, so a failed transcription still reports them
+            if config.inbox_auto_transcribe && !report.imported_slice_ids.is_empty() {
+                if let Err(e) = TranscriptionEngine::new(&config, &db).transcribe_imported_slices(&report.imported_slice_ids) {
+                    error!("Inbox: failed to transcribe imported slices: {}", e);
+                }
+            }
+            Ok(report)
+        });
+
+        match result {
+            Ok(report) if report.imported_slice_ids.is_empty() && report.errors.is_empty() => {}
+            Ok(report) => crate::emit_inbox_import(&report),
+            Err(e) => {
+                error!("Inbox import failed: {}", e);
+                crate::emit_inbox_import(&FolderImportReport {
+                    errors: vec![ImportFailure {
+                        file_path: inbox.to_string_lossy().to_string(),
+                        message: e.to_string(),
+                    }],
+                    ..FolderImportReport::default()
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_process_inbox_moves_files_into_library() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let inbox = temp_dir.path().join("Inbox");
+        fs::create_dir_all(&inbox)?;
+        fs::write(inbox.join("New Recording.m4a"), b"airdropped memo")?;
+        fs::write(inbox.join("again.m4a"), b"airdropped memo")?;
+        fs::write(inbox.join(".partial.m4a"), b"still downloading")?;
+        fs::write(inbox.join("notes.txt"), b"not audio")?;
+
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        let report = process_inbox(&config, &db, &inbox)?;
+        assert_eq!(report.imported_slice_ids.len(), 1);
+        assert_eq!(report.duplicates.len(), 1);
+        assert!(report.errors.is_empty());
+
+        // Both copies of the audio left the inbox; the hidden and non-audio files stay
+        assert!(!inbox.join("New Recording.m4a").exists());
+        assert!(!inbox.join("again.m4a").exists());
+        assert!(inbox.join(".partial.m4a").exists());
+        assert!(inbox.join("notes.txt").exists());
+
+        let slices = db.list_all_slices()?;
+        assert_eq!(slices.len(), 1);
+        assert_eq!(slices[0].source.as_deref(), Some(SOURCE));
+        assert!(config.audio_dir().join(&slices[0].original_audio_file_name).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_inbox_renames_a_taken_filename() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let inbox = temp_dir.path().join("Inbox");
+        fs::create_dir_all(&inbox)?;
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        fs::write(inbox.join("New Recording.m4a"), b"first memo")?;
+        process_inbox(&config, &db, &inbox)?;
+        fs::write(inbox.join("New Recording.m4a"), b"second memo")?;
+        let report = process_inbox(&config, &db, &inbox)?;
+
+        assert_eq!(report.imported_slice_ids.len(), 1);
+        assert!(report.errors.is_empty());
+        assert!(!inbox.join("New Recording.m4a").exists());
+        let slice = db.get_slice(report.imported_slice_ids[0])?.unwrap();
+        assert_eq!(slice.original_audio_file_name, "New Recording 2.m4a");
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::migrate::MigrationEngine;
+use super::models::MigrationSummary;
+
+/// Backup domain holding the Voice Memos shared container.
+const VOICE_MEMOS_DOMAIN: &str = "AppDomainGroup-group.com.apple.VoiceMemos.shared";
+
+/// `Files.flags` value for a regular file in Manifest.db.
+const MANIFEST_FLAG_FILE: i64 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IosBackup {
+    pub path: String,
+    pub device_id: String,
+    pub last_modified: Option<i64>, // Unix timestamp of the backup directory
+    pub encrypted: bool,
+    pub voice_memo_count: u32,
+}
+
+/// Where Finder/iTunes keep local device backups.
+pub fn default_backup_root() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("Library/Application Support/MobileSync/Backup")
+}
+
+/// Manifest.db of an encrypted backup is itself encrypted, so it won't open as SQLite.
+fn open_manifest(backup_dir: &Path) -> Result<Connection> {
+    let manifest_path = backup_dir.join("Manifest.db");
+    if !manifest_path.exists() {
+        return Err(anyhow::anyhow!("No Manifest.db in {:?}; not a device backup", backup_dir));
+    }
+    let conn = Connection::open_with_flags(&manifest_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.query_row("SELECT COUNT(*) FROM Files", [], |row| row.get::<_, i64>(0))
+        .map_err(|_| anyhow::anyhow!("Backup is encrypted; disable backup encryption in Finder and back up again"))?;
+    Ok(conn)
+}
+
+/// (fileID, relativePath) of Voice Memos recordings and their database inside the backup.
+fn voice_memo_entries(conn: &Connection) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT fileID, relativePath FROM Files
+        WHERE domain = ?1 AND flags = ?2
+          AND (relativePath LIKE '%.m4a' OR relativePath LIKE '%CloudRecordings.db%')
+        ORDER BY relativePath
+        "#,
+    )?;
+    let entries = stmt
+        .query_map(rusqlite::params![VOICE_MEMOS_DOMAIN, MANIFEST_FLAG_FILE], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+/// Backups store each file as `<first two hex chars of fileID>/<fileID>`.
+fn backup_file_path(backup_dir: &Path, file_id: &str) -> PathBuf {
+    backup_dir.join(file_id.get(..2).unwrap_or_default()).join(file_id)
+}
+
+/// List the device backups under `root`, noting which can be read and how many memos they hold.
+pub fn list_backups(root: &Path) -> Vec<IosBackup> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Cannot read backup directory {:?}: {}", root, e);
+            return Vec::new();
+        }
+    };
+
+    let mut backups: Vec<IosBackup> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join("Manifest.db").exists())
+        .map(|path| {
+            let last_modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64);
+            let (encrypted, voice_memo_count) = match open_manifest(&path) {
+                Ok(conn) => {
+                    let count = voice_memo_entries(&conn)
+                        .map(|e| e.iter().filter(|(_, rel)| rel.ends_with(".m4a")).count())
+                        .unwrap_or(0);
+                    (false, count as u32)
+                }
+                Err(_) => (true, 0),
+            };
+            IosBackup {
+                device_id: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                path: path.to_string_lossy().to_string(),
+                last_modified,
+                encrypted,
+                voice_memo_count,
+            }
+        })
+        .collect();
+
+    backups.sort_by_key(|b| std::cmp::Reverse(b.last_modified));
+    backups
+}
+
+/// Recreate the Voice Memos container layout from the backup's hashed files under `staging_dir`.
+/// Files are hard-linked where possible so large libraries don't need twice the disk space.
+/// Returns the number of recordings staged.
+pub fn stage_voice_memos(backup_dir: &Path, staging_dir: &Path) -> Result<u32> {
+    let conn = open_manifest(backup_dir)?;
+    let mut recordings = 0;
+
+    for (file_id, relative_path) in voice_memo_entries(&conn)? {
+        // Anything but plain names (`..`, a root) could land outside the staging folder
+        if !Path::new(&relative_path).components().all(|c| matches!(c, Component::Normal(_))) {
+            warn!("Skipping backup entry with an unsafe path: {}", relative_path);
+            continue;
+        }
+
+        let source = backup_file_path(backup_dir, &file_id);
+        if !source.exists() {
+            warn!("Backup is missing {} ({})", relative_path, file_id);
+            continue;
+        }
+
+        let dest = staging_dir.join(&relative_path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let _ = fs::remove_file(&dest);
+        if fs::hard_link(&source, &dest).is_err() {
+            fs::copy(&source, &dest).with_context(|| format!("Failed to stage {}", relative_path))?;
+        }
+
+        if relative_path.ends_with(".m4a") {
+            recordings += 1;
+        }
+    }
+
+    Ok(recordings)
+}
+
+/// Run the regular migration pipeline against the Voice Memos found in an iPhone backup.
+pub fn migrate_from_backup(config: &Config, backup_dir: &Path) -> Result<MigrationSummary> {
+    let staging_dir = config.ciderpress_home_path().join("ios_backup_staging");
+    let _ = fs::remove_dir_all(&staging_dir);
+
+    let staged = stage_voice_memos(backup_dir, &staging_dir)?;
+    info!("Staged {} recordings from iPhone backup {:?}", staged, backup_dir);
+
+    let mut backup_config = config.clone();
+    backup_config.voice_memo_root = staging_dir.join("Recordings").to_string_lossy().to_string();
+    // Call recordings on this Mac are not part of the backup
+    backup_config.call_recordings_root = staging_dir.join("CallRecordings").to_string_lossy().to_string();
+
+    let result = MigrationEngine::for_ios_backup(&backup_config).start_migration();
+    let _ = fs::remove_dir_all(&staging_dir);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::Database;
+    use tempfile::TempDir;
+
+    /// Build a minimal unencrypted backup containing the given Voice Memos files.
+    fn create_test_backup(backup_dir: &Path, files: &[(&str, &str, &[u8])]) -> Result<()> {
+        fs::create_dir_all(backup_dir)?;
+        let conn = Connection::open(backup_dir.join("Manifest.db"))?;
+        conn.execute(
+            "CREATE TABLE Files (fileID TEXT PRIMARY KEY, domain TEXT, relativePath TEXT, flags INTEGER, file BLOB)",
+            [],
+        )?;
+        for (file_id, relative_path, contents) in files {
+            conn.execute(
+                "INSERT INTO Files (fileID, domain, relativePath, flags) VALUES (?1, ?2, ?3, 1)",
+                rusqlite::params![file_id, VOICE_MEMOS_DOMAIN, relative_path],
+            )?;
+            let path = backup_file_path(backup_dir, file_id);
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(path, contents)?;
+        }
+        // Unrelated app data must be ignored
+        conn.execute(
+            "INSERT INTO Files (fileID, domain, relativePath, flags) VALUES ('ff00', 'HomeDomain', 'Library/x.m4a', 1)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_from_backup() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let backup_dir = temp_dir.path().join("Backup").join("00008030-TEST");
+
+        // The staged CloudRecordings.db needs a ZCLOUDRECORDING table for the migration to run
+        let apple_db = temp_dir.path().join("CloudRecordings.db");
+        let conn = Connection::open(&apple_db)?;
+        conn.execute("CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT)", [])?;
+        drop(conn);
+        let apple_db_bytes = fs::read(&apple_db)?;
+
+        create_test_backup(&backup_dir, &[
+            ("aa11", "Recordings/20240101 101010.m4a", b"memo one"),
+            ("bb22", "Recordings/20240102 101010.m4a", b"memo two"),
+            ("cc33", "Recordings/CloudRecordings.db", &apple_db_bytes),
+        ])?;
+
+        let backups = list_backups(&temp_dir.path().join("Backup"));
+        assert_eq!(backups.len(), 1);
+        assert!(!backups[0].encrypted);
+        assert_eq!(backups[0].voice_memo_count, 2);
+
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+
+        let summary = migrate_from_backup(&config, &backup_dir)?;
+        assert_eq!(summary.copied, 2);
+        assert!(!config.ciderpress_home_path().join("ios_backup_staging").exists());
+
+        let db = Database::new(config.ciderpress_home_path().join("CiderPress-db.sqlite"))?;
+        let slices = db.list_all_slices()?;
+        assert_eq!(slices.len(), 2);
+        assert!(slices.iter().all(|s| s.source.as_deref() == Some("ios_backup")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stage_skips_paths_outside_the_staging_folder() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let backup_dir = temp_dir.path().join("Backup");
+        create_test_backup(&backup_dir, &[
+            ("aa11", "Recordings/20240101 101010.m4a", b"memo one"),
+            ("bb22", "../escaped.m4a", b"outside"),
+            ("cc33", "/tmp/absolute.m4a", b"outside"),
+        ])?;
+
+        let staging_dir = temp_dir.path().join("stage");
+        assert_eq!(stage_voice_memos(&backup_dir, &staging_dir)?, 1);
+        assert!(staging_dir.join("Recordings/20240101 101010.m4a").exists());
+        assert!(!temp_dir.path().join("escaped.m4a").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypted_backup_is_reported() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let backup_dir = temp_dir.path().join("ENCRYPTED");
+        fs::create_dir_all(&backup_dir)?;
+        fs::write(backup_dir.join("Manifest.db"), b"definitely not sqlite ciphertext here")?;
+
+        let backups = list_backups(temp_dir.path());
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].encrypted);
+        assert!(stage_voice_memos(&backup_dir, &temp_dir.path().join("stage")).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Prompt pipelines per label: a label can carry a prompt ("meeting" → minutes, "idea" →
+//! a one-paragraph pitch) that the local LLM runs over each newly transcribed slice with
+//! that label, keeping the result as a derived document attached to the slice.
+
+use anyhow::{anyhow, Result};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::database::Database;
+use super::models::{DerivedDocument, LabelPrompt, Slice};
+use super::ollama::{open_db, Ollama};
+use super::summarize::transcript_or_summary;
+
+const PIPELINE_INSTRUCTIONS: &str = "You turn transcripts of voice recordings into documents. Follow the \
+instructions you are given, write in the transcript's language unless told otherwise, and reply with the \
+document only.";
+
+lazy_static::lazy_static! {
+    /// Transcribed slices waiting for their labels' prompts, oldest first.
+    static ref QUEUE: Mutex<VecDeque<i64>> = Mutex::new(VecDeque::new());
+}
+
+/// Set while a thread is working through the queue, so only one does.
+static WORKER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Run one label's prompt over a slice's transcript (or its summary, when the transcript
+/// is long) and store the result.
+pub async fn run_prompt(config: &Config, ollama: &Ollama, slice: &Slice, prompt: &LabelPrompt) -> Result<DerivedDocument> {
+    let slice_id = slice.id.unwrap_or_default();
+    let context = transcript_or_summary(ollama, slice).await?;
+    let request = format!("Instructions: {}\n\nTranscript:\n{}", prompt.prompt, context);
+    let content = ollama.generate(Some(PIPELINE_INSTRUCTIONS), &request).await?;
+    if content.is_empty() {
+        return Err(anyhow!("{} returned an empty document", ollama.model()));
+    }
+
+    let mut document = DerivedDocument {
+        id: 0,
+        slice_id,
+        label_id: prompt.label_id,
+        label_name: prompt.label_name.clone(),
+        prompt: prompt.prompt.clone(),
+        content,
+        model: ollama.model().to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    document.id = open_db(config)?.save_derived_document(&document)?;
+    Ok(document)
+}
+
+/// Run the prompts of every label a slice has. A prompt that fails is logged and the
+/// rest still run; the first error is returned only if none succeeded.
+pub async fn run_for_slice(config: &Config, slice_id: i64) -> Result<Vec<DerivedDocument>> {
+    let db = open_db(config)?;
+    let slice = db
+        .get_slice(slice_id)?
+        .ok_or_else(|| anyhow!("No slice found with ID: {}", slice_id))?;
+    let prompts = db.get_slice_label_prompts(slice_id)?;
+    drop(db);
+    if prompts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ollama = Ollama::new(config)?;
+    let mut documents = Vec::new();
+    let mut first_error = None;
+    for prompt in &prompts {
+        match run_prompt(config, &ollama, &slice, prompt).await {
+            Ok(document) => documents.push(document),
+            Err(e) => {
+                warn!("Prompt of label \"{}\" failed on slice {}: {}", prompt.label_name, slice_id, e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match first_error {
+        Some(e) if documents.is_empty() => Err(e),
+        _ => {
+            info!("Ran {} of {} label prompt(s) on slice {}", documents.len(), prompts.len(), slice_id);
+            Ok(documents)
+        }
+    }
+}
+
+/// After a slice is transcribed: queue it for its labels' prompts, if it has any. Nothing
+/// runs until `spawn_worker`, and problems are only logged.
+pub fn queue_slice(db: &Database, slice_id: i64) {
+    match db.get_slice_label_prompts(slice_id) {
+        Ok(prompts) if prompts.is_empty() => {}
+        Ok(_) => {
+            if let Ok(mut queue) = QUEUE.lock() {
+                if !queue.contains(&slice_id) {
+                    queue.push_back(slice_id);
+                }
+            }
+        }
+        Err(e) => warn!("Failed to look up label prompts for slice {}: {}", slice_id, e),
+    }
+}
+
+fn next_queued() -> Option<i64> {
+    QUEUE.lock().ok().and_then(|mut queue| queue.pop_front())
+}
+
+fn queue_is_empty() -> bool {
+    QUEUE.lock().map(|queue| queue.is_empty()).unwrap_or(true)
+}
+
+/// Run the prompts of every queued slice, one after another on a single background thread.
+/// Does nothing if the queue is empty or a worker is already running; it picks up whatever
+/// is queued meanwhile.
+pub fn spawn_worker(config: Config) {
+    if queue_is_empty() || WORKER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        let rt = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                warn!("Failed to start runtime for label prompts: {}", e);
+                WORKER_RUNNING.store(false, Ordering::SeqCst);
+                return;
+            }
+        };
+        loop {
+            while let Some(slice_id) = next_queued() {
+                if let Err(e) = rt.block_on(run_for_slice(&config, slice_id)) {
+                    warn!("Label prompts failed on slice {}: {}", slice_id, e);
+                }
+            }
+            WORKER_RUNNING.store(false, Ordering::SeqCst);
+            // Something queued just after the last check would otherwise wait for the next slice
+            if queue_is_empty() || WORKER_RUNNING.swap(true, Ordering::SeqCst) {
+                break;
+            }
+        }
+    });
+}
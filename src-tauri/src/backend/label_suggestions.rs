@@ -0,0 +1,224 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Label suggestions for unlabeled slices from what their transcripts resemble, rather than
+//! from keywords (`labeling`). A slice is represented by the mean of its passage embeddings
+//! in the transcript index, and a label by the mean of its slices'. Accepting a suggestion
+//! assigns the label, so the slice counts towards it from then on; rejecting one is
+//! remembered, never offered again, and pulls the label away from slices like that one.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+
+use super::database::Database;
+use super::embeddings::cosine;
+use super::models::LabelSuggestion;
+
+/// Lowest score a suggestion needs when the caller doesn't say.
+pub const DEFAULT_MIN_SCORE: f32 = 0.5;
+
+/// Suggestions per slice when the caller doesn't say.
+pub const DEFAULT_SUGGESTIONS_PER_SLICE: usize = 3;
+
+/// How much resembling slices a label was rejected for counts against a suggestion.
+const REJECTION_WEIGHT: f32 = 0.5;
+
+/// The mean of `vectors`, scaled to unit length, or `None` without any.
+fn unit_mean<'a>(vectors: impl IntoIterator<Item = &'a Vec<f32>>) -> Option<Vec<f32>> {
+    let vectors: Vec<&Vec<f32>> = vectors.into_iter().collect();
+    let mut mean = vec![0.0f32; vectors.first()?.len()];
+    // Vectors of another length were embedded by a different model
+    for vector in vectors.iter().filter(|v| v.len() == mean.len()) {
+        mean.iter_mut().zip(vector.iter()).for_each(|(m, v)| *m += v);
+    }
+    let norm = mean.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        mean.iter_mut().for_each(|v| *v /= norm);
+    }
+    Some(mean)
+}
+
+/// One embedding per indexed slice outside the trash: the mean of its passages'.
+fn slice_embeddings(db: &Database, model: &str) -> Result<HashMap<i64, Vec<f32>>> {
+    let mut passages: HashMap<i64, Vec<Vec<f32>>> = HashMap::new();
+    for chunk in db.load_transcript_chunks(model)? {
+        passages.entry(chunk.slice_id).or_default().push(chunk.embedding);
+    }
+    Ok(passages
+        .into_iter()
+        .filter_map(|(slice_id, vectors)| unit_mean(&vectors).map(|v| (slice_id, v)))
+        .collect())
+}
+
+/// Suggest labels for `slice_ids`, or with `None` every indexed slice without a label, from
+/// passage embeddings made with `model`. At most `per_slice` suggestions per slice, best
+/// first, each scoring at least `min_score`; labels without any indexed slice yet, labels the
+/// slice already has and suggestions rejected before are left out.
+pub fn suggest_labels(
+    db: &Database,
+    model: &str,
+    slice_ids: Option<&[i64]>,
+    per_slice: usize,
+    min_score: f32,
+) -> Result<Vec<LabelSuggestion>> {
+    let embeddings = slice_embeddings(db, model)?;
+    let slice_labels = db.get_labels_for_all_slices()?;
+    let rejected: HashSet<(i64, i64)> = db.list_rejected_label_suggestions()?.into_iter().collect();
+
+    let mut candidates: Vec<i64> = match slice_ids {
+        Some(ids) => ids.iter().copied().filter(|id| embeddings.contains_key(id)).collect(),
+        None => embeddings.keys().copied().filter(|id| !slice_labels.contains_key(id)).collect(),
+    };
+    candidates.sort_unstable();
+
+    // Each label's centroid over its slices, and over the slices it was rejected for
+    let labels: Vec<(i64, String, Vec<f32>, Option<Vec<f32>>)> = db
+        .list_labels()?
+        .into_iter()
+        .filter_map(|label| {
+            let label_id = label.id?;
+            let positive = unit_mean(
+                slice_labels
+                    .iter()
+                    .filter(|(_, labels)| labels.iter().any(|l| l.id == Some(label_id)))
+                    .filter_map(|(slice_id, _)| embeddings.get(slice_id)),
+            )?;
+            let negative = unit_mean(
+                rejected
+                    .iter()
+                    .filter(|(_, rejected_label)| *rejected_label == label_id)
+                    .filter_map(|(slice_id, _)| embeddings.get(slice_id)),
+            );
+            Some((label_id, label.name, positive, negative))
+        })
+        .collect();
+
+    let mut suggestions = Vec::new();
+    for slice_id in candidates {
+        let embedding = &embeddings[&slice_id];
+        let has = |label_id: i64| {
+            slice_labels
+                .get(&slice_id)
+                .is_some_and(|labels| labels.iter().any(|l| l.id == Some(label_id)))
+        };
+        let mut scored: Vec<LabelSuggestion> = labels
+            .iter()
+            .filter(|(label_id, ..)| !has(*label_id) && !rejected.contains(&(slice_id, *label_id)))
+            .map(|(label_id, name, positive, negative)| {
+                let penalty = negative.as_ref().map_or(0.0, |n| cosine(embedding, n).max(0.0));
+                LabelSuggestion {
+                    slice_id,
+                    label_id: *label_id,
+                    label_name: name.clone(),
+                    score: cosine(embedding, positive) - REJECTION_WEIGHT * penalty,
+                }
+            })
+            .filter(|s| s.score >= min_score)
+            .collect();
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scored.truncate(per_slice);
+        suggestions.extend(scored);
+    }
+    Ok(suggestions)
+}
+
+/// Accept a suggestion: assign the label, and remember the decision.
+pub fn accept_suggestion(db: &Database, slice_id: i64, label_id: i64) -> Result<()> {
+    db.assign_label(label_id, &[slice_id])?;
+    db.record_label_feedback(slice_id, label_id, true)?;
+    info!("Accepted label {} suggested for slice {}", label_id, slice_id);
+    Ok(())
+}
+
+/// Reject a suggestion, so it isn't offered again and counts against the label for
+/// similar slices.
+pub fn reject_suggestion(db: &Database, slice_id: i64, label_id: i64) -> Result<()> {
+    db.record_label_feedback(slice_id, label_id, false)?;
+    info!("Rejected label {} suggested for slice {}", label_id, slice_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+    use crate::backend::models::{Label, Slice, TranscriptChunk};
+    use tempfile::TempDir;
+
+    const MODEL: &str = "nomic-embed-text";
+
+    fn indexed_slice(db: &Database, name: &str, embedding: Vec<f32>) -> Result<i64> {
+        let slice_id = db.insert_slice(&Slice {
+            transcribed: true,
+            transcription: Some("text".to_string()),
+            ..create_test_slice(name)
+        })?;
+        let chunk = TranscriptChunk { slice_id, start_char: 0, end_char: 4, text: "text".to_string(), embedding };
+        db.replace_transcript_chunks(slice_id, MODEL, "hash", &[chunk])?;
+        Ok(slice_id)
+    }
+
+    fn label(db: &Database, name: &str) -> Result<i64> {
+        db.create_label(&Label { id: None, name: name.into(), color: "#228be6".into(), keywords: String::new() })
+    }
+
+    #[test]
+    fn test_unit_mean() {
+        assert_eq!(unit_mean(&[vec![3.0, 0.0], vec![0.0, 0.0]]), Some(vec![1.0, 0.0]));
+        assert_eq!(unit_mean(&[vec![1.0, 0.0], vec![1.0, 2.0, 3.0]]), Some(vec![1.0, 0.0]));
+        assert_eq!(unit_mean(&Vec::<Vec<f32>>::new()), None);
+    }
+
+    #[test]
+    fn test_suggestions_follow_labeled_slices_and_feedback() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        let work = label(&db, "Work")?;
+        let family = label(&db, "Family")?;
+        label(&db, "Unused")?;
+        let standup = indexed_slice(&db, "standup.m4a", vec![1.0, 0.1, 0.0])?;
+        let birthday = indexed_slice(&db, "birthday.m4a", vec![0.0, 0.1, 1.0])?;
+        db.assign_label(work, &[standup])?;
+        db.assign_label(family, &[birthday])?;
+        let sprint = indexed_slice(&db, "sprint.m4a", vec![0.9, 0.2, 0.1])?;
+        let errand = indexed_slice(&db, "errand.m4a", vec![0.8, 0.0, 0.3])?;
+        let noise = indexed_slice(&db, "noise.m4a", vec![0.0, 1.0, 0.0])?;
+
+        let pairs = |suggestions: Vec<LabelSuggestion>| -> Vec<(i64, i64)> {
+            suggestions.iter().map(|s| (s.slice_id, s.label_id)).collect()
+        };
+        let suggestions = suggest_labels(&db, MODEL, None, 3, DEFAULT_MIN_SCORE)?;
+        assert!(suggestions.iter().all(|s| s.slice_id != noise), "nothing scores high enough for noise");
+        assert_eq!(pairs(suggestions), vec![(sprint, work), (errand, work)]);
+
+        // Turning Work down for the errand also lowers it for slices like the errand
+        let before = suggest_labels(&db, MODEL, Some(&[sprint]), 3, 0.0)?[0].score;
+        reject_suggestion(&db, errand, work)?;
+        let after = suggest_labels(&db, MODEL, Some(&[sprint]), 3, 0.0)?[0].score;
+        assert!(after < before);
+        assert!(suggest_labels(&db, MODEL, Some(&[errand]), 3, 0.0)?.iter().all(|s| s.label_id != work));
+
+        accept_suggestion(&db, sprint, work)?;
+        assert_eq!(db.list_slices_with_labels(&[work], false)?.len(), 2);
+        assert!(pairs(suggest_labels(&db, MODEL, None, 3, DEFAULT_MIN_SCORE)?).iter().all(|(id, _)| *id != sprint));
+
+        db.delete_label(work)?;
+        assert!(db.list_rejected_label_suggestions()?.is_empty());
+        Ok(())
+    }
+}
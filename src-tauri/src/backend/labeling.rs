@@ -0,0 +1,207 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use std::sync::Mutex;
+use tracing::info;
+
+use super::config::{Config, KeywordMatchMode};
+use super::database::Database;
+use super::models::{AutoLabelReport, LabelMatchCount};
+
+lazy_static::lazy_static! {
+    // The configured mode, used when labels are applied as transcripts are saved
+    static ref MATCH_MODE: Mutex<KeywordMatchMode> = Mutex::new(KeywordMatchMode::default());
+}
+
+/// Take the keyword match mode from the config.
+pub fn apply_config(config: &Config) {
+    if let Ok(mut mode) = MATCH_MODE.lock() {
+        *mode = config.keyword_match_mode;
+    }
+}
+
+/// The configured keyword match mode.
+pub fn match_mode() -> KeywordMatchMode {
+    MATCH_MODE.lock().map(|mode| *mode).unwrap_or_default()
+}
+
+/// Lowercased words of `text`, split on anything that isn't a letter, digit or apostrophe.
+pub fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Edit distance between two words, counted in characters.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Misspellings tolerated in fuzzy mode; short words must match exactly, since
+/// one edit turns "cat" into "car".
+pub fn allowed_edits(keyword_word: &str) -> usize {
+    match keyword_word.chars().count() {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+fn words_match(keyword_word: &str, text_word: &str, mode: KeywordMatchMode) -> bool {
+    match mode {
+        KeywordMatchMode::Fuzzy => levenshtein(keyword_word, text_word) <= allowed_edits(keyword_word),
+        _ => keyword_word == text_word,
+    }
+}
+
+/// Whether `phrase` (one comma-separated keyword entry) occurs in a transcript.
+/// `text_lower` and `text_words` are the transcript lowercased and split with `words`.
+fn phrase_matches(phrase: &str, text_lower: &str, text_words: &[String], mode: KeywordMatchMode) -> bool {
+    if mode == KeywordMatchMode::Substring {
+        return text_lower.contains(&phrase.to_lowercase());
+    }
+
+    let phrase_words = words(phrase);
+    if phrase_words.is_empty() || phrase_words.len() > text_words.len() {
+        return false;
+    }
+    text_words.windows(phrase_words.len()).any(|window| {
+        window.iter().zip(&phrase_words).all(|(text_word, keyword_word)| words_match(keyword_word, text_word, mode))
+    })
+}
+
+/// Whether a label's comma-separated `keywords` match a transcript under `mode`.
+pub fn keywords_match(keywords: &str, text: &str, mode: KeywordMatchMode) -> bool {
+    let text_lower = text.to_lowercase();
+    let text_words = words(text);
+    keywords
+        .split(',')
+        .map(|phrase| phrase.trim())
+        .filter(|phrase| !phrase.is_empty())
+        .any(|phrase| phrase_matches(phrase, &text_lower, &text_words, mode))
+}
+
+/// Scan every transcribed slice outside the trash for each label's keywords and assign the
+/// labels that match. Only adds labels, so ones assigned by hand are never taken away.
+pub fn auto_label_slices(db: &Database, mode: KeywordMatchMode) -> Result<AutoLabelReport> {
+    let labels: Vec<_> = db.list_labels()?
+        .into_iter()
+        .filter(|label| label.id.is_some() && !label.keywords.trim().is_empty())
+        .collect();
+    let transcripts: Vec<(i64, String)> = db.list_active_slices()?
+        .into_iter()
+        .filter_map(|slice| match (slice.id, slice.transcription) {
+            (Some(id), Some(text)) if !text.trim().is_empty() => Some((id, text)),
+            _ => None,
+        })
+        .collect();
+
+    let mut report = AutoLabelReport {
+        slices_scanned: transcripts.len() as u32,
+        labels: Vec::new(),
+    };
+
+    for label in labels {
+        let label_id = label.id.unwrap_or_default();
+        let matched: Vec<i64> = transcripts
+            .iter()
+            .filter(|(_, text)| keywords_match(&label.keywords, text, mode))
+            .map(|(id, _)| *id)
+            .collect();
+        let newly_assigned = db.assign_label(label_id, &matched)?;
+
+        report.labels.push(LabelMatchCount {
+            label_id,
+            label_name: label.name,
+            matched_slices: matched.len() as u32,
+            newly_assigned,
+        });
+    }
+
+    let added: u32 = report.labels.iter().map(|l| l.newly_assigned).sum();
+    info!("Auto-labeling ({:?}) scanned {} slices, assigned {} new label(s)", mode, report.slices_scanned, added);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+    use crate::backend::models::{Label, Slice};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_keyword_match_modes() {
+        let text = "Let's start the quarterly budget review, then the marketting plan.";
+
+        assert!(keywords_match("art", text, KeywordMatchMode::Substring));
+        assert!(!keywords_match("art", text, KeywordMatchMode::WordBoundary));
+        assert!(keywords_match("Budget Review", text, KeywordMatchMode::WordBoundary));
+        assert!(!keywords_match("marketing", text, KeywordMatchMode::WordBoundary));
+        assert!(keywords_match("groceries, marketing", text, KeywordMatchMode::Fuzzy));
+        assert!(!keywords_match("tart", text, KeywordMatchMode::Fuzzy), "short words match exactly");
+    }
+
+    #[test]
+    fn test_auto_label_slices_reports_per_label() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        let insert = |name: &str, transcription: Option<&str>| -> Result<i64> {
+            db.insert_slice(&Slice {
+                transcribed: transcription.is_some(),
+                transcription: transcription.map(str::to_string),
+                ..create_test_slice(name)
+            })
+        };
+        let standup = insert("a.m4a", Some("Notes from the team standup"))?;
+        let groceries = insert("b.m4a", Some("Buy milk and eggs"))?;
+        insert("c.m4a", None)?;
+
+        let work = db.create_label(&Label { id: None, name: "Work".into(), color: "#228be6".into(), keywords: "standup, sprint".into() })?;
+        db.create_label(&Label { id: None, name: "Untagged".into(), color: "#868e96".into(), keywords: String::new() })?;
+        let shopping = db.create_label(&Label { id: None, name: "Shopping".into(), color: "#40c057".into(), keywords: "milk".into() })?;
+        db.assign_label(shopping, &[groceries])?;
+
+        let report = auto_label_slices(&db, KeywordMatchMode::WordBoundary)?;
+        assert_eq!(report.slices_scanned, 2);
+        assert_eq!(report.labels.len(), 2, "labels without keywords are skipped");
+        let counts: Vec<_> = report.labels.iter().map(|l| (l.label_id, l.matched_slices, l.newly_assigned)).collect();
+        assert_eq!(counts, vec![(work, 1, 1), (shopping, 1, 0)]);
+
+        let labels = db.get_labels_for_all_slices()?;
+        assert_eq!(labels[&standup][0].name, "Work");
+        Ok(())
+    }
+}
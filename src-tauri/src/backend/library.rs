@@ -0,0 +1,551 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+use super::config::Config;
+use super::database::Database;
+use super::migrate::{is_composition_dir, place_file, sha256_file, verify_copy};
+use super::logging;
+use super::models::{DatabaseCheckReport, ImportFailure, LibraryRepairOptions, LibraryVerifyReport, Slice, SliceDeleteReport, VacuumReport};
+
+/// Slices created from typed or imported text have no audio file.
+pub(crate) fn has_audio(slice: &Slice) -> bool {
+    slice.audio_file_type != "text"
+}
+
+/// Slices that came from the Voice Memos folder (older rows have no source).
+fn from_voice_memos(slice: &Slice) -> bool {
+    matches!(slice.source.as_deref(), None | Some("voice_memos"))
+}
+
+/// Names of the regular files in the audio directory, ignoring hidden files like .DS_Store.
+fn library_files(audio_dir: &Path) -> Result<Vec<String>> {
+    if !audio_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(audio_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with('.') {
+            files.push(name);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Where each of `wanted` lives under the Voice Memos folder, skipping edit compositions
+/// the same way migration does.
+fn find_in_voice_memos(root: &Path, wanted: &HashSet<&str>) -> HashMap<String, PathBuf> {
+    let mut found = HashMap::new();
+    if wanted.is_empty() || !root.exists() {
+        return found;
+    }
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| !is_composition_dir(e.path()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        if let Some(name) = entry.file_name().to_str() {
+            if wanted.contains(name) {
+                found.entry(name.to_string()).or_insert_with(|| entry.path().to_path_buf());
+            }
+        }
+    }
+    found
+}
+
+/// Copy a missing recording back into the library. A slice with a checksum only accepts
+/// the exact same audio; otherwise the checksum is recorded now.
+fn recopy(config: &Config, db: &Database, slice: &Slice, source: &Path) -> Result<()> {
+    let source_hash = sha256_file(source)?;
+    if let Some(expected) = &slice.content_hash {
+        if *expected != source_hash {
+            return Err(anyhow::anyhow!(
+                "Voice Memos copy of {} no longer matches the library's checksum",
+                slice.original_audio_file_name
+            ));
+        }
+    }
+
+    fs::create_dir_all(config.audio_dir())?;
+    let dest = config.audio_dir().join(&slice.original_audio_file_name);
+    place_file(source, &dest, config.copy_mode)?;
+    verify_copy(&source_hash, &dest)?;
+    if slice.content_hash.is_none() {
+        if let Some(id) = slice.id {
+            db.set_slice_content_hash(id, &source_hash)?;
+        }
+    }
+    Ok(())
+}
+
+/// Cross-check every slice against the audio directory, reporting slices whose audio is
+/// missing and files no slice refers to, then make the repairs `options` asks for.
+/// Missing audio is re-copied before rows are removed, so only unrecoverable slices go.
+pub fn verify_library(config: &Config, db: &Database, options: &LibraryRepairOptions) -> Result<LibraryVerifyReport> {
+    let audio_dir = config.audio_dir();
+    let slices = db.list_all_slices()?;
+    let files = library_files(&audio_dir)?;
+
+    let mut report = LibraryVerifyReport {
+        checked_slices: slices.len() as u32,
+        ..LibraryVerifyReport::default()
+    };
+
+    let known: HashSet<&str> = slices.iter().map(|s| s.original_audio_file_name.as_str()).collect();
+    report.orphan_files = files.iter().filter(|f| !known.contains(f.as_str())).cloned().collect();
+
+    let present: HashSet<&str> = files.iter().map(String::as_str).collect();
+    let mut missing: Vec<&Slice> = slices.iter()
+        .filter(|s| has_audio(s) && !present.contains(s.original_audio_file_name.as_str()))
+        .collect();
+    report.missing_files = missing.iter().map(|s| s.original_audio_file_name.clone()).collect();
+
+    info!(
+        "Library check: {} slices, {} missing audio files, {} orphan files",
+        slices.len(), report.missing_files.len(), report.orphan_files.len()
+    );
+
+    if options.recopy_missing {
+        let wanted: HashSet<&str> = missing.iter()
+            .filter(|s| from_voice_memos(s))
+            .map(|s| s.original_audio_file_name.as_str())
+            .collect();
+        let sources = find_in_voice_memos(&config.voice_memo_root_path(), &wanted);
+
+        missing.retain(|slice| {
+            let Some(source) = sources.get(&slice.original_audio_file_name) else {
+                return true;
+            };
+            match recopy(config, db, slice, source) {
+                Ok(()) => {
+                    report.recopied_files.push(slice.original_audio_file_name.clone());
+                    false
+                }
+                Err(e) => {
+                    warn!("Could not re-copy {}: {}", slice.original_audio_file_name, e);
+                    report.errors.push(ImportFailure {
+                        file_path: source.to_string_lossy().to_string(),
+                        message: e.to_string(),
+                    });
+                    true
+                }
+            }
+        });
+    }
+
+    if options.remove_orphan_files {
+        for name in &report.orphan_files {
+            match fs::remove_file(audio_dir.join(name)) {
+                Ok(()) => report.removed_files.push(name.clone()),
+                Err(e) => report.errors.push(ImportFailure {
+                    file_path: audio_dir.join(name).to_string_lossy().to_string(),
+                    message: e.to_string(),
+                }),
+            }
+        }
+    }
+
+    if options.remove_missing_rows {
+        for slice in &missing {
+            if let Some(id) = slice.id {
+                db.delete_slice(id)?;
+                report.removed_slice_ids.push(id);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Files in the audio directory that belong to `slice`: its audio and the WAV left
+/// next to it when transcription converts the recording.
+fn slice_files(audio_dir: &Path, slice: &Slice) -> Vec<PathBuf> {
+    if !has_audio(slice) {
+        return Vec::new();
+    }
+    let audio = audio_dir.join(&slice.original_audio_file_name);
+    let wav = audio.with_extension("wav");
+    if wav == audio {
+        vec![audio]
+    } else {
+        vec![audio, wav]
+    }
+}
+
+/// Check the database file itself and cross-check it against the library files; only
+/// reports, see `verify_library` for repairs.
+pub fn check_database(config: &Config, db: &Database) -> Result<DatabaseCheckReport> {
+    let mut report = DatabaseCheckReport {
+        integrity_errors: db.integrity_check()?,
+        foreign_key_violations: db.foreign_key_check()?,
+        dangling_label_links: db.count_dangling_label_links()?,
+        files: verify_library(config, db, &LibraryRepairOptions::default())?,
+        ..DatabaseCheckReport::default()
+    };
+
+    let audio_dir = config.audio_dir();
+    for slice in db.list_all_slices()?.iter().filter(|s| has_audio(s)) {
+        let Ok(metadata) = fs::metadata(audio_dir.join(&slice.original_audio_file_name)) else {
+            continue; // already reported as missing
+        };
+        if metadata.len() as i64 != slice.audio_file_size {
+            report.size_mismatches.push(slice.original_audio_file_name.clone());
+        }
+    }
+
+    report.ok = report.integrity_errors.is_empty()
+        && report.foreign_key_violations.is_empty()
+        && report.dangling_label_links == 0
+        && report.size_mismatches.is_empty()
+        && report.files.missing_files.is_empty()
+        && report.files.orphan_files.is_empty();
+    info!(
+        "Database check: {} integrity error(s), {} size mismatch(es), {} dangling label link(s)",
+        report.integrity_errors.len(), report.size_mismatches.len(), report.dangling_label_links
+    );
+    Ok(report)
+}
+
+/// Bytes the database at `db_path` takes on disk, counting its write-ahead log.
+fn database_size(db_path: &Path) -> u64 {
+    let name = db_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    [db_path.to_path_buf(), db_path.with_file_name(format!("{}-wal", name))]
+        .into_iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// VACUUM the database at `db_path` (which `db` is connected to), e.g. after deleting many
+/// slices, reporting how much smaller the file got.
+pub fn compact_database(db: &Database, db_path: &Path) -> Result<VacuumReport> {
+    let bytes_before = database_size(db_path);
+    db.vacuum()?;
+    let bytes_after = database_size(db_path);
+
+    let report = VacuumReport {
+        bytes_before,
+        bytes_after,
+        reclaimed_bytes: bytes_before.saturating_sub(bytes_after),
+    };
+    logging::log_info(
+        "library",
+        &format!("Compacted database, reclaimed {} bytes", report.reclaimed_bytes),
+        Some(serde_json::json!({ "bytes_before": bytes_before, "bytes_after": bytes_after })),
+    );
+    Ok(report)
+}
+
+/// Delete slices for good: their rows, labels and source links, their audio files and any
+/// leftover transcription WAVs. A slice that can't be found or removed is reported, not fatal.
+pub fn delete_slices(config: &Config, db: &Database, slice_ids: &[i64]) -> Result<SliceDeleteReport> {
+    let audio_dir = config.audio_dir();
+    let mut report = SliceDeleteReport::default();
+
+    for &slice_id in slice_ids {
+        let Some(slice) = db.get_slice(slice_id)? else {
+            report.errors.push(ImportFailure {
+                file_path: slice_id.to_string(),
+                message: format!("No slice found with ID: {}", slice_id),
+            });
+            continue;
+        };
+
+        db.delete_slice(slice_id)?;
+        report.deleted_slice_ids.push(slice_id);
+
+        for path in slice_files(&audio_dir, &slice).into_iter().filter(|p| p.exists()) {
+            match fs::remove_file(&path) {
+                Ok(()) => report.removed_files.push(path.file_name().unwrap_or_default().to_string_lossy().to_string()),
+                Err(e) => {
+                    warn!("Failed to remove {:?}: {}", path, e);
+                    report.errors.push(ImportFailure {
+                        file_path: path.to_string_lossy().to_string(),
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    logging::log_info(
+        "library",
+        &format!("Deleted {} slice(s), removed {} file(s)", report.deleted_slice_ids.len(), report.removed_files.len()),
+        Some(serde_json::json!({
+            "slice_ids": report.deleted_slice_ids,
+            "removed_files": report.removed_files,
+            "errors": report.errors.len(),
+        })),
+    );
+    Ok(report)
+}
+
+/// Permanently delete slices from the trash: all of them, or with `older_than_days` only
+/// those trashed at least that many days ago.
+pub fn empty_trash(config: &Config, db: &Database, older_than_days: Option<u32>) -> Result<SliceDeleteReport> {
+    let cutoff = match older_than_days {
+        Some(days) => chrono::Utc::now().timestamp() - i64::from(days) * 24 * 60 * 60,
+        None => i64::MAX,
+    };
+    let expired = db.get_slices_trashed_before(cutoff)?;
+    if expired.is_empty() {
+        return Ok(SliceDeleteReport::default());
+    }
+    info!("Emptying {} slice(s) from the trash", expired.len());
+    delete_slices(config, db, &expired)
+}
+
+/// Delete trashed slices older than the configured `trash_retention_days` (0 keeps them forever).
+pub fn purge_expired_trash(config: &Config, db: &Database) -> Result<SliceDeleteReport> {
+    if config.trash_retention_days == 0 {
+        return Ok(SliceDeleteReport::default());
+    }
+    empty_trash(config, db, Some(config.trash_retention_days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+    use tempfile::TempDir;
+
+    fn audio_slice(filename: &str, content_hash: Option<String>) -> Slice {
+        Slice {
+            audio_file_size: 5,
+            source: Some("voice_memos".to_string()),
+            content_hash,
+            ..create_test_slice(filename)
+        }
+    }
+
+    #[test]
+    fn test_delete_slices_removes_rows_and_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        fs::write(config.audio_dir().join("memo.m4a"), b"memo")?;
+        fs::write(config.audio_dir().join("memo.wav"), b"pcm")?;
+        fs::write(config.audio_dir().join("kept.m4a"), b"kept")?;
+        let memo_id = db.insert_slice(&audio_slice("memo.m4a", None))?;
+        let kept_id = db.insert_slice(&audio_slice("kept.m4a", None))?;
+        let mut text = audio_slice("note.txt", None);
+        text.audio_file_type = "text".to_string();
+        let text_id = db.insert_slice(&text)?;
+        let label_id = db.get_or_create_label("work", "#000000")?;
+        db.assign_label(label_id, &[memo_id])?;
+
+        let report = delete_slices(&config, &db, &[memo_id, text_id, 999])?;
+        assert_eq!(report.deleted_slice_ids, vec![memo_id, text_id]);
+        assert_eq!(report.removed_files, vec!["memo.m4a", "memo.wav"]);
+        assert_eq!(report.errors.len(), 1);
+
+        assert!(!config.audio_dir().join("memo.m4a").exists());
+        assert!(config.audio_dir().join("kept.m4a").exists());
+        let remaining: Vec<_> = db.list_all_slices()?.into_iter().filter_map(|s| s.id).collect();
+        assert_eq!(remaining, vec![kept_id]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_trash_restore_and_expiry() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            trash_retention_days: 30,
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        fs::write(config.audio_dir().join("old.m4a"), b"old")?;
+        fs::write(config.audio_dir().join("recent.m4a"), b"recent")?;
+        let old_id = db.insert_slice(&audio_slice("old.m4a", None))?;
+        let recent_id = db.insert_slice(&audio_slice("recent.m4a", None))?;
+        let live_id = db.insert_slice(&audio_slice("live.m4a", None))?;
+
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(db.trash_slices(&[old_id], now - 31 * 24 * 60 * 60)?, 1);
+        assert_eq!(db.trash_slices(&[recent_id, old_id], now)?, 1, "already trashed keeps its time");
+
+        let active: Vec<_> = db.list_active_slices()?.into_iter().filter_map(|s| s.id).collect();
+        assert_eq!(active, vec![live_id]);
+        let trashed: Vec<_> = db.list_trashed_slices()?.into_iter().filter_map(|t| t.slice.id).collect();
+        assert_eq!(trashed, vec![recent_id, old_id]);
+        assert_eq!(db.get_stats()?.total_files, 1);
+
+        // Only the slice past the retention period goes, audio and all
+        let report = purge_expired_trash(&config, &db)?;
+        assert_eq!(report.deleted_slice_ids, vec![old_id]);
+        assert!(!config.audio_dir().join("old.m4a").exists());
+        assert!(config.audio_dir().join("recent.m4a").exists());
+
+        assert_eq!(db.restore_slices(&[recent_id, live_id])?, 1);
+        assert_eq!(db.list_active_slices()?.len(), 2);
+        assert!(empty_trash(&config, &db, None)?.deleted_slice_ids.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_database_reports_problems() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        fs::write(config.audio_dir().join("good.m4a"), b"12345")?;
+        db.insert_slice(&audio_slice("good.m4a", None))?;
+        assert!(check_database(&config, &db)?.ok);
+
+        fs::write(config.audio_dir().join("resized.m4a"), b"longer than recorded")?;
+        let resized = db.insert_slice(&audio_slice("resized.m4a", None))?;
+        let label_id = db.get_or_create_label("work", "#000000")?;
+        db.assign_label(label_id, &[resized])?;
+        // A link left behind by a slice deleted outside CiderPress
+        rusqlite::Connection::open(temp_dir.path().join("test.db"))?
+            .execute("INSERT INTO slice_labels (slice_id, label_id) VALUES (999, ?1)", [label_id])?;
+
+        let report = check_database(&config, &db)?;
+        assert!(!report.ok);
+        assert!(report.integrity_errors.is_empty());
+        assert_eq!(report.size_mismatches, vec!["resized.m4a"]);
+        assert_eq!(report.dangling_label_links, 1);
+        assert_eq!(report.files.checked_slices, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_database_reclaims_space() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join("test.db");
+        let db = Database::new(&db_path)?;
+
+        let mut ids = Vec::new();
+        for i in 0..200 {
+            let mut slice = audio_slice(&format!("memo-{}.m4a", i), None);
+            slice.transcription = Some("words ".repeat(200));
+            ids.push(db.insert_slice(&slice)?);
+        }
+        db.vacuum()?;
+        for id in ids {
+            db.delete_slice(id)?;
+        }
+
+        let report = compact_database(&db, &db_path)?;
+        assert!(report.reclaimed_bytes > 100_000, "reclaimed {} bytes", report.reclaimed_bytes);
+        assert_eq!(report.bytes_after, fs::metadata(&db_path)?.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_library_reports_and_repairs() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let memos = temp_dir.path().join("memos");
+        fs::create_dir_all(&memos)?;
+        fs::write(memos.join("lost.m4a"), b"lost audio")?;
+
+        let config = Config {
+            voice_memo_root: memos.to_string_lossy().to_string(),
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        fs::write(config.audio_dir().join("present.m4a"), b"here")?;
+        fs::write(config.audio_dir().join("stray.m4a"), b"stray")?;
+        fs::write(config.audio_dir().join(".DS_Store"), b"")?;
+        db.insert_slice(&audio_slice("present.m4a", None))?;
+        let lost_hash = sha256_file(&memos.join("lost.m4a"))?;
+        db.insert_slice(&audio_slice("lost.m4a", Some(lost_hash)))?;
+        let gone_id = db.insert_slice(&audio_slice("gone.m4a", None))?;
+        let mut text = audio_slice("text_entry.txt", None);
+        text.audio_file_type = "text".to_string();
+        db.insert_slice(&text)?;
+
+        // Report only
+        let report = verify_library(&config, &db, &LibraryRepairOptions::default())?;
+        assert_eq!(report.checked_slices, 4);
+        assert_eq!(report.missing_files, vec!["lost.m4a", "gone.m4a"]);
+        assert_eq!(report.orphan_files, vec!["stray.m4a"]);
+        assert!(config.audio_dir().join("stray.m4a").exists());
+        assert_eq!(db.list_all_slices()?.len(), 4);
+
+        let options = LibraryRepairOptions {
+            recopy_missing: true,
+            remove_orphan_files: true,
+            remove_missing_rows: true,
+        };
+        let report = verify_library(&config, &db, &options)?;
+        assert_eq!(report.recopied_files, vec!["lost.m4a"]);
+        assert_eq!(report.removed_files, vec!["stray.m4a"]);
+        assert_eq!(report.removed_slice_ids, vec![gone_id]);
+        assert!(report.errors.is_empty());
+        assert_eq!(fs::read(config.audio_dir().join("lost.m4a"))?, b"lost audio");
+        assert!(!config.audio_dir().join("stray.m4a").exists());
+
+        // Everything now checks out
+        let report = verify_library(&config, &db, &LibraryRepairOptions::default())?;
+        assert_eq!(report.checked_slices, 3);
+        assert!(report.missing_files.is_empty());
+        assert!(report.orphan_files.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_recopy_rejects_changed_source() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let memos = temp_dir.path().join("memos");
+        fs::create_dir_all(&memos)?;
+        fs::write(memos.join("memo.m4a"), b"re-recorded")?;
+
+        let config = Config {
+            voice_memo_root: memos.to_string_lossy().to_string(),
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+        db.insert_slice(&audio_slice("memo.m4a", Some("0".repeat(64))))?;
+
+        let options = LibraryRepairOptions { recopy_missing: true, ..LibraryRepairOptions::default() };
+        let report = verify_library(&config, &db, &options)?;
+        assert!(report.recopied_files.is_empty());
+        assert_eq!(report.errors.len(), 1);
+        assert!(!config.audio_dir().join("memo.m4a").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use chrono::{Local, NaiveDateTime, TimeZone};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use super::config::Config;
+use super::database::Database;
+use super::importer::{self, ImportDetails};
+use super::models::{FolderImportProgress, FolderImportReport};
+
+const SOURCE: &str = "meeting";
+
+/// Every imported meeting recording is tagged with this label.
+const MEETING_LABEL: &str = "meeting";
+const MEETING_LABEL_COLOR: &str = "#7950f2";
+
+lazy_static::lazy_static! {
+    // Zoom local recording folder: "2024-01-15 10.00.00 Weekly Sync 81234567890"
+    static ref ZOOM_FOLDER: Regex =
+        Regex::new(r"^(\d{4}-\d{2}-\d{2} \d{2}\.\d{2}\.\d{2}) (.+?)(?: \d{9,11})?$").unwrap();
+    // Teams recording file: "Weekly Sync-20240115_100012-Meeting Recording"
+    static ref TEAMS_FILE: Regex =
+        Regex::new(r"^(.+?)-(\d{8}_\d{6})-Meeting Recording").unwrap();
+}
+
+/// Where Zoom and Teams save local recordings by default.
+pub fn default_meeting_folders() -> Vec<PathBuf> {
+    let documents = dirs::document_dir()
+        .or_else(|| dirs::home_dir().map(|home| home.join("Documents")))
+        .unwrap_or_else(|| PathBuf::from("/tmp"));
+    vec![
+        documents.join("Zoom"),
+        documents.join("Microsoft Teams").join("Recordings"),
+    ]
+}
+
+/// Recordings to import under `root`. Zoom saves an audio-only `.m4a` next to the video,
+/// so a folder's `.mp4` files are only used (audio extracted) when it has no `.m4a`.
+fn select_recordings(root: &Path) -> Vec<PathBuf> {
+    let mut by_folder: BTreeMap<PathBuf, (Vec<PathBuf>, Vec<PathBuf>)> = BTreeMap::new();
+    for path in importer::scan_files_with_extensions(root, &["m4a", "mp4"]) {
+        let folder = path.parent().unwrap_or(root).to_path_buf();
+        let (audio, video) = by_folder.entry(folder).or_default();
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("m4a")) {
+            audio.push(path);
+        } else {
+            video.push(path);
+        }
+    }
+
+    by_folder
+        .into_values()
+        .flat_map(|(audio, video)| if audio.is_empty() { video } else { audio })
+        .collect()
+}
+
+fn parse_local(datetime: &str, format: &str) -> Option<i64> {
+    let naive = NaiveDateTime::parse_from_str(datetime, format).ok()?;
+    Local.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp())
+}
+
+/// Library name, title and date for a meeting recording, read from Zoom's folder name or
+/// Teams' file name. Zoom's own file names (`audio_only.m4a`) repeat across meetings,
+/// so the folder name becomes the library filename.
+fn describe_recording(path: &Path) -> ImportDetails {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording").to_string();
+    let folder_name = path.parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+
+    if let Some(caps) = ZOOM_FOLDER.captures(folder_name) {
+        return ImportDetails {
+            stem: format!("{} {}", folder_name, stem),
+            title: caps[2].to_string(),
+            recording_date: parse_local(&caps[1], "%Y-%m-%d %H.%M.%S"),
+        };
+    }
+
+    if let Some(caps) = TEAMS_FILE.captures(&stem) {
+        return ImportDetails {
+            title: caps[1].to_string(),
+            recording_date: parse_local(&caps[2], "%Y%m%d_%H%M%S"),
+            stem,
+        };
+    }
+
+    ImportDetails {
+        title: stem.clone(),
+        recording_date: importer::file_modified_timestamp(path),
+        stem,
+    }
+}
+
+/// Import Zoom/Teams recordings from each folder, extracting the audio from video files.
+/// Newly imported slices get the "meeting" label. Missing folders are skipped.
+pub fn import_meeting_recordings(
+    config: &Config,
+    db: &Database,
+    folders: &[PathBuf],
+    mut on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<FolderImportReport> {
+    let mut report = FolderImportReport::default();
+
+    for folder in folders.iter().filter(|f| f.is_dir()) {
+        let files = select_recordings(folder);
+        info!("Meeting import: {} recordings found in {:?}", files.len(), folder);
+
+        let folder_report = importer::import_tracked_files(
+            config, db, folder, &files, SOURCE, describe_recording, &mut on_progress,
+        )?;
+        report.imported_slice_ids.extend(folder_report.imported_slice_ids);
+        report.duplicates.extend(folder_report.duplicates);
+        report.errors.extend(folder_report.errors);
+    }
+
+    if !report.imported_slice_ids.is_empty() {
+        let label_id = db.get_or_create_label(MEETING_LABEL, MEETING_LABEL_COLOR)?;
+        db.assign_label(label_id, &report.imported_slice_ids)?;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_describe_zoom_and_teams_recordings() {
+        let zoom = Path::new("/Zoom/2024-01-15 10.00.00 Weekly Sync 81234567890/audio_only.m4a");
+        let details = describe_recording(zoom);
+        assert_eq!(details.stem, "2024-01-15 10.00.00 Weekly Sync 81234567890 audio_only");
+        assert_eq!(details.title, "Weekly Sync");
+        assert_eq!(details.recording_date, parse_local("2024-01-15 10.00.00", "%Y-%m-%d %H.%M.%S"));
+        assert!(details.recording_date.is_some());
+
+        let teams = Path::new("/Recordings/Design Review-20240115_100012-Meeting Recording.mp4");
+        let details = describe_recording(teams);
+        assert_eq!(details.stem, "Design Review-20240115_100012-Meeting Recording");
+        assert_eq!(details.title, "Design Review");
+        assert_eq!(details.recording_date, parse_local("20240115_100012", "%Y%m%d_%H%M%S"));
+    }
+
+    #[test]
+    fn test_select_recordings_prefers_audio_only() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let with_audio = temp_dir.path().join("2024-01-15 10.00.00 Standup");
+        let video_only = temp_dir.path().join("2024-01-16 10.00.00 Standup");
+        fs::create_dir_all(&with_audio)?;
+        fs::create_dir_all(&video_only)?;
+        fs::write(with_audio.join("audio_only.m4a"), b"audio")?;
+        fs::write(with_audio.join("zoom_0.mp4"), b"video")?;
+        fs::write(video_only.join("zoom_0.mp4"), b"video")?;
+        fs::write(video_only.join("playback.m3u"), b"playlist")?;
+
+        let selected = select_recordings(temp_dir.path());
+        assert_eq!(selected, vec![with_audio.join("audio_only.m4a"), video_only.join("zoom_0.mp4")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_imported_meetings_get_meeting_label() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let zoom = temp_dir.path().join("Zoom");
+        let first = zoom.join("2024-01-15 10.00.00 Standup");
+        let second = zoom.join("2024-01-16 10.00.00 Standup");
+        fs::create_dir_all(&first)?;
+        fs::create_dir_all(&second)?;
+        fs::write(first.join("audio_only.m4a"), b"monday")?;
+        fs::write(second.join("audio_only.m4a"), b"tuesday")?;
+
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        let folders = vec![zoom.clone(), temp_dir.path().join("missing")];
+        let report = import_meeting_recordings(&config, &db, &folders, |_| {})?;
+        assert_eq!(report.imported_slice_ids.len(), 2);
+        assert!(report.errors.is_empty());
+
+        // Same file name in both meetings, stored under distinct library names
+        let slices = db.list_all_slices()?;
+        assert!(slices.iter().all(|s| s.title.as_deref() == Some("Standup")));
+        assert!(slices.iter().any(|s| s.original_audio_file_name == "2024-01-15 10.00.00 Standup audio_only.m4a"));
+
+        let labels = db.get_labels_for_all_slices()?;
+        for id in &report.imported_slice_ids {
+            assert_eq!(labels[id].iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), vec![MEETING_LABEL]);
+        }
+
+        // A second run skips both
+        let report = import_meeting_recordings(&config, &db, &folders, |_| {})?;
+        assert!(report.imported_slice_ids.is_empty());
+        assert!(report.errors.is_empty());
+        Ok(())
+    }
+}
@@ -15,16 +15,24 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
 use tracing::{info, error, warn};
 use walkdir::WalkDir;
 
-use super::config::Config;
+use super::apple_transcript::{read_apple_transcript, APPLE_MODEL};
+use super::config::{Config, CopyMode, SourceKind, SourceProfile};
 use super::database::Database;
+use super::db_pool;
+use super::importer::{self, ImportOutcome};
+use super::ios_backup;
 use super::logging;
-use super::models::{MigrationSummary, MigrationProgress, Slice};
+use super::models::{DuplicateSkip, ImportFailure, MigrationBatchFile, MigrationFileEvent, MigrationRollbackReport, MigrationSummary, MigrationProgress, Slice, SourceSummary};
 
 /// Helper to emit migration log events
 fn log_migration(message: &str, level: &str) {
@@ -39,21 +47,81 @@ fn log_migration(message: &str, level: &str) {
     crate::emit_migration_log(message, level);
 }
 
+/// Seconds between the Unix epoch and Apple's Core Data epoch (2001-01-01)
+pub const APPLE_EPOCH_OFFSET: i64 = 978_307_200;
+
+/// Worker threads copying recordings during migration.
+const MIGRATION_COPY_WORKERS: usize = 4;
+/// Copies queued or running at once, so the workers never wait on the scan loop.
+const MIGRATION_COPY_BACKLOG: usize = MIGRATION_COPY_WORKERS * 2;
+
+/// Where a file discovered during migration came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MigrationSource {
+    VoiceMemos,
+    CallRecordings,
+    IosBackup, // Voice Memos staged out of a local iPhone backup
+    Additional(usize), // Index into `Config::additional_source_paths`
+}
+
+impl MigrationSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MigrationSource::VoiceMemos => "voice_memos",
+            MigrationSource::CallRecordings => "call_recording",
+            MigrationSource::IosBackup => "ios_backup",
+            MigrationSource::Additional(_) => "additional_source",
+        }
+    }
+}
+
+/// Metadata Apple keeps for a call recording in the call container's own database.
+#[derive(Debug, Clone, Default)]
+struct CallRecordingMetadata {
+    caller_name: Option<String>,
+    recording_date: Option<i64>,
+}
+
 // Global migration progress state
 lazy_static::lazy_static! {
     static ref MIGRATION_PROGRESS: Arc<Mutex<Option<MigrationProgress>>> = Arc::new(Mutex::new(None));
+    static ref LAST_MIGRATION_SUMMARY: Arc<Mutex<Option<MigrationSummary>>> = Arc::new(Mutex::new(None));
+    // Held for the whole of a migration, so manual, watch mode and scheduled runs never overlap
+    static ref MIGRATION_RUN: Mutex<()> = Mutex::new(());
 }
 
 pub struct MigrationEngine<'a> {
     config: &'a Config,
+    // Source recorded on slices found under `voice_memo_root`
+    primary_source: MigrationSource,
 }
 
 impl<'a> MigrationEngine<'a> {
     pub fn new(config: &'a Config) -> Self {
-        Self { config }
+        Self { config, primary_source: MigrationSource::VoiceMemos }
     }
 
-    pub fn start_migration(&self) -> Result<()> {
+    /// Engine for a Voice Memos container extracted from an iPhone backup.
+    /// `config.voice_memo_root` must point at the staged Recordings directory.
+    pub fn for_ios_backup(config: &'a Config) -> Self {
+        Self { config, primary_source: MigrationSource::IosBackup }
+    }
+
+    /// Run a migration, first waiting for one already running to finish.
+    pub fn start_migration(&self) -> Result<MigrationSummary> {
+        let _running = MIGRATION_RUN.lock().unwrap();
+        self.run_migration()
+    }
+
+    /// Run a migration unless one is already running, in which case `None`.
+    pub fn try_start_migration(&self) -> Option<Result<MigrationSummary>> {
+        let Ok(_running) = MIGRATION_RUN.try_lock() else {
+            return None;
+        };
+        Some(self.run_migration())
+    }
+
+    fn run_migration(&self) -> Result<MigrationSummary> {
         log_migration("Starting migration process", "info");
 
         // Reset progress
@@ -79,7 +147,7 @@ impl<'a> MigrationEngine<'a> {
             fs::create_dir_all(parent)?;
         }
 
-        let db = Database::new(&ciderpress_db_path)?;
+        let db = db_pool::connect(&ciderpress_db_path)?;
 
         self.update_progress("Connecting to Apple Voice Memo database...", None, None)?;
         log_migration("Connecting to Apple Voice Memo database...", "info");
@@ -110,6 +178,13 @@ impl<'a> MigrationEngine<'a> {
             }
         }
 
+        // 1b. Carry over Voice Memos folders as labels
+        match db.import_apple_folders(apple_db_path.to_str().unwrap()) {
+            Ok(0) => {}
+            Ok(created) => log_migration(&format!("Created {} labels from Voice Memos folders", created), "success"),
+            Err(e) => log_migration(&format!("Failed to import Voice Memos folders: {}", e), "warn"),
+        }
+
         // 2. Find all .m4a files to process
         self.update_progress("Scanning for .m4a audio files...", None, None)?;
         log_migration("Scanning for .m4a audio files...", "info");
@@ -171,19 +246,67 @@ impl<'a> MigrationEngine<'a> {
             return Err(anyhow::anyhow!(error_message));
         }
         
-        let m4a_files = self.scan_m4a_files(&voice_memo_dir)?;
+        let mut m4a_files = self.scan_m4a_files(&voice_memo_dir)?;
         log_migration(&format!("Found {} .m4a files to process", m4a_files.len()), "success");
 
+        // 2a. Memos in Recently Deleted stay in the folder until Apple purges them after 30 days
+        let recently_deleted = load_recently_deleted(&apple_db_path);
+        if !recently_deleted.is_empty() {
+            if self.config.include_recently_deleted {
+                log_migration(&format!("Including {} recordings from Recently Deleted", recently_deleted.len()), "info");
+            } else {
+                m4a_files.retain(|f| !is_recently_deleted(&recently_deleted, f));
+                log_migration(
+                    &format!("Leaving out {} recordings in Recently Deleted (enable \"include recently deleted\" to keep them)", recently_deleted.len()),
+                    "info",
+                );
+            }
+        }
+
+        // 2b. Call recordings live in a separate container with their own database
+        let call_recordings_dir = self.config.call_recordings_root_path();
+        let (call_files, call_metadata) = if call_recordings_dir.exists() {
+            self.update_progress("Scanning for call recordings...", None, None)?;
+            log_migration("Scanning for call recordings...", "info");
+            let files = self.scan_m4a_files(&call_recordings_dir)?;
+            log_migration(&format!("Found {} call recordings to process", files.len()), "success");
+            (files, load_call_recording_metadata(&call_recordings_dir))
+        } else {
+            info!("No call recordings container at {:?}", call_recordings_dir);
+            (Vec::new(), HashMap::new())
+        };
+
+        // 2c. Extra folders from settings (e.g. iCloud Drive), which may hold any audio format
+        let additional_roots = self.config.additional_source_paths();
+        let mut additional_files = Vec::new();
+        for (index, root) in additional_roots.iter().enumerate() {
+            if !root.is_dir() {
+                log_migration(&format!("Additional source not found, skipping: {:?}", root), "warn");
+                continue;
+            }
+            let files = importer::scan_audio_files(root);
+            log_migration(&format!("Found {} audio files in {:?}", files.len(), root), "info");
+            additional_files.extend(files.into_iter().map(|f| (f, MigrationSource::Additional(index))));
+        }
+
+        let m4a_files: Vec<(PathBuf, MigrationSource)> = m4a_files
+            .into_iter()
+            .map(|f| (f, self.primary_source))
+            .chain(call_files.into_iter().map(|f| (f, MigrationSource::CallRecordings)))
+            .chain(additional_files)
+            .collect();
+
         if m4a_files.is_empty() {
+            self.assign_folder_labels(&db);
             log_migration("No files to migrate. All files have already been migrated.", "success");
             self.update_progress("No files to migrate.", Some(0), Some(0))?;
             *MIGRATION_PROGRESS.lock().unwrap() = None;
-            return Ok(());
+            return Ok(MigrationSummary::default());
         }
 
         // Log first few files found
         info!("=== FILES TO PROCESS ===");
-        for (i, file) in m4a_files.iter().take(10).enumerate() {
+        for (i, (file, _)) in m4a_files.iter().take(10).enumerate() {
             info!("  [{}] {:?}", i + 1, file);
         }
         if m4a_files.len() > 10 {
@@ -191,7 +314,7 @@ impl<'a> MigrationEngine<'a> {
         }
 
         // 3. Calculate total size and update progress
-        let total_size_bytes: u64 = m4a_files.iter().map(|f| {
+        let total_size_bytes: u64 = m4a_files.iter().map(|(f, _)| {
             fs::metadata(f).map(|m| m.len()).unwrap_or(0)
         }).sum();
 
@@ -211,12 +334,24 @@ impl<'a> MigrationEngine<'a> {
         )?;
 
         let mut summary = MigrationSummary {
-            copied: 0,
-            skipped: 0,
-            errors: 0,
             total_size_bytes,
+            ..MigrationSummary::default()
         };
 
+        // Pick up an interrupted run: files it already handled are counted, not reprocessed
+        let batch_kind = self.primary_source.as_str();
+        let (batch_id, mut handled) = match db.active_migration_batch(batch_kind)? {
+            Some(batch_id) => {
+                let files = db.get_migration_batch_files(batch_id)?;
+                log_migration(&format!("Resuming interrupted migration ({} files already handled)", files.len()), "info");
+                let handled: HashMap<String, MigrationBatchFile> =
+                    files.into_iter().map(|f| (f.file_path.clone(), f)).collect();
+                (batch_id, handled)
+            }
+            None => (db.start_migration_batch(batch_kind)?, HashMap::new()),
+        };
+        summary.batch_id = Some(batch_id);
+
         // Ensure destination directory exists
         let dest_audio_dir = self.config.audio_dir();
 
@@ -228,35 +363,62 @@ impl<'a> MigrationEngine<'a> {
             }
         }
 
-        // 4. Process each .m4a file
-        for (index, m4a_file) in m4a_files.iter().enumerate() {
-            let filename = m4a_file.file_name()
+        // Slices migrated before checksums existed need a hash to take part in duplicate detection
+        self.update_progress("Indexing library for duplicate detection...", None, None)?;
+        match self.backfill_content_hashes(&db) {
+            Ok(0) => {}
+            Ok(count) => log_migration(&format!("Indexed {} existing recordings for duplicate detection", count), "info"),
+            Err(e) => log_migration(&format!("Failed to index existing recordings: {}", e), "warn"),
+        }
+
+        // 4. Process each file. Database work stays on this thread; hashing and copying
+        // new recordings runs on a small worker pool so many small files don't copy serially.
+        let total_files = m4a_files.len() as u32;
+        let mut processed_files: u32 = 0;
+        let mut copied_bytes: u64 = 0;
+
+        let mut finish_file = |file: &Path, source: MigrationSource, result: Result<ProcessResult>, resumed: bool| {
+            let filename = file.file_name()
                 .and_then(|f| f.to_str())
                 .unwrap_or("unknown.m4a");
+            let source_summary = self.source_summary_entry(&mut summary.sources, source, &additional_roots);
+            processed_files += 1;
+
+            if resumed {
+                summary.resumed_files += 1;
+            } else if let Ok(processed) = &result {
+                // Errors aren't recorded, so a resumed run retries them
+                let file_key = file.to_string_lossy().to_string();
+                if let Err(e) = db.record_migration_batch_file(batch_id, &processed.to_batch_file(file_key)) {
+                    warn!("Failed to record migration progress for {}: {}", filename, e);
+                }
+            }
 
-            log_migration(&format!("Processing ({}/{}): {}", index + 1, m4a_files.len(), filename), "info");
-
-            self.update_progress(
-                &format!("Processing ({}/{}): {}", index + 1, m4a_files.len(), filename),
-                None,
-                None,
-            )?;
+            let (outcome, bytes, message) = match &result {
+                Ok(ProcessResult::Copied(size)) => ("copied", Some(*size), None),
+                Ok(ProcessResult::Skipped) => ("skipped", None, None),
+                Ok(ProcessResult::Duplicate(existing)) => ("duplicate", None, Some(format!("Duplicate of {}", existing))),
+                Err(e) => ("error", None, Some(e.to_string())),
+            };
+            copied_bytes += bytes.unwrap_or(0);
 
-            match self.process_m4a_file(&m4a_file, &db) {
+            match result {
                 Ok(ProcessResult::Copied(size)) => {
                     summary.copied += 1;
+                    summary.sources[source_summary].copied += 1;
 
                     // Log to JSON log
                     logging::log_migration_file(filename, "copied", Some(size), None);
 
                     let mut progress = MIGRATION_PROGRESS.lock().unwrap();
                     if let Some(ref mut p) = *progress {
-                        p.processed_recordings = (index + 1) as u32;
+                        p.processed_recordings = processed_files;
                         p.processed_size_bytes += size;
                     }
                 }
                 Ok(ProcessResult::Skipped) => {
                     summary.skipped += 1;
+                    summary.sources[source_summary].skipped += 1;
                     log_migration(&format!("  Skipped (already migrated): {}", filename), "warn");
 
                     // Log to JSON log
@@ -264,12 +426,31 @@ impl<'a> MigrationEngine<'a> {
 
                     let mut progress = MIGRATION_PROGRESS.lock().unwrap();
                     if let Some(ref mut p) = *progress {
-                        p.processed_recordings = (index + 1) as u32;
+                        p.processed_recordings = processed_files;
+                    }
+                }
+                Ok(ProcessResult::Duplicate(existing)) => {
+                    summary.skipped += 1;
+                    summary.sources[source_summary].skipped += 1;
+                    log_migration(&format!("  Skipped (duplicate of {}): {}", existing, filename), "warn");
+
+                    // Log to JSON log
+                    logging::log_migration_file(filename, "duplicate", None, Some(&format!("duplicate of {}", existing)));
+
+                    summary.duplicates.push(DuplicateSkip {
+                        file_path: file.to_string_lossy().to_string(),
+                        duplicate_of: existing,
+                    });
+
+                    let mut progress = MIGRATION_PROGRESS.lock().unwrap();
+                    if let Some(ref mut p) = *progress {
+                        p.processed_recordings = processed_files;
                     }
                 }
                 Err(e) => {
                     log_migration(&format!("  Error: {} - {}", filename, e), "error");
                     summary.errors += 1;
+                    summary.sources[source_summary].errors += 1;
 
                     // Log to JSON log
                     logging::log_migration_file(filename, "error", None, Some(&e.to_string()));
@@ -277,11 +458,134 @@ impl<'a> MigrationEngine<'a> {
                     let mut progress = MIGRATION_PROGRESS.lock().unwrap();
                     if let Some(ref mut p) = *progress {
                         p.failed_recordings += 1;
-                        p.processed_recordings = (index + 1) as u32; // Also count as processed
+                        p.processed_recordings = processed_files; // Also count as processed
                     }
                 }
             }
-        }
+
+            crate::emit_migration_file_event(&MigrationFileEvent {
+                file_name: filename.to_string(),
+                file_path: file.to_string_lossy().to_string(),
+                source: source.as_str().to_string(),
+                result: outcome.to_string(),
+                bytes,
+                message,
+                processed_files,
+                total_files,
+                copied: summary.copied,
+                skipped: summary.skipped,
+                errors: summary.errors,
+                copied_bytes,
+                total_bytes: total_size_bytes,
+            });
+        };
+
+        // Settle a copy a worker has finished: duplicate check and slice insert
+        let settle = |job: &CopyJob, copied: Result<CopiedAudio>| -> Result<ProcessResult> {
+            let metadata = match job.source {
+                MigrationSource::CallRecordings => call_metadata.get(job.original_file_name()),
+                _ => None,
+            };
+            self.finish_recording(job, &db, metadata, copied?)
+        };
+
+        let copy_mode = self.config.copy_mode;
+        let (job_tx, job_rx) = mpsc::channel::<CopyJob>();
+        let (done_tx, done_rx) = mpsc::channel::<(CopyJob, Result<CopiedAudio>)>();
+        let job_rx = Mutex::new(job_rx);
+        std::thread::scope(|scope| -> Result<()> {
+            for _ in 0..MIGRATION_COPY_WORKERS {
+                let job_rx = &job_rx;
+                let done_tx = done_tx.clone();
+                scope.spawn(move || loop {
+                    let job = match job_rx.lock().unwrap().recv() {
+                        Ok(job) => job,
+                        Err(_) => break, // No more files
+                    };
+                    let copied = copy_recording(&job.source_path, &job.dest_path, copy_mode);
+                    if done_tx.send((job, copied)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(done_tx);
+
+            // Filenames being copied right now. A second file with the same name waits for
+            // the first to reach the database, so it is seen as already migrated.
+            let mut in_flight: HashSet<String> = HashSet::new();
+
+            for (index, (m4a_file, source)) in m4a_files.iter().enumerate() {
+                let filename = m4a_file.file_name()
+                    .and_then(|f| f.to_str())
+                    .unwrap_or("unknown.m4a");
+
+                log_migration(&format!("Processing ({}/{}): {}", index + 1, m4a_files.len(), filename), "info");
+
+                self.update_progress(
+                    &format!("Processing ({}/{}): {}", index + 1, m4a_files.len(), filename),
+                    None,
+                    None,
+                )?;
+
+                if let Some(previous) = handled.remove(m4a_file.to_string_lossy().as_ref()) {
+                    finish_file(m4a_file, *source, Ok(ProcessResult::from_batch_file(&previous)), true);
+                    continue;
+                }
+
+                // Additional sources import through the importer, whose duplicate check needs
+                // every pending copy in the database first
+                let is_additional = matches!(source, MigrationSource::Additional(_));
+                while in_flight.contains(filename)
+                    || in_flight.len() >= MIGRATION_COPY_BACKLOG
+                    || (is_additional && !in_flight.is_empty())
+                {
+                    let (job, copied) = done_rx.recv().context("Migration copy workers stopped unexpectedly")?;
+                    in_flight.remove(&job.filename);
+                    finish_file(&job.source_path, job.source, settle(&job, copied), false);
+                }
+
+                if let MigrationSource::Additional(index) = source {
+                    let result = self.process_additional_file(m4a_file, &additional_roots[*index], *source, &db, batch_id);
+                    finish_file(m4a_file, *source, result, false);
+                    continue;
+                }
+
+                let (library_name, relative_path) = match self.library_file_name(m4a_file, *source, &db) {
+                    Ok(names) => names,
+                    Err(e) => {
+                        finish_file(m4a_file, *source, Err(e), false);
+                        continue;
+                    }
+                };
+                if let Some(result) = self.precheck_recording(m4a_file, &library_name, &db).transpose() {
+                    finish_file(m4a_file, *source, result, false);
+                    continue;
+                }
+
+                in_flight.insert(library_name.clone());
+                let job = CopyJob {
+                    source_path: m4a_file.clone(),
+                    dest_path: dest_audio_dir.join(&library_name),
+                    filename: library_name,
+                    relative_path,
+                    source: *source,
+                    batch_id: Some(batch_id),
+                    recently_deleted: *source != MigrationSource::CallRecordings
+                        && is_recently_deleted(&recently_deleted, m4a_file),
+                };
+                job_tx.send(job).context("Migration copy workers stopped unexpectedly")?;
+            }
+
+            // Let the workers finish what is queued, then settle the remaining copies
+            drop(job_tx);
+            for (job, copied) in done_rx {
+                finish_file(&job.source_path, job.source, settle(&job, copied), false);
+            }
+            Ok(())
+        })?;
+
+        self.assign_folder_labels(&db);
+        db.finish_migration_batch(batch_id)?;
 
         self.update_progress("Migration completed!", None, None)?;
 
@@ -289,6 +593,10 @@ impl<'a> MigrationEngine<'a> {
         log_migration("", "info");
         log_migration("=== MIGRATION SUMMARY ===", "info");
 
+        if summary.resumed_files > 0 {
+            log_migration(&format!("Resumed an interrupted migration; {} files were handled before the restart", summary.resumed_files), "info");
+        }
+
         if summary.copied == 0 && summary.errors == 0 {
             // All files were already migrated
             log_migration("No files to migrate. All files have already been migrated.", "success");
@@ -300,10 +608,24 @@ impl<'a> MigrationEngine<'a> {
             if summary.skipped > 0 {
                 log_migration(&format!("Files skipped (already migrated): {}", summary.skipped), "warn");
             }
+            if !summary.duplicates.is_empty() {
+                log_migration(&format!("Duplicate recordings skipped: {}", summary.duplicates.len()), "warn");
+                for dup in &summary.duplicates {
+                    log_migration(&format!("  {} (same audio as {})", dup.file_path, dup.duplicate_of), "warn");
+                }
+            }
             if summary.errors > 0 {
                 log_migration(&format!("Files with errors: {}", summary.errors), "error");
             }
             log_migration(&format!("Total size processed: {}", format_file_size(summary.total_size_bytes)), "info");
+            if summary.sources.len() > 1 {
+                for source in &summary.sources {
+                    log_migration(
+                        &format!("  {}: {} copied, {} skipped, {} errors", source.root, source.copied, source.skipped, source.errors),
+                        "info",
+                    );
+                }
+            }
         }
 
         if summary.errors == 0 {
@@ -327,14 +649,20 @@ impl<'a> MigrationEngine<'a> {
             let mut progress = MIGRATION_PROGRESS.lock().unwrap();
             *progress = None;
         }
+        *LAST_MIGRATION_SUMMARY.lock().unwrap() = Some(summary.clone());
 
-        Ok(())
+        Ok(summary)
     }
 
     pub fn get_migration_progress() -> Option<MigrationProgress> {
         MIGRATION_PROGRESS.lock().unwrap().clone()
     }
 
+    /// Summary (including the duplicate report) of the most recent completed migration.
+    pub fn get_last_migration_summary() -> Option<MigrationSummary> {
+        LAST_MIGRATION_SUMMARY.lock().unwrap().clone()
+    }
+
     pub fn get_migration_progress_ref() -> &'static Arc<Mutex<Option<MigrationProgress>>> {
         &MIGRATION_PROGRESS
     }
@@ -353,6 +681,108 @@ impl<'a> MigrationEngine<'a> {
         Ok(())
     }
 
+    /// Replace a migrated recording's audio with the edited version Voice Memos now holds.
+    fn refresh_edited_recording(&self, m4a_file_path: &Path, filename: &str, db: &Database) -> Result<ProcessResult> {
+        let dest_path = self.config.audio_dir().join(filename);
+        let size = place_file(m4a_file_path, &dest_path, self.config.copy_mode)
+            .with_context(|| format!("Failed to copy edited version of {}", filename))?;
+        let content_hash = sha256_file(m4a_file_path)?;
+        verify_copy(&content_hash, &dest_path)?;
+        let audio_duration = get_audio_duration(&dest_path);
+        db.mark_slice_edited(filename, size as i64, audio_duration, &content_hash)?;
+
+        log_migration(&format!("  Updated to edited version: {} ({})", filename, format_file_size(size)), "success");
+        Ok(ProcessResult::Copied(size))
+    }
+
+    /// Index of the per-source summary for `source`, creating it on first use.
+    fn source_summary_entry(
+        &self,
+        sources: &mut Vec<SourceSummary>,
+        source: MigrationSource,
+        additional_roots: &[PathBuf],
+    ) -> usize {
+        let root = match source {
+            MigrationSource::CallRecordings => self.config.call_recordings_root_path(),
+            MigrationSource::Additional(index) => additional_roots[index].clone(),
+            _ => self.config.voice_memo_root_path(),
+        }
+        .to_string_lossy()
+        .to_string();
+
+        if let Some(index) = sources.iter().position(|s| s.root == root) {
+            return index;
+        }
+        sources.push(SourceSummary {
+            source: source.as_str().to_string(),
+            root,
+            ..SourceSummary::default()
+        });
+        sources.len() - 1
+    }
+
+    /// Import one file from an additional source folder. Which files a source has already
+    /// contributed is tracked per source root, because names from different folders can collide.
+    fn process_additional_file(
+        &self,
+        path: &Path,
+        root: &Path,
+        source: MigrationSource,
+        db: &Database,
+        batch_id: i64,
+    ) -> Result<ProcessResult> {
+        let root_key = root.to_string_lossy().to_string();
+        let relative_path = path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string();
+
+        if db.source_file_seen(&root_key, &relative_path)? {
+            return Ok(ProcessResult::Skipped);
+        }
+
+        let size = fs::metadata(path)?.len();
+        let recording_date = importer::file_modified_timestamp(path);
+        match importer::import_audio_file(self.config, db, path, None, recording_date, Some(source.as_str()))? {
+            ImportOutcome::Imported(slice_id) => {
+                db.record_source_file(&root_key, &relative_path, Some(slice_id), "imported")?;
+                let filename = path.file_name().unwrap_or_default().to_string_lossy();
+                db.add_migration_batch_slice(batch_id, slice_id, &filename)?;
+                log_migration(&format!("  Copied: {} ({})", relative_path, format_file_size(size)), "success");
+                Ok(ProcessResult::Copied(size))
+            }
+            ImportOutcome::Duplicate(existing) => {
+                db.record_source_file(&root_key, &relative_path, None, "duplicate")?;
+                Ok(ProcessResult::Duplicate(existing))
+            }
+            ImportOutcome::NameTaken => Err(anyhow::anyhow!(
+                "A different recording named {:?} is already in the library",
+                path.file_name().unwrap_or_default()
+            )),
+        }
+    }
+
+    /// Hash library copies of slices that predate checksum verification.
+    fn backfill_content_hashes(&self, db: &Database) -> Result<u32> {
+        let mut count = 0;
+        for (slice_id, filename) in db.get_slices_without_content_hash()? {
+            let audio_path = self.config.audio_dir().join(&filename);
+            if !audio_path.exists() {
+                continue;
+            }
+            db.set_slice_content_hash(slice_id, &sha256_file(&audio_path)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Give migrated slices the labels of their Voice Memos folders.
+    /// Failures are logged but never abort the migration.
+    fn assign_folder_labels(&self, db: &Database) {
+        match db.assign_apple_folder_labels() {
+            Ok(0) => {}
+            Ok(assigned) => log_migration(&format!("Assigned folder labels to {} recordings", assigned), "success"),
+            Err(e) => log_migration(&format!("Failed to assign folder labels: {}", e), "warn"),
+        }
+    }
+
     fn scan_m4a_files(&self, voice_memo_dir: &Path) -> Result<Vec<PathBuf>> {
         log_migration(&format!("Scanning directory: {:?}", voice_memo_dir), "info");
 
@@ -365,7 +795,12 @@ impl<'a> MigrationEngine<'a> {
         let mut directories_scanned = 0;
         let mut access_errors = 0;
 
-        for entry in WalkDir::new(voice_memo_dir).into_iter() {
+        // Edit compositions hold the original takes of a trimmed memo; the top-level
+        // .m4a next to them is the current edited version, which is what we migrate.
+        for entry in WalkDir::new(voice_memo_dir)
+            .into_iter()
+            .filter_entry(|e| !is_composition_dir(e.path()))
+        {
 
             match entry {
                 Ok(entry) => {
@@ -399,89 +834,568 @@ impl<'a> MigrationEngine<'a> {
         Ok(m4a_files)
     }
 
+    #[cfg(test)]
     fn process_m4a_file(&self, m4a_file_path: &Path, db: &Database) -> Result<ProcessResult> {
+        self.process_recording(m4a_file_path, db, MigrationSource::VoiceMemos, None)
+    }
+
+    #[cfg(test)]
+    fn process_call_recording(
+        &self,
+        m4a_file_path: &Path,
+        db: &Database,
+        metadata: Option<&CallRecordingMetadata>,
+    ) -> Result<ProcessResult> {
+        self.process_recording(m4a_file_path, db, MigrationSource::CallRecordings, metadata)
+    }
+
+    /// One recording end to end on the calling thread. `start_migration` runs the same
+    /// steps with the copy moved onto a worker.
+    #[cfg(test)]
+    fn process_recording(
+        &self,
+        m4a_file_path: &Path,
+        db: &Database,
+        source: MigrationSource,
+        call_metadata: Option<&CallRecordingMetadata>,
+    ) -> Result<ProcessResult> {
+        let (filename, relative_path) = self.library_file_name(m4a_file_path, source, db)?;
+        if let Some(result) = self.precheck_recording(m4a_file_path, &filename, db)? {
+            return Ok(result);
+        }
+
+        let dest_dir = self.config.audio_dir();
+        fs::create_dir_all(&dest_dir).with_context(|| format!("Failed to create destination directory at {:?}", dest_dir))?;
+
+        let job = CopyJob {
+            source_path: m4a_file_path.to_path_buf(),
+            dest_path: dest_dir.join(&filename),
+            filename,
+            relative_path,
+            source,
+            batch_id: None,
+            recently_deleted: false,
+        };
+        let copied = copy_recording(&job.source_path, &job.dest_path, self.config.copy_mode)?;
+        self.finish_recording(&job, db, call_metadata, copied)
+    }
+
+    /// Name the recording gets in the library, and its path relative to its source root.
+    /// A file sharing its name with a recording migrated from a different subfolder gets
+    /// a short hash of its relative path appended, so both are kept.
+    fn library_file_name(&self, m4a_file_path: &Path, source: MigrationSource, db: &Database) -> Result<(String, String)> {
         let filename = m4a_file_path.file_name()
             .and_then(|f| f.to_str())
             .context("Invalid file name")?;
+        let root = match source {
+            MigrationSource::CallRecordings => self.config.call_recordings_root_path(),
+            _ => self.config.voice_memo_root_path(),
+        };
+        let relative_path = m4a_file_path.strip_prefix(&root)
+            .unwrap_or(m4a_file_path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let library_name = match db.get_slice_source_path(filename)? {
+            Some(Some(existing)) if existing != relative_path => {
+                let renamed = collision_file_name(filename, &relative_path);
+                if db.get_slice_source_path(&renamed)?.is_none() {
+                    log_migration(
+                        &format!("  Name collision: {} is already taken by {}, migrating as {}", relative_path, existing, renamed),
+                        "warn",
+                    );
+                }
+                renamed
+            }
+            Some(None) => {
+                // Migrated before source paths were tracked: the first file found under
+                // this name is taken to be the one already in the library
+                db.set_slice_source_path(filename, &relative_path)?;
+                filename.to_string()
+            }
+            _ => filename.to_string(),
+        };
+        Ok((library_name, relative_path))
+    }
 
-        // 1. Check if the slice already exists in the database
-        if db.slice_exists(filename)? {
-            info!("Skipping (already in DB): {}", filename);
-            return Ok(ProcessResult::Skipped);
+    /// Settle a recording from the database alone when possible: already migrated
+    /// (`Skipped`), or migrated and since trimmed in Voice Memos (audio refreshed in place).
+    /// `None` means it is new and needs copying to `filename`.
+    fn precheck_recording(&self, m4a_file_path: &Path, filename: &str, db: &Database) -> Result<Option<ProcessResult>> {
+        let was_edited = has_edit_composition(m4a_file_path);
+
+        // A memo trimmed since it was migrated gets its audio refreshed so the library
+        // holds the edited version
+        match db.slice_was_edited(filename)? {
+            Some(already_edited) if !was_edited || already_edited => {
+                info!("Skipping (already in DB): {}", filename);
+                Ok(Some(ProcessResult::Skipped))
+            }
+            Some(_) => self.refresh_edited_recording(m4a_file_path, filename, db).map(Some),
+            None => Ok(None),
         }
+    }
 
-        // 2. Determine destination path
-        let dest_dir = self.config.audio_dir();
-        fs::create_dir_all(&dest_dir).with_context(|| format!("Failed to create destination directory at {:?}", dest_dir))?;
-        let dest_path = dest_dir.join(filename);
+    /// Record a freshly copied recording as a slice. If the same audio is already in the
+    /// library under another name, the copy is removed and reported as a duplicate instead.
+    fn finish_recording(
+        &self,
+        job: &CopyJob,
+        db: &Database,
+        call_metadata: Option<&CallRecordingMetadata>,
+        copied: CopiedAudio,
+    ) -> Result<ProcessResult> {
+        let m4a_file_path = job.source_path.as_path();
+        let source = job.source;
+        let filename = job.filename.as_str();
+        let apple_filename = job.original_file_name();
+        let CopiedAudio { dest_path, size, content_hash, audio_duration, apple_transcript } = copied;
+
+        if let Some(existing) = db.find_slice_by_content_hash(&content_hash)? {
+            info!("Skipping (duplicate of {}): {}", existing, filename);
+            if let Err(e) = fs::remove_file(&dest_path) {
+                warn!("Failed to remove duplicate copy {:?}: {}", dest_path, e);
+            }
+            return Ok(ProcessResult::Duplicate(existing));
+        }
 
-        // 3. Copy the file
-        info!("Attempting to copy from '{}' to '{}'", m4a_file_path.display(), dest_path.display());
+        let was_edited = has_edit_composition(m4a_file_path);
+
+        // Create and insert a slice record
+        let file_type = m4a_file_path.extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("m4a")
+            .to_string();
+
+        // Voice memos get their date from the copied ZCLOUDRECORDING table; call
+        // recordings carry the caller name and date from their own container
+        let (title, recording_date) = match source {
+            MigrationSource::CallRecordings => (
+                call_metadata.and_then(|m| m.caller_name.clone()),
+                call_metadata.and_then(|m| m.recording_date),
+            ),
+            _ => (None, db.get_recording_date_for_filename(apple_filename).ok().flatten()),
+        };
 
-        match fs::copy(m4a_file_path, &dest_path) {
-            Ok(size) => {
-                info!("✅ SUCCESSFULLY COPIED FILE: {} ({} bytes)", filename, size);
+        // Apple's favorite flag carries over as starred
+        let starred = source != MigrationSource::CallRecordings
+            && db.get_favorite_for_filename(apple_filename).unwrap_or(false);
+
+        let slice = Slice {
+            id: None,
+            original_audio_file_name: filename.to_string(),
+            title: title.clone(),
+            transcribed: false,
+            audio_file_size: size as i64,
+            audio_file_type: file_type.clone(),
+            estimated_time_to_transcribe: estimate_transcription_time(size, audio_duration),
+            audio_time_length_seconds: audio_duration,
+            transcription: None,
+            transcription_time_taken: None,
+            transcription_word_count: None,
+            transcription_model: None,
+            recording_date,
+            source: Some(source.as_str().to_string()),
+            starred,
+            was_edited,
+            content_hash: Some(content_hash),
+            source_relative_path: Some(job.relative_path.clone()),
+            notes: None,
+            pinned: false,
+            summary: None,
+        };
 
-                // Verify the file actually exists at destination
-                if dest_path.exists() {
-                    let actual_size = fs::metadata(&dest_path)?.len();
-                    info!("✅ VERIFIED: File exists at destination with size {} bytes", actual_size);
-                } else {
-                    error!("❌ CRITICAL: File copy reported success but file not found at destination!");
-                    return Err(anyhow::anyhow!("File copy verification failed"));
-                }
+        let slice_id = db.insert_slice(&slice)?;
+        info!(slice = ?&slice, "Inserted slice record");
+        if let Some(batch_id) = job.batch_id {
+            db.add_migration_batch_slice(batch_id, slice_id, filename)?;
+        }
+        if job.recently_deleted {
+            let label_id = db.get_or_create_label(RECENTLY_DELETED_LABEL, RECENTLY_DELETED_COLOR)?;
+            db.assign_label(label_id, &[slice_id])?;
+        }
 
-                // 4. Create and insert a slice record
-                let file_type = m4a_file_path.extension()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("m4a")
-                    .to_string();
-
-                // Extract audio duration from the file
-                let audio_duration = get_audio_duration(&dest_path);
-
-                // Get the recording date from Apple's ZCLOUDRECORDING table
-                let recording_date = db.get_recording_date_for_filename(filename).ok().flatten();
-
-                let slice = Slice {
-                    id: None,
-                    original_audio_file_name: filename.to_string(),
-                    title: None,
-                    transcribed: false,
-                    audio_file_size: size as i64,
-                    audio_file_type: file_type.clone(),
-                    estimated_time_to_transcribe: estimate_transcription_time(size, audio_duration),
-                    audio_time_length_seconds: audio_duration,
-                    transcription: None,
-                    transcription_time_taken: None,
-                    transcription_word_count: None,
-                    transcription_model: None,
-                    recording_date,
-                };
+        // Voice Memos' own transcript stands in until the recording is transcribed here
+        let apple_words = match &apple_transcript {
+            Some(text) => {
+                let word_count = text.split_whitespace().count() as i32;
+                db.update_slice_transcription(slice_id, text, 0, word_count, APPLE_MODEL)?;
+                Some(word_count)
+            }
+            None => None,
+        };
 
-                db.insert_slice(&slice)?;
-                info!(slice = ?&slice, "Inserted slice record");
+        // Log file details and metadata to the migration log window
+        log_migration(&format!("  Copied: {} ({})", filename, format_file_size(size)), "success");
+        let mut meta_parts: Vec<String> = Vec::new();
+        meta_parts.push(format!("type: {}", file_type));
+        if source == MigrationSource::CallRecordings {
+            if let Some(ref caller) = title {
+                meta_parts.push(format!("caller: {}", caller));
+            }
+        }
+        if let Some(duration) = audio_duration {
+            meta_parts.push(format!("duration: {}", format_audio_duration(duration)));
+        }
+        if let Some(date) = recording_date {
+            meta_parts.push(format!("recorded: {}", format_recording_date(date)));
+        }
+        if starred {
+            meta_parts.push("favorite".to_string());
+        }
+        if was_edited {
+            meta_parts.push("edited".to_string());
+        }
+        if job.recently_deleted {
+            meta_parts.push("recently deleted".to_string());
+        }
+        if let Some(words) = apple_words {
+            meta_parts.push(format!("Apple transcript: {} words", words));
+        }
+        log_migration(&format!("  Metadata: {}", meta_parts.join(", ")), "info");
 
-                // Log file details and metadata to the migration log window
-                log_migration(&format!("  Copied: {} ({})", filename, format_file_size(size)), "success");
-                let mut meta_parts: Vec<String> = Vec::new();
-                meta_parts.push(format!("type: {}", file_type));
-                if let Some(duration) = audio_duration {
-                    meta_parts.push(format!("duration: {}", format_audio_duration(duration)));
-                }
-                if let Some(date) = recording_date {
-                    meta_parts.push(format!("recorded: {}", format_recording_date(date)));
-                }
-                log_migration(&format!("  Metadata: {}", meta_parts.join(", ")), "info");
+        Ok(ProcessResult::Copied(size))
+    }
+}
 
-                Ok(ProcessResult::Copied(size))
-            },
-            Err(e) => {
-                error!("Failed to copy file from '{}' to '{}'. Error: {}", m4a_file_path.display(), dest_path.display(), e);
-                Err(e.into())
+/// Label colour for slices brought in by a source profile.
+const PROFILE_LABEL_COLOR: &str = "#15aabf";
+
+/// Migrate one source profile on its own, then give the slices it brought in a label
+/// named after the profile.
+pub fn migrate_source_profile(config: &Config, profile: &SourceProfile) -> Result<MigrationSummary> {
+    log_migration(&format!("Migrating source profile \"{}\" ({:?} at {})", profile.name, profile.kind, profile.path), "info");
+    let summary = match profile.kind {
+        SourceKind::VoiceMemos => MigrationEngine::new(&config.for_voice_memos_profile(profile)).start_migration()?,
+        SourceKind::IosBackup => ios_backup::migrate_from_backup(config, Path::new(&profile.path))?,
+    };
+
+    let db = db_pool::connect(config.ciderpress_home_path().join("CiderPress-db.sqlite"))?;
+    if let Some(batch_id) = summary.batch_id {
+        let slices = db.get_migration_batch_slices(batch_id)?;
+        if !slices.is_empty() {
+            let label_id = db.get_or_create_label(&profile.name, PROFILE_LABEL_COLOR)?;
+            let slice_ids: Vec<i64> = slices.into_iter().map(|(slice_id, _)| slice_id).collect();
+            db.assign_label(label_id, &slice_ids)?;
+        }
+    }
+    Ok(summary)
+}
+
+/// A rollback was asked for while a migration was running.
+#[derive(Debug)]
+pub struct MigrationInProgress;
+
+impl std::fmt::Display for MigrationInProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cannot roll back while a migration is in progress")
+    }
+}
+
+impl std::error::Error for MigrationInProgress {}
+
+/// Undo the most recent migration batch: delete the slices it created and their audio.
+/// Slices transcribed since (Voice Memos' own transcripts don't count) or renamed are kept,
+/// so no work done in CiderPress is lost. Fails with `MigrationInProgress` while a migration
+/// is running, and holds off new ones until it is done.
+pub fn rollback_last_migration(config: &Config, db: &Database) -> Result<MigrationRollbackReport> {
+    let Ok(_running) = MIGRATION_RUN.try_lock() else {
+        return Err(MigrationInProgress.into());
+    };
+    let (batch_id, status) = db.last_migration_batch()?
+        .context("No migration to roll back")?;
+
+    let mut report = MigrationRollbackReport { batch_id, ..MigrationRollbackReport::default() };
+    for (slice_id, migrated_name) in db.get_migration_batch_slices(batch_id)? {
+        let Some(slice) = db.get_slice(slice_id)? else {
+            continue; // Already deleted
+        };
+        let transcribed_here = slice.transcribed && slice.transcription_model.as_deref() != Some(APPLE_MODEL);
+        if transcribed_here || slice.original_audio_file_name != migrated_name {
+            report.kept_files.push(slice.original_audio_file_name);
+            continue;
+        }
+
+        let audio_path = config.audio_dir().join(&slice.original_audio_file_name);
+        if let Err(e) = fs::remove_file(&audio_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                report.errors.push(ImportFailure {
+                    file_path: audio_path.to_string_lossy().to_string(),
+                    message: e.to_string(),
+                });
+                continue;
             }
         }
+        db.delete_slice(slice_id)?;
+        report.removed_files.push(slice.original_audio_file_name);
+        report.removed_slice_ids.push(slice_id);
     }
+
+    db.mark_migration_batch_rolled_back(batch_id)?;
+    log_migration(
+        &format!(
+            "Rolled back migration batch {} ({}): removed {} recordings, kept {}",
+            batch_id, status, report.removed_slice_ids.len(), report.kept_files.len()
+        ),
+        "warn",
+    );
+    Ok(report)
+}
+
+/// A new recording for a copy worker.
+struct CopyJob {
+    source_path: PathBuf,
+    dest_path: PathBuf,
+    filename: String, // name in the library; differs from the source's on a collision
+    relative_path: String, // under the source root
+    source: MigrationSource,
+    batch_id: Option<i64>, // migration batch the new slice belongs to, for rollback
+    recently_deleted: bool, // in Voice Memos' Recently Deleted; labelled as such
+}
+
+impl CopyJob {
+    /// The file's own name, which Apple's databases know it by.
+    fn original_file_name(&self) -> &str {
+        self.source_path.file_name().and_then(|f| f.to_str()).unwrap_or(&self.filename)
+    }
+}
+
+/// `memo.m4a` from `Work/memo.m4a` becomes `memo-1a2b3c4d.m4a`.
+fn collision_file_name(filename: &str, relative_path: &str) -> String {
+    let hash = format!("{:x}", Sha256::digest(relative_path.as_bytes()));
+    match filename.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}-{}.{}", stem, &hash[..8], ext),
+        None => format!("{}-{}", filename, &hash[..8]),
+    }
+}
+
+/// A recording copied into the library and checked against its source.
+struct CopiedAudio {
+    dest_path: PathBuf,
+    size: u64,
+    content_hash: String,
+    audio_duration: Option<f64>,
+    apple_transcript: Option<String>,
+}
+
+/// The file work of migrating one recording: hash, copy, verify, probe the duration and
+/// pick up any transcript Voice Memos embedded.
+/// Touches no database, so it runs on the migration copy workers.
+fn copy_recording(source_path: &Path, dest_path: &Path, copy_mode: CopyMode) -> Result<CopiedAudio> {
+    let filename = source_path.file_name().unwrap_or_default().to_string_lossy();
+    let content_hash = sha256_file(source_path)?;
+
+    info!("Attempting to copy from '{}' to '{}'", source_path.display(), dest_path.display());
+    let size = place_file(source_path, dest_path, copy_mode).map_err(|e| {
+        error!("Failed to copy file from '{}' to '{}'. Error: {}", source_path.display(), dest_path.display(), e);
+        e
+    })?;
+    info!("✅ SUCCESSFULLY COPIED FILE: {} ({} bytes)", filename, size);
+
+    // Verify the file actually exists at destination
+    if dest_path.exists() {
+        let actual_size = fs::metadata(dest_path)?.len();
+        info!("✅ VERIFIED: File exists at destination with size {} bytes", actual_size);
+    } else {
+        error!("❌ CRITICAL: File copy reported success but file not found at destination!");
+        return Err(anyhow::anyhow!("File copy verification failed"));
+    }
+
+    // Make sure the bytes actually survived the copy
+    verify_copy(&content_hash, dest_path)?;
+    info!("✅ VERIFIED: SHA-256 matches ({})", content_hash);
+
+    Ok(CopiedAudio {
+        dest_path: dest_path.to_path_buf(),
+        size,
+        content_hash,
+        audio_duration: get_audio_duration(dest_path),
+        apple_transcript: read_apple_transcript(dest_path).unwrap_or_else(|e| {
+            warn!("Could not read Apple transcript from {:?}: {}", dest_path, e);
+            None
+        }),
+    })
+}
+
+/// Put `source` at `dest` using `mode`, returning the file size. Clones and hard links
+/// fall back to a plain copy when unsupported, e.g. across volumes. An existing `dest` is
+/// removed first so a previous hard link to the source is never written through.
+pub fn place_file(source: &Path, dest: &Path, mode: CopyMode) -> std::io::Result<u64> {
+    if fs::symlink_metadata(dest).is_ok() {
+        fs::remove_file(dest)?;
+    }
+
+    let placed = match mode {
+        CopyMode::Copy => return fs::copy(source, dest),
+        CopyMode::Clone => clone_file(source, dest),
+        CopyMode::Hardlink => fs::hard_link(source, dest),
+    };
+    match placed {
+        Ok(()) => Ok(fs::metadata(dest)?.len()),
+        Err(e) => {
+            warn!("{:?} of {:?} failed ({}), copying instead", mode, source, e);
+            fs::copy(source, dest)
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn clone_file(source: &Path, dest: &Path) -> std::io::Result<()> {
+    use std::ffi::CString;
+    use std::os::raw::{c_char, c_int};
+    use std::os::unix::ffi::OsStrExt;
+
+    extern "C" {
+        // <sys/clonefile.h>; fails with EXDEV across volumes and ENOTSUP off APFS
+        fn clonefile(src: *const c_char, dst: *const c_char, flags: u32) -> c_int;
+    }
+
+    let src = CString::new(source.as_os_str().as_bytes())?;
+    let dst = CString::new(dest.as_os_str().as_bytes())?;
+    if unsafe { clonefile(src.as_ptr(), dst.as_ptr(), 0) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn clone_file(_source: &Path, _dest: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "clonefile is only available on macOS"))
+}
+
+/// Hex-encoded SHA-256 of a file's contents, streamed so large recordings aren't loaded into memory.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path).with_context(|| format!("Failed to open {:?} for hashing", path))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Compare a copied file against the checksum of its source. On mismatch the corrupt
+/// destination is removed and an error is returned.
+pub fn verify_copy(source_hash: &str, dest: &Path) -> Result<()> {
+    let dest_hash = sha256_file(dest)?;
+    if source_hash != dest_hash {
+        error!("❌ CRITICAL: Checksum mismatch copying to {:?} (source {}, destination {})", dest, source_hash, dest_hash);
+        let _ = fs::remove_file(dest);
+        return Err(anyhow::anyhow!(
+            "Checksum mismatch after copy: source {} != destination {}",
+            source_hash,
+            dest_hash
+        ));
+    }
+    Ok(())
+}
+
+/// Voice Memos keeps the segments of an edited memo in a `<name>.composition` directory.
+pub fn is_composition_dir(path: &Path) -> bool {
+    path.is_dir() && path.extension().is_some_and(|ext| ext == "composition")
+}
+
+/// True if the recording has been trimmed or otherwise edited in Voice Memos.
+fn has_edit_composition(m4a_file_path: &Path) -> bool {
+    is_composition_dir(&m4a_file_path.with_extension("composition"))
+}
+
+/// Label given to recordings migrated out of Voice Memos' Recently Deleted.
+const RECENTLY_DELETED_LABEL: &str = "Recently Deleted";
+const RECENTLY_DELETED_COLOR: &str = "#868e96";
+
+/// File names of the memos in Recently Deleted, which Voice Memos marks with a
+/// ZEVICTIONDATE, mapped to when they were deleted (Unix time).
+fn load_recently_deleted(apple_db_path: &Path) -> HashMap<String, i64> {
+    let mut deleted = HashMap::new();
+    let conn = match Connection::open_with_flags(apple_db_path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+        Ok(conn) => conn,
+        Err(e) => {
+            warn!("Failed to open {:?} to check Recently Deleted: {}", apple_db_path, e);
+            return deleted;
+        }
+    };
+
+    // Older databases have no ZEVICTIONDATE column, and so no Recently Deleted
+    let mut stmt = match conn.prepare("SELECT ZPATH, ZEVICTIONDATE FROM ZCLOUDRECORDING WHERE ZEVICTIONDATE IS NOT NULL") {
+        Ok(stmt) => stmt,
+        Err(_) => return deleted,
+    };
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, f64>(1)?)));
+    if let Ok(rows) = rows {
+        for (path, evicted) in rows.flatten() {
+            if let Some(name) = path.as_deref().and_then(|p| Path::new(p).file_name()) {
+                deleted.insert(name.to_string_lossy().to_string(), evicted as i64 + APPLE_EPOCH_OFFSET);
+            }
+        }
+    }
+    deleted
+}
+
+fn is_recently_deleted(recently_deleted: &HashMap<String, i64>, path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| recently_deleted.contains_key(name))
+}
+
+/// Read caller name and date for each call recording, keyed by file name.
+/// A missing or unreadable database just means the recordings import without metadata.
+fn load_call_recording_metadata(call_recordings_dir: &Path) -> HashMap<String, CallRecordingMetadata> {
+    let mut metadata = HashMap::new();
+    let db_path = call_recordings_dir.join("CloudRecordings.db");
+    if !db_path.exists() {
+        warn!("No call recordings database at {:?}; importing without caller metadata", db_path);
+        return metadata;
+    }
+
+    let conn = match Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+        Ok(conn) => conn,
+        Err(e) => {
+            warn!("Failed to open call recordings database {:?}: {}", db_path, e);
+            return metadata;
+        }
+    };
+
+    // SELECT * so that schema differences between OS versions don't break the lookup
+    let mut stmt = match conn.prepare("SELECT * FROM ZCLOUDRECORDING") {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            warn!("Failed to read call recordings table: {}", e);
+            return metadata;
+        }
+    };
+
+    let rows = stmt.query_map([], |row| {
+        let path: Option<String> = row.get("ZPATH").ok().flatten();
+        let caller_name: Option<String> = row
+            .get::<_, Option<String>>("ZCUSTOMLABEL")
+            .ok()
+            .flatten()
+            .or_else(|| row.get::<_, Option<String>>("ZENCRYPTEDTITLE").ok().flatten())
+            .filter(|name| !name.trim().is_empty());
+        let recording_date = row
+            .get::<_, Option<f64>>("ZDATE")
+            .ok()
+            .flatten()
+            .map(|date| date as i64 + APPLE_EPOCH_OFFSET);
+        Ok((path, CallRecordingMetadata { caller_name, recording_date }))
+    });
+
+    if let Ok(rows) = rows {
+        for (path, meta) in rows.flatten() {
+            if let Some(name) = path.as_deref().and_then(|p| Path::new(p).file_name()) {
+                metadata.insert(name.to_string_lossy().to_string(), meta);
+            }
+        }
+    }
+
+    info!("Loaded metadata for {} call recordings", metadata.len());
+    metadata
 }
 
 fn estimate_transcription_time(file_size_bytes: u64, audio_duration_seconds: Option<f64>) -> i32 {
@@ -562,6 +1476,26 @@ pub fn get_audio_duration(audio_path: &Path) -> Option<f64> {
 enum ProcessResult {
     Copied(u64), // Size in bytes
     Skipped,
+    Duplicate(String), // Filename of the slice that already holds the same audio
+}
+
+impl ProcessResult {
+    fn to_batch_file(&self, file_path: String) -> MigrationBatchFile {
+        let (result, size_bytes, duplicate_of) = match self {
+            ProcessResult::Copied(size) => ("copied", Some(*size as i64), None),
+            ProcessResult::Skipped => ("skipped", None, None),
+            ProcessResult::Duplicate(existing) => ("duplicate", None, Some(existing.clone())),
+        };
+        MigrationBatchFile { file_path, result: result.to_string(), size_bytes, duplicate_of }
+    }
+
+    fn from_batch_file(file: &MigrationBatchFile) -> Self {
+        match (file.result.as_str(), &file.duplicate_of) {
+            ("copied", _) => ProcessResult::Copied(file.size_bytes.unwrap_or(0) as u64),
+            ("duplicate", Some(existing)) => ProcessResult::Duplicate(existing.clone()),
+            _ => ProcessResult::Skipped,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -569,6 +1503,7 @@ mod tests {
     use super::*;
     use rusqlite::{params, Connection};
     use tempfile::TempDir;
+    use super::super::models::Label;
 
     #[test]
     fn test_estimate_transcription_time() {
@@ -663,7 +1598,7 @@ mod tests {
             ProcessResult::Copied(size) => {
                 assert_eq!(size, test_content.len() as u64);
             }
-            ProcessResult::Skipped => {
+            ProcessResult::Skipped | ProcessResult::Duplicate(_) => {
                 panic!("File should have been copied, not skipped");
             }
         }
@@ -683,10 +1618,557 @@ mod tests {
         assert_eq!(slice.original_audio_file_name, "test_recording.m4a");
         assert_eq!(slice.audio_file_size, test_content.len() as i64);
         assert!(!slice.transcribed);
+        assert_eq!(slice.content_hash, Some(sha256_file(&source_file)?));
 
         Ok(())
     }
 
+    #[test]
+    fn test_process_call_recording_preserves_metadata() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let call_dir = temp_dir.path().join("calls");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(&call_dir)?;
+        fs::create_dir_all(&dest_dir)?;
+
+        let source_file = call_dir.join("call_001.m4a");
+        fs::write(&source_file, b"fake call audio")?;
+
+        // Mock call recordings database, ZDATE is seconds since 2001-01-01
+        let conn = Connection::open(call_dir.join("CloudRecordings.db"))?;
+        conn.execute(
+            "CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZDATE REAL, ZCUSTOMLABEL TEXT, ZPATH TEXT)",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO ZCLOUDRECORDING (ZDATE, ZCUSTOMLABEL, ZPATH) VALUES (?, ?, ?)",
+            params![700_000_000.0, "Call with Jane Doe", "call_001.m4a"],
+        )?;
+        drop(conn);
+
+        let config = Config {
+            voice_memo_root: temp_dir.path().join("memos").to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            call_recordings_root: call_dir.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db_path = dest_dir.join("test.db");
+        let db = Database::new(&db_path)?;
+
+        let metadata = load_call_recording_metadata(&call_dir);
+        let engine = MigrationEngine::new(&config);
+        let result = engine.process_call_recording(&source_file, &db, metadata.get("call_001.m4a"))?;
+        assert!(matches!(result, ProcessResult::Copied(_)));
+
+        let slices = db.list_all_slices()?;
+        assert_eq!(slices.len(), 1);
+        assert_eq!(slices[0].title.as_deref(), Some("Call with Jane Doe"));
+        assert_eq!(slices[0].recording_date, Some(700_000_000 + APPLE_EPOCH_OFFSET));
+        assert_eq!(slices[0].source.as_deref(), Some("call_recording"));
+        assert!(config.audio_dir().join("call_001.m4a").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha256_file_and_verify_copy() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source = temp_dir.path().join("a.m4a");
+        let good = temp_dir.path().join("b.m4a");
+        let bad = temp_dir.path().join("c.m4a");
+        fs::write(&source, b"abc")?;
+        fs::write(&good, b"abc")?;
+        fs::write(&bad, b"abd")?;
+
+        // Well-known SHA-256 of "abc"
+        let expected = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(sha256_file(&source)?, expected);
+        assert!(verify_copy(expected, &good).is_ok());
+
+        assert!(verify_copy(expected, &bad).is_err());
+        assert!(!bad.exists(), "corrupt copy should be removed");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_place_file_modes() -> Result<()> {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp_dir = TempDir::new()?;
+        let source = temp_dir.path().join("memo.m4a");
+        fs::write(&source, b"voice memo audio")?;
+
+        let linked = temp_dir.path().join("linked.m4a");
+        assert_eq!(place_file(&source, &linked, CopyMode::Hardlink)?, 16);
+        assert_eq!(fs::metadata(&linked)?.ino(), fs::metadata(&source)?.ino());
+
+        // Re-placing over an existing hard link must not write through to the source
+        assert_eq!(place_file(&source, &linked, CopyMode::Copy)?, 16);
+        assert_ne!(fs::metadata(&linked)?.ino(), fs::metadata(&source)?.ino());
+        assert_eq!(fs::read(&source)?, b"voice memo audio");
+
+        // Clones fall back to a copy wherever clonefile can't be used
+        let cloned = temp_dir.path().join("cloned.m4a");
+        assert_eq!(place_file(&source, &cloned, CopyMode::Clone)?, 16);
+        assert_eq!(fs::read(&cloned)?, b"voice memo audio");
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_audio_under_new_name_is_skipped() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_dir = temp_dir.path().join("source");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(&source_dir)?;
+
+        let original = source_dir.join("original.m4a");
+        let renamed = source_dir.join("renamed copy.m4a");
+        fs::write(&original, b"same audio")?;
+        fs::write(&renamed, b"same audio")?;
+
+        let config = Config {
+            voice_memo_root: source_dir.to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db_path = dest_dir.join("test.db");
+        let db = Database::new(&db_path)?;
+        let engine = MigrationEngine::new(&config);
+
+        assert!(matches!(engine.process_m4a_file(&original, &db)?, ProcessResult::Copied(_)));
+        match engine.process_m4a_file(&renamed, &db)? {
+            ProcessResult::Duplicate(existing) => assert_eq!(existing, "original.m4a"),
+            _ => panic!("renamed copy should be detected as a duplicate"),
+        }
+        assert_eq!(db.list_all_slices()?.len(), 1);
+        assert!(!config.audio_dir().join("renamed copy.m4a").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apple_transcript_is_imported() -> Result<()> {
+        fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+            [((body.len() + 8) as u32).to_be_bytes().as_slice(), kind, body].concat()
+        }
+
+        let temp_dir = TempDir::new()?;
+        let source_dir = temp_dir.path().join("source");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(&source_dir)?;
+
+        let transcript = br#"{"attributedString":{"runs":["Call the dentist",0," tomorrow",1]}}"#;
+        let moov = mp4_box(b"moov", &mp4_box(b"udta", &mp4_box(b"tsrp", transcript)));
+        let memo = source_dir.join("memo.m4a");
+        fs::write(&memo, [mp4_box(b"ftyp", b"M4A \0\0\0\0"), moov].concat())?;
+        fs::write(source_dir.join("plain.m4a"), b"no transcript here")?;
+
+        let config = Config {
+            voice_memo_root: source_dir.to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(dest_dir.join("test.db"))?;
+        let label_id = db.get_or_create_label("health", "#fa5252")?;
+        db.update_label(label_id, &Label {
+            id: Some(label_id),
+            name: "health".to_string(),
+            color: "#fa5252".to_string(),
+            keywords: "dentist".to_string(),
+        })?;
+
+        let engine = MigrationEngine::new(&config);
+        engine.process_m4a_file(&memo, &db)?;
+        engine.process_m4a_file(&source_dir.join("plain.m4a"), &db)?;
+
+        let slices = db.list_all_slices()?;
+        assert!(slices[0].transcribed);
+        assert_eq!(slices[0].transcription.as_deref(), Some("Call the dentist tomorrow"));
+        assert_eq!(slices[0].transcription_model.as_deref(), Some(APPLE_MODEL));
+        assert_eq!(slices[0].transcription_word_count, Some(4));
+        assert!(!slices[1].transcribed);
+        assert_eq!(slices[1].transcription, None);
+
+        let labels = db.get_labels_for_all_slices()?;
+        assert_eq!(labels[&slices[0].id.unwrap()][0].name, "health");
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_name_in_different_subfolders_is_kept() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_dir = temp_dir.path().join("source");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(source_dir.join("Work"))?;
+        fs::create_dir_all(source_dir.join("Home"))?;
+        fs::create_dir_all(source_dir.join("Old"))?;
+
+        let work = source_dir.join("Work").join("memo.m4a");
+        let home = source_dir.join("Home").join("memo.m4a");
+        fs::write(&work, b"work audio")?;
+        fs::write(&home, b"home audio")?;
+
+        let config = Config {
+            voice_memo_root: source_dir.to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(dest_dir.join("test.db"))?;
+        let engine = MigrationEngine::new(&config);
+
+        assert!(matches!(engine.process_m4a_file(&work, &db)?, ProcessResult::Copied(_)));
+        assert!(matches!(engine.process_m4a_file(&home, &db)?, ProcessResult::Copied(_)));
+
+        let renamed = collision_file_name("memo.m4a", "Home/memo.m4a");
+        assert!(renamed.starts_with("memo-") && renamed.ends_with(".m4a") && renamed.len() == "memo-12345678.m4a".len());
+        assert_eq!(fs::read(config.audio_dir().join("memo.m4a"))?, b"work audio");
+        assert_eq!(fs::read(config.audio_dir().join(&renamed))?, b"home audio");
+        let slices = db.list_all_slices()?;
+        assert_eq!(slices[0].source_relative_path.as_deref(), Some("Work/memo.m4a"));
+        assert_eq!(slices[1].original_audio_file_name, renamed);
+        assert_eq!(slices[1].source_relative_path.as_deref(), Some("Home/memo.m4a"));
+
+        // A second run skips both
+        assert!(matches!(engine.process_m4a_file(&work, &db)?, ProcessResult::Skipped));
+        assert!(matches!(engine.process_m4a_file(&home, &db)?, ProcessResult::Skipped));
+
+        // A slice from before source paths were tracked is claimed by the first file under its name
+        fs::write(source_dir.join("Old").join("legacy.m4a"), b"legacy audio")?;
+        let mut legacy = Slice {
+            original_audio_file_name: "legacy.m4a".to_string(),
+            content_hash: Some(sha256_file(&source_dir.join("Old").join("legacy.m4a"))?),
+            ..slices[0].clone()
+        };
+        legacy.source_relative_path = None;
+        db.insert_slice(&legacy)?;
+        assert!(matches!(engine.process_m4a_file(&source_dir.join("Old").join("legacy.m4a"), &db)?, ProcessResult::Skipped));
+        assert_eq!(db.get_slice_source_path("legacy.m4a")?, Some(Some("Old/legacy.m4a".to_string())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_edited_recording_uses_current_version() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_dir = temp_dir.path().join("source");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(&source_dir)?;
+
+        // Migrate the memo before it was trimmed
+        let memo = source_dir.join("memo.m4a");
+        fs::write(&memo, b"original long recording")?;
+
+        let config = Config {
+            voice_memo_root: source_dir.to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db_path = dest_dir.join("test.db");
+        let db = Database::new(&db_path)?;
+        let engine = MigrationEngine::new(&config);
+        assert!(matches!(engine.process_m4a_file(&memo, &db)?, ProcessResult::Copied(_)));
+        let slice_id = db.list_all_slices()?[0].id.unwrap();
+        db.update_slice_transcription(slice_id, "the whole long recording", 3, 4, "base.en")?;
+
+        // Trim it: Voice Memos keeps the original take in a composition and rewrites the top-level file
+        let composition = source_dir.join("memo.composition");
+        fs::create_dir_all(&composition)?;
+        fs::write(composition.join("memo.m4a"), b"original long recording")?;
+        fs::write(&memo, b"trimmed")?;
+
+        let files = engine.scan_m4a_files(&source_dir)?;
+        assert_eq!(files, vec![memo.clone()], "composition segments must not be migrated");
+
+        assert!(matches!(engine.process_m4a_file(&memo, &db)?, ProcessResult::Copied(7)));
+        assert_eq!(fs::read(config.audio_dir().join("memo.m4a"))?, b"trimmed");
+
+        let slices = db.list_all_slices()?;
+        assert_eq!(slices.len(), 1);
+        assert!(slices[0].was_edited);
+        assert_eq!(slices[0].audio_file_size, 7);
+        assert!(!slices[0].transcribed, "the old transcript doesn't match the trimmed audio");
+        assert_eq!(slices[0].transcription, None);
+
+        // Nothing changes on the next pass
+        assert!(matches!(engine.process_m4a_file(&memo, &db)?, ProcessResult::Skipped));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_additional_source_tracks_files_per_source() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_dir = temp_dir.path().join("source");
+        let icloud_dir = temp_dir.path().join("icloud");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(&source_dir)?;
+        fs::create_dir_all(icloud_dir.join("2023"))?;
+
+        fs::write(source_dir.join("memo.m4a"), b"voice memo")?;
+        fs::write(icloud_dir.join("2023").join("lecture.mp3"), b"lecture audio")?;
+        fs::write(icloud_dir.join("memo copy.m4a"), b"voice memo")?; // same audio as the voice memo
+
+        let apple_db = Connection::open(source_dir.join("CloudRecordings.db"))?;
+        apple_db.execute("CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT)", [])?;
+        drop(apple_db);
+
+        let config = Config {
+            voice_memo_root: source_dir.to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            additional_sources: vec![icloud_dir.to_string_lossy().to_string()],
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+
+        let summary = MigrationEngine::new(&config).start_migration()?;
+        assert_eq!(summary.copied, 2);
+        assert_eq!(summary.duplicates.len(), 1);
+
+        let icloud = summary.sources.iter().find(|s| s.source == "additional_source").unwrap();
+        assert_eq!((icloud.copied, icloud.skipped, icloud.errors), (1, 1, 0));
+
+        let db = Database::new(dest_dir.join("CiderPress-db.sqlite"))?;
+        let root = icloud_dir.to_string_lossy().to_string();
+        assert!(db.source_file_seen(&root, &format!("2023{}lecture.mp3", std::path::MAIN_SEPARATOR))?);
+        assert!(db.source_file_seen(&root, "memo copy.m4a")?);
+
+        // Second run: everything is skipped through per-source tracking
+        let summary = MigrationEngine::new(&config).start_migration()?;
+        assert_eq!(summary.copied, 0);
+        assert!(summary.duplicates.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interrupted_migration_resumes_batch() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_dir = temp_dir.path().join("source");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(&source_dir)?;
+        fs::write(source_dir.join("a.m4a"), b"first memo")?;
+        fs::write(source_dir.join("b.m4a"), b"second memo")?;
+
+        let apple_db = Connection::open(source_dir.join("CloudRecordings.db"))?;
+        apple_db.execute("CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT)", [])?;
+        drop(apple_db);
+
+        let config = Config {
+            voice_memo_root: source_dir.to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+
+        // A previous run handled a.m4a and then the app quit
+        let db = Database::new(dest_dir.join("CiderPress-db.sqlite"))?;
+        let batch_id = db.start_migration_batch("voice_memos")?;
+        db.record_migration_batch_file(batch_id, &ProcessResult::Copied(10).to_batch_file(
+            source_dir.join("a.m4a").to_string_lossy().to_string(),
+        ))?;
+
+        let summary = MigrationEngine::new(&config).start_migration()?;
+        assert_eq!(summary.resumed_files, 1);
+        assert_eq!(summary.copied, 2); // Combined: one from before the restart, one now
+        assert_eq!(db.list_all_slices()?.len(), 1, "a.m4a must not be processed again");
+        assert_eq!(db.active_migration_batch("voice_memos")?, None);
+
+        // The next run starts a fresh batch
+        let summary = MigrationEngine::new(&config).start_migration()?;
+        assert_eq!(summary.resumed_files, 0);
+        assert_eq!((summary.copied, summary.skipped), (1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rollback_last_migration() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_dir = temp_dir.path().join("source");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(&source_dir)?;
+        for name in ["a.m4a", "b.m4a", "c.m4a"] {
+            fs::write(source_dir.join(name), name.as_bytes())?;
+        }
+
+        let apple_db = Connection::open(source_dir.join("CloudRecordings.db"))?;
+        apple_db.execute("CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT)", [])?;
+        drop(apple_db);
+
+        let config = Config {
+            voice_memo_root: source_dir.to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(dest_dir.join("CiderPress-db.sqlite"))?;
+        assert!(rollback_last_migration(&config, &db).is_err(), "nothing to roll back yet");
+        {
+            let _running = MIGRATION_RUN.lock().unwrap();
+            let err = rollback_last_migration(&config, &db).unwrap_err();
+            assert!(err.is::<MigrationInProgress>());
+        }
+
+        MigrationEngine::new(&config).start_migration()?;
+        let slices = db.list_all_slices()?;
+        assert_eq!(slices.len(), 3);
+        let transcribed = slices.iter().find(|s| s.original_audio_file_name == "b.m4a").unwrap();
+        db.update_slice_transcription(transcribed.id.unwrap(), "keep me", 3, 2, "base.en")?;
+
+        // A later run with nothing new to copy doesn't hide the batch that did
+        let summary = MigrationEngine::new(&config).start_migration()?;
+        assert_eq!(summary.copied, 0);
+
+        let mut report = rollback_last_migration(&config, &db)?;
+        report.removed_files.sort();
+        assert_eq!(report.removed_files, vec!["a.m4a", "c.m4a"]);
+        assert_eq!(report.kept_files, vec!["b.m4a"]);
+        assert!(report.errors.is_empty());
+        assert!(!config.audio_dir().join("a.m4a").exists());
+        assert!(config.audio_dir().join("b.m4a").exists());
+        assert_eq!(db.list_all_slices()?.len(), 1);
+
+        // The batch is done with; the removed recordings migrate again next time
+        assert!(rollback_last_migration(&config, &db).is_err());
+        let summary = MigrationEngine::new(&config).start_migration()?;
+        assert_eq!((summary.copied, summary.skipped), (2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_source_profiles_migrate_independently() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dest_dir = temp_dir.path().join("ciderpress");
+        let mut profiles = Vec::new();
+        for (name, memo) in [("Work Mac", "standup.m4a"), ("Personal Mac", "groceries.m4a")] {
+            let root = temp_dir.path().join(name).join("Recordings");
+            fs::create_dir_all(&root)?;
+            fs::write(root.join(memo), memo.as_bytes())?;
+            let apple_db = Connection::open(root.join("CloudRecordings.db"))?;
+            apple_db.execute("CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT)", [])?;
+            profiles.push(SourceProfile {
+                name: name.to_string(),
+                path: root.to_string_lossy().to_string(),
+                kind: SourceKind::VoiceMemos,
+            });
+        }
+
+        let config = Config {
+            voice_memo_root: temp_dir.path().join("unused").to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            additional_sources: vec![temp_dir.path().join("Personal Mac").to_string_lossy().to_string()],
+            source_profiles: profiles,
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+
+        let work = config.source_profile("Work Mac").unwrap();
+        let summary = migrate_source_profile(&config, work)?;
+        assert_eq!(summary.copied, 1, "only the work profile is scanned");
+
+        let db = Database::new(dest_dir.join("CiderPress-db.sqlite"))?;
+        let slices = db.list_all_slices()?;
+        assert_eq!(slices.len(), 1);
+        assert_eq!(slices[0].original_audio_file_name, "standup.m4a");
+        let labels = db.get_labels_for_all_slices()?;
+        assert_eq!(labels[&slices[0].id.unwrap()][0].name, "Work Mac");
+
+        migrate_source_profile(&config, config.source_profile("Personal Mac").unwrap())?;
+        // Nothing new to copy: the personal memos aren't labeled with the work profile
+        migrate_source_profile(&config, work)?;
+        let labels = db.get_labels_for_all_slices()?;
+        let names: Vec<_> = db.list_all_slices()?.iter()
+            .map(|s| labels[&s.id.unwrap()].iter().map(|l| l.name.clone()).collect::<Vec<_>>())
+            .collect();
+        assert_eq!(names, vec![vec!["Work Mac".to_string()], vec!["Personal Mac".to_string()]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_recently_deleted_recordings_are_opt_in() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_dir = temp_dir.path().join("source");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(&source_dir)?;
+        fs::write(source_dir.join("kept.m4a"), b"kept memo")?;
+        fs::write(source_dir.join("deleted.m4a"), b"deleted memo")?;
+
+        let apple_db = Connection::open(source_dir.join("CloudRecordings.db"))?;
+        apple_db.execute_batch(
+            r#"
+            CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT, ZEVICTIONDATE TIMESTAMP);
+            INSERT INTO ZCLOUDRECORDING VALUES (1, 'kept.m4a', NULL);
+            INSERT INTO ZCLOUDRECORDING VALUES (2, 'deleted.m4a', 750000000.0);
+            "#,
+        )?;
+        drop(apple_db);
+
+        let mut config = Config {
+            voice_memo_root: source_dir.to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+
+        let summary = MigrationEngine::new(&config).start_migration()?;
+        assert_eq!(summary.copied, 1);
+        let db = Database::new(dest_dir.join("CiderPress-db.sqlite"))?;
+        assert!(!db.slice_exists("deleted.m4a")?);
+
+        config.include_recently_deleted = true;
+        let summary = MigrationEngine::new(&config).start_migration()?;
+        assert_eq!((summary.copied, summary.skipped), (1, 1));
+
+        let labels = db.get_labels_for_all_slices()?;
+        for slice in db.list_all_slices()? {
+            let names: Vec<_> = labels.get(&slice.id.unwrap()).into_iter().flatten().map(|l| l.name.as_str()).collect();
+            if slice.original_audio_file_name == "deleted.m4a" {
+                assert_eq!(names, vec![RECENTLY_DELETED_LABEL]);
+            } else {
+                assert!(names.is_empty());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_copies_keep_duplicate_detection() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_dir = temp_dir.path().join("source");
+        let dest_dir = temp_dir.path().join("ciderpress");
+        fs::create_dir_all(&source_dir)?;
+        for i in 0..20 {
+            fs::write(source_dir.join(format!("memo{:02}.m4a", i)), format!("audio {}", i))?;
+        }
+        // Same audio as memo03, likely copied at the same time on another worker
+        fs::write(source_dir.join("memo03 copy.m4a"), "audio 3")?;
+
+        let apple_db = Connection::open(source_dir.join("CloudRecordings.db"))?;
+        apple_db.execute("CREATE TABLE ZCLOUDRECORDING (Z_PK INTEGER PRIMARY KEY, ZPATH TEXT)", [])?;
+        drop(apple_db);
+
+        let config = Config {
+            voice_memo_root: source_dir.to_string_lossy().to_string(),
+            ciderpress_home: dest_dir.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+
+        let summary = MigrationEngine::new(&config).start_migration()?;
+        assert_eq!((summary.copied, summary.skipped, summary.errors), (20, 1, 0));
+        assert_eq!(summary.duplicates.len(), 1);
+
+        let db = Database::new(dest_dir.join("CiderPress-db.sqlite"))?;
+        assert_eq!(db.list_all_slices()?.len(), 20);
+        assert_eq!(fs::read_dir(config.audio_dir())?.count(), 20, "the duplicate's copy is removed");
+        Ok(())
+    }
+
     #[test]
     fn test_full_migration_with_multiple_files() -> Result<()> {
         // Create temporary directories
@@ -994,6 +2476,9 @@ mod tests {
             Ok(ProcessResult::Skipped) => {
                 println!("File was skipped (already exists in database)");
             }
+            Ok(ProcessResult::Duplicate(existing)) => {
+                println!("File was skipped (duplicate of {})", existing);
+            }
             Err(e) => {
                 println!("ERROR processing file: {}", e);
                 return Err(e);
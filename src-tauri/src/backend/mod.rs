@@ -14,12 +14,53 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+pub mod apple_notes;
+pub mod apple_transcript;
+pub mod ask;
+pub mod backup;
 pub mod config;
+pub mod convert;
 pub mod database;
+pub mod date_query;
+pub mod db_pool;
+pub mod drive;
+pub mod duplicates;
+pub mod email;
+pub mod embeddings;
+pub mod encryption;
+pub mod entities;
+pub mod export;
+pub mod importer;
+pub mod inbox;
+pub mod ios_backup;
+pub mod label_prompts;
+pub mod label_suggestions;
+pub mod labeling;
+pub mod library;
 pub mod logging;
+pub mod meetings;
 pub mod migrate;
 pub mod models;
 pub mod nlm;
+pub mod nlm_audio;
+pub mod nlm_notes;
+pub mod nlm_provision;
+pub mod nlm_upload;
+pub mod ollama;
 pub mod parakeet;
+pub mod pii;
+pub mod podcast;
+pub mod portable;
+pub mod recorders;
+pub mod scheduler;
+pub mod search;
+pub mod sentiment;
 pub mod stats;
-pub mod transcribe;
\ No newline at end of file
+pub mod summarize;
+pub mod sync_export;
+pub mod telegram;
+pub mod titles;
+pub mod transcribe;
+pub mod translate;
+pub mod watch;
+pub mod whatsapp;
\ No newline at end of file
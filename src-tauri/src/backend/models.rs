@@ -54,7 +54,8 @@ pub struct RecordingWithTranscript {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Stats {
-    pub total_files: i64,
+    pub total_files: i64, // outside the trash, archived included
+    pub archived_files: i64,
     pub total_transcribed: i64,
     pub avg_transcribe_sec_10m: Option<f64>,
     pub total_audio_bytes: i64,
@@ -62,6 +63,16 @@ pub struct Stats {
     pub avg_file_bytes: f64,
     pub count_by_year: Vec<YearCount>,
     pub count_by_audio_length: Vec<AudioLengthBucket>,
+    #[serde(default)]
+    pub mood_by_month: Vec<MoodPoint>, // from slices with a sentiment score, oldest month first
+}
+
+/// Average sentiment of the slices recorded in one month.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MoodPoint {
+    pub month: String, // "YYYY-MM"
+    pub average_score: f64,
+    pub count: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,12 +87,410 @@ pub struct AudioLengthBucket {
     pub count: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MigrationSummary {
     pub copied: u32,
     pub skipped: u32,
     pub errors: u32,
     pub total_size_bytes: u64,
+    #[serde(default)]
+    pub duplicates: Vec<DuplicateSkip>,
+    #[serde(default)]
+    pub sources: Vec<SourceSummary>,
+    #[serde(default)]
+    pub resumed_files: u32, // Files already handled by an interrupted run this one resumed
+    #[serde(default)]
+    pub batch_id: Option<i64>, // migration batch this run recorded its slices in
+}
+
+/// Per-source breakdown of a migration run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceSummary {
+    pub source: String, // e.g. "voice_memos", "call_recording", "additional_source"
+    pub root: String,
+    pub copied: u32,
+    pub skipped: u32,
+    pub errors: u32,
+}
+
+/// A file an unfinished migration batch has already handled, so a resumed run can skip it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationBatchFile {
+    pub file_path: String,
+    pub result: String, // 'copied', 'skipped' or 'duplicate'
+    pub size_bytes: Option<i64>,
+    pub duplicate_of: Option<String>,
+}
+
+/// Result of undoing the most recent migration batch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MigrationRollbackReport {
+    pub batch_id: i64,
+    pub removed_slice_ids: Vec<i64>,
+    pub removed_files: Vec<String>,
+    pub kept_files: Vec<String>, // slices left alone because they were transcribed or renamed since
+    pub errors: Vec<ImportFailure>,
+}
+
+/// A file skipped because its audio (by SHA-256) is already in the library.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateSkip {
+    pub file_path: String,
+    pub duplicate_of: String, // original_audio_file_name of the existing slice
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportFailure {
+    pub file_path: String,
+    pub message: String,
+}
+
+/// Result of a bulk folder import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FolderImportReport {
+    pub imported_slice_ids: Vec<i64>,
+    pub duplicates: Vec<DuplicateSkip>,
+    pub errors: Vec<ImportFailure>,
+}
+
+/// One episode listed in a podcast feed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PodcastEpisode {
+    pub id: String, // the item's guid, or its enclosure URL when it has none
+    pub title: String,
+    pub published: Option<i64>,
+    pub audio_url: String,
+    pub duration_seconds: Option<f64>,
+}
+
+/// A podcast feed, as listed for picking episodes to import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodcastFeed {
+    pub title: String,
+    pub episodes: Vec<PodcastEpisode>,
+}
+
+/// Column a slice listing is ordered by.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SliceSortField {
+    #[default]
+    Id, // order of import
+    RecordingDate,
+    Duration,
+    Size,
+    Title, // falls back to the filename for untitled slices
+    WordCount,
+    Transcribed, // untranscribed first when ascending
+}
+
+/// How a slice listing's `search` text is matched.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Case-insensitive substring
+    #[default]
+    Substring,
+    /// Tolerates typos and matches word prefixes; every search word has to match
+    Fuzzy,
+    /// Case-insensitive regular expression, e.g. ticket IDs or phone numbers
+    Regex,
+    /// Transcript words that sound like every search word ("Kathryn" finds "Catherine");
+    /// needs the phonetic index turned on
+    Phonetic,
+}
+
+/// One page of the slice listing. Filters are combined with AND; trashed slices never appear.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SliceQuery {
+    #[serde(default)]
+    pub limit: Option<u32>, // None = no limit
+    #[serde(default)]
+    pub offset: Option<u32>,
+    #[serde(default)]
+    pub sort_by: SliceSortField,
+    #[serde(default)]
+    pub descending: bool,
+    #[serde(default)]
+    pub search: Option<String>, // match on title, filename, transcription or notes
+    #[serde(default)]
+    pub search_mode: SearchMode,
+    #[serde(default)]
+    pub natural_dates: bool, // read dates in `search` ("last week", "march 2023") as a recording date range
+    #[serde(default)]
+    pub label_id: Option<i64>,
+    #[serde(default)]
+    pub collection_id: Option<i64>, // slices filed directly in this collection
+    #[serde(default)]
+    pub filter: SliceFilter,
+    #[serde(default)]
+    pub starred_first: bool, // starred slices ahead of the sort order
+    #[serde(default)]
+    pub archived: bool, // list the archived slices instead of the library
+    #[serde(default)]
+    pub without_transcription: bool, // leave `transcription` out to keep large listings light
+}
+
+/// Structured conditions on a slice listing; unset fields don't filter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SliceFilter {
+    #[serde(default)]
+    pub recorded_after: Option<i64>, // Unix timestamp, inclusive
+    #[serde(default)]
+    pub recorded_before: Option<i64>, // Unix timestamp, exclusive
+    #[serde(default)]
+    pub min_duration_seconds: Option<f64>,
+    #[serde(default)]
+    pub max_duration_seconds: Option<f64>,
+    #[serde(default)]
+    pub transcribed: Option<bool>,
+    #[serde(default)]
+    pub transcription_model: Option<String>, // implies transcribed
+    #[serde(default)]
+    pub starred: Option<bool>,
+}
+
+/// Slices on the requested page, with how many match the query across all pages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlicePage {
+    pub slices: Vec<Slice>,
+    pub total: u32,
+    #[serde(default)]
+    pub truncated: bool, // a regex search stopped at its match or time limit; `total` is a lower bound
+}
+
+/// One place a search matched within a slice. Offsets count characters (Unicode scalar
+/// values) in the stored field, so the UI can jump straight to the match.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchMatch {
+    pub field: String, // "title", "original_audio_file_name", "transcription", "notes" or "label"
+    pub start: usize,
+    pub end: usize, // exclusive
+    pub snippet: String, // the match with some surrounding text, "…" where it was cut
+    pub highlight_start: usize, // the match's character range within `snippet`
+    pub highlight_end: usize,
+}
+
+/// A slice found by a search, with where it matched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub slice: Slice,
+    pub matches: Vec<SearchMatch>,
+    #[serde(default)]
+    pub score: f64, // relevance from where it matched; higher is better
+}
+
+/// One page of search results; `total` and `truncated` as for `SlicePage`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResultPage {
+    pub results: Vec<SearchResult>,
+    pub total: u32,
+    pub truncated: bool,
+}
+
+/// A find/replace over stored transcriptions, e.g. to fix a name whisper always misspells.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptReplacement {
+    pub find: String,
+    pub replace: String, // taken literally, no capture references
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub within_words: bool, // also replace inside longer words; by default only whole words
+    #[serde(default)]
+    pub slice_ids: Option<Vec<i64>>, // None = every transcript outside the trash
+}
+
+/// Replacements in one slice's transcription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SliceReplaceCount {
+    pub slice_id: i64,
+    pub name: String, // title, or the file name for untitled slices
+    pub replacements: u32,
+    pub examples: Vec<SearchMatch>, // the first few places, in the text as it was
+}
+
+/// Outcome of a find/replace; with `applied` false it was a dry run and nothing changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaceReport {
+    pub slices: Vec<SliceReplaceCount>,
+    pub total_replacements: u32,
+    pub applied: bool,
+}
+
+/// A slice in the trash, with when it was moved there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedSlice {
+    #[serde(flatten)]
+    pub slice: Slice,
+    pub deleted_at: i64, // Unix timestamp
+}
+
+/// A search the user ran, for recall and suggestions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    pub last_searched_at: i64, // Unix timestamp
+    pub times_searched: u32,
+}
+
+/// A recently opened slice, with when its audio or transcript was last fetched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentSlice {
+    #[serde(flatten)]
+    pub slice: Slice,
+    pub last_opened_at: i64, // Unix timestamp
+}
+
+/// A slice linked to another one, seen from the other slice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedSlice {
+    #[serde(flatten)]
+    pub slice: Slice,
+    pub relation: String, // e.g. "part", "follow_up", "related"
+    pub outgoing: bool, // the link was made from the other slice to this one, e.g. part 1 -> part 2
+}
+
+/// A single slice with what the detail view shows alongside it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SliceDetails {
+    pub slice: Slice,
+    pub related: Vec<RelatedSlice>,
+}
+
+/// One edit to a slice's name, title or transcription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SliceChange {
+    pub id: i64,
+    pub slice_id: i64,
+    pub field: String, // "name", "title", "transcription" or "notes"
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_at: i64, // Unix timestamp
+}
+
+/// Why slices were grouped as likely duplicates.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateReason {
+    IdenticalAudio,     // same content hash
+    SimilarTranscript,  // same duration and nearly the same words
+}
+
+/// Slices that look like copies of one recording, oldest import first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub reason: DuplicateReason,
+    pub slices: Vec<Slice>,
+}
+
+/// Result of deleting slices: rows removed and the files that went with them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SliceDeleteReport {
+    pub deleted_slice_ids: Vec<i64>,
+    pub removed_files: Vec<String>,
+    pub errors: Vec<ImportFailure>,
+}
+
+/// Slices whose transcripts matched one label's keywords in an auto-labeling run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelMatchCount {
+    pub label_id: i64,
+    pub label_name: String,
+    pub matched_slices: u32,
+    pub newly_assigned: u32, // matched slices that did not have the label yet
+}
+
+/// Result of an auto-labeling run over the library.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutoLabelReport {
+    pub slices_scanned: u32,
+    pub labels: Vec<LabelMatchCount>, // one entry per label with keywords
+}
+
+/// A label that an unlabeled slice's transcript resembles the labeled slices of.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LabelSuggestion {
+    pub slice_id: i64,
+    pub label_id: i64,
+    pub label_name: String,
+    pub score: f32, // similarity to the label's slices, less similarity to slices it was rejected for
+}
+
+/// Result of swapping a backup in as the library database.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRestoreReport {
+    pub restored_from: String,
+    pub previous_database: Option<String>, // copy of the replaced database, kept in case of regret
+    pub slice_count: u32,
+}
+
+/// Which repairs `verify_library` should make; with none set it only reports.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibraryRepairOptions {
+    #[serde(default)]
+    pub recopy_missing: bool, // copy missing audio back from the Voice Memos folder
+    #[serde(default)]
+    pub remove_orphan_files: bool, // delete audio files no slice refers to
+    #[serde(default)]
+    pub remove_missing_rows: bool, // delete slices whose audio is still missing
+}
+
+/// Result of cross-checking the slices table against the audio directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibraryVerifyReport {
+    pub checked_slices: u32,
+    pub missing_files: Vec<String>, // slices whose audio file is not in the library
+    pub orphan_files: Vec<String>,  // audio files with no slice
+    pub recopied_files: Vec<String>,
+    pub removed_files: Vec<String>,
+    pub removed_slice_ids: Vec<i64>,
+    pub errors: Vec<ImportFailure>,
+}
+
+/// Result of `check_database`: SQLite's own checks plus the library files cross-check.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DatabaseCheckReport {
+    pub ok: bool, // nothing below needs attention
+    pub integrity_errors: Vec<String>, // PRAGMA integrity_check messages
+    pub foreign_key_violations: Vec<String>,
+    pub dangling_label_links: u32, // slice_labels rows whose slice or label no longer exists
+    pub size_mismatches: Vec<String>, // audio files whose size differs from the slice's record
+    pub files: LibraryVerifyReport,
+}
+
+/// Size of the database file (with its write-ahead log) around a VACUUM.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VacuumReport {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub reclaimed_bytes: u64,
+}
+
+/// Result of one sync-folder export run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncExportReport {
+    pub folder: String,
+    pub exported: u32,  // written because they were new, changed or missing from the folder
+    pub unchanged: u32,
+    pub removed: u32,   // files of slices that were deleted, trashed or lost their transcript
+    pub errors: Vec<ImportFailure>,
+}
+
+/// Result of exporting library files to a folder
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibraryExportReport {
+    pub destination: String,
+    pub exported_files: Vec<String>, // file names written under `destination`
+    pub errors: Vec<ImportFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderImportProgress {
+    pub total_files: u32,
+    pub processed_files: u32,
+    pub current_file: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,6 +519,22 @@ pub struct Slice {
     pub transcription_word_count: Option<i32>,
     pub transcription_model: Option<String>, // whisper model used for transcription
     pub recording_date: Option<i64>, // Unix timestamp of original recording from Apple's ZDATE
+    #[serde(default)]
+    pub source: Option<String>, // migration source/importer that produced the slice, e.g. "voice_memos", "call_recording"
+    #[serde(default)]
+    pub starred: bool,
+    #[serde(default)]
+    pub was_edited: bool, // trimmed/edited in Voice Memos; the current edited version was migrated
+    #[serde(default)]
+    pub content_hash: Option<String>, // hex SHA-256 of the library copy of the audio
+    #[serde(default)]
+    pub source_relative_path: Option<String>, // path of the original file under its migration source root
+    #[serde(default)]
+    pub notes: Option<String>, // user's annotation, kept apart from the transcription
+    #[serde(default)]
+    pub pinned: bool, // listed ahead of everything else in the library
+    #[serde(default)]
+    pub summary: Option<String>, // written by the local LLM (Ollama); None until summarized
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -165,6 +590,14 @@ pub struct Label {
     pub keywords: String,
 }
 
+/// A folder of slices; collections nest through `parent_id` (None = top level).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: Option<i64>,
+    pub name: String,
+    pub parent_id: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreMigrationStats {
     // Origin (Apple Voice Memos) stats
@@ -220,10 +653,371 @@ pub struct MigrationLogEntry {
     pub level: String, // "info", "warn", "error", "success"
 }
 
+/// Emitted as a `migration-file` event after each file a migration processes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationFileEvent {
+    pub file_name: String,
+    pub file_path: String,
+    pub source: String,         // e.g. "voice_memos", "call_recording"
+    pub result: String,         // "copied", "skipped", "duplicate" or "error"
+    pub bytes: Option<u64>,     // Size copied, for "copied"
+    pub message: Option<String>, // Error text, or which slice a duplicate matches
+    pub processed_files: u32,
+    pub total_files: u32,
+    pub copied: u32,
+    pub skipped: u32,
+    pub errors: u32,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelDownloadProgress {
     pub model_name: String,
     pub percentage: f32,
     pub status: String, // "started", "progress", "completed", "error"
     pub error_message: Option<String>,
-} 
\ No newline at end of file
+} 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchModeEvent {
+    pub status: String, // "watching", "stopped", "migration_started", "migration_completed", "error"
+    pub message: String,
+    pub copied: u32,
+}
+
+/// Emitted as an `nlm-output` event for each line an NLM command prints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NlmOutputLine {
+    pub command: String, // nlm subcommand, e.g. "add"
+    pub stream: String,  // "stdout" or "stderr"
+    pub line: String,
+}
+
+/// What of a slice goes up to a NotebookLM notebook as a source.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NlmSourceKind {
+    Text,  // the transcription
+    Audio, // the library audio file
+}
+
+impl NlmSourceKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NlmSourceKind::Text => "text",
+            NlmSourceKind::Audio => "audio",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(NlmSourceKind::Text),
+            "audio" => Some(NlmSourceKind::Audio),
+            _ => None,
+        }
+    }
+}
+
+/// A source CiderPress added to a NotebookLM notebook.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NlmSource {
+    pub notebook_id: String,
+    pub source_id: String,
+    pub slice_id: Option<i64>, // None for free text sent with `nlm_add_text`
+    pub kind: NlmSourceKind,
+    pub title: Option<String>,
+    pub added_at: i64, // Unix timestamp
+    #[serde(default)]
+    pub content_hash: Option<String>, // SHA-256 of what was uploaded; None for sources recorded before hashing
+}
+
+/// A source holding one part of a transcript too long to upload whole. Parts overlap a
+/// little, so `start_char` of one is before `end_char` of the one before.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NlmSourcePart {
+    pub notebook_id: String,
+    pub source_id: String,
+    pub slice_id: i64,
+    pub part: u32,        // 1-based
+    pub part_count: u32,
+    pub start_char: usize, // character range of the transcript, end exclusive
+    pub end_char: usize,
+}
+
+/// One upload of a slice to a NotebookLM notebook. Unlike `NlmSource` these stay after the
+/// source is removed, as a history of what was sent where.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NlmUpload {
+    pub id: i64,
+    pub slice_id: i64,
+    pub notebook_id: String,
+    pub source_type: NlmSourceKind,
+    pub source_id: Option<String>, // None when nlm didn't report one
+    pub uploaded_at: i64,          // Unix timestamp
+    pub account: Option<String>,   // saved NLM account in use; None if none was saved
+}
+
+/// One run of the nlm binary, kept so failed uploads and queries can be looked into later.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NlmInvocation {
+    pub id: i64,
+    pub command: String,            // subcommand, e.g. "add" or "list"
+    pub args: Vec<String>,          // each cut to a couple of hundred characters
+    pub started_at: i64,            // Unix timestamp
+    pub duration_ms: i64,
+    pub success: bool,
+    pub error_kind: Option<String>, // "NlmTimeout", "NlmAuthError" or "NlmError" when it failed
+    pub output: Option<String>,     // start of the output or error; None for sign-in, which may hold credentials
+    pub account: Option<String>,    // saved NLM account in use
+}
+
+/// A slice's transcript or audio as uploaded to Google Drive.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DriveFile {
+    pub slice_id: i64,
+    pub kind: NlmSourceKind, // same choice of transcript or audio as for NotebookLM
+    pub file_id: Option<String>, // None until a first upload finishes
+    pub status: String,          // "uploading" or "synced"
+    pub content_hash: String,    // SHA-256 of what was (or is being) uploaded
+    pub updated_at: i64,
+    #[serde(skip)]
+    pub session_uri: Option<String>, // resumable upload in progress
+}
+
+/// One upload that failed in a Drive sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriveSyncFailure {
+    pub slice_id: i64,
+    pub kind: NlmSourceKind,
+    pub message: String,
+}
+
+/// Result of sending slices to Google Drive.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DriveSyncReport {
+    pub uploaded: u32,
+    pub unchanged: u32, // already on Drive as is
+    pub failed: Vec<DriveSyncFailure>,
+}
+
+/// A slice a local LLM task (summarizing, titling, ...) couldn't be done for, and why.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmSliceFailure {
+    pub slice_id: i64,
+    pub message: String,
+}
+
+/// How positive or negative a slice sounds, as judged by the local LLM.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SliceSentiment {
+    pub slice_id: i64,
+    pub score: f64,    // -1.0 (very negative) to 1.0 (very positive)
+    pub label: String, // "positive", "neutral" or "negative"
+    pub model: String,
+    pub analyzed_at: i64, // Unix timestamp
+}
+
+/// What a named entity mentioned in a transcript is.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Person,
+    Place,
+    Organization,
+}
+
+impl EntityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EntityKind::Person => "person",
+            EntityKind::Place => "place",
+            EntityKind::Organization => "organization",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "person" => Some(EntityKind::Person),
+            "place" => Some(EntityKind::Place),
+            "organization" => Some(EntityKind::Organization),
+            _ => None,
+        }
+    }
+}
+
+/// A person, place or organization a slice's transcript mentions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SliceEntity {
+    pub slice_id: i64,
+    pub kind: EntityKind,
+    pub name: String,
+    pub mentions: u32, // times the name occurs in the transcript, at least 1
+}
+
+/// An entity across the library, with how many slices mention it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EntityCount {
+    pub kind: EntityKind,
+    pub name: String,
+    pub slice_count: u32,
+    pub mentions: u32,
+}
+
+/// A slice's transcript translated into another language by the local LLM.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SliceTranslation {
+    pub slice_id: i64,
+    pub language: String, // target language as requested, lowercased, e.g. "es" or "german"
+    pub text: String,
+    pub model: String,
+    pub source_hash: String, // hash of the transcript translated, to tell when it has changed since
+    pub translated_at: i64,
+}
+
+/// A stretch of a transcript with its embedding, as stored in the transcript index.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptChunk {
+    pub slice_id: i64,
+    pub start_char: usize,
+    pub end_char: usize,
+    pub text: String,
+    pub embedding: Vec<f32>,
+}
+
+/// A transcript passage found for a question or query, most similar first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptPassage {
+    pub slice_id: i64,
+    pub title: Option<String>,
+    pub recording_date: Option<i64>,
+    pub text: String,
+    pub score: f32, // cosine similarity to the question
+}
+
+/// The local LLM's answer to a question about the transcripts, with what it was based on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptAnswer {
+    pub answer: String,
+    pub cited_slice_ids: Vec<i64>, // slices the answer cites, in order of first citation
+    pub passages: Vec<TranscriptPassage>,
+}
+
+/// Result of running a local LLM task over several slices.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LlmBatchReport {
+    pub processed: u32,
+    pub skipped: u32, // already done, or nothing to work from (no transcript)
+    pub failed: Vec<LlmSliceFailure>,
+}
+
+/// A label whose slices are sent to a NotebookLM notebook once transcribed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LabelNotebook {
+    pub label_id: i64,
+    pub label_name: String,
+    pub notebook_id: String,
+}
+
+/// A kind of personal information the PII scan looks for.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    Phone,
+    Email,
+    Address,
+    CardNumber,
+}
+
+/// Personal information found in a transcript. Offsets are in characters.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PiiMatch {
+    pub kind: PiiKind,
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// The personal information found in one slice's transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlicePiiFindings {
+    pub slice_id: i64,
+    pub name: String, // title, or the audio file name without one
+    pub matches: Vec<PiiMatch>,
+}
+
+/// Result of scanning transcripts for personal information.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PiiScanReport {
+    pub slices_scanned: u32,
+    pub slices: Vec<SlicePiiFindings>, // only slices with something found
+}
+
+/// Result of masking personal information in stored text.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PiiRedactionReport {
+    pub slices_redacted: u32,
+    pub values_changed: u32, // texts rewritten: transcripts, titles, notes, history, translations...
+    pub backup_path: Option<String>, // copy of the database saved before anything was rewritten
+}
+
+/// A label's prompt, run through the local LLM on each newly transcribed slice with the label.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LabelPrompt {
+    pub label_id: i64,
+    pub label_name: String,
+    pub prompt: String, // e.g. "Write minutes of this meeting: attendees, decisions, action items"
+}
+
+/// What a label's prompt made of a slice's transcript, kept with the slice.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DerivedDocument {
+    pub id: i64,
+    pub slice_id: i64,
+    pub label_id: i64,
+    pub label_name: String, // as it was when the document was made
+    pub prompt: String,
+    pub content: String,
+    pub model: String,
+    pub created_at: i64,
+}
+
+/// One upload in a NotebookLM batch, kept until the batch is done so it can be resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NlmBatchItem {
+    pub batch_id: String,
+    pub position: u32,
+    pub slice_id: i64,
+    pub notebook_id: String,
+    pub kind: NlmSourceKind,
+    pub status: String, // "pending", "uploaded", "already_present" or "failed"
+    pub error: Option<String>, // for a queued item back to "pending", why the last try failed
+    pub queued: bool,   // queued in the background; retried automatically when it fails
+    pub attempts: u32,  // failed tries so far
+}
+
+/// Emitted as an `nlm-batch-progress` event after each upload in a batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NlmBatchProgress {
+    pub batch_id: String,
+    pub item: NlmBatchItem,
+    pub processed: u32,
+    pub total: u32,
+}
+
+/// Emitted as an `nlm-audio-overview-progress` event while an Audio Overview is generated
+/// and brought into the library.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioOverviewProgress {
+    pub notebook_id: String,
+    pub stage: String, // "generating", "downloading" or "importing"
+    pub elapsed_seconds: u64,
+}
+
+/// Result of running (or resuming) a NotebookLM batch upload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NlmBatchReport {
+    pub batch_id: String,
+    pub uploaded: u32,
+    pub already_present: u32, // skipped: the notebook already held the same content
+    pub failed: Vec<NlmBatchItem>, // resume the batch to retry these
+}
@@ -0,0 +1,126 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! NotebookLM Audio Overviews back into the library: generate one for a notebook, wait for
+//! it to finish, download it and import it as a new slice.
+
+use anyhow::{anyhow, Result};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::database::Database;
+use super::export::sanitize_title;
+use super::importer::{self, ImportOutcome};
+use super::models::AudioOverviewProgress;
+use super::nlm::{self, AudioOverviewStatus};
+
+/// Slice source for imported Audio Overviews.
+pub const SOURCE: &str = "notebooklm";
+
+/// How often to ask whether generation has finished, and when to stop asking. Overviews
+/// usually take a few minutes.
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const MAX_WAIT: Duration = Duration::from_secs(30 * 60);
+
+/// File extension for the audio at `path`, going by its first bytes: NotebookLM doesn't
+/// say which format it serves. Formats not recognised are left to the transcoder to probe.
+fn audio_extension(path: &Path) -> Result<&'static str> {
+    let mut head = [0u8; 12];
+    let read = File::open(path)?.read(&mut head)?;
+    let head = &head[..read];
+    let extension = if head.starts_with(b"RIFF") && head.get(8..12) == Some(&b"WAVE"[..]) {
+        "wav"
+    } else if head.get(4..8) == Some(&b"ftyp"[..]) {
+        "m4a"
+    } else if head.starts_with(b"ID3") || matches!(head, [0xFF, second, ..] if second & 0xE0 == 0xE0) {
+        "mp3"
+    } else if head.starts_with(b"OggS") {
+        "ogg"
+    } else if head.starts_with(b"fLaC") {
+        "flac"
+    } else {
+        "audio"
+    };
+    Ok(extension)
+}
+
+/// Generate an Audio Overview of `notebook_id` and import it as a slice titled after the
+/// notebook. Blocks until done; returns the new slice's ID.
+pub fn generate_into_library(
+    config: &Config,
+    db: &Database,
+    notebook_id: &str,
+    notebook_title: &str,
+    instructions: Option<&str>,
+    mut on_progress: impl FnMut(&AudioOverviewProgress),
+) -> Result<i64> {
+    let start = Instant::now();
+    let mut report = |stage: &str| on_progress(&AudioOverviewProgress {
+        notebook_id: notebook_id.to_string(),
+        stage: stage.to_string(),
+        elapsed_seconds: start.elapsed().as_secs(),
+    });
+
+    report("generating");
+    nlm::create_audio_overview(notebook_id, instructions.filter(|i| !i.trim().is_empty()))?;
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        match nlm::get_audio_overview_status(notebook_id)? {
+            AudioOverviewStatus::Ready => break,
+            AudioOverviewStatus::Failed(output) => return Err(anyhow!("Audio Overview generation failed: {}", output)),
+            AudioOverviewStatus::Generating if start.elapsed() > MAX_WAIT => {
+                return Err(anyhow!("Audio Overview still not ready after {} minutes", MAX_WAIT.as_secs() / 60));
+            }
+            AudioOverviewStatus::Generating => report("generating"),
+        }
+    }
+
+    report("downloading");
+    let staging_dir = config.ciderpress_home_path().join("conversion_staging");
+    fs::create_dir_all(&staging_dir)?;
+    let download = staging_dir.join(format!("audio-overview-{}.download", notebook_id));
+    let mut staged = download.clone();
+    let now = chrono::Local::now();
+    let stem = sanitize_title(&format!("Audio Overview - {} {}", notebook_title, now.format("%Y-%m-%d %H%M")));
+    let result = nlm::download_audio_overview(notebook_id, &download).and_then(|_| {
+        // Named for what was actually served, so m4a isn't transcoded again
+        staged = download.with_extension(audio_extension(&download)?);
+        fs::rename(&download, &staged)?;
+        report("importing");
+        let title = format!("Audio Overview: {}", notebook_title);
+        importer::import_converted_audio(config, db, &staged, &stem, Some(title), Some(now.timestamp()), Some(SOURCE))
+    });
+
+    for path in [&download, &staged] {
+        if path.exists() {
+            if let Err(e) = fs::remove_file(path) {
+                warn!("Failed to remove downloaded Audio Overview {:?}: {}", path, e);
+            }
+        }
+    }
+    match result? {
+        ImportOutcome::Imported(id) => {
+            info!("Imported Audio Overview of notebook {} as slice {}", notebook_id, id);
+            Ok(id)
+        }
+        ImportOutcome::Duplicate(existing) => Err(anyhow!("This Audio Overview is already in the library as {}", existing)),
+        ImportOutcome::NameTaken => Err(anyhow!("A slice named {}.m4a already exists", stem)),
+    }
+}
@@ -0,0 +1,148 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! NotebookLM notes back into the library: each note of a notebook becomes a text slice,
+//! labelled with the notebook, so summaries NotebookLM wrote sit next to the recordings
+//! they came from.
+
+use anyhow::Result;
+use tracing::info;
+
+use super::database::Database;
+use super::export::strip_html_tags;
+use super::models::{FolderImportReport, ImportFailure, Slice};
+use super::nlm::{self, NlmNote};
+use super::nlm_audio::SOURCE;
+
+/// Colour of labels created for notebooks.
+const NOTEBOOK_LABEL_COLOR: &str = "#4285f4";
+
+/// Slice metadata key holding the notebook a note came from.
+pub const NOTEBOOK_METADATA_KEY: &str = "notebooklm_notebook";
+
+/// `source_files` root under which a notebook's imported notes are remembered.
+fn source_root(notebook_id: &str) -> String {
+    format!("notebooklm://{}", notebook_id)
+}
+
+/// The text slice a note becomes.
+fn note_slice(note: &NlmNote, content: String) -> Slice {
+    let title = Some(note.title.trim())
+        .filter(|t| !t.is_empty())
+        .unwrap_or("NotebookLM note")
+        .to_string();
+    let word_count = content.split_whitespace().count() as i32;
+    Slice {
+        id: None,
+        original_audio_file_name: format!("notebooklm_note_{}.txt", note.id),
+        title: Some(title),
+        transcribed: true,
+        audio_file_size: content.len() as i64,
+        audio_file_type: "text".to_string(),
+        estimated_time_to_transcribe: 0,
+        audio_time_length_seconds: None,
+        transcription: Some(content),
+        transcription_time_taken: Some(0),
+        transcription_word_count: Some(word_count),
+        transcription_model: Some(SOURCE.to_string()),
+        recording_date: Some(chrono::Utc::now().timestamp()),
+        source: Some(SOURCE.to_string()),
+        starred: false,
+        was_edited: false,
+        content_hash: None,
+        source_relative_path: None,
+        notes: None,
+        pinned: false,
+        summary: None,
+    }
+}
+
+/// Fetch a notebook's notes and import the ones not imported before.
+pub fn import_notes(db: &Database, notebook_id: &str, notebook_title: &str) -> Result<FolderImportReport> {
+    let notes = nlm::list_notes(notebook_id)?;
+    import_note_list(db, notebook_id, notebook_title, &notes)
+}
+
+/// Import `notes` of a notebook as text slices labelled with `notebook_title`. Notes
+/// imported from this notebook before, and empty ones, are skipped.
+pub fn import_note_list(db: &Database, notebook_id: &str, notebook_title: &str, notes: &[NlmNote]) -> Result<FolderImportReport> {
+    let root = source_root(notebook_id);
+    let mut report = FolderImportReport::default();
+    for note in notes {
+        // Notes written in NotebookLM's editor come as HTML; ones saved from chat are plain
+        let content = if note.content.contains("</") { strip_html_tags(&note.content) } else { note.content.clone() };
+        let content = content.trim().to_string();
+        if content.is_empty() || db.source_file_seen(&root, &note.id)? {
+            continue;
+        }
+        let result = db.insert_slice(&note_slice(note, content)).and_then(|id| {
+            db.set_slice_metadata(id, NOTEBOOK_METADATA_KEY, notebook_id)?;
+            db.record_source_file(&root, &note.id, Some(id), "imported")?;
+            Ok(id)
+        });
+        match result {
+            Ok(id) => report.imported_slice_ids.push(id),
+            Err(e) => report.errors.push(ImportFailure {
+                file_path: note.title.clone(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    if !report.imported_slice_ids.is_empty() {
+        let label_name = Some(notebook_title.trim()).filter(|t| !t.is_empty()).unwrap_or("NotebookLM");
+        let label_id = db.get_or_create_label(label_name, NOTEBOOK_LABEL_COLOR)?;
+        db.assign_label(label_id, &report.imported_slice_ids)?;
+    }
+    info!("Imported {} notes from notebook {}", report.imported_slice_ids.len(), notebook_id);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_notes_are_imported_once_and_labelled() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        let note = |id: &str, title: &str, content: &str| NlmNote {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+        };
+        let notes = vec![
+            note("n1", "Summary", "<p>We agreed to ship in May.</p>"),
+            note("n2", "", "Follow up with Dana"),
+            note("n3", "Empty", "  "),
+        ];
+
+        let report = import_note_list(&db, "nb-1", "Planning", &notes).unwrap();
+        assert_eq!(report.imported_slice_ids.len(), 2);
+        let summary = db.get_slice(report.imported_slice_ids[0]).unwrap().unwrap();
+        assert_eq!(summary.transcription.as_deref(), Some("We agreed to ship in May."));
+        assert_eq!(summary.source.as_deref(), Some(SOURCE));
+        let untitled = db.get_slice(report.imported_slice_ids[1]).unwrap().unwrap();
+        assert_eq!(untitled.title.as_deref(), Some("NotebookLM note"));
+        let metadata = db.get_slice_metadata(summary.id.unwrap()).unwrap();
+        assert_eq!(metadata.get(NOTEBOOK_METADATA_KEY).map(String::as_str), Some("nb-1"));
+        assert!(db.find_label_by_name("Planning").unwrap().is_some());
+
+        let again = import_note_list(&db, "nb-1", "Planning", &notes).unwrap();
+        assert!(again.imported_slice_ids.is_empty());
+    }
+}
@@ -0,0 +1,407 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Health check and self-install for the `nlm` binary. Builds without the bundled
+//! sidecar can fetch the release matching this machine's OS and architecture from
+//! GitHub into `~/.ciderpress/bin`, verified against the release's checksum list.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+use super::migrate::sha256_file;
+use super::nlm;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/tmc/nlm/releases/latest";
+const USER_AGENT: &str = "ciderpress";
+/// How long `nlm -version` may take before the binary is reported as not runnable.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NlmBinaryHealth {
+    pub binary_path: Option<String>,
+    /// "bundled", "development" or "installed"
+    pub source: Option<String>,
+    pub runs: bool,
+    pub version: Option<String>,
+    /// Only filled in when the check asked for the latest release.
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Where a self-installed binary lives: `~/.ciderpress/bin`.
+pub fn install_dir() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    Ok(home.join(".ciderpress").join("bin"))
+}
+
+pub fn installed_binary_path() -> Result<PathBuf> {
+    Ok(install_dir()?.join("nlm"))
+}
+
+/// The release tag written next to the binary at install time; more reliable than
+/// asking the binary, which may not print a version at all.
+fn installed_version() -> Option<String> {
+    let path = install_dir().ok()?.join("nlm.version");
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn binary_source(path: &Path) -> &'static str {
+    if installed_binary_path().map(|p| p == path).unwrap_or(false) {
+        "installed"
+    } else if path.starts_with(env!("CARGO_MANIFEST_DIR")) {
+        "development"
+    } else {
+        "bundled"
+    }
+}
+
+/// Pull a version number out of whatever the binary printed.
+fn parse_version(output: &str) -> Option<String> {
+    let re = regex::Regex::new(r"v?(\d+\.\d+(?:\.\d+)?)").ok()?;
+    re.captures(output).map(|c| c[1].to_string())
+}
+
+fn normalize_version(version: &str) -> &str {
+    version.trim().trim_start_matches('v')
+}
+
+/// Run the binary with `-version` to see that it starts at all (right architecture,
+/// not quarantined, not truncated) and what it reports.
+async fn probe(path: &Path) -> Result<Option<String>> {
+    let output = tokio::time::timeout(
+        VERSION_TIMEOUT,
+        tokio::process::Command::new(path).arg("-version").kill_on_drop(true).output(),
+    )
+    .await
+    .map_err(|_| anyhow!("nlm did not respond within {} seconds", VERSION_TIMEOUT.as_secs()))?
+    .with_context(|| format!("Failed to run {:?}", path))?;
+
+    // Older builds don't know -version and print usage instead; that still proves the
+    // binary runs.
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(parse_version(&text))
+}
+
+/// Check that an nlm binary is present and runs. With `check_latest`, also look up
+/// the newest release and say whether it is newer than what is installed.
+pub async fn check_health(check_latest: bool) -> NlmBinaryHealth {
+    let mut health = NlmBinaryHealth {
+        binary_path: None,
+        source: None,
+        runs: false,
+        version: None,
+        latest_version: None,
+        update_available: false,
+        error: None,
+    };
+
+    match nlm::resolve_nlm_path() {
+        Ok(path) => {
+            health.binary_path = Some(path.to_string_lossy().to_string());
+            let source = binary_source(&path);
+            health.source = Some(source.to_string());
+            match probe(&path).await {
+                Ok(reported) => {
+                    health.runs = true;
+                    health.version = if source == "installed" {
+                        installed_version().or(reported)
+                    } else {
+                        reported
+                    };
+                }
+                Err(e) => health.error = Some(format!("{:#}", e)),
+            }
+        }
+        Err(e) => health.error = Some(e.to_string()),
+    }
+
+    if check_latest {
+        match latest_release(&reqwest::Client::new()).await {
+            Ok(release) => {
+                let latest = normalize_version(&release.tag_name).to_string();
+                health.update_available = match &health.version {
+                    Some(current) => normalize_version(current) != latest,
+                    None => !health.runs,
+                };
+                health.latest_version = Some(latest);
+            }
+            Err(e) => {
+                if health.error.is_none() {
+                    health.error = Some(format!("Could not check for updates: {:#}", e));
+                }
+            }
+        }
+    }
+
+    health
+}
+
+async fn latest_release(client: &reqwest::Client) -> Result<Release> {
+    let body = client
+        .get(LATEST_RELEASE_URL)
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .context("Failed to reach GitHub")?
+        .error_for_status()
+        .context("GitHub refused the release lookup")?
+        .text()
+        .await?;
+    serde_json::from_str(&body).context("Unexpected release response from GitHub")
+}
+
+fn os_names() -> &'static [&'static str] {
+    if cfg!(target_os = "macos") {
+        &["darwin", "macos"]
+    } else {
+        &["linux"]
+    }
+}
+
+fn arch_names() -> &'static [&'static str] {
+    if cfg!(target_arch = "aarch64") {
+        &["arm64", "aarch64"]
+    } else {
+        &["amd64", "x86_64"]
+    }
+}
+
+/// Pick the archive built for this OS and architecture out of a release's assets.
+fn pick_asset<'a>(assets: &'a [ReleaseAsset], os: &[&str], arch: &[&str]) -> Option<&'a ReleaseAsset> {
+    assets.iter().find(|asset| {
+        let name = asset.name.to_lowercase();
+        (name.ends_with(".tar.gz") || name.ends_with(".tgz") || name.ends_with(".zip"))
+            && os.iter().any(|o| name.contains(o))
+            && arch.iter().any(|a| name.contains(a))
+    })
+}
+
+/// Find an asset's SHA-256 in a `sha256sum`-style checksum list.
+fn expected_checksum(checksums: &str, asset_name: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        (name == asset_name && hash.len() == 64).then(|| hash.to_lowercase())
+    })
+}
+
+/// Pull the `nlm` executable out of a release archive into `dest`.
+fn extract_binary(archive: &Path, archive_name: &str, dest: &Path) -> Result<()> {
+    let mut contents = Vec::new();
+    if archive_name.to_lowercase().ends_with(".zip") {
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(archive)?)
+            .context("Release archive is not a valid zip")?;
+        let index = (0..zip.len())
+            .find(|&i| zip.by_index(i).map(|f| is_nlm_entry(f.name())).unwrap_or(false))
+            .ok_or_else(|| anyhow!("Release archive does not contain nlm"))?;
+        zip.by_index(index)?.read_to_end(&mut contents)?;
+    } else {
+        let file = std::fs::File::open(archive)?;
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        let mut found = false;
+        for entry in tar.entries().context("Release archive is not a valid tar.gz")? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().to_string();
+            if is_nlm_entry(&path) {
+                entry.read_to_end(&mut contents)?;
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            return Err(anyhow!("Release archive does not contain nlm"));
+        }
+    }
+    std::fs::write(dest, contents).with_context(|| format!("Failed to write {:?}", dest))?;
+    Ok(())
+}
+
+fn is_nlm_entry(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name == "nlm" || name == "nlm.exe"
+}
+
+/// Download the latest nlm release for this machine into `~/.ciderpress/bin`,
+/// replacing any earlier self-installed copy. Refuses archives that aren't listed
+/// in the release's checksums or don't match them.
+pub async fn install_latest<F>(on_progress: F) -> Result<String>
+where
+    F: Fn(f32),
+{
+    let client = reqwest::Client::new();
+    let release = latest_release(&client).await?;
+    let asset = pick_asset(&release.assets, os_names(), arch_names()).ok_or_else(|| {
+        anyhow!(
+            "Release {} has no nlm build for {} {}",
+            release.tag_name,
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    })?;
+    let checksums_asset = release
+        .assets
+        .iter()
+        .find(|a| a.name.to_lowercase().contains("checksums"))
+        .ok_or_else(|| anyhow!("Release {} publishes no checksums; not installing", release.tag_name))?;
+    let checksums = client
+        .get(&checksums_asset.browser_download_url)
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let expected = expected_checksum(&checksums, &asset.name)
+        .ok_or_else(|| anyhow!("No checksum listed for {}", asset.name))?;
+
+    let dir = install_dir()?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let archive_path = dir.join(&asset.name);
+
+    info!("Downloading nlm {} from {}", release.tag_name, asset.browser_download_url);
+    let response = client
+        .get(&asset.browser_download_url)
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .send()
+        .await
+        .with_context(|| format!("Failed to GET {}", asset.browser_download_url))?
+        .error_for_status()?;
+    let total = response.content_length().unwrap_or(0);
+
+    use futures_util::StreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    let mut file = tokio::fs::File::create(&archive_path)
+        .await
+        .with_context(|| format!("Failed to create {:?}", archive_path))?;
+    let mut downloaded: u64 = 0;
+    let mut stream = Box::pin(response.bytes_stream());
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.context("Error while downloading nlm")?;
+        file.write_all(&chunk).await?;
+        downloaded += chunk.len() as u64;
+        if total > 0 {
+            on_progress((downloaded as f32 / total as f32 * 95.0).min(95.0));
+        }
+    }
+    file.flush().await?;
+    drop(file);
+
+    let actual = sha256_file(&archive_path)?;
+    if actual != expected {
+        let _ = std::fs::remove_file(&archive_path);
+        return Err(anyhow!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            asset.name,
+            expected,
+            actual
+        ));
+    }
+
+    // Extract next to the old binary and swap it in, so a failure leaves the
+    // previous install working.
+    let staging = dir.join("nlm.download");
+    let target = installed_binary_path()?;
+    let archive_name = asset.name.clone();
+    let (archive_clone, staging_clone) = (archive_path.clone(), staging.clone());
+    tokio::task::spawn_blocking(move || extract_binary(&archive_clone, &archive_name, &staging_clone))
+        .await
+        .context("Extraction task panicked")??;
+    let _ = std::fs::remove_file(&archive_path);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staging, std::fs::Permissions::from_mode(0o755))?;
+    }
+    std::fs::rename(&staging, &target).with_context(|| format!("Failed to install {:?}", target))?;
+
+    let version = normalize_version(&release.tag_name).to_string();
+    std::fs::write(dir.join("nlm.version"), &version)?;
+    on_progress(100.0);
+    info!("Installed nlm {} at {:?}", version, target);
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> ReleaseAsset {
+        ReleaseAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+        }
+    }
+
+    #[test]
+    fn picks_archive_for_platform() {
+        let assets = vec![
+            asset("checksums.txt"),
+            asset("nlm_0.3.0_Darwin_arm64.tar.gz"),
+            asset("nlm_0.3.0_Darwin_x86_64.tar.gz"),
+            asset("nlm_0.3.0_Linux_x86_64.tar.gz"),
+        ];
+        let mac = pick_asset(&assets, &["darwin", "macos"], &["arm64", "aarch64"]).unwrap();
+        assert_eq!(mac.name, "nlm_0.3.0_Darwin_arm64.tar.gz");
+        let linux = pick_asset(&assets, &["linux"], &["amd64", "x86_64"]).unwrap();
+        assert_eq!(linux.name, "nlm_0.3.0_Linux_x86_64.tar.gz");
+        assert!(pick_asset(&assets, &["linux"], &["arm64", "aarch64"]).is_none());
+    }
+
+    #[test]
+    fn finds_checksum_for_asset() {
+        let hash = "a".repeat(64);
+        let list = format!("{}  nlm_Linux_x86_64.tar.gz\n{} *nlm_Darwin_arm64.tar.gz\n", "b".repeat(64), hash);
+        assert_eq!(expected_checksum(&list, "nlm_Darwin_arm64.tar.gz"), Some(hash));
+        assert_eq!(expected_checksum(&list, "nlm_Linux_arm64.tar.gz"), None);
+        assert_eq!(expected_checksum("short  nlm_Darwin_arm64.tar.gz", "nlm_Darwin_arm64.tar.gz"), None);
+    }
+
+    #[test]
+    fn parses_reported_version() {
+        assert_eq!(parse_version("nlm version v0.4.1 (abc123)"), Some("0.4.1".to_string()));
+        assert_eq!(parse_version("Usage: nlm <command>"), None);
+        assert_eq!(normalize_version("v1.2.0"), "1.2.0");
+    }
+}
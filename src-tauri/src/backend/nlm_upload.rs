@@ -0,0 +1,651 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Sending slices to NotebookLM in bulk: a batch is stored item by item before anything is
+//! uploaded, so one that fails part-way (or is interrupted) picks up where it stopped.
+
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use super::config::Config;
+use super::database::Database;
+use super::db_pool;
+use super::export::sanitize_title;
+use super::migrate::sha256_file;
+use super::models::{NlmBatchItem, NlmBatchProgress, NlmBatchReport, NlmSource, NlmSourceKind, NlmSourcePart, Slice};
+use super::nlm;
+use super::pii;
+
+/// Longest transcript sent as a single source; longer ones go up in parts that share
+/// `PART_OVERLAP_CHARS` with their neighbours, so no passage is cut off from its context.
+const MAX_SOURCE_CHARS: usize = 200_000;
+const PART_OVERLAP_CHARS: usize = 2_000;
+
+/// Wait before retrying a failed queued upload, doubling with each failure up to the cap.
+const QUEUE_RETRY_BASE_SECS: i64 = 60;
+const QUEUE_RETRY_MAX_SECS: i64 = 6 * 60 * 60;
+
+/// Failed attempts after which a queued upload is given up on and marked failed.
+const QUEUE_MAX_ATTEMPTS: u32 = 8;
+
+/// Longest the queue worker sleeps between checks while uploads wait on a retry, so ones
+/// queued in the meantime aren't held up.
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Set while a thread is working through queued uploads, so only one does.
+static QUEUE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+lazy_static::lazy_static! {
+    /// Batches `run_batch` is working through right now, so none is uploaded twice at once.
+    static ref RUNNING_BATCHES: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// A batch claimed for one `run_batch`; the claim is released when this is dropped.
+struct BatchClaim(String);
+
+impl BatchClaim {
+    fn take(batch_id: &str) -> Result<Self> {
+        let mut running = RUNNING_BATCHES.lock().map_err(|e| anyhow!("Failed to lock running batches: {}", e))?;
+        if !running.insert(batch_id.to_string()) {
+            return Err(anyhow!("NotebookLM batch {} is already running", batch_id));
+        }
+        Ok(BatchClaim(batch_id.to_string()))
+    }
+}
+
+fn is_running(batch_id: &str) -> bool {
+    RUNNING_BATCHES.lock().is_ok_and(|running| running.contains(batch_id))
+}
+
+impl Drop for BatchClaim {
+    fn drop(&mut self) {
+        if let Ok(mut running) = RUNNING_BATCHES.lock() {
+            running.remove(&self.0);
+        }
+    }
+}
+
+/// Title a slice's transcript gets as a notebook source.
+pub fn source_title(slice: &Slice) -> String {
+    let name = slice.title.as_deref()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(&slice.original_audio_file_name);
+    format!("{}.txt", sanitize_title(name))
+}
+
+/// Character ranges to split a transcript into: at most `max_chars` each,
+/// ending at whitespace where possible, each starting `overlap` chars before the last ended.
+pub fn split_ranges(text: &[char], max_chars: usize, overlap: usize) -> Vec<(usize, usize)> {
+    let len = text.len();
+    if len <= max_chars {
+        return vec![(0, len)];
+    }
+    let overlap = overlap.min(max_chars / 4);
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    loop {
+        let mut end = (start + max_chars).min(len);
+        if end < len {
+            // Break after the last space in the second half of the part rather than mid-word
+            if let Some(space) = (start + max_chars / 2..end).rev().find(|&i| text[i].is_whitespace()) {
+                end = space + 1;
+            }
+        }
+        ranges.push((start, end));
+        if end == len {
+            return ranges;
+        }
+        // Back up by the overlap, then forward to the start of a word
+        let mut next = end - overlap;
+        while next < end && !text[next - 1].is_whitespace() {
+            next += 1;
+        }
+        start = if next == end { end - overlap } else { next };
+    }
+}
+
+/// Title of part `part` (1-based) of `count` for a source titled `title`.
+fn part_title(title: &str, part: usize, count: usize) -> String {
+    let (stem, ext) = title.rsplit_once('.').unwrap_or((title, "txt"));
+    format!("{} (part {} of {}).{}", stem, part, count, ext)
+}
+
+/// What `upload_slice` did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UploadOutcome {
+    /// Sent; holds nlm's output.
+    Uploaded(String),
+    /// Not sent, because the notebook already holds the same content; holds the ID(s) of
+    /// the source(s) it is in.
+    AlreadyPresent(String),
+}
+
+/// SHA-256 of a transcript (or part of one) as uploaded.
+pub fn text_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// The sources NotebookLM lists for a notebook, fetched at most once per upload and only
+/// when a local record says the content is already there.
+struct RemoteSources<'a> {
+    notebook_id: &'a str,
+    ids: Option<Option<HashSet<String>>>,
+}
+
+impl<'a> RemoteSources<'a> {
+    fn new(notebook_id: &'a str) -> Self {
+        RemoteSources { notebook_id, ids: None }
+    }
+
+    /// Whether NotebookLM still has `source_id`; if the notebook can't be listed, trust
+    /// the local record.
+    fn contains(&mut self, source_id: &str) -> bool {
+        let notebook_id = self.notebook_id;
+        let ids = self.ids.get_or_insert_with(|| match nlm::list_sources(notebook_id) {
+            Ok(sources) => Some(sources.into_iter().map(|s| s.id).collect()),
+            Err(e) => {
+                warn!("Couldn't list sources of notebook {} to check for duplicates: {:#}", notebook_id, e);
+                None
+            }
+        });
+        match ids {
+            Some(ids) => ids.contains(source_id),
+            None => true,
+        }
+    }
+}
+
+/// The source of `notebook_id` that already holds content hashed `hash`, if there is one.
+/// A recorded source since deleted in NotebookLM is forgotten and doesn't count.
+fn existing_source(db: &Database, remote: &mut RemoteSources, kind: NlmSourceKind, hash: &str, title: &str, slice_id: Option<i64>) -> Result<Option<String>> {
+    let Some(source) = db.find_nlm_source(remote.notebook_id, kind, hash, title, slice_id)? else {
+        return Ok(None);
+    };
+    if remote.contains(&source.source_id) {
+        return Ok(Some(source.source_id));
+    }
+    info!("Source {} is gone from notebook {}, uploading again", source.source_id, remote.notebook_id);
+    db.remove_nlm_source(remote.notebook_id, &source.source_id)?;
+    Ok(None)
+}
+
+/// Upload one slice as a source of `notebook_id`, noting the new source so it can be
+/// removed again later. Transcripts over `MAX_SOURCE_CHARS` go up as numbered parts, each
+/// its own source. Content the notebook already holds (as recorded locally) isn't sent again.
+pub fn upload_slice(config: &Config, db: &Database, notebook_id: &str, slice: &Slice, kind: NlmSourceKind) -> Result<UploadOutcome> {
+    let slice = &pii::for_export(slice.clone());
+    let mut remote = RemoteSources::new(notebook_id);
+    match kind {
+        NlmSourceKind::Text => {
+            let text = slice.transcription.as_deref()
+                .filter(|t| !t.trim().is_empty())
+                .ok_or_else(|| anyhow!("Slice {} has no transcription", slice.id.unwrap_or_default()))?;
+            let title = source_title(slice);
+            let chars: Vec<char> = text.chars().collect();
+            let ranges = split_ranges(&chars, MAX_SOURCE_CHARS, PART_OVERLAP_CHARS);
+            if ranges.len() == 1 {
+                let hash = text_hash(text);
+                if let Some(source_id) = existing_source(db, &mut remote, kind, &hash, &title, slice.id)? {
+                    return Ok(UploadOutcome::AlreadyPresent(source_id));
+                }
+                let output = nlm::add_text_to_notebook(notebook_id, text, Some(&title))?;
+                record_upload(db, notebook_id, slice.id, kind, &title, &hash, &output)?;
+                return Ok(UploadOutcome::Uploaded(output));
+            }
+
+            // Parts are checked one by one, so an upload that stopped part-way only sends the rest
+            info!("Uploading slice {} in {} parts", slice.id.unwrap_or_default(), ranges.len());
+            let mut outputs = Vec::new();
+            let mut present = Vec::new();
+            for (i, &(start, end)) in ranges.iter().enumerate() {
+                let part_title = part_title(&title, i + 1, ranges.len());
+                let part_text: String = chars[start..end].iter().collect();
+                let hash = text_hash(&part_text);
+                if let Some(source_id) = existing_source(db, &mut remote, kind, &hash, &part_title, slice.id)? {
+                    present.push(source_id);
+                    continue;
+                }
+                let output = nlm::add_text_to_notebook(notebook_id, &part_text, Some(&part_title))?;
+                let source_id = record_upload(db, notebook_id, slice.id, kind, &part_title, &hash, &output)?;
+                if let (Some(source_id), Some(slice_id)) = (source_id, slice.id) {
+                    db.record_nlm_source_part(&NlmSourcePart {
+                        notebook_id: notebook_id.to_string(),
+                        source_id,
+                        slice_id,
+                        part: i as u32 + 1,
+                        part_count: ranges.len() as u32,
+                        start_char: start,
+                        end_char: end,
+                    })?;
+                }
+                outputs.push(output);
+            }
+            if outputs.is_empty() {
+                return Ok(UploadOutcome::AlreadyPresent(present.join(", ")));
+            }
+            Ok(UploadOutcome::Uploaded(outputs.join("\n")))
+        }
+        NlmSourceKind::Audio => {
+            let audio_path = config.audio_dir().join(&slice.original_audio_file_name);
+            if !audio_path.exists() {
+                return Err(anyhow!("Audio file not found: {}", audio_path.display()));
+            }
+            let hash = sha256_file(&audio_path)?;
+            if let Some(source_id) = existing_source(db, &mut remote, kind, &hash, &slice.original_audio_file_name, slice.id)? {
+                return Ok(UploadOutcome::AlreadyPresent(source_id));
+            }
+            let output = nlm::add_audio_to_notebook(notebook_id, &audio_path.to_string_lossy())?;
+            record_upload(db, notebook_id, slice.id, kind, &slice.original_audio_file_name, &hash, &output)?;
+            Ok(UploadOutcome::Uploaded(output))
+        }
+    }
+}
+
+/// Upload free text, not tied to a slice, as a source of `notebook_id` titled `title`,
+/// unless the notebook already holds the same text.
+pub fn upload_text(db: &Database, notebook_id: &str, text: &str, title: Option<&str>) -> Result<UploadOutcome> {
+    let text = pii::redact_if_enabled(text);
+    let title = title.unwrap_or("ciderpress-upload.txt");
+    let hash = text_hash(&text);
+    let mut remote = RemoteSources::new(notebook_id);
+    if let Some(source_id) = existing_source(db, &mut remote, NlmSourceKind::Text, &hash, title, None)? {
+        return Ok(UploadOutcome::AlreadyPresent(source_id));
+    }
+    let output = nlm::add_text_to_notebook(notebook_id, &text, Some(title))?;
+    record_upload(db, notebook_id, None, NlmSourceKind::Text, title, &hash, &output)?;
+    Ok(UploadOutcome::Uploaded(output))
+}
+
+/// Note a source just added from slice `slice_id` (if any), in both the live sources and the upload
+/// history. Returns the source's ID when nlm reported one.
+fn record_upload(db: &Database, notebook_id: &str, slice_id: Option<i64>, kind: NlmSourceKind, title: &str, content_hash: &str, output: &str) -> Result<Option<String>> {
+    let source_id = nlm::parse_source_id(output);
+    match &source_id {
+        Some(source_id) => db.record_nlm_source(&NlmSource {
+            notebook_id: notebook_id.to_string(),
+            source_id: source_id.clone(),
+            slice_id,
+            kind,
+            title: Some(title.to_string()),
+            added_at: chrono::Utc::now().timestamp(),
+            content_hash: Some(content_hash.to_string()),
+        })?,
+        None => warn!("No source ID in nlm output for slice {}: {}", slice_id.unwrap_or_default(), output.trim()),
+    }
+    if let Some(slice_id) = slice_id {
+        db.record_nlm_upload(slice_id, notebook_id, kind, source_id.as_deref(), nlm::active_account().as_deref())?;
+    }
+    Ok(source_id)
+}
+
+/// Take a source out of its notebook and forget it locally.
+pub fn remove_source(db: &Database, notebook_id: &str, source_id: &str) -> Result<()> {
+    nlm::remove_source(notebook_id, source_id)?;
+    db.remove_nlm_source(notebook_id, source_id)?;
+    Ok(())
+}
+
+/// Store a new batch uploading each slice's transcript and/or audio to `notebook_id`, and
+/// return its ID. Nothing is uploaded until `run_batch`.
+pub fn create_batch(db: &Database, notebook_id: &str, slice_ids: &[i64], kinds: &[NlmSourceKind]) -> Result<String> {
+    new_batch(db, notebook_id, slice_ids, kinds, false)
+}
+
+fn new_batch(db: &Database, notebook_id: &str, slice_ids: &[i64], kinds: &[NlmSourceKind], queued: bool) -> Result<String> {
+    if slice_ids.is_empty() || kinds.is_empty() {
+        return Err(anyhow!("Nothing to upload"));
+    }
+    let items: Vec<(i64, NlmSourceKind)> = slice_ids.iter()
+        .flat_map(|&id| kinds.iter().map(move |&kind| (id, kind)))
+        .collect();
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    db.create_nlm_batch(&batch_id, notebook_id, &items, queued)?;
+    Ok(batch_id)
+}
+
+/// Seconds to wait before retrying a queued upload that has failed `attempts` times.
+fn queue_retry_delay(attempts: u32) -> i64 {
+    (QUEUE_RETRY_BASE_SECS << attempts.saturating_sub(1).min(16)).min(QUEUE_RETRY_MAX_SECS)
+}
+
+/// Whether a queued item that just failed with `err` should be tried again: only for
+/// failures that may pass (a network blip, a rate limit), and only so many times.
+fn should_retry(item: &NlmBatchItem, err: &anyhow::Error) -> bool {
+    item.queued && nlm::is_transient(err) && item.attempts + 1 < QUEUE_MAX_ATTEMPTS
+}
+
+/// Upload every item of a batch not uploaded yet, one after another, reporting each through
+/// `on_progress`; fails if the batch is already being run. A failed item doesn't stop the
+/// batch; it stays failed until resumed, or for a queued batch is scheduled to be tried
+/// again if the failure looks temporary and it hasn't used up its `QUEUE_MAX_ATTEMPTS`.
+pub fn run_batch(
+    config: &Config,
+    db: &Database,
+    batch_id: &str,
+    on_progress: impl Fn(&NlmBatchProgress),
+) -> Result<NlmBatchReport> {
+    let _claim = BatchClaim::take(batch_id)?;
+    let items = db.get_nlm_batch_items(batch_id)?;
+    if items.is_empty() {
+        return Err(anyhow!("No NotebookLM batch with ID {}", batch_id));
+    }
+
+    let total = items.len() as u32;
+    let mut report = NlmBatchReport { batch_id: batch_id.to_string(), uploaded: 0, already_present: 0, failed: Vec::new() };
+    for (processed, mut item) in items.into_iter().enumerate() {
+        if item.status != "uploaded" && item.status != "already_present" {
+            let result = db.get_slice(item.slice_id)?
+                .ok_or_else(|| anyhow!("No slice found with ID: {}", item.slice_id))
+                .and_then(|slice| upload_slice(config, db, &item.notebook_id, &slice, item.kind));
+            match result {
+                Ok(UploadOutcome::Uploaded(_)) => {
+                    item.status = "uploaded".to_string();
+                    item.error = None;
+                }
+                Ok(UploadOutcome::AlreadyPresent(source_id)) => {
+                    info!("Slice {} is already in notebook {} as {}, skipped", item.slice_id, item.notebook_id, source_id);
+                    item.status = "already_present".to_string();
+                    item.error = None;
+                }
+                Err(e) if should_retry(&item, &e) => {
+                    item.attempts += 1;
+                    let delay = queue_retry_delay(item.attempts);
+                    warn!("Queued NotebookLM upload of slice {} failed (attempt {}), retrying in {}s: {}", item.slice_id, item.attempts, delay, e);
+                    item.status = "pending".to_string();
+                    item.error = Some(e.to_string());
+                    db.schedule_nlm_batch_retry(batch_id, item.position, &e.to_string(), chrono::Utc::now().timestamp() + delay)?;
+                }
+                Err(e) => {
+                    if item.queued {
+                        item.attempts += 1;
+                    }
+                    warn!("NotebookLM upload of slice {} failed: {}", item.slice_id, e);
+                    item.status = "failed".to_string();
+                    item.error = Some(e.to_string());
+                }
+            }
+            if item.status != "pending" {
+                db.set_nlm_batch_item_status(batch_id, item.position, &item.status, item.error.as_deref())?;
+            }
+        }
+
+        if item.status == "uploaded" {
+            report.uploaded += 1;
+        } else if item.status == "already_present" {
+            report.already_present += 1;
+        } else {
+            report.failed.push(item.clone());
+        }
+        on_progress(&NlmBatchProgress {
+            batch_id: batch_id.to_string(),
+            item,
+            processed: processed as u32 + 1,
+            total,
+        });
+    }
+    Ok(report)
+}
+
+/// Queue an upload of a freshly transcribed slice to `default_notebook` (the config's
+/// auto-upload notebook) and each notebook its labels are mapped to. Returns how many
+/// notebooks it was queued for; `spawn_queue_worker` sends them, retrying failures.
+pub fn queue_transcript_uploads(db: &Database, slice_id: i64, default_notebook: Option<&str>) -> Result<usize> {
+    let mut notebooks = db.get_slice_notebooks(slice_id)?;
+    if let Some(default_notebook) = default_notebook.map(str::trim).filter(|id| !id.is_empty()) {
+        if !notebooks.iter().any(|id| id == default_notebook) {
+            notebooks.insert(0, default_notebook.to_string());
+        }
+    }
+    for notebook_id in &notebooks {
+        new_batch(db, notebook_id, &[slice_id], &[NlmSourceKind::Text], true)?;
+    }
+    Ok(notebooks.len())
+}
+
+/// Upload everything queued, on a background thread. Does nothing if a worker is already
+/// running; it picks up whatever was queued meanwhile.
+pub fn spawn_queue_worker(config: Config) {
+    if QUEUE_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        loop {
+            if let Err(e) = db_pool::connect(&db_path).and_then(|db| drain_queue(&config, &db)) {
+                // Wait a while rather than spin on an error that won't clear by itself
+                error!("NotebookLM upload queue failed, trying again in {:?}: {}", QUEUE_POLL_INTERVAL, e);
+                std::thread::sleep(QUEUE_POLL_INTERVAL);
+            }
+            // Stay around while failed uploads wait on a retry
+            let next_retry = db_pool::connect(&db_path)
+                .and_then(|db| db.next_nlm_retry_at())
+                .unwrap_or(None);
+            if let Some(retry_at) = next_retry {
+                let wait = (retry_at - chrono::Utc::now().timestamp()).max(1) as u64;
+                std::thread::sleep(Duration::from_secs(wait).min(QUEUE_POLL_INTERVAL));
+                continue;
+            }
+            QUEUE_RUNNING.store(false, Ordering::SeqCst);
+            // Something queued just after the last check would otherwise wait for the next one
+            let more = db_pool::connect(&db_path)
+                .and_then(|db| due_batches(&db))
+                .is_ok_and(|batches| !batches.is_empty());
+            if !more || QUEUE_RUNNING.swap(true, Ordering::SeqCst) {
+                break;
+            }
+        }
+    });
+}
+
+/// Queued batches with items due to be tried, leaving out any being run right now.
+fn due_batches(db: &Database) -> Result<Vec<String>> {
+    Ok(db.pending_nlm_batches()?.into_iter().filter(|id| !is_running(id)).collect())
+}
+
+/// Run batches with untried items until there are none left.
+fn drain_queue(config: &Config, db: &Database) -> Result<()> {
+    loop {
+        let batches = due_batches(db)?;
+        if batches.is_empty() {
+            return Ok(());
+        }
+        for batch_id in batches {
+            let report = run_batch(config, db, &batch_id, crate::emit_nlm_batch_progress)?;
+            info!("Queued NotebookLM batch {}: {} uploaded, {} failed", batch_id, report.uploaded, report.failed.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_batch_items_are_stored_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+
+        assert!(create_batch(&db, "nb", &[], &[NlmSourceKind::Text]).is_err());
+        let batch_id = create_batch(&db, "nb", &[7, 3], &[NlmSourceKind::Text, NlmSourceKind::Audio]).unwrap();
+        let items = db.get_nlm_batch_items(&batch_id).unwrap();
+        let order: Vec<_> = items.iter().map(|i| (i.slice_id, i.kind)).collect();
+        assert_eq!(order, vec![
+            (7, NlmSourceKind::Text),
+            (7, NlmSourceKind::Audio),
+            (3, NlmSourceKind::Text),
+            (3, NlmSourceKind::Audio),
+        ]);
+        assert!(items.iter().all(|i| i.status == "pending" && i.notebook_id == "nb"));
+
+        // A batch being run can't be started a second time until the first run is done
+        let claim = BatchClaim::take(&batch_id).unwrap();
+        assert!(run_batch(&Config::default(), &db, &batch_id, |_| {}).is_err());
+        drop(claim);
+        assert!(BatchClaim::take(&batch_id).is_ok());
+
+        db.set_nlm_batch_item_status(&batch_id, 1, "failed", Some("timed out")).unwrap();
+        let failed = &db.get_nlm_batch_items(&batch_id).unwrap()[1];
+        assert_eq!((failed.status.as_str(), failed.error.as_deref()), ("failed", Some("timed out")));
+    }
+
+    #[test]
+    fn test_sources_are_forgotten_once_removed() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        let source = |source_id: &str, added_at| NlmSource {
+            notebook_id: "nb".to_string(),
+            source_id: source_id.to_string(),
+            slice_id: Some(4),
+            kind: NlmSourceKind::Text,
+            title: Some("memo.txt".to_string()),
+            added_at,
+            content_hash: None,
+        };
+        db.record_nlm_source(&source("first", 100)).unwrap();
+        db.record_nlm_source(&source("second", 200)).unwrap();
+        assert_eq!(db.get_slice_nlm_sources(4).unwrap(), vec![source("second", 200), source("first", 100)]);
+
+        assert!(db.remove_nlm_source("nb", "second").unwrap());
+        assert!(!db.remove_nlm_source("nb", "second").unwrap());
+        assert_eq!(db.get_slice_nlm_sources(4).unwrap(), vec![source("first", 100)]);
+    }
+
+    #[test]
+    fn test_sources_are_matched_by_content_hash() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        let source = |source_id: &str, title: &str, content_hash: Option<&str>| NlmSource {
+            notebook_id: "nb".to_string(),
+            source_id: source_id.to_string(),
+            slice_id: Some(4),
+            kind: NlmSourceKind::Text,
+            title: Some(title.to_string()),
+            added_at: 100,
+            content_hash: content_hash.map(str::to_string),
+        };
+        db.record_nlm_source(&source("hashed", "memo.txt", Some(&text_hash("hello")))).unwrap();
+        db.record_nlm_source(&source("legacy", "old.txt", None)).unwrap();
+
+        let found = |hash: &str, title: &str, slice_id: Option<i64>| {
+            db.find_nlm_source("nb", NlmSourceKind::Text, hash, title, slice_id).unwrap().map(|s| s.source_id)
+        };
+        assert_eq!(found(&text_hash("hello"), "renamed.txt", Some(4)).as_deref(), Some("hashed"));
+        assert_eq!(found(&text_hash("edited"), "memo.txt", Some(4)), None, "changed content is uploaded again");
+        assert_eq!(found(&text_hash("anything"), "old.txt", Some(4)).as_deref(), Some("legacy"));
+        assert_eq!(found(&text_hash("anything"), "old.txt", Some(5)), None, "another slice's title isn't its content");
+        assert_eq!(found(&text_hash("anything"), "old.txt", None), None);
+        assert_eq!(db.find_nlm_source("other", NlmSourceKind::Text, &text_hash("hello"), "memo.txt", Some(4)).unwrap(), None);
+        assert_eq!(db.find_nlm_source("nb", NlmSourceKind::Audio, &text_hash("hello"), "memo.txt", Some(4)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_upload_history_outlives_removed_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+
+        let first = db.record_nlm_upload(4, "nb-a", NlmSourceKind::Text, Some("src-1"), Some("work")).unwrap();
+        let second = db.record_nlm_upload(4, "nb-b", NlmSourceKind::Audio, None, None).unwrap();
+        db.record_nlm_upload(5, "nb-a", NlmSourceKind::Text, None, None).unwrap();
+        db.remove_nlm_source("nb-a", "src-1").unwrap();
+
+        let history = db.get_slice_nlm_uploads(4).unwrap();
+        assert_eq!(history.iter().map(|u| u.id).collect::<Vec<_>>(), vec![second, first]);
+        assert_eq!((history[0].notebook_id.as_str(), history[0].source_type), ("nb-b", NlmSourceKind::Audio));
+        assert_eq!((history[1].source_id.as_deref(), history[1].account.as_deref()), (Some("src-1"), Some("work")));
+    }
+
+    #[test]
+    fn test_long_transcripts_split_into_overlapping_parts() {
+        let short: Vec<char> = "just a few words".chars().collect();
+        assert_eq!(split_ranges(&short, 100, 10), vec![(0, short.len())]);
+
+        let text: Vec<char> = "alpha beta gamma delta ".repeat(50).chars().collect();
+        let ranges = split_ranges(&text, 200, 40);
+        assert!(ranges.len() > 1);
+        assert_eq!(ranges.first().unwrap().0, 0);
+        assert_eq!(ranges.last().unwrap().1, text.len());
+        for pair in ranges.windows(2) {
+            let ((start, end), (next_start, _)) = (pair[0], pair[1]);
+            assert!(end - start <= 200);
+            assert!(next_start < end && next_start > start, "parts must overlap and advance");
+            assert!(text[end - 1].is_whitespace() && text[next_start - 1].is_whitespace(), "parts break between words");
+        }
+
+        assert_eq!(part_title("Standup.txt", 2, 3), "Standup (part 2 of 3).txt");
+    }
+
+    #[test]
+    fn test_label_uploads_are_queued_per_notebook() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        let slice_id = db.insert_slice(&create_test_slice("memo.m4a")).unwrap();
+        let work = db.get_or_create_label("Work", "#0000ff").unwrap();
+        let ideas = db.get_or_create_label("Ideas", "#ff0000").unwrap();
+        db.assign_label(work, &[slice_id]).unwrap();
+        db.assign_label(ideas, &[slice_id]).unwrap();
+
+        assert_eq!(queue_transcript_uploads(&db, slice_id, None).unwrap(), 0);
+        assert!(db.set_label_notebook(999, Some("nb-x")).is_err());
+        db.set_label_notebook(work, Some("nb-work")).unwrap();
+        db.set_label_notebook(ideas, Some("nb-work")).unwrap();
+        assert_eq!(queue_transcript_uploads(&db, slice_id, None).unwrap(), 1);
+        let batches = db.pending_nlm_batches().unwrap();
+        assert_eq!(batches.len(), 1);
+        let claim = BatchClaim::take(&batches[0]).unwrap();
+        assert!(due_batches(&db).unwrap().is_empty(), "the queue leaves batches being run alone");
+        drop(claim);
+        assert_eq!(due_batches(&db).unwrap(), batches);
+
+        // Untranscribed, so the upload fails; retrying wouldn't help, so it isn't put back
+        let report = run_batch(&Config::default(), &db, &batches[0], |_| {}).unwrap();
+        assert_eq!((report.failed[0].status.as_str(), report.failed[0].attempts), ("failed", 1));
+        assert!(db.pending_nlm_batches().unwrap().is_empty());
+        assert!(db.next_nlm_retry_at().unwrap().is_none());
+        assert_eq!(queue_retry_delay(1), QUEUE_RETRY_BASE_SECS);
+        assert_eq!(queue_retry_delay(40), QUEUE_RETRY_MAX_SECS);
+
+        // Only temporary failures are retried, and only until the attempts run out
+        let mut item = report.failed[0].clone();
+        let timed_out = anyhow::Error::from(nlm::NlmError::TimedOut { command: "source".to_string(), seconds: 30 });
+        item.attempts = 0;
+        assert!(should_retry(&item, &timed_out));
+        assert!(!should_retry(&item, &anyhow!("Slice has no transcript")));
+        item.attempts = QUEUE_MAX_ATTEMPTS - 1;
+        assert!(!should_retry(&item, &timed_out));
+        item.attempts = 0;
+        item.queued = false;
+        assert!(!should_retry(&item, &timed_out));
+
+        // Batches the user started aren't picked up by the queue worker
+        create_batch(&db, "nb-user", &[slice_id], &[NlmSourceKind::Text]).unwrap();
+        assert!(db.pending_nlm_batches().unwrap().is_empty());
+
+        // The auto-upload notebook is added unless a label already sends the slice there
+        assert_eq!(queue_transcript_uploads(&db, slice_id, Some("nb-work")).unwrap(), 1);
+        assert_eq!(queue_transcript_uploads(&db, slice_id, Some("nb-all")).unwrap(), 2);
+
+        db.set_label_notebook(ideas, None).unwrap();
+        let mappings = db.list_label_notebooks().unwrap();
+        assert_eq!(mappings.iter().map(|m| m.label_name.as_str()).collect::<Vec<_>>(), vec!["Work"]);
+    }
+}
@@ -0,0 +1,248 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Client for a local Ollama server, the LLM behind summaries, generated titles, sentiment,
+//! translations and transcript questions, and the loop batch LLM tasks share. Everything
+//! stays on the machine: requests go to `config.ollama_url` with the model in
+//! `config.ollama_model`, and embeddings come from `config.ollama_embedding_model`.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::db_pool::{self, PooledDatabase};
+use super::models::{FolderImportProgress, LlmBatchReport, LlmSliceFailure, Slice};
+
+/// Local models can take minutes over a long transcript.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a str>,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct GenerateResponse {
+    #[serde(default)]
+    response: String,
+}
+
+#[derive(Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+pub struct Ollama {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    embedding_model: String,
+}
+
+impl Ollama {
+    pub fn new(config: &Config) -> Result<Self> {
+        let model = config.ollama_model.trim();
+        if model.is_empty() {
+            return Err(anyhow!("No Ollama model is configured"));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Ollama {
+            client,
+            base_url: config.ollama_url.trim().trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            embedding_model: config.ollama_embedding_model.trim().to_string(),
+        })
+    }
+
+    /// This client with `model` in place of the configured one, unless it is blank.
+    pub fn with_model(mut self, model: Option<&str>) -> Self {
+        if let Some(model) = model.map(str::trim).filter(|m| !m.is_empty()) {
+            self.model = model.to_string();
+        }
+        self
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn embedding_model(&self) -> &str {
+        &self.embedding_model
+    }
+
+    /// POST `body` to an API endpoint and return the response text, turning Ollama's
+    /// `{"error": ...}` replies and a server that isn't running into readable errors.
+    async fn post(&self, endpoint: &str, body: String) -> Result<String> {
+        let url = format!("{}/api/{}", self.base_url, endpoint);
+        let response = self
+            .client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    anyhow!("Ollama isn't running at {}", self.base_url)
+                } else {
+                    anyhow!("Request to Ollama failed: {}", e)
+                }
+            })?;
+        let status = response.status();
+        let text = response.text().await.context("Failed to read Ollama's response")?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<ErrorResponse>(&text)
+                .map(|e| e.error)
+                .unwrap_or(text);
+            return Err(anyhow!("Ollama returned {}: {}", status, message.trim()));
+        }
+        Ok(text)
+    }
+
+    /// Run `prompt` through the model, with `system` as its instructions, and return
+    /// the reply.
+    pub async fn generate(&self, system: Option<&str>, prompt: &str) -> Result<String> {
+        self.request(system, prompt, None).await
+    }
+
+    /// `generate`, with the model held to replying in JSON.
+    pub async fn generate_json(&self, system: Option<&str>, prompt: &str) -> Result<String> {
+        self.request(system, prompt, Some("json")).await
+    }
+
+    /// Embed each of `inputs` with the embedding model, in the same order.
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        if self.embedding_model.is_empty() {
+            return Err(anyhow!("No Ollama embedding model is configured"));
+        }
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = serde_json::to_string(&EmbedRequest { model: &self.embedding_model, input: inputs })?;
+        let text = self.post("embed", body).await?;
+        let reply: EmbedResponse = serde_json::from_str(&text).context("Unexpected response from Ollama")?;
+        if reply.embeddings.len() != inputs.len() {
+            return Err(anyhow!(
+                "{} returned {} embeddings for {} inputs",
+                self.embedding_model,
+                reply.embeddings.len(),
+                inputs.len()
+            ));
+        }
+        Ok(reply.embeddings)
+    }
+
+    async fn request(&self, system: Option<&str>, prompt: &str, format: Option<&str>) -> Result<String> {
+        let body = serde_json::to_string(&GenerateRequest {
+            model: &self.model,
+            prompt,
+            system,
+            format,
+            stream: false,
+        })?;
+        let text = self.post("generate", body).await?;
+        let reply: GenerateResponse =
+            serde_json::from_str(&text).context("Unexpected response from Ollama")?;
+        Ok(reply.response.trim().to_string())
+    }
+}
+
+/// A connection to the library database, for the short steps between model calls.
+pub fn open_db(config: &Config) -> Result<PooledDatabase> {
+    db_pool::connect(config.ciderpress_home_path().join("CiderPress-db.sqlite"))
+}
+
+/// A slice's transcript, if it has one worth sending to the model.
+pub fn transcript(slice: &Slice) -> Option<&str> {
+    slice.transcription.as_deref().filter(|t| !t.trim().is_empty())
+}
+
+/// Run `task` over each slice in turn, reporting progress through `on_progress`. Slices
+/// `skip` picks out are counted as skipped; one whose task fails is noted and the rest
+/// carry on. `task_name` is for the log.
+pub async fn run_over_slices<F, Fut>(
+    config: &Config,
+    task_name: &str,
+    slice_ids: &[i64],
+    skip: impl Fn(&Slice) -> bool,
+    mut task: F,
+    mut on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<LlmBatchReport>
+where
+    F: FnMut(Slice) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut report = LlmBatchReport::default();
+    let mut progress = FolderImportProgress {
+        total_files: slice_ids.len() as u32,
+        processed_files: 0,
+        current_file: None,
+    };
+    on_progress(&progress);
+
+    for &slice_id in slice_ids {
+        // The connection isn't held across the (slow) model calls
+        let slice = open_db(config)?.get_slice(slice_id)?;
+        let result = match slice {
+            None => Err(anyhow!("No slice found with ID: {}", slice_id)),
+            Some(slice) if skip(&slice) => {
+                report.skipped += 1;
+                progress.processed_files += 1;
+                on_progress(&progress);
+                continue;
+            }
+            Some(slice) => {
+                progress.current_file = Some(slice.title.clone().unwrap_or_else(|| slice.original_audio_file_name.clone()));
+                on_progress(&progress);
+                task(slice).await
+            }
+        };
+        match result {
+            Ok(()) => report.processed += 1,
+            Err(e) => {
+                warn!("{}: slice {} failed: {}", task_name, slice_id, e);
+                report.failed.push(LlmSliceFailure { slice_id, message: e.to_string() });
+            }
+        }
+        progress.processed_files += 1;
+        on_progress(&progress);
+    }
+    info!("{}: {} done, {} skipped, {} failed", task_name, report.processed, report.skipped, report.failed.len());
+    Ok(report)
+}
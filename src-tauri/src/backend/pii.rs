@@ -0,0 +1,295 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Phone numbers, email addresses, street addresses and card numbers in transcripts: a scan
+//! that flags them, and a redaction mode (`config.pii_redaction_enabled`) that masks them.
+//! Turning the mode on masks the text already stored, after saving a copy of the database;
+//! while it is on, slice text is masked as it is stored and exports as they are written.
+
+use anyhow::Result;
+use regex::Regex;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::info;
+
+use super::backup;
+use super::config::Config;
+use super::database::Database;
+use super::models::{PiiKind, PiiMatch, PiiRedactionReport, PiiScanReport, Slice, SlicePiiFindings};
+
+lazy_static::lazy_static! {
+    static ref EMAIL: Regex = Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b").unwrap();
+    static ref CARD: Regex = Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap();
+    // With a country code, "+44 20 7946 0958"; without, an optional area code, three digits
+    // and four, "(555) 123-4567" or "555.123.4567". Runs of four-digit numbers (years,
+    // amounts) don't fit either.
+    static ref PHONE: Regex = Regex::new(
+        r"\+\d{1,3}[\s.-]?(?:\(\d{1,4}\)[\s.-]?|\d{1,4}[\s.-])\d{2,4}(?:[\s.-]\d{2,4}){1,2}\b|(?:\(\d{3}\)\s?|\b\d{3}[\s.-])?\b\d{3}[\s.-]\d{4}\b"
+    ).unwrap();
+    // A house number, one to four capitalized words and a street type, e.g. "42 Elm Street, Apt 3"
+    static ref ADDRESS: Regex = Regex::new(
+        r"\b\d{1,6}\s+(?:[A-Z][\w'.-]*\s+){1,4}(?i:street|st|avenue|ave|road|rd|boulevard|blvd|lane|ln|drive|dr|court|ct|way|place|pl|terrace|circle|cir|highway|hwy|parkway|pkwy)\b\.?(?:,?\s+(?i:apt|apartment|suite|unit)\.?\s*\w+)?"
+    ).unwrap();
+}
+
+static REDACTION_ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn apply_config(config: &Config) {
+    REDACTION_ENABLED.store(config.pii_redaction_enabled, Ordering::Relaxed);
+}
+
+pub fn redaction_enabled() -> bool {
+    REDACTION_ENABLED.load(Ordering::Relaxed)
+}
+
+impl PiiKind {
+    /// What a match is replaced with when redacted.
+    pub fn mask(self) -> &'static str {
+        match self {
+            PiiKind::Phone => "[redacted phone]",
+            PiiKind::Email => "[redacted email]",
+            PiiKind::Address => "[redacted address]",
+            PiiKind::CardNumber => "[redacted card]",
+        }
+    }
+}
+
+/// Luhn checksum, which every real card number passes.
+fn luhn_valid(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| if i % 2 == 1 { if d * 2 > 9 { d * 2 - 9 } else { d * 2 } } else { d })
+        .sum();
+    sum % 10 == 0
+}
+
+fn digits(text: &str) -> Vec<u32> {
+    text.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// Byte ranges of the personal information in `text`, in order. Where candidates overlap,
+/// emails win over card numbers, card numbers over phone numbers and those over addresses.
+fn detect(text: &str) -> Vec<(PiiKind, Range<usize>)> {
+    let mut found: Vec<(PiiKind, Range<usize>)> = Vec::new();
+    let mut add = |kind: PiiKind, range: Range<usize>| {
+        if !found.iter().any(|(_, r)| r.start < range.end && range.start < r.end) {
+            found.push((kind, range));
+        }
+    };
+
+    for m in EMAIL.find_iter(text) {
+        add(PiiKind::Email, m.range());
+    }
+    for m in CARD.find_iter(text) {
+        let digits = digits(m.as_str());
+        if (13..=19).contains(&digits.len()) && luhn_valid(&digits) {
+            add(PiiKind::CardNumber, m.range());
+        }
+    }
+    for m in PHONE.find_iter(text) {
+        // Not part of a longer number or word, like "1234-555-1234" or "555-1234-5678"
+        let mut before = text[..m.start()].chars().rev();
+        let mut after = text[m.end()..].chars();
+        let joined = |sep: Option<char>, next: Option<char>| {
+            matches!(sep, Some('-' | '.')) && next.is_some_and(|c| c.is_ascii_digit())
+        };
+        let first = before.next();
+        if first.is_some_and(|c| c.is_alphanumeric()) || joined(first, before.next()) || joined(after.next(), after.next()) {
+            continue;
+        }
+        let count = digits(m.as_str()).len();
+        let local = count == 7 && m.as_str().contains(['-', '.']);
+        if (10..=15).contains(&count) || local {
+            add(PiiKind::Phone, m.range());
+        }
+    }
+    for m in ADDRESS.find_iter(text) {
+        add(PiiKind::Address, m.range());
+    }
+
+    found.sort_by_key(|(_, range)| range.start);
+    found
+}
+
+/// The personal information in `text`, in order, with character offsets.
+pub fn find_pii(text: &str) -> Vec<PiiMatch> {
+    detect(text)
+        .into_iter()
+        .map(|(kind, range)| PiiMatch {
+            kind,
+            start: text[..range.start].chars().count(),
+            end: text[..range.end].chars().count(),
+            text: text[range].to_string(),
+        })
+        .collect()
+}
+
+/// `text` with its personal information masked.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let found = detect(text);
+    if found.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    let mut redacted = String::with_capacity(text.len());
+    let mut last = 0;
+    for (kind, range) in found {
+        redacted.push_str(&text[last..range.start]);
+        redacted.push_str(kind.mask());
+        last = range.end;
+    }
+    redacted.push_str(&text[last..]);
+    Cow::Owned(redacted)
+}
+
+/// `text` masked if redaction mode is on, for text about to be stored.
+pub fn redact_if_enabled(text: &str) -> Cow<'_, str> {
+    if redaction_enabled() {
+        redact(text)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// A slice about to be exported, with its transcript, title, notes and summary masked if
+/// redaction mode is on.
+pub fn for_export(mut slice: Slice) -> Slice {
+    if redaction_enabled() {
+        for field in [&mut slice.transcription, &mut slice.title, &mut slice.notes, &mut slice.summary] {
+            let redacted = match field.as_deref().map(redact) {
+                Some(Cow::Owned(redacted)) => redacted,
+                _ => continue,
+            };
+            *field = Some(redacted);
+        }
+    }
+    slice
+}
+
+/// Flag the personal information in transcripts: of `slice_ids`, or with `None` every slice
+/// outside the trash.
+pub fn scan(db: &Database, slice_ids: Option<&[i64]>) -> Result<PiiScanReport> {
+    let slices = match slice_ids {
+        Some(ids) => ids.iter().filter_map(|&id| db.get_slice(id).transpose()).collect::<Result<Vec<_>>>()?,
+        None => db.list_active_slices()?,
+    };
+    let mut report = PiiScanReport::default();
+    for slice in slices {
+        let (Some(slice_id), Some(text)) = (slice.id, slice.transcription.as_deref()) else { continue };
+        report.slices_scanned += 1;
+        let matches = find_pii(text);
+        if !matches.is_empty() {
+            report.slices.push(SlicePiiFindings {
+                slice_id,
+                name: slice.title.clone().filter(|t| !t.is_empty()).unwrap_or_else(|| slice.original_audio_file_name.clone()),
+                matches,
+            });
+        }
+    }
+    info!("PII scan: {} of {} transcripts flagged", report.slices.len(), report.slices_scanned);
+    Ok(report)
+}
+
+/// Mask personal information everywhere a slice's text is stored: of `slice_ids`, or with
+/// `None` every slice, trashed ones included; and in the nlm run log and search history.
+/// The originals can't be recovered from the library afterwards, so a copy of the database
+/// is saved to the backups folder first.
+pub fn redact_stored(config: &Config, db: &Database, slice_ids: Option<&[i64]>) -> Result<PiiRedactionReport> {
+    let backup = backup::save_copy(config, db, "redaction")?;
+    let report = redact_slices(db, slice_ids)?;
+    Ok(PiiRedactionReport { backup_path: Some(backup.to_string_lossy().to_string()), ..report })
+}
+
+/// `redact_stored` for a copy of the library about to leave it (a portable archive's
+/// database), which needs no backup.
+pub fn redact_copy(db: &Database) -> Result<PiiRedactionReport> {
+    redact_slices(db, None)
+}
+
+fn redact_slices(db: &Database, slice_ids: Option<&[i64]>) -> Result<PiiRedactionReport> {
+    let slice_ids = match slice_ids {
+        Some(ids) => ids.to_vec(),
+        None => db.list_all_slices()?.into_iter().filter_map(|s| s.id).collect(),
+    };
+    let mut report = PiiRedactionReport::default();
+    let mask = |text: &str| redact(text).into_owned();
+    for slice_id in slice_ids {
+        let changed = db.scrub_slice_text(slice_id, &mask)?;
+        if changed > 0 {
+            report.slices_redacted += 1;
+            report.values_changed += changed;
+        }
+    }
+    report.values_changed += db.scrub_logged_text(&mask)?;
+    info!("PII redaction: {} values masked in {} slices", report.values_changed, report.slices_redacted);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<(PiiKind, String)> {
+        find_pii(text).into_iter().map(|m| (m.kind, m.text)).collect()
+    }
+
+    #[test]
+    fn test_find_pii() {
+        assert_eq!(
+            kinds("Mail jo.smith+memo@example.co.uk or call (555) 123-4567 tonight."),
+            vec![(PiiKind::Email, "jo.smith+memo@example.co.uk".into()), (PiiKind::Phone, "(555) 123-4567".into())]
+        );
+        assert_eq!(kinds("From London, +44 20 7946 0958."), vec![(PiiKind::Phone, "+44 20 7946 0958".into())]);
+        assert_eq!(kinds("Card 4111 1111 1111 1111 expires soon"), vec![(PiiKind::CardNumber, "4111 1111 1111 1111".into())]);
+        assert_eq!(kinds("Meet at 42 Elm Street, Apt 3B at noon"), vec![(PiiKind::Address, "42 Elm Street, Apt 3B".into())]);
+        assert!(kinds("On 2024-10-15 we walked 5 miles down the road and spent 1500 2000 dollars").is_empty());
+        assert_eq!(kinds("Dial 555.123.4567 or 555-1234"), vec![
+            (PiiKind::Phone, "555.123.4567".into()),
+            (PiiKind::Phone, "555-1234".into()),
+        ]);
+    }
+
+    #[test]
+    fn test_number_runs_are_not_phones() {
+        assert!(kinds("We won the 2019 2020 2021 seasons").is_empty());
+        assert!(kinds("It cost 1500 2000 3000 dollars over the years").is_empty());
+        assert!(kinds("Order 1234-5678-9012 shipped").is_empty());
+        assert!(kinds("Ticket 555-1234-5678 and 12-555-1234").is_empty());
+    }
+
+    #[test]
+    fn test_offsets_are_in_characters() {
+        let matches = find_pii("Café: 555-123-4567");
+        assert_eq!((matches[0].start, matches[0].end), (6, 18));
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact("Call 555-123-4567 or write to a@b.io"),
+            "Call [redacted phone] or write to [redacted email]"
+        );
+        assert!(matches!(redact("nothing here"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_luhn() {
+        assert!(luhn_valid(&digits("4111111111111111")));
+        assert!(!luhn_valid(&digits("4111111111111112")));
+    }
+}
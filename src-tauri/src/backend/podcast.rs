@@ -0,0 +1,394 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use quick_xml::events::Event;
+use quick_xml::{Reader, XmlVersion};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::export::sanitize_title;
+use super::importer::{self, ImportOutcome};
+use super::logging;
+use super::ollama::open_db;
+use super::models::{DuplicateSkip, FolderImportProgress, FolderImportReport, ImportFailure, PodcastEpisode, PodcastFeed};
+use super::transcribe::TranscriptionEngine;
+
+const SOURCE: &str = "podcast";
+
+/// Fields of the `<item>` being read.
+#[derive(Default)]
+struct ItemFields {
+    guid: Option<String>,
+    title: Option<String>,
+    pub_date: Option<String>,
+    duration: Option<String>,
+    enclosure_url: Option<String>,
+}
+
+/// `<itunes:duration>` is either plain seconds or `[HH:]MM:SS`.
+fn parse_duration(value: &str) -> Option<f64> {
+    value.trim().split(':').try_fold(0.0, |total, part| {
+        part.parse::<f64>().ok().map(|n| total * 60.0 + n)
+    })
+}
+
+/// Parse an RSS 2.0 podcast feed. Items without an audio enclosure are left out.
+pub fn parse_feed(xml: &str) -> Result<PodcastFeed> {
+    let mut reader = Reader::from_str(xml);
+    let mut feed_title = None;
+    let mut episodes = Vec::new();
+    let mut item: Option<ItemFields> = None;
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+    let mut saw_channel = false;
+
+    loop {
+        match reader.read_event().context("Invalid podcast feed XML")? {
+            Event::Start(e) => {
+                let name = e.name().as_ref().to_string();
+                match name.as_str() {
+                    "channel" => saw_channel = true,
+                    "item" => item = Some(ItemFields::default()),
+                    _ => {}
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Empty(e) if e.name().as_ref() == "enclosure" => {
+                if let Some(fields) = item.as_mut() {
+                    let is_audio = e.try_get_attribute("type")?
+                        .map(|a| a.normalized_value(XmlVersion::Implicit1_0).map(|v| v.starts_with("audio/")))
+                        .transpose()?
+                        .unwrap_or(true);
+                    if let (true, Some(url)) = (is_audio, e.try_get_attribute("url")?) {
+                        fields.enclosure_url = Some(url.normalized_value(XmlVersion::Implicit1_0)?.into_owned());
+                    }
+                }
+            }
+            Event::Text(e) => text.push_str(&e.xml10_content()),
+            Event::CData(e) => text.push_str(&e.xml10_content()),
+            Event::GeneralRef(e) => match e.resolve_char_ref()? {
+                Some(c) => text.push(c),
+                None => text.push_str(quick_xml::escape::resolve_predefined_entity(&e.xml10_content()).unwrap_or_default()),
+            },
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let value = text.trim().to_string();
+                text.clear();
+                let parent = path.last().map(String::as_str);
+
+                match (parent, name.as_str()) {
+                    (Some("channel"), "title") => feed_title = Some(value),
+                    (Some("item"), field) => {
+                        if let Some(fields) = item.as_mut() {
+                            match field {
+                                "guid" => fields.guid = Some(value),
+                                "title" => fields.title = Some(value),
+                                "pubDate" => fields.pub_date = Some(value),
+                                "itunes:duration" => fields.duration = Some(value),
+                                _ => {}
+                            }
+                        }
+                    }
+                    (_, "item") => {
+                        if let Some(fields) = item.take() {
+                            if let Some(audio_url) = fields.enclosure_url {
+                                episodes.push(PodcastEpisode {
+                                    id: fields.guid.filter(|g| !g.is_empty()).unwrap_or_else(|| audio_url.clone()),
+                                    title: fields.title.filter(|t| !t.is_empty()).unwrap_or_else(|| "Untitled episode".to_string()),
+                                    published: fields.pub_date
+                                        .and_then(|d| DateTime::parse_from_rfc2822(&d).ok())
+                                        .map(|d| d.timestamp()),
+                                    audio_url,
+                                    duration_seconds: fields.duration.as_deref().and_then(parse_duration),
+                                });
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !saw_channel {
+        return Err(anyhow::anyhow!("Not an RSS podcast feed"));
+    }
+    Ok(PodcastFeed {
+        title: feed_title.unwrap_or_else(|| "Podcast".to_string()),
+        episodes,
+    })
+}
+
+/// Download and parse the feed at `feed_url`.
+pub async fn fetch_feed(feed_url: &str) -> Result<PodcastFeed> {
+    let xml = reqwest::get(feed_url)
+        .await
+        .with_context(|| format!("Failed to GET {}", feed_url))?
+        .error_for_status()
+        .with_context(|| format!("Bad status fetching {}", feed_url))?
+        .text()
+        .await
+        .context("Failed to read podcast feed")?;
+    parse_feed(&xml)
+}
+
+/// Stream an episode's audio to `dest`.
+async fn download_episode(audio_url: &str, dest: &Path) -> Result<()> {
+    use futures_util::StreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    let response = reqwest::get(audio_url)
+        .await
+        .with_context(|| format!("Failed to GET {}", audio_url))?
+        .error_for_status()
+        .with_context(|| format!("Bad status downloading {}", audio_url))?;
+
+    let mut file = tokio::fs::File::create(dest)
+        .await
+        .with_context(|| format!("Failed to create {:?}", dest))?;
+    let mut stream = Box::pin(response.bytes_stream());
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.context("Error while downloading episode")?;
+        file.write_all(&chunk).await.context("Failed to write episode")?;
+    }
+    file.flush().await.context("Failed to flush episode")?;
+    Ok(())
+}
+
+/// Where an episode is downloaded to before import; the extension tells ffmpeg the format.
+fn staged_download_path(staging_dir: &Path, episode: &PodcastEpisode) -> PathBuf {
+    let url_path = episode.audio_url.split(['?', '#']).next().unwrap_or_default();
+    let extension = Path::new(url_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .filter(|e| e.len() <= 4)
+        .unwrap_or("mp3");
+    let digest = format!("{:x}", Sha256::digest(episode.id.as_bytes()));
+    staging_dir.join(format!("podcast-{}.{}", &digest[..12], extension))
+}
+
+/// Library filename (no extension) for an episode: "Feed - Episode".
+fn episode_stem(feed_title: &str, episode: &PodcastEpisode) -> String {
+    sanitize_title(&format!("{} - {}", feed_title, episode.title))
+}
+
+/// Download one episode and import it. `None` when it was imported from this feed before.
+async fn import_episode(
+    config: &Config,
+    feed_url: &str,
+    feed_title: &str,
+    episode: &PodcastEpisode,
+    staging_dir: &Path,
+) -> Result<Option<ImportOutcome>> {
+    let seen = {
+        let config = config.clone();
+        let feed_url = feed_url.to_string();
+        let episode_id = episode.id.clone();
+        tokio::task::spawn_blocking(move || open_db(&config)?.source_file_seen(&feed_url, &episode_id))
+            .await
+            .context("Podcast import task failed")??
+    };
+    if seen {
+        return Ok(None);
+    }
+
+    let staged = staged_download_path(staging_dir, episode);
+    let downloaded = download_episode(&episode.audio_url, &staged).await;
+
+    let result = match downloaded {
+        Ok(()) => {
+            let config = config.clone();
+            let feed_url = feed_url.to_string();
+            let stem = episode_stem(feed_title, episode);
+            let episode = episode.clone();
+            let staged = staged.clone();
+            tokio::task::spawn_blocking(move || -> Result<Option<ImportOutcome>> {
+                let db = open_db(&config)?;
+                let outcome = importer::import_converted_audio(
+                    &config, &db, &staged, &stem, Some(episode.title.clone()), episode.published, Some(SOURCE),
+                )?;
+                match &outcome {
+                    ImportOutcome::Imported(id) => db.record_source_file(&feed_url, &episode.id, Some(*id), "imported")?,
+                    ImportOutcome::Duplicate(_) => db.record_source_file(&feed_url, &episode.id, None, "duplicate")?,
+                    ImportOutcome::NameTaken => {}
+                }
+                Ok(Some(outcome))
+            })
+            .await
+            .context("Podcast import task failed")?
+        }
+        Err(e) => Err(e),
+    };
+
+    if staged.exists() {
+        if let Err(e) = fs::remove_file(&staged) {
+            warn!("Failed to remove downloaded episode {:?}: {}", staged, e);
+        }
+    }
+    result
+}
+
+/// Download the chosen episodes of the feed at `feed_url` (by `PodcastEpisode::id`), import
+/// each as a slice, and transcribe them when `transcribe` is set. Episodes imported from this
+/// feed before are skipped; a failing episode is recorded in the report and retried next time.
+pub async fn import_episodes(
+    config: &Config,
+    feed_url: &str,
+    episode_ids: &[String],
+    transcribe: bool,
+    mut on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<FolderImportReport> {
+    let feed = fetch_feed(feed_url).await?;
+    let episodes: Vec<&PodcastEpisode> = feed.episodes.iter()
+        .filter(|e| episode_ids.contains(&e.id))
+        .collect();
+    info!("Podcast import: {} of {} episodes selected from {}", episodes.len(), feed.episodes.len(), feed_url);
+
+    let staging_dir = config.ciderpress_home_path().join("conversion_staging");
+    fs::create_dir_all(&staging_dir)?;
+
+    let mut report = FolderImportReport::default();
+    let mut progress = FolderImportProgress {
+        total_files: episodes.len() as u32,
+        processed_files: 0,
+        current_file: None,
+    };
+    on_progress(&progress);
+
+    for episode in episodes {
+        progress.current_file = Some(episode.title.clone());
+
+        match import_episode(config, feed_url, &feed.title, episode, &staging_dir).await {
+            Ok(Some(ImportOutcome::Imported(id))) => report.imported_slice_ids.push(id),
+            Ok(Some(ImportOutcome::Duplicate(existing))) => report.duplicates.push(DuplicateSkip {
+                file_path: episode.audio_url.clone(),
+                duplicate_of: existing,
+            }),
+            Ok(Some(ImportOutcome::NameTaken)) => report.errors.push(ImportFailure {
+                file_path: episode.audio_url.clone(),
+                message: "A slice with this filename already exists".to_string(),
+            }),
+            Ok(None) => {}
+            Err(e) => report.errors.push(ImportFailure {
+                file_path: episode.audio_url.clone(),
+                message: e.to_string(),
+            }),
+        }
+
+        progress.processed_files += 1;
+        on_progress(&progress);
+    }
+
+    logging::log_info(
+        "import",
+        &format!("Podcast import: {} imported, {} duplicates, {} errors", report.imported_slice_ids.len(), report.duplicates.len(), report.errors.len()),
+        Some(serde_json::json!({
+            "feed_url": feed_url,
+            "feed_title": feed.title,
+        })),
+    );
+
+    if transcribe && !report.imported_slice_ids.is_empty() {
+        let config = config.clone();
+        let slice_ids = report.imported_slice_ids.clone();
+        let transcribed = tokio::task::spawn_blocking(move || -> Result<bool> {
+            let db = open_db(&config)?;
+            TranscriptionEngine::new(&config, &db).transcribe_imported_slices(&slice_ids)
+        })
+        .await
+        .context("Podcast transcription task failed")
+        .and_then(|result| result);
+        // The episodes are in the library either way, so the report still goes back
+        if let Err(e) = transcribed {
+            warn!("Podcast import: failed to transcribe imported episodes: {:#}", e);
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Tom &amp; Jerry&#39;s Show</title>
+    <image><title>Artwork</title><url>https://example.com/art.jpg</url></image>
+    <item>
+      <title><![CDATA[Episode 2: <Live>]]></title>
+      <guid isPermaLink="false">ep-2</guid>
+      <pubDate>Mon, 15 Jan 2024 10:00:00 +0000</pubDate>
+      <itunes:duration>1:02:03</itunes:duration>
+      <enclosure url="https://cdn.example.com/ep2.mp3?x=1&amp;y=2" length="123" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 1</title>
+      <itunes:duration>754</itunes:duration>
+      <enclosure url="https://cdn.example.com/ep1.m4a" type="audio/x-m4a"/>
+    </item>
+    <item>
+      <title>Trailer video</title>
+      <guid>video</guid>
+      <enclosure url="https://cdn.example.com/trailer.mp4" type="video/mp4"/>
+    </item>
+    <item><title>Show notes only</title><guid>notes</guid></item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn test_parse_feed() -> Result<()> {
+        let feed = parse_feed(FEED)?;
+        assert_eq!(feed.title, "Tom & Jerry's Show");
+        assert_eq!(feed.episodes.len(), 2);
+
+        let latest = &feed.episodes[0];
+        assert_eq!(latest.id, "ep-2");
+        assert_eq!(latest.title, "Episode 2: <Live>");
+        assert_eq!(latest.published, Some(1_705_312_800));
+        assert_eq!(latest.audio_url, "https://cdn.example.com/ep2.mp3?x=1&y=2");
+        assert_eq!(latest.duration_seconds, Some(3723.0));
+
+        // Without a guid the enclosure URL identifies the episode
+        let first = &feed.episodes[1];
+        assert_eq!(first.id, "https://cdn.example.com/ep1.m4a");
+        assert_eq!(first.published, None);
+        assert_eq!(first.duration_seconds, Some(754.0));
+
+        assert!(parse_feed("<html><body>Not a feed</body></html>").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_staged_path_and_stem() -> Result<()> {
+        let feed = parse_feed(FEED)?;
+        let staging = Path::new("/staging");
+        let staged = staged_download_path(staging, &feed.episodes[0]);
+        assert_eq!(staged.extension().and_then(|e| e.to_str()), Some("mp3"));
+        assert_ne!(staged, staged_download_path(staging, &feed.episodes[1]));
+        assert_eq!(episode_stem(&feed.title, &feed.episodes[0]), "Tom & Jerry's Show - Episode 2 Live");
+        Ok(())
+    }
+}
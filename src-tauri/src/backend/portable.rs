@@ -0,0 +1,370 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Portable library archives: the database, audio and transcripts of a library in one
+//! `.tar` file, for moving CiderPress to another Mac.
+//!
+//! Slices only refer to audio by file name within the audio directory, so nothing needs
+//! rewriting for the new home folder. What is tied to the old Mac (migration source roots,
+//! migration batches) stays behind; the first migration on the new Mac starts afresh.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::database::{self, Database};
+use super::encryption;
+use super::importer::{free_file_name, ImportOutcome};
+use super::library::has_audio;
+use super::migrate::sha256_file;
+use super::models::{DuplicateSkip, FolderImportReport, ImportFailure, Label, LibraryExportReport, Slice};
+use super::pii;
+
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "manifest.json";
+const DATABASE_NAME: &str = "CiderPress-db.sqlite";
+
+/// Written first into every archive, so a portable library is recognisable before unpacking.
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format_version: u32,
+    app_version: String,
+    exported_at: i64,
+    slice_count: u32,
+}
+
+/// A new, empty staging folder for one export or import, so runs at the same time don't
+/// share (and clear) each other's files.
+fn staging_dir(config: &Config) -> Result<PathBuf> {
+    let dir = config.ciderpress_home_path()
+        .join(format!("portable_staging-{}", uuid::Uuid::new_v4().simple()));
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn append_file(builder: &mut tar::Builder<File>, path: &Path, name: &str) -> Result<()> {
+    builder.append_path_with_name(path, name)
+        .with_context(|| format!("Failed to add {:?} to the archive", path))
+}
+
+/// Mask the personal information in the database snapshot, for redaction mode: text stored
+/// before the mode was turned on may still hold some.
+fn redact_snapshot(config: &Config, snapshot: &Path) -> Result<()> {
+    let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+    database::set_encryption_key(snapshot, database::encryption_key(&db_path));
+    let result = Database::connect(snapshot).and_then(|copy| pii::redact_copy(&copy));
+    database::set_encryption_key(snapshot, None);
+    result.map(|_| ())
+}
+
+/// Add the transcripts folder, each file masked in redaction mode.
+fn append_transcripts(builder: &mut tar::Builder<File>, transcript_dir: &Path) -> Result<()> {
+    if !pii::redaction_enabled() {
+        builder.append_dir_all("transcripts", transcript_dir)
+            .context("Failed to add transcripts to the archive")?;
+        return Ok(());
+    }
+    for entry in fs::read_dir(transcript_dir)?.flatten().filter(|e| e.path().is_file()) {
+        let text = fs::read_to_string(entry.path())
+            .with_context(|| format!("Failed to read transcript {:?}", entry.path()))?;
+        let masked = pii::redact(&text);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(masked.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        let name = format!("transcripts/{}", entry.file_name().to_string_lossy());
+        builder.append_data(&mut header, name, masked.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_archive(config: &Config, db: &Database, snapshot: &Path, dest: &Path) -> Result<LibraryExportReport> {
+    db.backup_to(snapshot)?;
+    if pii::redaction_enabled() {
+        redact_snapshot(config, snapshot)?;
+    }
+    let slices = db.list_active_slices()?;
+    let mut report = LibraryExportReport {
+        destination: dest.to_string_lossy().to_string(),
+        ..LibraryExportReport::default()
+    };
+
+    let mut builder = tar::Builder::new(
+        File::create(dest).with_context(|| format!("Failed to create {:?}", dest))?,
+    );
+    let manifest = serde_json::to_vec_pretty(&Manifest {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Utc::now().timestamp(),
+        slice_count: slices.len() as u32,
+    })?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_NAME, manifest.as_slice())?;
+
+    append_file(&mut builder, snapshot, DATABASE_NAME)?;
+    report.exported_files.push(DATABASE_NAME.to_string());
+
+    let audio_dir = config.audio_dir();
+    for slice in slices.iter().filter(|s| has_audio(s)) {
+        let path = audio_dir.join(&slice.original_audio_file_name);
+        if !path.exists() {
+            report.errors.push(ImportFailure {
+                file_path: path.to_string_lossy().to_string(),
+                message: "Audio file is missing".to_string(),
+            });
+            continue;
+        }
+        let name = format!("audio/{}", slice.original_audio_file_name);
+        append_file(&mut builder, &path, &name)?;
+        report.exported_files.push(name);
+    }
+
+    let transcript_dir = config.transcript_dir();
+    if transcript_dir.is_dir() {
+        append_transcripts(&mut builder, &transcript_dir)?;
+    }
+    builder.into_inner()?.sync_all()?;
+    Ok(report)
+}
+
+/// Bundle the library into a `.tar` at `dest`: a snapshot of the database, the audio of
+/// every slice outside the trash, and the transcripts folder. Slices whose audio is missing
+/// are still listed in the database copy and reported as errors.
+pub fn export_portable_library(config: &Config, db: &Database, dest: &Path) -> Result<LibraryExportReport> {
+    let staging_dir = staging_dir(config)?;
+    let result = write_archive(config, db, &staging_dir.join(DATABASE_NAME), dest);
+    let _ = fs::remove_dir_all(&staging_dir);
+    let report = result?;
+    info!("Exported portable library with {} file(s) to {:?}", report.exported_files.len(), dest);
+    Ok(report)
+}
+
+/// The library's label with this name, created from the archived one if there is none yet.
+fn matching_label(db: &Database, label: &Label) -> Result<i64> {
+    match db.find_label_by_name(&label.name)?.and_then(|l| l.id) {
+        Some(id) => Ok(id),
+        None => db.create_label(&Label { id: None, ..label.clone() }),
+    }
+}
+
+/// Add one archived slice to the library, with its audio from `unpacked/audio` and its labels.
+fn import_slice(config: &Config, db: &Database, unpacked: &Path, mut slice: Slice, labels: &[Label]) -> Result<ImportOutcome> {
+    if has_audio(&slice) {
+        let staged = unpacked.join("audio").join(&slice.original_audio_file_name);
+        if !staged.exists() {
+            return Err(anyhow::anyhow!("Audio file is missing from the archive"));
+        }
+        let content_hash = match slice.content_hash.take() {
+            Some(hash) => hash,
+            None => sha256_file(&staged)?,
+        };
+        if let Some(existing) = db.find_slice_by_content_hash(&content_hash)? {
+            return Ok(ImportOutcome::Duplicate(existing));
+        }
+
+        slice.original_audio_file_name = free_file_name(db, &config.audio_dir(), &slice.original_audio_file_name)?;
+        let dest = config.audio_dir().join(&slice.original_audio_file_name);
+        // Staging sits under the CiderPress home, so this is normally a rename
+        if fs::rename(&staged, &dest).is_err() {
+            fs::copy(&staged, &dest)
+                .with_context(|| format!("Failed to copy audio to {:?}", dest))?;
+        }
+        slice.content_hash = Some(content_hash);
+    } else if db.slice_exists(&slice.original_audio_file_name)? {
+        // Text slices have no audio to compare, so the same name counts as the same slice
+        return Ok(ImportOutcome::Duplicate(slice.original_audio_file_name));
+    }
+
+    slice.id = None;
+    let slice_id = db.insert_slice(&slice)?;
+    for label in labels {
+        db.assign_label(matching_label(db, label)?, &[slice_id])?;
+    }
+    Ok(ImportOutcome::Imported(slice_id))
+}
+
+fn import_unpacked(config: &Config, db: &Database, archive: &Path, unpacked: &Path) -> Result<FolderImportReport> {
+    let file = File::open(archive).with_context(|| format!("Failed to open {:?}", archive))?;
+    tar::Archive::new(file).unpack(unpacked)
+        .with_context(|| format!("Failed to unpack {:?}", archive))?;
+
+    let manifest: Manifest = fs::read(unpacked.join(MANIFEST_NAME)).ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .with_context(|| format!("{:?} is not a portable CiderPress library", archive))?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(anyhow::anyhow!(
+            "{:?} was exported by a newer CiderPress ({}); update to import it",
+            archive, manifest.app_version
+        ));
+    }
+
+    // An export from an encrypted library holds an encrypted snapshot, opened with this
+    // library's passphrase
+    let snapshot = unpacked.join(DATABASE_NAME);
+    if encryption::is_encrypted(&snapshot) {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let key = database::encryption_key(&db_path).or_else(encryption::load_passphrase)
+            .with_context(|| format!("{:?} is encrypted; turn on database encryption with its passphrase to import it", archive))?;
+        database::set_encryption_key(&snapshot, Some(key));
+    }
+    let result = import_snapshot(config, db, unpacked, &snapshot);
+    database::set_encryption_key(&snapshot, None);
+    result
+}
+
+/// Add the slices and transcripts of an unpacked archive whose database is `snapshot`.
+fn import_snapshot(config: &Config, db: &Database, unpacked: &Path, snapshot: &Path) -> Result<FolderImportReport> {
+    // Opening runs the schema migrations, so archives from older versions read the same
+    let archived = Database::new(snapshot)
+        .context("The archive's database couldn't be opened (an encrypted one needs this library's passphrase)")?;
+    let mut labels = archived.get_labels_for_all_slices()?;
+    fs::create_dir_all(config.audio_dir())?;
+
+    let mut report = FolderImportReport::default();
+    for slice in archived.list_active_slices()? {
+        let display_path = slice.original_audio_file_name.clone();
+        let slice_labels = slice.id.and_then(|id| labels.remove(&id)).unwrap_or_default();
+        match import_slice(config, db, unpacked, slice, &slice_labels) {
+            Ok(ImportOutcome::Imported(id)) => report.imported_slice_ids.push(id),
+            Ok(ImportOutcome::Duplicate(existing)) => report.duplicates.push(DuplicateSkip {
+                file_path: display_path,
+                duplicate_of: existing,
+            }),
+            Ok(ImportOutcome::NameTaken) => unreachable!("imported slices are renamed instead"),
+            Err(e) => report.errors.push(ImportFailure {
+                file_path: display_path,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    let transcripts = unpacked.join("transcripts");
+    if transcripts.is_dir() {
+        let dest_dir = config.transcript_dir();
+        fs::create_dir_all(&dest_dir)?;
+        for entry in fs::read_dir(&transcripts)?.flatten() {
+            let dest = dest_dir.join(entry.file_name());
+            if !dest.exists() {
+                if let Err(e) = fs::copy(entry.path(), &dest) {
+                    warn!("Failed to copy transcript {:?}: {}", entry.path(), e);
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Import a portable library archive into this library. Slices whose audio is already here
+/// (by SHA-256) are reported as duplicates; others get a fresh name if theirs is taken.
+/// Labels are matched by name, and transcripts not yet present are copied over.
+pub fn import_portable_library(config: &Config, db: &Database, archive: &Path) -> Result<FolderImportReport> {
+    let unpacked = staging_dir(config)?;
+    let result = import_unpacked(config, db, archive, &unpacked);
+    let _ = fs::remove_dir_all(&unpacked);
+    let report = result?;
+    info!(
+        "Imported portable library {:?}: {} slice(s), {} duplicate(s), {} error(s)",
+        archive, report.imported_slice_ids.len(), report.duplicates.len(), report.errors.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+    use tempfile::TempDir;
+
+    fn library(root: &Path) -> Result<(Config, Database)> {
+        let config = Config {
+            ciderpress_home: root.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(root.join(DATABASE_NAME))?;
+        Ok((config, db))
+    }
+
+    fn audio_slice(config: &Config, name: &str, audio: &[u8]) -> Result<Slice> {
+        let path = config.audio_dir().join(name);
+        fs::write(&path, audio)?;
+        Ok(Slice {
+            title: Some(name.to_string()),
+            transcribed: true,
+            audio_file_size: audio.len() as i64,
+            transcription: Some(format!("transcript of {}", name)),
+            source: Some("voice_memos".to_string()),
+            content_hash: Some(sha256_file(&path)?),
+            ..create_test_slice(name)
+        })
+    }
+
+    #[test]
+    fn test_portable_library_round_trip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let (old_config, old_db) = library(&temp_dir.path().join("old-mac"))?;
+        let memo = old_db.insert_slice(&audio_slice(&old_config, "memo.m4a", b"memo audio")?)?;
+        old_db.insert_slice(&audio_slice(&old_config, "shared.m4a", b"already on new mac")?)?;
+        let label = old_db.get_or_create_label("Work", "#228be6")?;
+        old_db.assign_label(label, &[memo])?;
+
+        let archive = temp_dir.path().join("library.tar");
+        let export = export_portable_library(&old_config, &old_db, &archive)?;
+        assert!(export.errors.is_empty());
+        assert_eq!(export.exported_files.len(), 3);
+
+        // The new Mac already has one of the recordings, and another under the same name
+        let (new_config, new_db) = library(&temp_dir.path().join("new-mac"))?;
+        new_db.insert_slice(&audio_slice(&new_config, "shared.m4a", b"already on new mac")?)?;
+        new_db.insert_slice(&audio_slice(&new_config, "memo.m4a", b"a different memo")?)?;
+
+        let report = import_portable_library(&new_config, &new_db, &archive)?;
+        assert_eq!(report.imported_slice_ids.len(), 1);
+        assert_eq!(report.duplicates.len(), 1);
+        assert!(report.errors.is_empty());
+
+        let imported = new_db.get_slice(report.imported_slice_ids[0])?.unwrap();
+        assert_eq!(imported.original_audio_file_name, "memo 2.m4a");
+        assert_eq!(imported.transcription.as_deref(), Some("transcript of memo.m4a"));
+        assert_eq!(fs::read(new_config.audio_dir().join("memo 2.m4a"))?, b"memo audio");
+        let labels = new_db.get_labels_for_all_slices()?;
+        assert_eq!(labels[&imported.id.unwrap()][0].name, "Work");
+        let leftovers = fs::read_dir(new_config.ciderpress_home_path())?.flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("portable_staging"))
+            .count();
+        assert_eq!(leftovers, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_free_file_name() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let (config, db) = library(temp_dir.path())?;
+        db.insert_slice(&audio_slice(&config, "memo.m4a", b"audio")?)?;
+        fs::write(config.audio_dir().join("notes"), b"text")?;
+
+        assert_eq!(free_file_name(&db, &config.audio_dir(), "memo.m4a")?, "memo 2.m4a");
+        assert_eq!(free_file_name(&db, &config.audio_dir(), "notes")?, "notes 2");
+        assert_eq!(free_file_name(&db, &config.audio_dir(), "new.m4a")?, "new.m4a");
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use super::config::Config;
+use super::database::Database;
+use super::importer::{self, ImportDetails};
+use super::models::{FolderImportProgress, FolderImportReport};
+
+/// Third-party recorder apps whose recordings can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecorderApp {
+    JustPressRecord, // iCloud Drive folder: `2024-01-15/10-22-31.m4a`
+    Otter,           // Audio exported from otter.ai: `Weekly Sync_otter_ai.mp3`
+}
+
+impl RecorderApp {
+    /// Stored as the slices' `source`.
+    fn source(self) -> &'static str {
+        match self {
+            RecorderApp::JustPressRecord => "just_press_record",
+            RecorderApp::Otter => "otter",
+        }
+    }
+
+    /// Where the app keeps its recordings, when it has a fixed place.
+    /// Otter exports land wherever the browser saves them, so there is none.
+    pub fn default_folder(self) -> Option<PathBuf> {
+        match self {
+            RecorderApp::JustPressRecord => dirs::home_dir().map(|home| {
+                home.join("Library/Mobile Documents/iCloud~com~openplanetsoftware~just-press-record/Documents")
+            }),
+            RecorderApp::Otter => None,
+        }
+    }
+}
+
+/// Just Press Record names each recording by its local time, in a folder per day.
+fn describe_just_press_record(path: &Path) -> ImportDetails {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    let day = path.parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .and_then(|n| NaiveDate::parse_from_str(n, "%Y-%m-%d").ok());
+    let time = NaiveTime::parse_from_str(stem, "%H-%M-%S").ok();
+
+    match (day, time) {
+        (Some(day), Some(time)) => {
+            let naive = NaiveDateTime::new(day, time);
+            ImportDetails {
+                // File names repeat every day, so the date goes into the library name
+                stem: format!("Just Press Record {} {}", day.format("%Y-%m-%d"), stem),
+                title: format!("Just Press Record {}", naive.format("%Y-%m-%d %H:%M")),
+                recording_date: Local.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp()),
+            }
+        }
+        _ => ImportDetails {
+            stem: format!("Just Press Record {}", stem),
+            title: stem.to_string(),
+            recording_date: importer::file_modified_timestamp(path),
+        },
+    }
+}
+
+/// Otter names exports after the conversation title with an `_otter_ai` suffix; the
+/// export carries no recording date, so the file's modification time stands in.
+fn describe_otter(path: &Path) -> ImportDetails {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording").to_string();
+    let title = stem.strip_suffix("_otter_ai").unwrap_or(&stem).replace('_', " ").trim().to_string();
+    ImportDetails {
+        title: if title.is_empty() { stem.clone() } else { title },
+        recording_date: importer::file_modified_timestamp(path),
+        stem,
+    }
+}
+
+/// Import every recording under `folder` made with `app`, converting non-m4a audio,
+/// with titles and dates read from the app's layout. Files seen on an earlier run are skipped.
+pub fn import_recorder_app(
+    config: &Config,
+    db: &Database,
+    app: RecorderApp,
+    folder: &Path,
+    on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<FolderImportReport> {
+    if !folder.is_dir() {
+        return Err(anyhow::anyhow!("Folder not found: {:?}", folder));
+    }
+
+    let files = importer::scan_audio_files(folder);
+    info!("{:?} import: {} recordings found in {:?}", app, files.len(), folder);
+
+    let describe: fn(&Path) -> ImportDetails = match app {
+        RecorderApp::JustPressRecord => describe_just_press_record,
+        RecorderApp::Otter => describe_otter,
+    };
+    importer::import_tracked_files(config, db, folder, &files, app.source(), describe, on_progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_describe_recordings() {
+        let jpr = describe_just_press_record(Path::new("/Documents/2024-01-15/10-22-31.m4a"));
+        assert_eq!(jpr.stem, "Just Press Record 2024-01-15 10-22-31");
+        assert_eq!(jpr.title, "Just Press Record 2024-01-15 10:22");
+        assert_eq!(
+            jpr.recording_date,
+            Local.with_ymd_and_hms(2024, 1, 15, 10, 22, 31).earliest().map(|d| d.timestamp())
+        );
+
+        let otter = describe_otter(Path::new("/Downloads/Weekly Sync_otter_ai.mp3"));
+        assert_eq!(otter.stem, "Weekly Sync_otter_ai");
+        assert_eq!(otter.title, "Weekly Sync");
+    }
+
+    #[test]
+    fn test_just_press_record_days_do_not_clash() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let documents = temp_dir.path().join("Documents");
+        fs::create_dir_all(documents.join("2024-01-15"))?;
+        fs::create_dir_all(documents.join("2024-01-16"))?;
+        fs::write(documents.join("2024-01-15").join("10-22-31.m4a"), b"monday")?;
+        fs::write(documents.join("2024-01-16").join("10-22-31.m4a"), b"tuesday")?;
+
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        let report = import_recorder_app(&config, &db, RecorderApp::JustPressRecord, &documents, |_| {})?;
+        assert_eq!(report.imported_slice_ids.len(), 2);
+        assert!(report.errors.is_empty());
+        let slices = db.list_all_slices()?;
+        assert!(slices.iter().all(|s| s.source.as_deref() == Some("just_press_record")));
+        assert!(slices.iter().any(|s| s.original_audio_file_name == "Just Press Record 2024-01-16 10-22-31.m4a"));
+
+        let report = import_recorder_app(&config, &db, RecorderApp::JustPressRecord, &documents, |_| {})?;
+        assert!(report.imported_slice_ids.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+use super::config::Config;
+use super::library;
+use super::migrate::MigrationEngine;
+use super::ollama::open_db;
+
+/// How often the scheduler thread wakes up to check whether it was cancelled or is due.
+const TICK: Duration = Duration::from_secs(60);
+
+/// How often expired slices are emptied from the trash while the app stays open.
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// A restartable background loop. Each start bumps the generation, and a loop keeps going
+/// only while its generation is the current one, so restarting or stopping needs no handle.
+#[derive(Default)]
+pub struct Periodic {
+    generation: AtomicU64,
+}
+
+impl Periodic {
+    pub const fn new() -> Self {
+        Periodic { generation: AtomicU64::new(0) }
+    }
+
+    /// Start a thread calling `task` every `interval`, replacing any loop already running;
+    /// `run_now` also calls it straight away. `task` returns false to be tried again at the
+    /// next tick rather than a whole interval later.
+    pub fn start(&'static self, interval: Duration, run_now: bool, mut task: impl FnMut() -> bool + Send + 'static) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        std::thread::spawn(move || {
+            let mut last_run = (!run_now).then(Instant::now);
+            while self.is_current(generation) {
+                if last_run.map_or(true, |run| run.elapsed() >= interval) && task() {
+                    last_run = Some(Instant::now());
+                }
+                std::thread::sleep(TICK);
+            }
+        });
+    }
+
+    /// Stop the running loop, if there is one.
+    pub fn stop(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+}
+
+static SCHEDULE: Periodic = Periodic::new();
+static TRASH_PURGE: Periodic = Periodic::new();
+
+/// Start (or restart) the background schedule from `migration_interval_hours`.
+/// An interval of 0 disables scheduled migration. The trash is emptied of expired slices
+/// now and daily after, whether or not migration is scheduled.
+pub fn apply_config(config: &Config) {
+    let trash_config = config.clone();
+    TRASH_PURGE.start(TRASH_PURGE_INTERVAL, true, move || {
+        purge_expired_trash(&trash_config);
+        true
+    });
+
+    if config.migration_interval_hours == 0 {
+        SCHEDULE.stop();
+        info!("Scheduled migration disabled");
+        return;
+    }
+
+    let interval = Duration::from_secs(config.migration_interval_hours as u64 * 3600);
+    let config = config.clone();
+    info!("Scheduled migration every {} hour(s)", config.migration_interval_hours);
+    SCHEDULE.start(interval, false, move || run_scheduled(&config));
+}
+
+/// Stop the scheduler threads, if they are running; `apply_config` starts them again.
+pub fn stop() {
+    SCHEDULE.stop();
+    TRASH_PURGE.stop();
+}
+
+/// One scheduled run; false when it had to wait for a migration already running.
+fn run_scheduled(config: &Config) -> bool {
+    info!("Running scheduled incremental migration");
+    match MigrationEngine::new(config).try_start_migration() {
+        // Skip this slot if the user (or watch mode) already has a migration running
+        None => return false,
+        Some(Ok(summary)) => {
+            // Stay quiet unless something new actually arrived
+            if summary.copied > 0 {
+                info!("Scheduled migration copied {} new recording(s)", summary.copied);
+                crate::emit_scheduled_migration(&summary);
+            }
+        }
+        Some(Err(e)) => {
+            error!("Scheduled migration failed: {}", e);
+            *MigrationEngine::get_migration_progress_ref().lock().unwrap() = None;
+        }
+    }
+    true
+}
+
+/// Drop slices that have sat in the trash past the retention period.
+fn purge_expired_trash(config: &Config) {
+    match open_db(config).and_then(|db| library::purge_expired_trash(config, &db)) {
+        Ok(report) if !report.deleted_slice_ids.is_empty() => {
+            info!("Emptied {} expired slice(s) from the trash", report.deleted_slice_ids.len())
+        }
+        Ok(_) => {}
+        Err(e) => error!("Failed to empty expired trash: {}", e),
+    }
+}
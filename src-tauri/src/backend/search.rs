@@ -0,0 +1,512 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Matching for the search modes that SQL `LIKE` can't express.
+
+use anyhow::Result;
+use regex::{Regex, RegexBuilder};
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use super::labeling::{allowed_edits, words};
+use super::models::{SearchMatch, SearchMode, Slice};
+
+/// Most slices a regex search collects before it stops looking.
+const MAX_REGEX_MATCHES: usize = 1000;
+
+/// Longest a regex search keeps scanning transcripts.
+const REGEX_TIME_LIMIT: Duration = Duration::from_secs(2);
+
+/// Cap on the compiled pattern, so a pathological expression is rejected rather than built.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Characters of context kept on each side of a match in its snippet.
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// Most matches reported per field of one slice.
+const MAX_MATCHES_PER_FIELD: usize = 20;
+
+/// Places shown per slice when previewing a find/replace.
+const MAX_REPLACE_EXAMPLES: usize = 3;
+
+/// How much a match in each field counts towards relevance; a title hit says more about a
+/// memo than the same word somewhere in an hour of transcript.
+const FIELD_WEIGHTS: [(&str, f64); 5] = [
+    ("title", 5.0),
+    ("label", 4.0),
+    ("notes", 2.0),
+    ("original_audio_file_name", 1.5),
+    ("transcription", 1.0),
+];
+
+/// Edit distance counting a swap of two neighbouring characters as one edit, the most
+/// common typo ("recieve" for "receive").
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..=b.len() {
+        rows[0][j] = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Whether one search word matches one word of the text, or the start of it so partial
+/// names match, within the misspellings tolerated for a word that long.
+fn word_matches(search_word: &str, text_word: &str) -> bool {
+    let allowed = allowed_edits(search_word);
+    let prefix: String = text_word.chars().take(search_word.chars().count()).collect();
+    edit_distance(search_word, &prefix) <= allowed || edit_distance(search_word, text_word) <= allowed
+}
+
+/// Typo-tolerant match of `search` against `text`: the exact phrase anywhere, or every
+/// search word close to some word of the text.
+pub fn fuzzy_matches(search: &str, text: &str) -> bool {
+    if text.to_lowercase().contains(&search.to_lowercase()) {
+        return true;
+    }
+    let search_words = words(search);
+    if search_words.is_empty() {
+        return false;
+    }
+    let text_words = words(text);
+    search_words.iter().all(|search_word| text_words.iter().any(|text_word| word_matches(search_word, text_word)))
+}
+
+/// Compile a user's search pattern, case-insensitively.
+pub fn compile_regex(pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Invalid regular expression: {}", e))
+}
+
+/// When a search matched in Rust has to stop early.
+pub struct SearchBudget {
+    pub max_matches: usize,
+    pub deadline: Instant,
+}
+
+impl SearchBudget {
+    /// Limits for one regex search starting now. The regex engine runs in linear time, so
+    /// these bound the work over a large library rather than a single runaway match.
+    pub fn for_regex() -> Self {
+        SearchBudget { max_matches: MAX_REGEX_MATCHES, deadline: Instant::now() + REGEX_TIME_LIMIT }
+    }
+
+    pub fn exhausted(&self, matches: usize) -> bool {
+        matches >= self.max_matches || Instant::now() >= self.deadline
+    }
+}
+
+/// Metaphone code of a word: roughly how it sounds, so spellings whisper mixes up for the
+/// same name ("Kathryn", "Katherine", "Catherine") share one code. Letters outside A-Z are
+/// ignored, and every leading vowel codes as "A".
+pub fn metaphone(word: &str) -> String {
+    let chars: Vec<char> = word.chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let is_vowel = |c: Option<char>| matches!(c, Some('A' | 'E' | 'I' | 'O' | 'U'));
+    let softens = |c: Option<char>| matches!(c, Some('E' | 'I' | 'Y'));
+    let mut code = String::new();
+
+    // Silent or changed first letters
+    let mut i = match (chars.first(), chars.get(1)) {
+        (Some('A'), Some('E')) | (Some('G' | 'K' | 'P'), Some('N')) | (Some('W'), Some('R')) => 1,
+        (Some('X'), _) => {
+            code.push('S');
+            1
+        }
+        (Some('W'), Some('H')) => {
+            code.push('W');
+            2
+        }
+        _ => 0,
+    };
+    if is_vowel(chars.get(i).copied()) {
+        code.push('A');
+        i += 1;
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1).copied();
+        let after = chars.get(i + 2).copied();
+        // A doubled letter sounds once, except C ("accept")
+        if prev == Some(c) && c != 'C' {
+            i += 1;
+            continue;
+        }
+        match c {
+            'A' | 'E' | 'I' | 'O' | 'U' => {}
+            'B' if prev == Some('M') && next.is_none() => {} // "lamb"
+            'C' if next == Some('H') => {
+                code.push(if prev == Some('S') || after == Some('R') { 'K' } else { 'X' });
+                i += 1;
+            }
+            'C' if next == Some('I') && after == Some('A') => code.push('X'),
+            'C' if softens(next) => {
+                if prev != Some('S') {
+                    code.push('S');
+                }
+            }
+            'K' | 'Q' if prev == Some('C') => {} // "jack", "acquire"
+            'C' | 'K' | 'Q' => code.push('K'),
+            'D' if next == Some('G') && softens(after) => {
+                code.push('J');
+                i += 1;
+            }
+            'D' => code.push('T'),
+            'G' if next == Some('H') => {
+                if is_vowel(after) {
+                    code.push('K');
+                }
+                i += 1;
+            }
+            'G' if next == Some('N') && (after.is_none() || (after == Some('E') && chars.get(i + 3) == Some(&'D'))) => {}
+            'G' if softens(next) => code.push('J'),
+            'G' => code.push('K'),
+            'H' if is_vowel(next) && !matches!(prev, Some('C' | 'S' | 'P' | 'T' | 'G')) => code.push('H'),
+            'H' => {}
+            'P' if next == Some('H') => {
+                code.push('F');
+                i += 1;
+            }
+            'S' if next == Some('H') => {
+                code.push('X');
+                i += 1;
+            }
+            'S' | 'T' if next == Some('I') && matches!(after, Some('O' | 'A')) => code.push('X'),
+            'T' if next == Some('H') => {
+                code.push('0');
+                i += 1;
+            }
+            'T' if next == Some('C') && after == Some('H') => {}
+            'V' => code.push('F'),
+            'W' | 'Y' if is_vowel(next) => code.push(c),
+            'W' | 'Y' => {}
+            'X' => code.push_str("KS"),
+            'Z' => code.push('S'),
+            _ => code.push(c),
+        }
+        i += 1;
+    }
+    code
+}
+
+/// Distinct metaphone codes of the words in `text`, for the phonetic index.
+pub fn phonetic_codes(text: &str) -> BTreeSet<String> {
+    words(text).iter().map(|w| metaphone(w)).filter(|code| !code.is_empty()).collect()
+}
+
+/// Byte ranges of the words of `text`, split the same way as `labeling::words`.
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        let in_word = c.is_alphanumeric() || c == '\'';
+        match (in_word, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+/// Finds where a search matched, for every mode.
+pub enum Matcher {
+    Pattern(Regex), // substring searches become an escaped pattern
+    Fuzzy { phrase: Regex, words: Vec<String> },
+    Phonetic(BTreeSet<String>), // codes of the search words
+}
+
+impl Matcher {
+    pub fn new(search: &str, mode: SearchMode) -> Result<Matcher> {
+        let search = search.trim();
+        Ok(match mode {
+            SearchMode::Substring => Matcher::Pattern(compile_regex(&regex::escape(search))?),
+            SearchMode::Regex => Matcher::Pattern(compile_regex(search)?),
+            SearchMode::Fuzzy => Matcher::Fuzzy {
+                phrase: compile_regex(&regex::escape(search))?,
+                words: words(search),
+            },
+            // Nothing to sound out (e.g. only digits): match it as text instead
+            SearchMode::Phonetic if phonetic_codes(search).is_empty() => {
+                Matcher::Pattern(compile_regex(&regex::escape(search))?)
+            }
+            SearchMode::Phonetic => Matcher::Phonetic(phonetic_codes(search)),
+        })
+    }
+
+    /// Byte ranges of the matches in `text`, in order, at most `MAX_MATCHES_PER_FIELD`.
+    fn ranges(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut ranges: Vec<(usize, usize)> = match self {
+            Matcher::Pattern(regex) => regex.find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .take(MAX_MATCHES_PER_FIELD)
+                .collect(),
+            Matcher::Fuzzy { phrase, words } => phrase.find_iter(text)
+                .map(|m| (m.start(), m.end()))
+                .chain(word_spans(text).into_iter().filter(|(start, end)| {
+                    let word = text[*start..*end].to_lowercase();
+                    words.iter().any(|search_word| word_matches(search_word, &word))
+                }))
+                .collect(),
+            Matcher::Phonetic(codes) => word_spans(text)
+                .into_iter()
+                .filter(|(start, end)| codes.contains(&metaphone(&text[*start..*end])))
+                .collect(),
+        };
+        ranges.sort();
+        // A phrase match covers the word matches inside it
+        ranges.dedup_by(|later, earlier| later.0 < earlier.1);
+        ranges.truncate(MAX_MATCHES_PER_FIELD);
+        ranges
+    }
+
+    /// Every match in the searchable fields of `slice`, with snippets.
+    pub fn find(&self, slice: &Slice) -> Vec<SearchMatch> {
+        let fields = [
+            ("title", slice.title.as_deref()),
+            ("original_audio_file_name", Some(slice.original_audio_file_name.as_str())),
+            ("transcription", slice.transcription.as_deref()),
+            ("notes", slice.notes.as_deref()),
+        ];
+        fields.into_iter()
+            .filter_map(|(field, text)| Some(self.find_in(field, text?)))
+            .flatten()
+            .collect()
+    }
+
+    /// Every match in one piece of text, reported as belonging to `field`.
+    pub fn find_in(&self, field: &str, text: &str) -> Vec<SearchMatch> {
+        self.ranges(text)
+            .into_iter()
+            .map(|(start, end)| search_match(field, text, start, end))
+            .collect()
+    }
+}
+
+/// Relevance of a slice from its matches: each field's weight, growing with the log of how
+/// often it matched there so a long transcript repeating a word doesn't drown out a title.
+pub fn relevance(matches: &[SearchMatch]) -> f64 {
+    FIELD_WEIGHTS
+        .iter()
+        .map(|(field, weight)| {
+            let count = matches.iter().filter(|m| m.field == *field).count();
+            if count == 0 { 0.0 } else { weight * (1.0 + (count as f64).ln()) }
+        })
+        .sum()
+}
+
+/// Pattern for a find/replace of the literal `find`. Unless `within_words`, it only matches
+/// where `find` isn't part of a longer word, so "Jon" leaves "Jonathan" alone.
+pub fn replacement_regex(find: &str, case_sensitive: bool, within_words: bool) -> Result<Regex> {
+    let find = find.trim();
+    if find.is_empty() {
+        return Err(anyhow::anyhow!("Text to find must not be empty"));
+    }
+    let word_edge = |c: Option<char>| !within_words && c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let pattern = format!(
+        "{}{}{}",
+        if word_edge(find.chars().next()) { r"\b" } else { "" },
+        regex::escape(find),
+        if word_edge(find.chars().last()) { r"\b" } else { "" },
+    );
+    Ok(RegexBuilder::new(&pattern).case_insensitive(!case_sensitive).build()?)
+}
+
+/// The first matches of `regex` in a transcription, to preview a replacement.
+pub fn replacement_examples(regex: &Regex, text: &str) -> Vec<SearchMatch> {
+    regex.find_iter(text)
+        .take(MAX_REPLACE_EXAMPLES)
+        .map(|m| search_match("transcription", text, m.start(), m.end()))
+        .collect()
+}
+
+/// A match at bytes `start..end` of `text`, described in character offsets with a snippet
+/// of up to `SNIPPET_CONTEXT_CHARS` either side.
+fn search_match(field: &str, text: &str, start: usize, end: usize) -> SearchMatch {
+    let char_start = text[..start].chars().count();
+    let char_end = char_start + text[start..end].chars().count();
+
+    let before: Vec<char> = text[..start].chars().rev().take(SNIPPET_CONTEXT_CHARS + 1).collect();
+    let after: Vec<char> = text[end..].chars().take(SNIPPET_CONTEXT_CHARS + 1).collect();
+    let mut snippet = String::new();
+    if before.len() > SNIPPET_CONTEXT_CHARS {
+        snippet.push('…');
+    }
+    snippet.extend(before.iter().take(SNIPPET_CONTEXT_CHARS).rev());
+    let highlight_start = snippet.chars().count();
+    snippet.push_str(&text[start..end]);
+    let highlight_end = snippet.chars().count();
+    snippet.extend(after.iter().take(SNIPPET_CONTEXT_CHARS));
+    if after.len() > SNIPPET_CONTEXT_CHARS {
+        snippet.push('…');
+    }
+
+    SearchMatch {
+        field: field.to_string(),
+        start: char_start,
+        end: char_end,
+        snippet,
+        highlight_start,
+        highlight_end,
+    }
+}
+
+/// The text of a slice that search looks at: title, file name, transcription and notes.
+pub fn searchable_text(slice: &Slice) -> String {
+    [
+        slice.title.as_deref(),
+        Some(slice.original_audio_file_name.as_str()),
+        slice.transcription.as_deref(),
+        slice.notes.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+
+    #[test]
+    fn test_edit_distance_counts_swaps_once() {
+        assert_eq!(edit_distance("recieve", "receive"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_compile_regex() {
+        let ticket = compile_regex(r"\bJIRA-\d+\b").unwrap();
+        assert!(ticket.is_match("see jira-1234 for details"));
+        assert!(!ticket.is_match("JIRA-x"));
+        assert!(compile_regex("(unclosed").is_err());
+        assert!(compile_regex(r"(\w{1000}){1000}").is_err(), "oversized patterns are refused");
+    }
+
+    #[test]
+    fn test_matcher_offsets_and_snippets() {
+        let mut slice = Slice {
+            id: Some(1),
+            title: Some("Café plans".to_string()),
+            transcribed: true,
+            ..create_test_slice("memo.m4a")
+        };
+        slice.transcription = Some(format!("{}the plans for the café{}", "x".repeat(50), "y".repeat(50)));
+
+        let found = Matcher::new("PLANS", SearchMode::Substring).unwrap().find(&slice);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].field.as_str(), found[0].start, found[0].end), ("title", 5, 10));
+        assert_eq!(found[0].snippet, "Café plans");
+        assert_eq!((found[0].highlight_start, found[0].highlight_end), (5, 10));
+
+        let in_transcript = &found[1];
+        assert_eq!((in_transcript.start, in_transcript.end), (54, 59));
+        assert!(in_transcript.snippet.starts_with('…') && in_transcript.snippet.ends_with('…'));
+        let highlighted: String = in_transcript.snippet.chars()
+            .skip(in_transcript.highlight_start)
+            .take(in_transcript.highlight_end - in_transcript.highlight_start)
+            .collect();
+        assert_eq!(highlighted, "plans");
+
+        let fuzzy = Matcher::new("plnas", SearchMode::Fuzzy).unwrap().find(&slice);
+        let hits: Vec<_> = fuzzy.iter().map(|m| (m.field.as_str(), m.start, m.end)).collect();
+        assert_eq!(hits, vec![("title", 5, 10), ("transcription", 54, 59)]);
+    }
+
+    #[test]
+    fn test_relevance_weights_fields() {
+        let matcher = Matcher::new("budget", SearchMode::Substring).unwrap();
+        let in_title = matcher.find_in("title", "Budget");
+        let in_transcript = matcher.find_in("transcription", &"budget ".repeat(20));
+        assert!(relevance(&in_title) > relevance(&in_transcript));
+        assert!(relevance(&matcher.find_in("label", "budget")) > relevance(&in_transcript[..1]));
+        assert_eq!(relevance(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_replacement_regex() {
+        let whole = replacement_regex("Jon", false, false).unwrap();
+        assert_eq!(whole.replace_all("jon met Jonathan and JON", "John"), "John met Jonathan and John");
+        let inside = replacement_regex("Jon", true, true).unwrap();
+        assert_eq!(inside.replace_all("jon met Jonathan", "John"), "jon met Johnathan");
+        let symbol = replacement_regex("C++", false, false).unwrap();
+        assert_eq!(symbol.replace_all("c++ code", "Rust"), "Rust code");
+        assert!(replacement_regex("  ", false, false).is_err());
+    }
+
+    #[test]
+    fn test_metaphone_groups_spellings() {
+        assert_eq!(metaphone("Kathryn"), "K0RN");
+        assert_eq!(metaphone("Catherine"), "K0RN");
+        assert_eq!(metaphone("Katherine"), "K0RN");
+        assert_eq!(metaphone("Philip"), metaphone("Filip"));
+        assert_eq!(metaphone("Aaron"), metaphone("Erin"));
+        assert_eq!(metaphone("knight"), "NT");
+        assert_ne!(metaphone("Mark"), metaphone("Matt"));
+        assert_eq!(metaphone("Jack"), "JK");
+        assert_eq!(metaphone("Jack"), metaphone("Jak"));
+        assert_eq!(metaphone("42"), "");
+
+        let matcher = Matcher::new("kathryn", SearchMode::Phonetic).unwrap();
+        let found = matcher.find_in("transcription", "met Catherine today");
+        assert_eq!((found[0].start, found[0].end), (4, 13));
+    }
+
+    #[test]
+    fn test_fuzzy_matches() {
+        let text = "Please receive the Henderson invoice before Friday";
+        assert!(fuzzy_matches("recieve", text));
+        assert!(fuzzy_matches("recieve", "I received it"), "typos in a partial word match too");
+        assert!(fuzzy_matches("hender", text), "partial names match");
+        assert!(fuzzy_matches("henderson invoise", text));
+        assert!(fuzzy_matches("the Hend", text));
+        assert!(!fuzzy_matches("invoice monday", text), "every word has to match");
+        assert!(!fuzzy_matches("fry", text), "short words need an exact or prefix match");
+        assert!(!fuzzy_matches("  ", text));
+    }
+}
@@ -0,0 +1,143 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Per-slice sentiment from the local LLM: a score from -1 to 1 and a label, kept in the
+//! database so the stats screen can chart mood over time for voice journals.
+
+use anyhow::{anyhow, Result};
+
+use super::config::Config;
+use super::models::{FolderImportProgress, LlmBatchReport, SliceSentiment};
+use super::ollama::{open_db, run_over_slices, transcript, Ollama};
+use super::summarize::transcript_or_summary;
+
+const SENTIMENT_INSTRUCTIONS: &str = "You rate the overall mood of the speaker in a voice recording transcript. \
+Reply with JSON only, in the form {\"score\": number, \"label\": string}: score from -1.0 (very negative) \
+through 0 (neutral) to 1.0 (very positive), and label one of \"positive\", \"neutral\" or \"negative\".";
+
+/// Scores within this distance of zero count as neutral when the model gives no usable label.
+const NEUTRAL_BAND: f64 = 0.2;
+
+/// Read the score and label out of the model's reply. The score is clamped to -1..1, and
+/// a missing or unknown label is derived from it.
+pub fn parse_sentiment(reply: &str) -> Option<(f64, String)> {
+    // Models sometimes wrap the JSON in prose or a code fence
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    let value: serde_json::Value = serde_json::from_str(reply.get(start..=end)?).ok()?;
+    let score = match &value["score"] {
+        serde_json::Value::Number(n) => n.as_f64()?,
+        serde_json::Value::String(s) => s.trim().parse().ok()?,
+        _ => return None,
+    };
+    if !score.is_finite() {
+        return None;
+    }
+    let score = score.clamp(-1.0, 1.0);
+    let label = value["label"]
+        .as_str()
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| matches!(l.as_str(), "positive" | "neutral" | "negative"))
+        .unwrap_or_else(|| {
+            if score > NEUTRAL_BAND {
+                "positive".to_string()
+            } else if score < -NEUTRAL_BAND {
+                "negative".to_string()
+            } else {
+                "neutral".to_string()
+            }
+        });
+    Some((score, label))
+}
+
+/// Rate one slice and store the result, replacing any earlier rating.
+pub async fn analyze_slice(config: &Config, ollama: &Ollama, slice_id: i64) -> Result<SliceSentiment> {
+    let slice = open_db(config)?
+        .get_slice(slice_id)?
+        .ok_or_else(|| anyhow!("No slice found with ID: {}", slice_id))?;
+    let context = transcript_or_summary(ollama, &slice).await?;
+    let reply = ollama.generate_json(Some(SENTIMENT_INSTRUCTIONS), &context).await?;
+    let (score, label) = parse_sentiment(&reply)
+        .ok_or_else(|| anyhow!("{} didn't return a usable sentiment: {}", ollama.model(), reply.trim()))?;
+
+    let sentiment = SliceSentiment {
+        slice_id,
+        score,
+        label,
+        model: ollama.model().to_string(),
+        analyzed_at: chrono::Utc::now().timestamp(),
+    };
+    open_db(config)?.save_slice_sentiment(&sentiment)?;
+    Ok(sentiment)
+}
+
+/// Rate slices one after another, reporting each through `on_progress`: the given ones,
+/// or with `None` every transcribed slice not rated yet. Rated slices are redone only
+/// with `overwrite`.
+pub async fn analyze_slices(
+    config: &Config,
+    slice_ids: Option<Vec<i64>>,
+    overwrite: bool,
+    on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<LlmBatchReport> {
+    let ollama = Ollama::new(config)?;
+    let ollama = &ollama;
+    let db = open_db(config)?;
+    let slice_ids = match slice_ids {
+        Some(ids) => ids,
+        None => db.list_slices_without_sentiment()?,
+    };
+    // Which of them are rated already, looked up once rather than per slice
+    let rated: std::collections::HashSet<i64> = if overwrite {
+        Default::default()
+    } else {
+        slice_ids
+            .iter()
+            .filter_map(|&id| db.get_slice_sentiment(id).ok().flatten().map(|s| s.slice_id))
+            .collect()
+    };
+    drop(db);
+
+    run_over_slices(
+        config,
+        "Sentiment analysis",
+        &slice_ids,
+        |slice| transcript(slice).is_none() || slice.id.is_some_and(|id| rated.contains(&id)),
+        move |slice| async move {
+            analyze_slice(config, ollama, slice.id.unwrap_or_default()).await.map(|_| ())
+        },
+        on_progress,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sentiment() {
+        assert_eq!(parse_sentiment(r#"{"score": 0.7, "label": "Positive"}"#), Some((0.7, "positive".to_string())));
+        assert_eq!(
+            parse_sentiment("Here you go:\n```json\n{\"score\": \"-0.5\"}\n```"),
+            Some((-0.5, "negative".to_string()))
+        );
+        assert_eq!(parse_sentiment(r#"{"score": 3, "label": "ecstatic"}"#), Some((1.0, "positive".to_string())));
+        assert_eq!(parse_sentiment(r#"{"score": 0.1}"#), Some((0.1, "neutral".to_string())));
+        assert_eq!(parse_sentiment(r#"{"label": "neutral"}"#), None);
+        assert_eq!(parse_sentiment("I can't tell"), None);
+    }
+}
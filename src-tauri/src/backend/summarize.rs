@@ -0,0 +1,112 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Transcript summaries from the local LLM. A transcript too long for a small model's
+//! context is summarized in parts, and the part summaries are then combined into one.
+
+use anyhow::{anyhow, Result};
+
+use super::config::Config;
+use super::models::{FolderImportProgress, LlmBatchReport, Slice};
+use super::nlm_upload::split_ranges;
+use super::ollama::{open_db, run_over_slices, transcript, Ollama};
+
+/// Most of a transcript sent to the model at once, and how much consecutive parts share.
+const CHUNK_CHARS: usize = 12_000;
+const CHUNK_OVERLAP_CHARS: usize = 500;
+
+const SUMMARY_INSTRUCTIONS: &str = "You summarize transcripts of voice recordings. Reply with the summary only, \
+in the transcript's language: one short paragraph, then the key points, decisions and to-dos as a bulleted list \
+if there are any.";
+
+const COMBINE_INSTRUCTIONS: &str = "You are given summaries of consecutive parts of one voice recording. \
+Combine them into a single summary of the whole recording, in the same language and form: one short paragraph, \
+then a bulleted list of key points, decisions and to-dos if there are any. Reply with the summary only.";
+
+/// Summarize a transcript, in parts if it is long.
+pub async fn summarize_text(ollama: &Ollama, text: &str) -> Result<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let ranges = split_ranges(&chars, CHUNK_CHARS, CHUNK_OVERLAP_CHARS);
+    if ranges.len() == 1 {
+        return ollama.generate(Some(SUMMARY_INSTRUCTIONS), text).await;
+    }
+
+    let mut parts = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        let part: String = chars[start..end].iter().collect();
+        parts.push(ollama.generate(Some(SUMMARY_INSTRUCTIONS), &part).await?);
+    }
+    let numbered: Vec<String> = parts
+        .iter()
+        .enumerate()
+        .map(|(i, summary)| format!("Part {}:\n{}", i + 1, summary))
+        .collect();
+    ollama.generate(Some(COMBINE_INSTRUCTIONS), &numbered.join("\n\n")).await
+}
+
+/// What to give the model about `slice` for a task that needs the gist rather than every
+/// word: its transcript, or once that is longer than `CHUNK_CHARS`, its summary (written
+/// now if it has none).
+pub async fn transcript_or_summary(ollama: &Ollama, slice: &Slice) -> Result<String> {
+    let text = transcript(slice)
+        .ok_or_else(|| anyhow!("Slice {} has no transcription", slice.id.unwrap_or_default()))?;
+    if text.chars().count() <= CHUNK_CHARS {
+        return Ok(text.to_string());
+    }
+    match &slice.summary {
+        Some(summary) => Ok(summary.clone()),
+        None => summarize_text(ollama, text).await,
+    }
+}
+
+/// Summarize one slice's transcript and store the summary on the slice.
+pub async fn summarize_slice(config: &Config, ollama: &Ollama, slice_id: i64) -> Result<String> {
+    let slice = open_db(config)?
+        .get_slice(slice_id)?
+        .ok_or_else(|| anyhow!("No slice found with ID: {}", slice_id))?;
+    let text = transcript(&slice).ok_or_else(|| anyhow!("Slice {} has no transcription", slice_id))?;
+
+    let summary = summarize_text(ollama, text).await?;
+    if summary.is_empty() {
+        return Err(anyhow!("{} returned an empty summary", ollama.model()));
+    }
+    open_db(config)?.set_slice_summary(slice_id, Some(&summary))?;
+    Ok(summary)
+}
+
+/// Summarize several slices one after another, reporting each through `on_progress`.
+/// Slices without a transcript, and unless `overwrite` those already summarized, are
+/// skipped; one that fails doesn't stop the rest.
+pub async fn summarize_slices(
+    config: &Config,
+    slice_ids: &[i64],
+    overwrite: bool,
+    on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<LlmBatchReport> {
+    let ollama = Ollama::new(config)?;
+    let ollama = &ollama;
+    run_over_slices(
+        config,
+        "Summarizing",
+        slice_ids,
+        |slice| transcript(slice).is_none() || (!overwrite && slice.summary.is_some()),
+        move |slice| async move {
+            summarize_slice(config, ollama, slice.id.unwrap_or_default()).await.map(|_| ())
+        },
+        on_progress,
+    )
+    .await
+}
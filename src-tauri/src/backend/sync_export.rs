@@ -0,0 +1,200 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Keeps a folder the user picked (e.g. inside iCloud Drive or Dropbox) in step with the
+//! library: one Markdown file per transcribed slice, rewritten only when its content changes.
+
+use anyhow::{anyhow, Result};
+use chrono::{Local, TimeZone};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use super::config::Config;
+use super::database::Database;
+use super::db_pool;
+use super::export::{sanitize_title, transcript_markdown};
+use super::models::{ImportFailure, Slice, SyncExportReport};
+use super::pii;
+use super::scheduler::Periodic;
+
+static SCHEDULE: Periodic = Periodic::new();
+
+// Held for each sync, so a manual run and the scheduled one never write the folder together
+lazy_static::lazy_static! {
+    static ref SYNC_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// `2023-04-15 Standup (42).md`: sorts by date in Finder, and the ID keeps names unique
+/// and stable while the title stays the same.
+fn sync_file_name(slice: &Slice, id: i64) -> String {
+    let stem = Path::new(&slice.original_audio_file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("recording");
+    let title = slice.title.as_deref()
+        .map(sanitize_title)
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| sanitize_title(stem));
+    match slice.recording_date.and_then(|ts| Local.timestamp_opt(ts, 0).single()) {
+        Some(date) => format!("{} {} ({}).md", date.format("%Y-%m-%d"), title, id),
+        None => format!("{} ({}).md", title, id),
+    }
+}
+
+/// Bring `folder` up to date: write files for new or changed transcripts (or ones deleted from
+/// the folder), and remove those of slices that are gone, trashed or no longer transcribed.
+pub fn sync_folder(db: &Database, folder: &Path) -> Result<SyncExportReport> {
+    fs::create_dir_all(folder)?;
+    let mut previous = db.get_sync_exports()?;
+    let mut report = SyncExportReport {
+        folder: folder.to_string_lossy().to_string(),
+        ..SyncExportReport::default()
+    };
+
+    let mut current: HashSet<i64> = HashSet::new();
+    for slice in db.list_active_slices()?.into_iter().map(pii::for_export) {
+        let (Some(id), Some(transcription)) = (slice.id, slice.transcription.as_deref()) else { continue };
+        current.insert(id);
+
+        let file_name = sync_file_name(&slice, id);
+        let content = transcript_markdown(&slice, transcription);
+        let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+        let path = folder.join(&file_name);
+
+        let last = previous.remove(&id);
+        if last.as_ref().is_some_and(|(name, last_hash)| *name == file_name && *last_hash == hash) && path.exists() {
+            report.unchanged += 1;
+            continue;
+        }
+        // Renamed by a title or date change; don't leave the old file behind
+        if let Some((old_name, _)) = last.filter(|(name, _)| *name != file_name) {
+            let _ = fs::remove_file(folder.join(old_name));
+        }
+
+        match fs::write(&path, &content) {
+            Ok(()) => {
+                db.record_sync_export(id, &file_name, &hash, chrono::Utc::now().timestamp())?;
+                report.exported += 1;
+            }
+            Err(e) => {
+                warn!("Failed to write {:?}: {}", path, e);
+                report.errors.push(ImportFailure {
+                    file_path: path.to_string_lossy().to_string(),
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+
+    // Whatever is left was exported before but has no transcript in the library any more
+    for (id, (file_name, _)) in previous {
+        if !current.contains(&id) {
+            let path = folder.join(&file_name);
+            if path.exists() {
+                fs::remove_file(&path)?;
+                report.removed += 1;
+            }
+            db.remove_sync_export(id)?;
+        }
+    }
+
+    Ok(report)
+}
+
+/// `sync_folder` on the library's database, waiting for any sync already running.
+pub fn sync_now(config: &Config, folder: &Path) -> Result<SyncExportReport> {
+    let _guard = SYNC_LOCK.lock().map_err(|e| anyhow!("Failed to lock sync export: {}", e))?;
+    let db = db_pool::connect(config.ciderpress_home_path().join("CiderPress-db.sqlite"))?;
+    sync_folder(&db, folder)
+}
+
+/// Start (or restart) the background sync from `sync_export_folder`; no folder turns it off.
+/// The first run happens right away, then every `sync_export_interval_minutes`.
+pub fn apply_config(config: &Config) {
+    let Some(folder) = config.sync_export_path() else {
+        SCHEDULE.stop();
+        info!("Sync folder export disabled");
+        return;
+    };
+
+    let interval = Duration::from_secs(config.sync_export_interval_minutes.max(1) as u64 * 60);
+    let config = config.clone();
+    info!("Exporting transcripts to {:?} every {} minute(s)", folder, config.sync_export_interval_minutes.max(1));
+    SCHEDULE.start(interval, true, move || {
+        match sync_now(&config, &folder) {
+            Ok(report) if report.exported > 0 || report.removed > 0 => info!(
+                "Sync folder export wrote {} and removed {} transcript(s)",
+                report.exported, report.removed
+            ),
+            Ok(_) => {}
+            Err(e) => error!("Sync folder export to {:?} failed: {}", folder, e),
+        }
+        true
+    });
+}
+
+/// Stop the export thread, if one is running; `apply_config` starts it again.
+pub fn stop() {
+    SCHEDULE.stop();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::database::create_test_slice;
+    use tempfile::TempDir;
+
+    fn transcribed_slice(filename: &str, title: &str, transcription: &str) -> Slice {
+        Slice {
+            title: Some(title.to_string()),
+            transcribed: true,
+            transcription: Some(transcription.to_string()),
+            ..create_test_slice(filename)
+        }
+    }
+
+    #[test]
+    fn test_sync_folder_rewrites_only_changes() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+        let folder = temp_dir.path().join("sync");
+        let a = db.insert_slice(&transcribed_slice("a.m4a", "Standup", "first take"))?;
+        let b = db.insert_slice(&transcribed_slice("b.m4a", "Ideas", "more ideas"))?;
+
+        let report = sync_folder(&db, &folder)?;
+        assert_eq!((report.exported, report.unchanged, report.removed), (2, 0, 0));
+        let report = sync_folder(&db, &folder)?;
+        assert_eq!((report.exported, report.unchanged), (0, 2));
+
+        // A changed transcript and a file removed by hand are both written again
+        db.update_slice_transcription(a, "second take", 0, 2, "base.en")?;
+        fs::remove_file(folder.join(format!("Ideas ({}).md", b)))?;
+        let report = sync_folder(&db, &folder)?;
+        assert_eq!((report.exported, report.unchanged), (2, 0));
+        assert!(fs::read_to_string(folder.join(format!("Standup ({}).md", a)))?.contains("second take"));
+
+        db.trash_slices(&[b], 1_700_000_000)?;
+        let report = sync_folder(&db, &folder)?;
+        assert_eq!((report.exported, report.unchanged, report.removed), (0, 1, 1));
+        assert!(!folder.join(format!("Ideas ({}).md", b)).exists());
+        Ok(())
+    }
+}
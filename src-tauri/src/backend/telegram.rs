@@ -0,0 +1,237 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDateTime, TimeZone};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::database::Database;
+use super::importer::{self, ImportDetails};
+use super::models::{FolderImportProgress, FolderImportReport};
+
+const SOURCE: &str = "telegram";
+
+/// Machine-readable export written by Telegram Desktop when "JSON" format is chosen.
+const EXPORT_JSON: &str = "result.json";
+
+/// `result.json` is either a single chat or, for a full account export, a list of chats.
+#[derive(Debug, Deserialize)]
+struct TelegramExport {
+    name: Option<String>,
+    #[serde(default)]
+    messages: Vec<TelegramMessage>,
+    chats: Option<TelegramChatList>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TelegramChatList {
+    #[serde(default)]
+    list: Vec<TelegramChat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TelegramChat {
+    name: Option<String>,
+    #[serde(default)]
+    messages: Vec<TelegramMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TelegramMessage {
+    date: Option<String>,          // Local time, e.g. "2024-01-01T12:00:00"
+    date_unixtime: Option<String>, // Newer exports; a string, not a number
+    media_type: Option<String>,
+    file: Option<String>,          // Path relative to the export folder
+}
+
+/// Message date and chat for a voice message listed in the export.
+#[derive(Debug, PartialEq)]
+struct MessageInfo {
+    date: Option<i64>,
+    chat_name: Option<String>,
+}
+
+impl TelegramMessage {
+    fn timestamp(&self) -> Option<i64> {
+        if let Some(unix) = self.date_unixtime.as_deref().and_then(|d| d.parse().ok()) {
+            return Some(unix);
+        }
+        let naive = NaiveDateTime::parse_from_str(self.date.as_deref()?, "%Y-%m-%dT%H:%M:%S").ok()?;
+        Local.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp())
+    }
+}
+
+/// Voice messages in `result.json`, keyed by their path relative to the export folder.
+fn parse_export(json: &str) -> Result<HashMap<String, MessageInfo>> {
+    let export: TelegramExport = serde_json::from_str(json).context("Invalid Telegram export JSON")?;
+
+    let chats = export.chats.map(|c| c.list).unwrap_or_default();
+    let single = TelegramChat { name: export.name, messages: export.messages };
+
+    let mut messages = HashMap::new();
+    for chat in std::iter::once(single).chain(chats) {
+        for message in &chat.messages {
+            if message.media_type.as_deref() != Some("voice_message") {
+                continue;
+            }
+            if let Some(file) = &message.file {
+                messages.insert(file.replace('\\', "/"), MessageInfo {
+                    date: message.timestamp(),
+                    chat_name: chat.name.clone(),
+                });
+            }
+        }
+    }
+    Ok(messages)
+}
+
+/// Read the date Telegram Desktop puts in voice message filenames, as local time:
+/// `audio_3@15-04-2023_10-22-31.ogg`.
+pub fn parse_filename_date(filename: &str) -> Option<i64> {
+    let stem = filename.rsplit_once('.').map_or(filename, |(stem, _)| stem);
+    let (_, datetime) = stem.split_once('@')?;
+    let naive = NaiveDateTime::parse_from_str(datetime, "%d-%m-%Y_%H-%M-%S").ok()?;
+    Local.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp())
+}
+
+/// The export folder is the one holding result.json: `folder` itself, or its parent
+/// when the `voice_messages` subfolder was picked.
+fn find_export_root(folder: &Path) -> Option<&Path> {
+    folder.ancestors()
+        .take(2)
+        .find(|dir| dir.join(EXPORT_JSON).exists())
+}
+
+/// Import every Telegram voice message (.ogg/.oga) under `folder`, an export made by
+/// Telegram Desktop, converting each to `.m4a`. Dates come from the export's result.json.
+pub fn import_voice_messages(
+    config: &Config,
+    db: &Database,
+    folder: &Path,
+    on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<FolderImportReport> {
+    if !folder.is_dir() {
+        return Err(anyhow::anyhow!("Folder not found: {:?}", folder));
+    }
+
+    let export_root = find_export_root(folder);
+    let messages = match export_root {
+        Some(root) => fs::read_to_string(root.join(EXPORT_JSON))
+            .map_err(anyhow::Error::from)
+            .and_then(|json| parse_export(&json))
+            .unwrap_or_else(|e| {
+                warn!("Could not read Telegram export {:?}: {}", root.join(EXPORT_JSON), e);
+                HashMap::new()
+            }),
+        None => HashMap::new(),
+    };
+    let export_root = export_root.unwrap_or(folder);
+
+    let files = importer::scan_files_with_extensions(folder, &["ogg", "oga"]);
+    info!("Telegram import: {} voice messages found in {:?}", files.len(), folder);
+
+    importer::import_tracked_files(
+        config,
+        db,
+        folder,
+        &files,
+        SOURCE,
+        |path| describe_message(path, export_root, &messages),
+        on_progress,
+    )
+}
+
+/// Title and recording date for a voice message: result.json first, then the
+/// filename, then the file's modification time.
+fn describe_message(path: &Path, export_root: &Path, messages: &HashMap<String, MessageInfo>) -> ImportDetails {
+    let key = path.strip_prefix(export_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let message = messages.get(&key);
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+
+    let recording_date = message.and_then(|m| m.date)
+        .or_else(|| parse_filename_date(file_name))
+        .or_else(|| importer::file_modified_timestamp(path));
+    let title = match message.and_then(|m| m.chat_name.as_deref()) {
+        Some(chat) => format!("Telegram voice message - {}", chat),
+        None => "Telegram voice message".to_string(),
+    };
+    ImportDetails {
+        stem: path.file_stem().and_then(|s| s.to_str()).unwrap_or(file_name).to_string(),
+        title,
+        recording_date,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_export_single_chat_and_full_account() -> Result<()> {
+        let single = r#"{
+            "name": "Alice",
+            "type": "personal_chat",
+            "messages": [
+                {"id": 1, "type": "message", "date": "2023-04-15T10:22:31", "date_unixtime": "1681554151",
+                 "media_type": "voice_message", "file": "voice_messages/audio_1@15-04-2023_10-22-31.ogg"},
+                {"id": 2, "type": "message", "date": "2023-04-15T10:23:00", "text": "hi"},
+                {"id": 3, "type": "message", "date": "2023-04-15T10:24:00",
+                 "media_type": "audio_file", "file": "files/song.ogg"}
+            ]
+        }"#;
+        let messages = parse_export(single)?;
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages["voice_messages/audio_1@15-04-2023_10-22-31.ogg"],
+            MessageInfo { date: Some(1_681_554_151), chat_name: Some("Alice".to_string()) }
+        );
+
+        // Older exports have no date_unixtime; the local date is used instead
+        let full = r#"{
+            "chats": {"list": [
+                {"name": "Team", "messages": [
+                    {"id": 7, "date": "2021-12-31T23:59:00", "media_type": "voice_message",
+                     "file": "chats\\chat_01\\voice_messages\\audio_7@31-12-2021_23-59-00.ogg"}
+                ]}
+            ]}
+        }"#;
+        let messages = parse_export(full)?;
+        let info = &messages["chats/chat_01/voice_messages/audio_7@31-12-2021_23-59-00.ogg"];
+        assert_eq!(info.chat_name.as_deref(), Some("Team"));
+        assert_eq!(info.date, Local.with_ymd_and_hms(2021, 12, 31, 23, 59, 0).earliest().map(|d| d.timestamp()));
+
+        assert!(parse_export("not json").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_filename_date() {
+        assert_eq!(
+            parse_filename_date("audio_3@15-04-2023_10-22-31.ogg"),
+            Local.with_ymd_and_hms(2023, 4, 15, 10, 22, 31).earliest().map(|d| d.timestamp())
+        );
+        assert_eq!(parse_filename_date("audio_3.ogg"), None);
+        assert_eq!(parse_filename_date("audio_3@yesterday.ogg"), None);
+    }
+}
@@ -0,0 +1,111 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Descriptive titles written by the local LLM from a slice's whole transcript, as an
+//! alternative to naming it after its first few seconds (`transcribe_for_name`).
+
+use anyhow::{anyhow, Result};
+
+use super::config::Config;
+use super::models::{FolderImportProgress, LlmBatchReport, Slice};
+use super::ollama::{open_db, run_over_slices, transcript, Ollama};
+use super::summarize::transcript_or_summary;
+
+const TITLE_INSTRUCTIONS: &str = "You write titles for voice recordings. Given a transcript, or a summary of \
+one, reply with a single concise, descriptive title of at most eight words, in the transcript's language. \
+Reply with the title only, without quotes or a trailing period.";
+
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Make the model's reply usable as a title: its first line, without quotes, a
+/// "Title:" label, a trailing period or characters that don't belong in a file name.
+pub fn clean_title(reply: &str) -> Option<String> {
+    let line = reply.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line
+        .strip_prefix("Title:")
+        .or_else(|| line.strip_prefix("title:"))
+        .unwrap_or(line)
+        .trim()
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '*' | '#' | '“' | '”'))
+        .trim_end_matches('.')
+        .replace(|c: char| matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'), "");
+    let title: String = line.trim().chars().take(MAX_TITLE_CHARS).collect();
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Ask the model for a title for `slice`, from its transcript, or from its summary when
+/// the transcript is long.
+pub async fn generate_title(ollama: &Ollama, slice: &Slice) -> Result<String> {
+    let context = transcript_or_summary(ollama, slice).await?;
+    let reply = ollama.generate(Some(TITLE_INSTRUCTIONS), &context).await?;
+    clean_title(&reply).ok_or_else(|| anyhow!("{} didn't return a usable title", ollama.model()))
+}
+
+/// Generate a title for one slice and set it; the old title stays in the slice's history.
+pub async fn title_slice(config: &Config, ollama: &Ollama, slice_id: i64) -> Result<String> {
+    let slice = open_db(config)?
+        .get_slice(slice_id)?
+        .ok_or_else(|| anyhow!("No slice found with ID: {}", slice_id))?;
+    let title = generate_title(ollama, &slice).await?;
+    open_db(config)?.update_recording_title_by_slice(slice_id, &title)?;
+    Ok(title)
+}
+
+/// Title slices one after another, reporting each through `on_progress`: the given ones,
+/// or with `None` every transcribed slice that has no title. Slices that already have a
+/// title are left alone unless `overwrite`.
+pub async fn title_slices(
+    config: &Config,
+    slice_ids: Option<Vec<i64>>,
+    overwrite: bool,
+    on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<LlmBatchReport> {
+    let ollama = Ollama::new(config)?;
+    let ollama = &ollama;
+    let slice_ids = match slice_ids {
+        Some(ids) => ids,
+        None => open_db(config)?.list_untitled_slice_ids()?,
+    };
+    run_over_slices(
+        config,
+        "Titling",
+        &slice_ids,
+        |slice| {
+            let titled = slice.title.as_deref().is_some_and(|t| !t.trim().is_empty());
+            transcript(slice).is_none() || (titled && !overwrite)
+        },
+        move |slice| async move {
+            title_slice(config, ollama, slice.id.unwrap_or_default()).await.map(|_| ())
+        },
+        on_progress,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(clean_title("\"Budget review with Sam.\"").as_deref(), Some("Budget review with Sam"));
+        assert_eq!(clean_title("\nTitle: Q3 planning\nBecause the memo...").as_deref(), Some("Q3 planning"));
+        assert_eq!(clean_title("Notes: what/why?").as_deref(), Some("Notes whatwhy"));
+        assert_eq!(clean_title("   \n  "), None);
+        assert_eq!(clean_title(&"word ".repeat(40)).unwrap().chars().count(), MAX_TITLE_CHARS - 1);
+    }
+}
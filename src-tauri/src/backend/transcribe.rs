@@ -26,7 +26,9 @@ use std::env;
 
 use super::config::Config;
 use super::database::Database;
+use super::label_prompts;
 use super::logging;
+use super::nlm_upload;
 use super::models::{Transcript, TranscriptionProgress};
 
 // Global transcription progress state
@@ -317,6 +319,45 @@ impl<'a> TranscriptionEngine<'a> {
         Ok(())
     }
 
+    /// Transcribe freshly imported slices in a run of their own, with the usual progress
+    /// tracking. Leaves them untranscribed (returning false) if another run is active.
+    pub fn transcribe_imported_slices(&self, slice_ids: &[i64]) -> Result<bool> {
+        if get_transcription_progress().is_some_and(|p| p.is_active) {
+            tracing::info!("Transcription already running, leaving {} imported slice(s) untranscribed", slice_ids.len());
+            return Ok(false);
+        }
+
+        let slices: Vec<_> = slice_ids
+            .iter()
+            .filter_map(|&id| self.db.get_slice(id).transpose())
+            .collect::<Result<_>>()?;
+        init_transcription_progress_with_logging(
+            slice_ids,
+            slice_ids.len() as u32,
+            slices.iter().map(|s| s.estimated_time_to_transcribe as u32).sum(),
+            self.db.get_transcription_speed().unwrap_or(34000.0),
+            slices.iter().map(|s| slice_audio_seconds(s.audio_time_length_seconds, s.audio_file_size)).sum(),
+            &self.config.model_name,
+        );
+
+        for slice_id in slice_ids {
+            wait_if_paused();
+            if is_stop_requested() {
+                break;
+            }
+            match self.transcribe_slice_sync(*slice_id) {
+                Ok(()) => mark_slice_completed(),
+                Err(_) if is_stop_requested() => break,
+                Err(e) => {
+                    tracing::error!("Failed to transcribe imported slice {}: {}", slice_id, e);
+                    mark_slice_failed();
+                }
+            }
+        }
+        clear_transcription_progress();
+        Ok(true)
+    }
+
     pub fn transcribe_recordings(&self, recording_ids: Vec<i64>) -> Result<()> {
         // For now, process sequentially to avoid thread safety issues with SQLite
         // TODO: Implement proper thread-safe database access or use a connection pool
@@ -389,11 +430,7 @@ impl<'a> TranscriptionEngine<'a> {
 
     async fn transcribe_single_slice(&self, slice_id: i64) -> Result<()> {
         // Get slice from database
-        let slices = self.db.list_all_slices()?;
-        let slice = slices
-            .into_iter()
-            .find(|s| s.id == Some(slice_id))
-            .context("Slice not found")?;
+        let slice = self.db.get_slice(slice_id)?.context("Slice not found")?;
 
         // Construct audio path from slice filename
         let audio_path = self.config.audio_dir().join(&slice.original_audio_file_name);
@@ -413,26 +450,37 @@ impl<'a> TranscriptionEngine<'a> {
         let word_count = transcribed_text.split_whitespace().count() as i32;
 
         // Update slice record with transcription results
-        self.db.update_slice_transcription(
-            slice_id,
-            &transcribed_text,
-            transcription_time_taken,
-            word_count,
-            &self.config.model_name,
-        )?;
+        self.store_transcription(slice_id, &transcribed_text, transcription_time_taken, word_count)?;
 
         tracing::info!("Successfully transcribed slice {} ({} words in {}s)",
                       slice_id, word_count, transcription_time_taken);
+        self.start_follow_up_work();
         Ok(())
     }
 
+    /// Save a finished transcript and queue what follows it: the upload to the auto-upload
+    /// notebook and the NotebookLM notebooks its labels are mapped to, and its labels'
+    /// prompts. Queuing never fails the transcription; nothing runs until
+    /// `start_follow_up_work`.
+    fn store_transcription(&self, slice_id: i64, text: &str, time_taken: i32, word_count: i32) -> Result<()> {
+        self.db.update_slice_transcription(slice_id, text, time_taken, word_count, &self.config.model_name)?;
+        if let Err(e) = nlm_upload::queue_transcript_uploads(self.db, slice_id, self.config.nlm_auto_upload_notebook.as_deref()) {
+            tracing::warn!("Failed to queue NotebookLM uploads for slice {}: {}", slice_id, e);
+        }
+        label_prompts::queue_slice(self.db, slice_id);
+        Ok(())
+    }
+
+    /// Start the background work `store_transcription` queued: uploads (retried when they
+    /// fail) and label prompts. Each worker picks up whatever else is queued.
+    fn start_follow_up_work(&self) {
+        nlm_upload::spawn_queue_worker(self.config.clone());
+        label_prompts::spawn_worker(self.config.clone());
+    }
+
     pub fn transcribe_slice_sync(&self, slice_id: i64) -> Result<()> {
         // Get slice from database
-        let slices = self.db.list_all_slices()?;
-        let slice = slices
-            .into_iter()
-            .find(|s| s.id == Some(slice_id))
-            .context("Slice not found")?;
+        let slice = self.db.get_slice(slice_id)?.context("Slice not found")?;
 
         // Construct audio path from slice filename
         let audio_path = self.config.audio_dir().join(&slice.original_audio_file_name);
@@ -467,13 +515,7 @@ impl<'a> TranscriptionEngine<'a> {
         );
 
         // Update slice record with transcription results
-        self.db.update_slice_transcription(
-            slice_id,
-            &transcribed_text,
-            transcription_time_taken,
-            word_count,
-            &self.config.model_name,
-        )?;
+        self.store_transcription(slice_id, &transcribed_text, transcription_time_taken, word_count)?;
 
         // Log to JSON log
         logging::log_transcription_slice(
@@ -487,16 +529,13 @@ impl<'a> TranscriptionEngine<'a> {
 
         tracing::info!("Successfully transcribed slice {} ({} words in {}s)",
                       slice_id, word_count, transcription_time_taken);
+        self.start_follow_up_work();
         Ok(())
     }
 
     pub async fn transcribe_slice_async(&self, slice_id: i64) -> Result<()> {
         // Get slice from database
-        let slices = self.db.list_all_slices()?;
-        let slice = slices
-            .into_iter()
-            .find(|s| s.id == Some(slice_id))
-            .context("Slice not found")?;
+        let slice = self.db.get_slice(slice_id)?.context("Slice not found")?;
 
         // Construct audio path from slice filename
         let audio_path = self.config.audio_dir().join(&slice.original_audio_file_name);
@@ -519,7 +558,8 @@ impl<'a> TranscriptionEngine<'a> {
                       slice_id, time_taken, word_count);
 
         // Update the slice in the database
-        self.db.update_slice_transcription(slice_id, &transcription, time_taken as i32, word_count as i32, &self.config.model_name)?;
+        self.store_transcription(slice_id, &transcription, time_taken as i32, word_count as i32)?;
+        self.start_follow_up_work();
 
         Ok(())
     }
@@ -892,11 +932,7 @@ impl<'a> TranscriptionEngine<'a> {
     /// Transcribe the first N seconds of a slice's audio and return text suitable for a filename
     pub fn transcribe_for_name(&self, slice_id: i64, duration_seconds: u32) -> Result<String> {
         // Get slice from database
-        let slices = self.db.list_all_slices()?;
-        let slice = slices
-            .into_iter()
-            .find(|s| s.id == Some(slice_id))
-            .context("Slice not found")?;
+        let slice = self.db.get_slice(slice_id)?.context("Slice not found")?;
 
         // Construct audio path from slice filename
         let audio_path = self.config.audio_dir().join(&slice.original_audio_file_name);
@@ -953,6 +989,7 @@ pub struct TranscribeProgress {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::database::create_test_slice;
     use tempfile::TempDir;
     use std::fs;
     
@@ -1025,6 +1062,14 @@ mod tests {
             transcription_word_count: None,
             transcription_model: None,
             recording_date: None,
+            source: None,
+            starred: false,
+            was_edited: false,
+            content_hash: None,
+            source_relative_path: None,
+            notes: None,
+            pinned: false,
+            summary: None,
         };
 
         let slice_id = db.insert_slice(&slice).unwrap();
@@ -1184,6 +1229,14 @@ mod tests {
             transcription_word_count: None,
             transcription_model: None,
             recording_date: None,
+            source: None,
+            starred: false,
+            was_edited: false,
+            content_hash: None,
+            source_relative_path: None,
+            notes: None,
+            pinned: false,
+            summary: None,
         };
 
         let slice_id = db.insert_slice(&slice).unwrap();
@@ -1198,4 +1251,31 @@ mod tests {
         assert!(result.is_err());
         println!("transcribe_slice_sync method works correctly (failed as expected with fake audio)");
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_stored_transcription_queues_notebook_uploads() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            ciderpress_home: temp_dir.path().to_string_lossy().to_string(),
+            nlm_auto_upload_notebook: Some("inbox-nb".to_string()),
+            ..Config::default()
+        };
+        let db = Database::new(temp_dir.path().join("test.db")).unwrap();
+        let slice_id = db.insert_slice(&create_test_slice("memo.m4a")).unwrap();
+        let label_id = db.get_or_create_label("Meetings", "#228be6").unwrap();
+        db.assign_label(label_id, &[slice_id]).unwrap();
+        db.set_label_notebook(label_id, Some("meetings-nb")).unwrap();
+
+        let engine = TranscriptionEngine::new(&config, &db);
+        engine.store_transcription(slice_id, "notes from the call", 3, 4).unwrap();
+
+        assert!(db.get_slice(slice_id).unwrap().unwrap().transcribed);
+        let mut notebooks: Vec<String> = db.pending_nlm_batches().unwrap().iter()
+            .flat_map(|batch_id| db.get_nlm_batch_items(batch_id).unwrap())
+            .inspect(|item| assert!(item.queued && item.slice_id == slice_id))
+            .map(|item| item.notebook_id)
+            .collect();
+        notebooks.sort();
+        assert_eq!(notebooks, vec!["inbox-nb", "meetings-nb"]);
+    }
+}
@@ -0,0 +1,115 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Transcript translations from the local LLM, kept per slice and language. The model is
+//! `config.translation_model` when set, since a multilingual model may suit translation
+//! better than the one used for summaries.
+
+use anyhow::{anyhow, Result};
+
+use super::config::Config;
+use super::models::SliceTranslation;
+use super::nlm_upload::{split_ranges, text_hash};
+use super::ollama::{open_db, transcript, Ollama};
+
+/// Most of a transcript translated in one request. Parts don't overlap, so the
+/// translation reads through without repeats.
+const CHUNK_CHARS: usize = 4_000;
+
+/// How a target language is stored: trimmed and lowercased, so "ES" and "es" are one.
+pub fn normalize_language(language: &str) -> String {
+    language.trim().to_lowercase()
+}
+
+fn instructions(language: &str) -> String {
+    format!(
+        "You translate transcripts of voice recordings into {}. Keep the meaning, tone and paragraph breaks, \
+leave names as they are, and reply with the translation only.",
+        language
+    )
+}
+
+/// Translate `text` into `language`, part by part if it is long.
+pub async fn translate_text(ollama: &Ollama, text: &str, language: &str) -> Result<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let instructions = instructions(language);
+    let mut parts = Vec::new();
+    for (start, end) in split_ranges(&chars, CHUNK_CHARS, 0) {
+        let part: String = chars[start..end].iter().collect();
+        parts.push(ollama.generate(Some(&instructions), &part).await?);
+    }
+    Ok(parts.join("\n"))
+}
+
+/// Translate a slice's transcript into `target_lang` and store it. A stored translation of
+/// the current transcript is returned as is unless `overwrite`.
+pub async fn translate_slice(config: &Config, slice_id: i64, target_lang: &str, overwrite: bool) -> Result<SliceTranslation> {
+    let language = normalize_language(target_lang);
+    if language.is_empty() {
+        return Err(anyhow!("No target language given"));
+    }
+    let slice = open_db(config)?
+        .get_slice(slice_id)?
+        .ok_or_else(|| anyhow!("No slice found with ID: {}", slice_id))?;
+    let text = transcript(&slice).ok_or_else(|| anyhow!("Slice {} has no transcription", slice_id))?;
+    let source_hash = text_hash(text);
+
+    if !overwrite {
+        let stored = open_db(config)?
+            .list_slice_translations(slice_id)?
+            .into_iter()
+            .find(|t| t.language == language && t.source_hash == source_hash);
+        if let Some(translation) = stored {
+            return Ok(translation);
+        }
+    }
+
+    let ollama = Ollama::new(config)?.with_model(config.translation_model.as_deref());
+    let translated = translate_text(&ollama, text, target_lang.trim()).await?;
+    if translated.trim().is_empty() {
+        return Err(anyhow!("{} returned an empty translation", ollama.model()));
+    }
+    let translation = SliceTranslation {
+        slice_id,
+        language,
+        text: translated,
+        model: ollama.model().to_string(),
+        source_hash,
+        translated_at: chrono::Utc::now().timestamp(),
+    };
+    open_db(config)?.save_slice_translation(&translation)?;
+    Ok(translation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language("  ES "), "es");
+        assert_eq!(normalize_language("Brazilian Portuguese"), "brazilian portuguese");
+    }
+
+    #[test]
+    fn test_long_transcripts_split_without_overlap() {
+        let chars: Vec<char> = "word ".repeat(2_000).chars().collect();
+        let ranges = split_ranges(&chars, CHUNK_CHARS, 0);
+        assert!(ranges.len() > 1);
+        assert!(ranges.windows(2).all(|w| w[0].1 == w[1].0));
+        assert_eq!(ranges.last().unwrap().1, chars.len());
+    }
+}
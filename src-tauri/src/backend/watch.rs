@@ -0,0 +1,163 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use super::config::Config;
+use super::migrate::MigrationEngine;
+use super::models::WatchModeEvent;
+
+/// How long the container must be quiet before a migration is triggered.
+/// Voice Memos writes a new recording in several steps, so wait for it to settle.
+const DEBOUNCE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Active watcher; dropping it stops the watch and ends the worker thread
+lazy_static::lazy_static! {
+    static ref WATCHER: Arc<Mutex<Option<RecommendedWatcher>>> = Arc::new(Mutex::new(None));
+}
+
+fn emit(status: &str, message: &str, copied: u32) {
+    crate::emit_watch_mode_event(&WatchModeEvent {
+        status: status.to_string(),
+        message: message.to_string(),
+        copied,
+    });
+}
+
+/// True if the event signals a new or changed .m4a file.
+fn is_new_recording_event(event: &Event) -> bool {
+    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+        && event.paths.iter().any(|p| is_m4a(p))
+}
+
+fn is_m4a(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("m4a")
+}
+
+pub fn is_watching() -> bool {
+    WATCHER.lock().map(|w| w.is_some()).unwrap_or(false)
+}
+
+/// Start watching the configured Voice Memos container, replacing any previous watcher.
+pub fn start_watcher(config: &Config) -> Result<()> {
+    stop_watcher();
+
+    let root: PathBuf = config.voice_memo_root_path();
+    if !root.exists() {
+        return Err(anyhow::anyhow!("Voice memo directory does not exist: {:?}", root));
+    }
+
+    let (tx, rx) = mpsc::channel::<()>();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) if is_new_recording_event(&event) => {
+            let _ = tx.send(());
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Watch mode event error: {}", e),
+    })
+    .context("Failed to create filesystem watcher")?;
+
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {:?}", root))?;
+
+    let config = config.clone();
+    std::thread::spawn(move || run_worker(config, rx));
+
+    *WATCHER.lock().unwrap() = Some(watcher);
+    info!("Watch mode started on {:?}", root);
+    emit("watching", &format!("Watching {} for new recordings", root.display()), 0);
+    Ok(())
+}
+
+pub fn stop_watcher() {
+    let previous = WATCHER.lock().unwrap().take();
+    if previous.is_some() {
+        info!("Watch mode stopped");
+        emit("stopped", "Watch mode stopped", 0);
+    }
+}
+
+/// Start or stop the watcher to match the `watch_mode_enabled` setting.
+pub fn apply_config(config: &Config) {
+    if config.watch_mode_enabled {
+        if let Err(e) = start_watcher(config) {
+            error!("Failed to start watch mode: {}", e);
+            emit("error", &e.to_string(), 0);
+        }
+    } else {
+        stop_watcher();
+    }
+}
+
+/// Waits for change notifications, debounces them and runs an incremental migration.
+/// Exits once the watcher (and with it the sender) is dropped.
+fn run_worker(config: Config, rx: mpsc::Receiver<()>) {
+    while rx.recv().is_ok() {
+        // Drain until the container has been quiet for a full interval
+        loop {
+            match rx.recv_timeout(DEBOUNCE_INTERVAL) {
+                Ok(()) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        info!("Watch mode detected new recordings, starting incremental migration");
+        emit("migration_started", "New recordings detected, migrating...", 0);
+
+        // Waits for a migration the user or the scheduler already started
+        match MigrationEngine::new(&config).start_migration() {
+            Ok(summary) => {
+                let message = format!("Migrated {} new recording(s)", summary.copied);
+                info!("{}", message);
+                emit("migration_completed", &message, summary.copied);
+            }
+            Err(e) => {
+                error!("Watch mode migration failed: {}", e);
+                *MigrationEngine::get_migration_progress_ref().lock().unwrap() = None;
+                emit("error", &format!("Migration failed: {}", e), 0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, ModifyKind, RemoveKind};
+
+    #[test]
+    fn test_is_new_recording_event() {
+        let create = Event::new(EventKind::Create(CreateKind::File)).add_path(PathBuf::from("/tmp/a.m4a"));
+        assert!(is_new_recording_event(&create));
+
+        let modify = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(PathBuf::from("/tmp/b.m4a"));
+        assert!(is_new_recording_event(&modify));
+
+        let other_file = Event::new(EventKind::Create(CreateKind::File)).add_path(PathBuf::from("/tmp/CloudRecordings.db-wal"));
+        assert!(!is_new_recording_event(&other_file));
+
+        let removed = Event::new(EventKind::Remove(RemoveKind::File)).add_path(PathBuf::from("/tmp/a.m4a"));
+        assert!(!is_new_recording_event(&removed));
+    }
+}
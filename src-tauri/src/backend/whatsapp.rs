@@ -0,0 +1,261 @@
+// VoiceMemoLiberator - Voice memo transcription and management tool
+// Copyright (C) 2026 APPSTART LLC
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::config::Config;
+use super::database::Database;
+use super::importer::{self, ImportDetails};
+use super::migrate::APPLE_EPOCH_OFFSET;
+use super::models::{FolderImportProgress, FolderImportReport};
+
+const SOURCE: &str = "whatsapp";
+
+/// Message database of the macOS WhatsApp app; maps media files to their messages.
+const CHAT_STORAGE_DB: &str = "ChatStorage.sqlite";
+
+/// Where the macOS WhatsApp app keeps received and sent media.
+pub fn default_container_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("Library/Group Containers/group.net.whatsapp.WhatsApp.shared/Message/Media")
+}
+
+/// Message date and chat for a voice note, as recorded by the WhatsApp app.
+struct MessageInfo {
+    date: Option<i64>,
+    chat_name: Option<String>,
+}
+
+/// Read the message date encoded in an exported voice note's filename, as local time:
+/// iOS exports use `00000012-AUDIO-2023-04-15-10-22-31.opus`,
+/// Android uses `PTT-20230415-WA0012.opus` (date only).
+pub fn parse_message_date(filename: &str) -> Option<i64> {
+    let stem = filename.rsplit_once('.').map_or(filename, |(stem, _)| stem);
+
+    let naive = if let Some((_, datetime)) = stem.split_once("-AUDIO-") {
+        NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d-%H-%M-%S").ok()?
+    } else {
+        let mut parts = stem.split('-');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("PTT" | "AUD"), Some(date), Some(_)) => {
+                NaiveDate::parse_from_str(date, "%Y%m%d").ok()?.and_hms_opt(0, 0, 0)?
+            }
+            _ => return None,
+        }
+    };
+
+    Local.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp())
+}
+
+/// Chat name from an export folder such as "WhatsApp Chat - Alice" or "WhatsApp Chat with Alice".
+fn chat_name_from_export(path: &Path) -> Option<String> {
+    path.ancestors().find_map(|dir| {
+        let name = dir.file_name()?.to_str()?;
+        name.strip_prefix("WhatsApp Chat - ")
+            .or_else(|| name.strip_prefix("WhatsApp Chat with "))
+            .map(|chat| chat.trim().to_string())
+    })
+}
+
+/// Look for ChatStorage.sqlite next to or above the media folder (the app container layout).
+fn find_chat_storage(folder: &Path) -> Option<PathBuf> {
+    folder.ancestors()
+        .take(3)
+        .map(|dir| dir.join(CHAT_STORAGE_DB))
+        .find(|path| path.exists())
+}
+
+/// Message dates and chat names for every opus file the app knows about, keyed by file name.
+fn load_chat_storage(db_path: &Path) -> Result<HashMap<String, MessageInfo>> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT i.ZMEDIALOCALPATH, m.ZMESSAGEDATE, s.ZPARTNERNAME
+        FROM ZWAMEDIAITEM i
+        JOIN ZWAMESSAGE m ON i.ZMESSAGE = m.Z_PK
+        LEFT JOIN ZWACHATSESSION s ON m.ZCHATSESSION = s.Z_PK
+        WHERE i.ZMEDIALOCALPATH LIKE '%.opus'
+        "#,
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<f64>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+        ))
+    })?;
+
+    let mut messages = HashMap::new();
+    for row in rows {
+        let (local_path, date, chat_name) = row?;
+        if let Some(file_name) = Path::new(&local_path).file_name().and_then(|n| n.to_str()) {
+            messages.insert(file_name.to_string(), MessageInfo {
+                date: date.map(|d| d as i64 + APPLE_EPOCH_OFFSET),
+                chat_name,
+            });
+        }
+    }
+    Ok(messages)
+}
+
+/// Import every WhatsApp voice note under `folder` — an exported chat or the app's media
+/// container — converting each to `.m4a`. Notes already imported from this folder are skipped.
+pub fn import_voice_notes(
+    config: &Config,
+    db: &Database,
+    folder: &Path,
+    on_progress: impl FnMut(&FolderImportProgress),
+) -> Result<FolderImportReport> {
+    if !folder.is_dir() {
+        return Err(anyhow::anyhow!("Folder not found: {:?}", folder));
+    }
+
+    let messages = match find_chat_storage(folder) {
+        Some(db_path) => load_chat_storage(&db_path).unwrap_or_else(|e| {
+            warn!("Could not read WhatsApp message database {:?}: {}", db_path, e);
+            HashMap::new()
+        }),
+        None => HashMap::new(),
+    };
+
+    let files = importer::scan_files_with_extensions(folder, &["opus"]);
+    info!("WhatsApp import: {} voice notes found in {:?}", files.len(), folder);
+
+    importer::import_tracked_files(config, db, folder, &files, SOURCE, |path| describe_note(path, &messages), on_progress)
+}
+
+/// Title and recording date for a voice note: the app's message database first,
+/// then the export filename, then the file's modification time.
+fn describe_note(path: &Path, messages: &HashMap<String, MessageInfo>) -> ImportDetails {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let message = messages.get(file_name);
+
+    let recording_date = message.and_then(|m| m.date)
+        .or_else(|| parse_message_date(file_name))
+        .or_else(|| importer::file_modified_timestamp(path));
+    let title = match message.and_then(|m| m.chat_name.clone()).or_else(|| chat_name_from_export(path)) {
+        Some(chat) => format!("WhatsApp voice note - {}", chat),
+        None => "WhatsApp voice note".to_string(),
+    };
+    ImportDetails {
+        stem: path.file_stem().and_then(|s| s.to_str()).unwrap_or(file_name).to_string(),
+        title,
+        recording_date,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn local_timestamp(y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> i64 {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, s).earliest().unwrap().timestamp()
+    }
+
+    #[test]
+    fn test_parse_message_date() {
+        assert_eq!(
+            parse_message_date("00000012-AUDIO-2023-04-15-10-22-31.opus"),
+            Some(local_timestamp(2023, 4, 15, 10, 22, 31))
+        );
+        assert_eq!(
+            parse_message_date("PTT-20230415-WA0012.opus"),
+            Some(local_timestamp(2023, 4, 15, 0, 0, 0))
+        );
+        assert_eq!(parse_message_date("AUD-20211231-WA0001.opus"), Some(local_timestamp(2021, 12, 31, 0, 0, 0)));
+        assert_eq!(parse_message_date("3F2504E0-4F89-11D3-9A0C-0305E82C3301.opus"), None);
+        assert_eq!(parse_message_date("PTT-2023xx15-WA0012.opus"), None);
+    }
+
+    #[test]
+    fn test_chat_name_from_export() {
+        let path = Path::new("/exports/WhatsApp Chat - Alice/00000012-AUDIO-2023-04-15-10-22-31.opus");
+        assert_eq!(chat_name_from_export(path), Some("Alice".to_string()));
+        let path = Path::new("/exports/WhatsApp Chat with Bob Smith/PTT-20230415-WA0012.opus");
+        assert_eq!(chat_name_from_export(path), Some("Bob Smith".to_string()));
+        assert_eq!(chat_name_from_export(Path::new("/tmp/PTT-20230415-WA0012.opus")), None);
+    }
+
+    #[test]
+    fn test_load_chat_storage_maps_media_to_messages() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let db_path = temp_dir.path().join(CHAT_STORAGE_DB);
+        let conn = Connection::open(&db_path)?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE ZWACHATSESSION (Z_PK INTEGER PRIMARY KEY, ZPARTNERNAME TEXT);
+            CREATE TABLE ZWAMESSAGE (Z_PK INTEGER PRIMARY KEY, ZMESSAGEDATE REAL, ZCHATSESSION INTEGER);
+            CREATE TABLE ZWAMEDIAITEM (Z_PK INTEGER PRIMARY KEY, ZMESSAGE INTEGER, ZMEDIALOCALPATH TEXT);
+            INSERT INTO ZWACHATSESSION VALUES (1, 'Family');
+            INSERT INTO ZWAMESSAGE VALUES (10, 700000000.5, 1);
+            INSERT INTO ZWAMESSAGE VALUES (11, 700000100.0, NULL);
+            INSERT INTO ZWAMEDIAITEM VALUES (1, 10, 'Media/123@s.whatsapp.net/a/b/note.opus');
+            INSERT INTO ZWAMEDIAITEM VALUES (2, 11, 'Media/456@s.whatsapp.net/c/d/other.opus');
+            INSERT INTO ZWAMEDIAITEM VALUES (3, 11, 'Media/456@s.whatsapp.net/c/d/photo.jpg');
+            "#,
+        )?;
+        drop(conn);
+
+        // Found from the media folder two levels below the container
+        let media = temp_dir.path().join("Message").join("Media");
+        fs::create_dir_all(&media)?;
+        assert_eq!(find_chat_storage(&media), Some(db_path.clone()));
+
+        let messages = load_chat_storage(&db_path)?;
+        assert_eq!(messages.len(), 2);
+        let note = &messages["note.opus"];
+        assert_eq!(note.date, Some(700_000_000 + APPLE_EPOCH_OFFSET));
+        assert_eq!(note.chat_name.as_deref(), Some("Family"));
+        assert_eq!(messages["other.opus"].chat_name, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_undecodable_note_is_reported_and_retried() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let export = temp_dir.path().join("WhatsApp Chat - Alice");
+        fs::create_dir_all(&export)?;
+        fs::write(export.join("00000012-AUDIO-2023-04-15-10-22-31.opus"), b"not really opus")?;
+        fs::write(export.join("_chat.txt"), b"[15/04/2023, 10:22:31] Alice: <attached>")?;
+
+        let config = Config {
+            ciderpress_home: temp_dir.path().join("ciderpress").to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        config.ensure_ciderpress_home()?;
+        let db = Database::new(temp_dir.path().join("test.db"))?;
+
+        let report = import_voice_notes(&config, &db, &export, |_| {})?;
+        assert!(report.imported_slice_ids.is_empty());
+        assert_eq!(report.errors.len(), 1);
+
+        // A failed note isn't recorded, so the next run tries it again
+        let root = export.to_string_lossy().to_string();
+        assert!(!db.source_file_seen(&root, "00000012-AUDIO-2023-04-15-10-22-31.opus")?);
+        assert!(!config.ciderpress_home_path().join("conversion_staging")
+            .join("00000012-AUDIO-2023-04-15-10-22-31.m4a").exists());
+        Ok(())
+    }
+}
@@ -14,22 +14,49 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Mutex, OnceLock};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{State, AppHandle, Emitter, Manager};
 use tracing::{info, error};
 
 mod backend;
 
 use backend::{
-    config::{Config, VoiceMemoValidation},
-    database::Database,
+    apple_notes,
+    backup,
+    config::{Config, KeywordMatchMode, VoiceMemoValidation},
+    db_pool::{self, DbPool, PooledDatabase},
+    date_query,
+    drive,
+    duplicates,
+    email,
+    encryption,
+    export,
     logging,
-    migrate::{MigrationEngine, get_audio_duration},
+    meetings,
+    importer::{self, ImportOutcome},
+    inbox,
+    label_suggestions,
+    labeling,
+    library,
+    ios_backup::{self, IosBackup},
+    migrate::{self, MigrationEngine, get_audio_duration},
+    podcast,
+    portable,
+    recorders::{self, RecorderApp},
     transcribe::{TranscriptionEngine, get_transcription_progress as get_transcription_progress_fn},
+    scheduler,
     stats,
-    models::{ApiError, MigrationProgress, TranscriptionProgress, TranscriptionEstimate, SliceEstimate, Stats, RecordingWithTranscript, Slice, PreMigrationStats, Label, MigrationLogEntry, ModelDownloadProgress},
+    sync_export,
+    telegram,
+    watch,
+    whatsapp,
+    nlm_audio,
+    nlm_notes,
+    nlm_upload,
+    pii,
+    models::{ApiError, AudioOverviewProgress, AutoLabelReport, DriveFile, DriveSyncReport, BackupRestoreReport, Collection, DatabaseCheckReport, DerivedDocument, DuplicateGroup, EntityCount, EntityKind, FolderImportReport, LibraryExportReport, LibraryRepairOptions, LibraryVerifyReport, MigrationProgress, PiiRedactionReport, PiiScanReport, PodcastFeed, TranscriptionProgress, TranscriptionEstimate, SliceEstimate, Stats, RecordingWithTranscript, Slice, SlicePage, SliceQuery, SearchHistoryEntry, SearchResultPage, PreMigrationStats, Label, LabelSuggestion, MigrationFileEvent, MigrationLogEntry, MigrationRollbackReport, MigrationSummary, SliceDeleteReport, SliceDetails, SliceEntity, SliceSentiment, SliceTranslation, RecentSlice, SyncExportReport, TrashedSlice, SliceChange, TranscriptAnswer, TranscriptPassage, TranscriptReplacement, ReplaceReport, VacuumReport, ModelDownloadProgress, LabelNotebook, LabelPrompt, LlmBatchReport, NlmBatchItem, NlmBatchProgress, NlmBatchReport, NlmInvocation, NlmOutputLine, NlmSource, NlmSourceKind, NlmSourcePart, NlmUpload, WatchModeEvent},
 };
 use walkdir::WalkDir;
 
@@ -53,10 +80,79 @@ pub fn emit_migration_log(message: &str, level: &str) {
     }
 }
 
+/// Emit the outcome of one migrated file, with running totals, to the frontend
+pub fn emit_migration_file_event(event: &MigrationFileEvent) {
+    if let Some(handle) = APP_HANDLE.get() {
+        let _ = handle.emit("migration-file", event.clone());
+    }
+}
+
+/// Emit a watch mode status change to the frontend
+pub fn emit_watch_mode_event(event: &WatchModeEvent) {
+    if let Some(handle) = APP_HANDLE.get() {
+        let _ = handle.emit("watch-mode", event.clone());
+    }
+}
+
+/// Notify the frontend that a scheduled migration brought in new recordings
+pub fn emit_scheduled_migration(summary: &MigrationSummary) {
+    if let Some(handle) = APP_HANDLE.get() {
+        let _ = handle.emit("scheduled-migration-completed", summary.clone());
+    }
+}
+
+/// Notify the frontend that files dropped in the inbox were imported (or failed to)
+pub fn emit_inbox_import(report: &FolderImportReport) {
+    if let Some(handle) = APP_HANDLE.get() {
+        let _ = handle.emit("inbox-imported", report.clone());
+    }
+}
+
+/// Forward a line of NLM command output to the frontend
+pub fn emit_nlm_output(line: &NlmOutputLine) {
+    if let Some(handle) = APP_HANDLE.get() {
+        let _ = handle.emit("nlm-output", line.clone());
+    }
+}
+
+/// Report how far along an Audio Overview import is to the frontend
+pub fn emit_audio_overview_progress(progress: &AudioOverviewProgress) {
+    if let Some(handle) = APP_HANDLE.get() {
+        let _ = handle.emit("nlm-audio-overview-progress", progress.clone());
+    }
+}
+
+/// Report one finished upload of a NotebookLM batch to the frontend
+pub fn emit_nlm_batch_progress(progress: &NlmBatchProgress) {
+    if let Some(handle) = APP_HANDLE.get() {
+        let _ = handle.emit("nlm-batch-progress", progress.clone());
+    }
+}
+
 // Application state
 pub struct AppState {
     config: Mutex<Config>,
-    db: Mutex<Option<Database>>,
+    db: Mutex<Option<DbPool>>,
+}
+
+impl AppState {
+    /// The library database's connection pool. The lock is only held to clone it,
+    /// so commands don't wait on each other's queries.
+    fn db_pool(&self) -> Result<DbPool, ApiError> {
+        let pool = self.db.lock().map_err(|e| ApiError {
+            message: format!("Failed to lock database: {}", e),
+            kind: "LockError".to_string(),
+        })?;
+        pool.clone().ok_or_else(|| ApiError {
+            message: "Database not initialized".to_string(),
+            kind: "DatabaseError".to_string(),
+        })
+    }
+
+    /// Check out a connection for the current command.
+    fn db(&self) -> Result<PooledDatabase, ApiError> {
+        Ok(self.db_pool()?.get()?)
+    }
 }
 
 #[tauri::command]
@@ -69,26 +165,38 @@ async fn get_config(state: State<'_, AppState>) -> Result<Config, ApiError> {
 }
 
 #[tauri::command]
-async fn update_config(state: State<'_, AppState>, new_config: Config) -> Result<(), ApiError> {
+async fn update_config(state: State<'_, AppState>, mut new_config: Config) -> Result<(), ApiError> {
     {
         let mut config = state.config.lock().map_err(|e| ApiError {
             message: format!("Failed to lock config: {}", e),
             kind: "LockError".to_string(),
         })?;
+        // Only enable_database_encryption changes this; a stale copy must not turn it off
+        new_config.database_encrypted = config.database_encrypted;
         *config = new_config.clone();
     }
     
     new_config.save()?;
+
+    // Watch the (possibly new) voice memo root, or stop if watch mode was turned off
+    watch::apply_config(&new_config);
+    scheduler::apply_config(&new_config);
+    inbox::apply_config(&new_config);
+    sync_export::apply_config(&new_config);
+    backend::nlm::apply_config(&new_config);
+    pii::apply_config(&new_config);
+    labeling::apply_config(&new_config);
     
     // Reinitialize database with new config
+    encryption::apply_config(&new_config);
     let db_path = new_config.ciderpress_home_path().join("CiderPress-db.sqlite");
-    let new_db = Database::new(&db_path)?;
+    let new_pool = db_pool::pool_for(&db_path)?;
     
     let mut db = state.db.lock().map_err(|e| ApiError {
         message: format!("Failed to lock database: {}", e),
         kind: "LockError".to_string(),
     })?;
-    *db = Some(new_db);
+    *db = Some(new_pool);
     
     Ok(())
 }
@@ -124,11 +232,324 @@ async fn start_migration(state: State<'_, AppState>) -> Result<(), ApiError> {
     Ok(())
 }
 
+/// Migrate one of the configured source profiles, by name.
+#[tauri::command]
+async fn migrate_source_profile(state: State<'_, AppState>, profile_name: String) -> Result<(), ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let profile = config.source_profile(&profile_name).cloned().ok_or_else(|| ApiError {
+        message: format!("No source profile named {:?}", profile_name),
+        kind: "NotFoundError".to_string(),
+    })?;
+
+    let running = MigrationEngine::get_migration_progress_ref().lock().map_err(|e| ApiError {
+        message: format!("Failed to lock migration progress: {}", e),
+        kind: "LockError".to_string(),
+    })?.is_some();
+    if running {
+        return Err(ApiError {
+            message: "A migration is already in progress".to_string(),
+            kind: "ValidationError".to_string(),
+        });
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = migrate::migrate_source_profile(&config, &profile) {
+            error!("Migration of source profile {:?} failed: {}", profile.name, e);
+            emit_migration_log(&format!("Migration of {} failed: {}", profile.name, e), "error");
+            if let Ok(mut progress) = MigrationEngine::get_migration_progress_ref().lock() {
+                *progress = None;
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_watch_mode(state: State<'_, AppState>, enabled: bool) -> Result<(), ApiError> {
+    let config = {
+        let mut config = state.config.lock().map_err(|e| ApiError {
+            message: format!("Failed to lock config: {}", e),
+            kind: "LockError".to_string(),
+        })?;
+        config.watch_mode_enabled = enabled;
+        config.clone()
+    };
+    config.save()?;
+
+    if enabled {
+        watch::start_watcher(&config)?;
+    } else {
+        watch::stop_watcher();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_migration_schedule(state: State<'_, AppState>, interval_hours: u32) -> Result<(), ApiError> {
+    let config = {
+        let mut config = state.config.lock().map_err(|e| ApiError {
+            message: format!("Failed to lock config: {}", e),
+            kind: "LockError".to_string(),
+        })?;
+        config.migration_interval_hours = interval_hours;
+        config.clone()
+    };
+    config.save()?;
+    scheduler::apply_config(&config);
+    Ok(())
+}
+
+/// Keep `folder` up to date with Markdown transcripts every `interval_minutes`, or stop
+/// when `folder` is omitted.
+#[tauri::command]
+async fn set_sync_export(
+    state: State<'_, AppState>,
+    folder: Option<String>,
+    interval_minutes: Option<u32>,
+) -> Result<(), ApiError> {
+    let config = {
+        let mut config = state.config.lock().map_err(|e| ApiError {
+            message: format!("Failed to lock config: {}", e),
+            kind: "LockError".to_string(),
+        })?;
+        config.sync_export_folder = folder;
+        if let Some(minutes) = interval_minutes {
+            config.sync_export_interval_minutes = minutes;
+        }
+        config.clone()
+    };
+    config.save()?;
+    sync_export::apply_config(&config);
+    Ok(())
+}
+
+/// Sync the export folder now instead of waiting for the next scheduled run.
+#[tauri::command]
+async fn run_sync_export(state: State<'_, AppState>) -> Result<SyncExportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    let folder = config.sync_export_path().ok_or_else(|| ApiError {
+        message: "No sync export folder is set".to_string(),
+        kind: "ConfigError".to_string(),
+    })?;
+
+    tokio::task::spawn_blocking(move || sync_export::sync_now(&config, &folder))
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Sync export task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn get_watch_mode_status() -> Result<bool, ApiError> {
+    Ok(watch::is_watching())
+}
+
+/// Set (or clear, with `None`) the inbox drop folder. Audio files dropped there are
+/// imported, optionally transcribed, and removed from the inbox; see `inbox-imported` events.
+#[tauri::command]
+async fn set_inbox_folder(
+    state: State<'_, AppState>,
+    folder_path: Option<String>,
+    auto_transcribe: bool,
+) -> Result<(), ApiError> {
+    let config = {
+        let mut config = state.config.lock().map_err(|e| ApiError {
+            message: format!("Failed to lock config: {}", e),
+            kind: "LockError".to_string(),
+        })?;
+        config.inbox_folder = folder_path;
+        config.inbox_auto_transcribe = auto_transcribe;
+        config.clone()
+    };
+    config.save()?;
+
+    if config.inbox_path().is_some() {
+        inbox::start_watcher(&config)?;
+    } else {
+        inbox::stop_watcher();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_inbox_status() -> Result<bool, ApiError> {
+    Ok(inbox::is_watching())
+}
+
+/// Stop the background work that writes to the library (watch mode, scheduled migration and
+/// trash emptying, the inbox and the sync folder export) before its database file is swapped out.
+fn stop_background_workers() {
+    watch::stop_watcher();
+    scheduler::stop();
+    inbox::stop_watcher();
+    sync_export::stop();
+}
+
+/// Start the background work `config` asks for, and the NotebookLM upload queue: at launch,
+/// and again after `stop_background_workers`.
+fn start_background_workers(config: &Config) {
+    watch::apply_config(config);
+    scheduler::apply_config(config);
+    inbox::apply_config(config);
+    sync_export::apply_config(config);
+    nlm_upload::spawn_queue_worker(config.clone());
+}
+
+/// Replace the library database with a backup file. The current database is saved to the
+/// backups folder first; commands pick up the restored one as soon as this returns.
+/// Background work is stopped for the swap, which fails if connections stay in use.
+#[tauri::command]
+async fn restore_backup(state: State<'_, AppState>, path: String) -> Result<BackupRestoreReport, ApiError> {
+    if MigrationEngine::get_migration_progress().is_some()
+        || get_transcription_progress_fn().is_some_and(|p| p.is_active)
+    {
+        return Err(ApiError {
+            message: "Cannot restore a backup while a migration or transcription is running".to_string(),
+            kind: "ValidationError".to_string(),
+        });
+    }
+
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    // Held for the whole swap so no command checks out a connection to the old file
+    let mut db = state.db.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock database: {}", e),
+        kind: "LockError".to_string(),
+    })?;
+    stop_background_workers();
+    let result = backup::restore_backup(&config, Path::new(&path));
+    start_background_workers(&config);
+    let (pool, report) = result?;
+    *db = Some(pool);
+    Ok(report)
+}
+
+/// Encrypt the library database with SQLCipher. The passphrase is kept in the keychain, so
+/// CiderPress opens the database without asking; the plaintext file is replaced, and so are
+/// the plaintext copies in the backups folder. The setting is saved before the swap (and
+/// put back if it fails), so the app never starts up reading the wrong kind of file.
+#[tauri::command]
+async fn enable_database_encryption(state: State<'_, AppState>, passphrase: String) -> Result<(), ApiError> {
+    if MigrationEngine::get_migration_progress().is_some()
+        || get_transcription_progress_fn().is_some_and(|p| p.is_active)
+    {
+        return Err(ApiError {
+            message: "Cannot encrypt the database while a migration or transcription is running".to_string(),
+            kind: "ValidationError".to_string(),
+        });
+    }
+
+    let mut config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?;
+    if config.database_encrypted {
+        return Err(ApiError {
+            message: "The database is already encrypted".to_string(),
+            kind: "ValidationError".to_string(),
+        });
+    }
+
+    let mut db = state.db.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock database: {}", e),
+        kind: "LockError".to_string(),
+    })?;
+    config.database_encrypted = true;
+    config.save()?;
+    stop_background_workers();
+    let result = encryption::enable_encryption(&config, &passphrase);
+    if result.is_err() {
+        config.database_encrypted = false;
+        if let Err(e) = config.save() {
+            error!("Failed to restore the database encryption setting: {}", e);
+        }
+    }
+    start_background_workers(&config);
+    *db = Some(result?);
+    Ok(())
+}
+
+/// Undo the most recent migration batch, e.g. after pointing at the wrong source folder.
+#[tauri::command]
+async fn rollback_last_migration(state: State<'_, AppState>) -> Result<MigrationRollbackReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        migrate::rollback_last_migration(&config, &db)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Rollback task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(|e| match e.downcast_ref::<migrate::MigrationInProgress>() {
+        Some(busy) => ApiError {
+            message: busy.to_string(),
+            kind: "ValidationError".to_string(),
+        },
+        None => ApiError::from(e),
+    })
+}
+
 #[tauri::command]
 async fn get_migration_stats() -> Result<Option<MigrationProgress>, ApiError> {
     Ok(MigrationEngine::get_migration_progress())
 }
 
+#[tauri::command]
+async fn list_ios_backups() -> Result<Vec<IosBackup>, ApiError> {
+    tokio::task::spawn_blocking(|| ios_backup::list_backups(&ios_backup::default_backup_root()))
+        .await
+        .map_err(|e| ApiError {
+            message: format!("Task failed: {}", e),
+            kind: "TaskError".to_string(),
+        })
+}
+
+/// Migrate the Voice Memos contained in a local (unencrypted) iPhone backup.
+/// Progress is reported through the regular migration progress/log channels.
+#[tauri::command]
+async fn start_ios_backup_migration(state: State<'_, AppState>, backup_path: String) -> Result<(), ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    tokio::spawn(async move {
+        if let Err(e) = ios_backup::migrate_from_backup(&config, &PathBuf::from(&backup_path)) {
+            error!("iPhone backup migration failed: {}", e);
+            emit_migration_log(&format!("iPhone backup migration failed: {}", e), "error");
+            let mut progress = MigrationEngine::get_migration_progress_ref().lock().unwrap();
+            *progress = None;
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_last_migration_summary() -> Result<Option<MigrationSummary>, ApiError> {
+    Ok(MigrationEngine::get_last_migration_summary())
+}
+
 #[tauri::command]
 async fn get_pre_migration_stats(
     state: State<'_, AppState>,
@@ -138,10 +559,7 @@ async fn get_pre_migration_stats(
         kind: "LockError".to_string(),
     })?.clone();
 
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
+    let db = state.db()?;
 
     // --- Origin (Apple Voice Memos) stats ---
     // Count actual .m4a files on disk (consistent with how migration works)
@@ -188,34 +606,32 @@ async fn get_pre_migration_stats(
     let mut not_transcribed_count: u32 = 0;
     let mut existing_slice_filenames: std::collections::HashSet<String> = std::collections::HashSet::new();
 
-    if let Some(db) = db_guard.as_ref() {
-        // Get slice statistics
-        if let Ok(slices) = db.list_all_slices() {
-            destination_total_files = slices.len() as u32;
-            transcribed_count = slices.iter().filter(|s| s.transcribed).count() as u32;
-            not_transcribed_count = destination_total_files - transcribed_count;
+    // Get slice statistics
+    if let Ok(slices) = db.list_all_slices() {
+        destination_total_files = slices.len() as u32;
+        transcribed_count = slices.iter().filter(|s| s.transcribed).count() as u32;
+        not_transcribed_count = destination_total_files - transcribed_count;
 
-            // Collect existing filenames
-            for slice in &slices {
-                existing_slice_filenames.insert(slice.original_audio_file_name.clone());
-            }
+        // Collect existing filenames
+        for slice in &slices {
+            existing_slice_filenames.insert(slice.original_audio_file_name.clone());
         }
+    }
 
-        // Get most recent audio file date from the audio directory
-        let audio_dir = config.audio_dir();
-        if audio_dir.exists() {
-            let mut most_recent: Option<std::time::SystemTime> = None;
-            if let Ok(entries) = std::fs::read_dir(&audio_dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_file() {
-                        if let Some(ext) = path.extension() {
-                            if ext == "m4a" {
-                                if let Ok(metadata) = std::fs::metadata(&path) {
-                                    if let Ok(modified) = metadata.modified() {
-                                        if most_recent.is_none() || modified > most_recent.unwrap() {
-                                            most_recent = Some(modified);
-                                        }
+    // Get most recent audio file date from the audio directory
+    let audio_dir = config.audio_dir();
+    if audio_dir.exists() {
+        let mut most_recent: Option<std::time::SystemTime> = None;
+        if let Ok(entries) = std::fs::read_dir(&audio_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_file() {
+                    if let Some(ext) = path.extension() {
+                        if ext == "m4a" {
+                            if let Ok(metadata) = std::fs::metadata(&path) {
+                                if let Ok(modified) = metadata.modified() {
+                                    if most_recent.is_none() || modified > most_recent.unwrap() {
+                                        most_recent = Some(modified);
                                     }
                                 }
                             }
@@ -223,11 +639,11 @@ async fn get_pre_migration_stats(
                     }
                 }
             }
-            if let Some(time) = most_recent {
-                if let Ok(duration) = time.duration_since(std::time::UNIX_EPOCH) {
-                    if let Some(dt) = chrono::DateTime::from_timestamp(duration.as_secs() as i64, 0) {
-                        destination_most_recent_date = Some(dt.format("%Y-%m-%d %H:%M:%S").to_string());
-                    }
+        }
+        if let Some(time) = most_recent {
+            if let Ok(duration) = time.duration_since(std::time::UNIX_EPOCH) {
+                if let Some(dt) = chrono::DateTime::from_timestamp(duration.as_secs() as i64, 0) {
+                    destination_most_recent_date = Some(dt.format("%Y-%m-%d %H:%M:%S").to_string());
                 }
             }
         }
@@ -270,68 +686,382 @@ async fn get_pre_migration_stats(
 
 #[tauri::command]
 async fn clear_database(state: State<'_, AppState>) -> Result<(), ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-    
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
     
     db.clear_all_slices()?;
     info!("Database cleared successfully");
     Ok(())
 }
 
+/// Permanently delete slices along with their audio files and leftover transcription WAVs.
 #[tauri::command]
-async fn get_slice_records(state: State<'_, AppState>) -> Result<Vec<Slice>, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
+async fn delete_slices(
+    state: State<'_, AppState>,
+    slice_ids: Vec<i64>,
+) -> Result<SliceDeleteReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
         kind: "LockError".to_string(),
-    })?;
-    
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
-    
-    let slices = db.list_all_slices()?;
-    Ok(slices)
+    })?.clone();
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        library::delete_slices(&config, &db, &slice_ids)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Delete task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
 }
 
+/// Groups of slices that look like the same recording imported more than once.
 #[tauri::command]
-async fn get_stats(state: State<'_, AppState>) -> Result<Stats, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-    
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
-    
-    let stats = stats::collect_stats(db)?;
-    Ok(stats)
+async fn find_duplicate_slices(state: State<'_, AppState>) -> Result<Vec<DuplicateGroup>, ApiError> {
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        duplicates::find_duplicates(&db)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Duplicate scan task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
 }
 
+/// Move slices to the trash. They drop out of the library but keep their audio, and can be
+/// restored until the trash is emptied. Returns the number of slices moved.
 #[tauri::command]
-async fn list_recordings(
-    state: State<'_, AppState>,
-    limit: Option<u32>,
-    offset: Option<u32>,
-) -> Result<Vec<RecordingWithTranscript>, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
+async fn trash_slices(state: State<'_, AppState>, slice_ids: Vec<i64>) -> Result<u32, ApiError> {
+    let db = state.db()?;
+
+    let moved = db.trash_slices(&slice_ids, chrono::Utc::now().timestamp())?;
+    info!("Moved {} slice(s) to the trash", moved);
+    Ok(moved)
+}
+
+/// Hide slices from the library without deleting them; returns how many were archived.
+#[tauri::command]
+async fn archive_slices(state: State<'_, AppState>, slice_ids: Vec<i64>) -> Result<u32, ApiError> {
+    let db = state.db()?;
+
+    let archived = db.set_slices_archived(&slice_ids, true)?;
+    info!("Archived {} slice(s)", archived);
+    Ok(archived)
+}
+
+#[tauri::command]
+async fn unarchive_slices(state: State<'_, AppState>, slice_ids: Vec<i64>) -> Result<u32, ApiError> {
+    let db = state.db()?;
+
+    let restored = db.set_slices_archived(&slice_ids, false)?;
+    info!("Unarchived {} slice(s)", restored);
+    Ok(restored)
+}
+
+#[tauri::command]
+async fn list_trash(state: State<'_, AppState>) -> Result<Vec<TrashedSlice>, ApiError> {
+    let db = state.db()?;
+
+    let trashed = db.list_trashed_slices()?;
+    Ok(trashed)
+}
+
+/// Take slices back out of the trash; returns the number restored.
+#[tauri::command]
+async fn restore_slices(state: State<'_, AppState>, slice_ids: Vec<i64>) -> Result<u32, ApiError> {
+    let db = state.db()?;
+
+    let restored = db.restore_slices(&slice_ids)?;
+    info!("Restored {} slice(s) from the trash", restored);
+    Ok(restored)
+}
+
+/// Permanently delete what is in the trash, or with `older_than_days` only what was
+/// trashed at least that long ago.
+#[tauri::command]
+async fn empty_trash(
+    state: State<'_, AppState>,
+    older_than_days: Option<u32>,
+) -> Result<SliceDeleteReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        library::empty_trash(&config, &db, older_than_days)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Empty trash task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Bundle the whole library (database, audio and transcripts) into one archive at `dest_path`,
+/// to be imported on another Mac with `import_portable_library`.
+#[tauri::command]
+async fn export_portable_library(
+    state: State<'_, AppState>,
+    dest_path: String,
+) -> Result<LibraryExportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        portable::export_portable_library(&config, &db, Path::new(&dest_path))
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Export task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Merge a portable library archive into this library, skipping recordings already here.
+#[tauri::command]
+async fn import_portable_library(
+    state: State<'_, AppState>,
+    archive_path: String,
+) -> Result<FolderImportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        portable::import_portable_library(&config, &db, Path::new(&archive_path))
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Import task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Cross-check the slices table against the audio directory. With no `options` it only
+/// reports; see `LibraryRepairOptions` for the repairs it can make.
+#[tauri::command]
+async fn verify_library(
+    state: State<'_, AppState>,
+    options: Option<LibraryRepairOptions>,
+) -> Result<LibraryVerifyReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    let options = options.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        library::verify_library(&config, &db, &options)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Library check task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Run SQLite's integrity and foreign key checks and cross-check slices against the files
+/// on disk. Reports only; nothing is changed.
+#[tauri::command]
+async fn check_database(state: State<'_, AppState>) -> Result<DatabaseCheckReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        library::check_database(&config, &db)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Database check task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Shrink the database file after many slices were deleted or the library was cleared.
+#[tauri::command]
+async fn compact_database(state: State<'_, AppState>) -> Result<VacuumReport, ApiError> {
+    if MigrationEngine::get_migration_progress().is_some()
+        || get_transcription_progress_fn().is_some_and(|p| p.is_active)
+    {
+        return Err(ApiError {
+            message: "Cannot compact the database while a migration or transcription is running".to_string(),
+            kind: "ValidationError".to_string(),
+        });
+    }
+
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = pool.get()?;
+        library::compact_database(&db, &db_path)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Compact task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn get_slice_records(state: State<'_, AppState>, archived: Option<bool>) -> Result<Vec<Slice>, ApiError> {
+    let db = state.db()?;
     
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let slices = db.list_library_slices(archived.unwrap_or(false))?;
+    Ok(slices)
+}
+
+/// A page of the library, filtered and sorted in the database, for views that don't need
+/// every slice at once. Set `without_transcription` on the query to skip the transcript text
+/// and fetch it per slice with `get_slice_transcription`.
+#[tauri::command]
+async fn list_slices(state: State<'_, AppState>, query: Option<SliceQuery>) -> Result<SlicePage, ApiError> {
+    let db = state.db()?;
+    let query = query.unwrap_or_default();
+    note_search(&state, &db, &query);
+
+    db.query_slices(&with_dates(&query)).map_err(ApiError::from)
+}
+
+/// Like `list_slices`, but each slice also carries where the search text matched: field,
+/// character offsets and a snippet to show in context.
+#[tauri::command]
+async fn search_slices(state: State<'_, AppState>, query: SliceQuery) -> Result<SearchResultPage, ApiError> {
+    let db = state.db()?;
+    note_search(&state, &db, &query);
+
+    db.search_slices(&with_dates(&query)).map_err(ApiError::from)
+}
+
+/// The one search box: matches the query's search text against titles, transcripts, notes
+/// and label names and returns a single list, most relevant first, with where each matched.
+#[tauri::command]
+async fn ranked_search(state: State<'_, AppState>, query: SliceQuery) -> Result<SearchResultPage, ApiError> {
+    let db = state.db()?;
+    note_search(&state, &db, &query);
+
+    db.ranked_search(&with_dates(&query)).map_err(ApiError::from)
+}
+
+/// `query` with any date phrase in its search text turned into a date filter, when the
+/// query asks for that.
+fn with_dates(query: &SliceQuery) -> SliceQuery {
+    date_query::resolve(query, chrono::Local::now().date_naive())
+}
+
+/// Turn the phonetic ("sounds like") index on or off. Turning it on indexes every
+/// transcript, which can take a while on a large library. Returns the slices indexed.
+#[tauri::command]
+async fn set_phonetic_index(state: State<'_, AppState>, enabled: bool) -> Result<u32, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        db.set_phonetic_index(enabled)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Phonetic index task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn get_phonetic_index_enabled(state: State<'_, AppState>) -> Result<bool, ApiError> {
+    let db = state.db()?;
+    db.phonetic_index_enabled().map_err(ApiError::from)
+}
+
+/// Map a failed NLM call to an `ApiError`, keeping timeouts and sign-in failures apart
+/// so the frontend can tell the user what to do.
+fn nlm_api_error(e: anyhow::Error) -> ApiError {
+    let kind = e.downcast_ref::<backend::nlm::NlmError>()
+        .map(|err| err.kind())
+        .unwrap_or("NlmError");
+    ApiError {
+        message: format!("{:#}", e), // includes the attempt count when retries ran out
+        kind: kind.to_string(),
+    }
+}
+
+/// Add a query's search text to the history unless the user turned it off. Like
+/// `note_slice_opened`, a failure here never fails the search.
+fn note_search(state: &AppState, db: &PooledDatabase, query: &SliceQuery) {
+    let Some(text) = query.search.as_deref().filter(|s| !s.trim().is_empty()) else { return };
+    let enabled = state.config.lock().map(|config| config.search_history_enabled).unwrap_or(false);
+    if !enabled || query.offset.unwrap_or(0) > 0 {
+        return; // later pages of the same search aren't new searches
+    }
+    if let Err(e) = db.record_search(text, chrono::Utc::now().timestamp()) {
+        tracing::warn!("Failed to record search: {}", e);
+    }
+}
+
+/// Recent searches, newest first, optionally only those starting with `prefix`.
+#[tauri::command]
+async fn list_search_history(state: State<'_, AppState>, prefix: Option<String>, limit: Option<u32>) -> Result<Vec<SearchHistoryEntry>, ApiError> {
+    let db = state.db()?;
+    db.list_search_history(prefix.as_deref(), limit.unwrap_or(20)).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn clear_search_history(state: State<'_, AppState>) -> Result<usize, ApiError> {
+    let db = state.db()?;
+    db.clear_search_history().map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn get_stats(state: State<'_, AppState>) -> Result<Stats, ApiError> {
+    let db = state.db()?;
+    
+    let stats = stats::collect_stats(&db)?;
+    Ok(stats)
+}
+
+#[tauri::command]
+async fn list_recordings(
+    state: State<'_, AppState>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<RecordingWithTranscript>, ApiError> {
+    let db = state.db()?;
     
     let recordings = db.list_recordings(limit, offset)?;
     Ok(recordings)
@@ -344,15 +1074,7 @@ async fn search_recordings(
     limit: Option<u32>,
     offset: Option<u32>,
 ) -> Result<Vec<RecordingWithTranscript>, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-    
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
     
     let recordings = db.search_recordings(&query, limit, offset)?;
     Ok(recordings)
@@ -368,17 +1090,9 @@ async fn transcribe_many(
         kind: "LockError".to_string(),
     })?;
     
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-    
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
     
-    let transcription_engine = TranscriptionEngine::new(&config, db);
+    let transcription_engine = TranscriptionEngine::new(&config, &db);
     transcription_engine.transcribe_recordings(recording_ids)?;
     
     Ok(())
@@ -396,18 +1110,10 @@ async fn transcribe_slices(
         kind: "LockError".to_string(),
     })?.clone();
 
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
 
     // Get all slices and filter based on skip_already_transcribed setting
-    let slices = db.list_all_slices()?;
+    let slices = db.list_active_slices()?;
     let skip_transcribed = config.skip_already_transcribed;
 
     // Filter slice IDs based on whether we should skip already transcribed
@@ -456,7 +1162,7 @@ async fn transcribe_slices(
     // Spawn the transcription work in a blocking thread pool
     tokio::task::spawn_blocking(move || {
         // Create a new database connection for this task
-        match Database::new(&db_path) {
+        match db_pool::connect(&db_path) {
             Ok(db) => {
                 // Get transcription speed from historical data
                 let bytes_per_second_rate = db.get_transcription_speed().unwrap_or(34000.0);
@@ -554,15 +1260,7 @@ async fn estimate_transcription(
         config.model_name.clone()
     };
 
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
 
     // Measured history beats any static table; fall back to defaults otherwise.
     let (realtime_factor, basis) = match db.measured_realtime_factor(&model) {
@@ -570,7 +1268,7 @@ async fn estimate_transcription(
         None => (default_realtime_factor(&model), "default".to_string()),
     };
 
-    let slices = db.list_all_slices()?;
+    let slices = db.list_active_slices()?;
 
     let mut per_slice: Vec<SliceEstimate> = Vec::new();
     let mut total_seconds: f64 = 0.0;
@@ -650,36 +1348,40 @@ async fn stop_transcription() -> Result<(), ApiError> {
     Ok(())
 }
 
+/// The slices an export works on: the given IDs, or else every slice matching `query`,
+/// resolved in the database so the frontend needn't send thousands of IDs.
+fn export_selection(db: &PooledDatabase, slice_ids: Option<Vec<i64>>, query: Option<&SliceQuery>) -> anyhow::Result<Vec<i64>> {
+    match (slice_ids, query) {
+        (Some(ids), _) => Ok(ids),
+        (None, Some(query)) => db.query_slice_ids(&with_dates(query)),
+        (None, None) => Err(anyhow::anyhow!("Select slices or a filter to export")),
+    }
+}
+
 #[tauri::command]
 async fn export_transcribed_text(
     state: State<'_, AppState>,
-    slice_ids: Vec<i64>,
+    slice_ids: Option<Vec<i64>>,
+    query: Option<SliceQuery>,
+    include_notes: Option<bool>,
 ) -> Result<String, ApiError> {
+    let include_notes = include_notes.unwrap_or(false);
     let config = state.config.lock().map_err(|e| ApiError {
         message: format!("Failed to lock config: {}", e),
         kind: "LockError".to_string(),
     })?.clone();
 
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
-
-    // Get all slices
-    let all_slices = db.list_all_slices()?;
+    let db = state.db()?;
+    let slice_ids = export_selection(&db, slice_ids, query.as_ref())?;
 
-    // Filter to only the selected slices that have transcriptions, preserving order
-    let slices_to_export: Vec<&Slice> = slice_ids
-        .iter()
-        .filter_map(|id| {
-            all_slices.iter().find(|s| s.id == Some(*id) && s.transcription.is_some())
-        })
-        .collect();
+    // Only the selected slices that have transcriptions, starred first, otherwise preserving order
+    let mut slices_to_export: Vec<Slice> = Vec::new();
+    for id in &slice_ids {
+        if let Some(slice) = db.get_slice(*id)?.filter(|s| s.transcription.is_some()) {
+            slices_to_export.push(pii::for_export(slice));
+        }
+    }
+    slices_to_export.sort_by_key(|slice| !slice.starred);
 
     if slices_to_export.is_empty() {
         return Err(ApiError {
@@ -711,14 +1413,20 @@ async fn export_transcribed_text(
         let word_count = slice.transcription_word_count.unwrap_or(0);
 
         content.push_str(&format!("Title: {}\n", title));
+        if slice.starred {
+            content.push_str("Starred: Yes\n");
+        }
         content.push_str(&format!("Export Date: {}\n", export_date));
         content.push_str(&format!("Word Count: {}\n", word_count));
+        if let Some(notes) = slice.notes.as_deref().filter(|_| include_notes) {
+            content.push_str(&format!("Notes: {}\n", notes));
+        }
         content.push_str("\n");
 
         // Transcription text (strip HTML tags if present)
         if let Some(transcription) = &slice.transcription {
             // Simple HTML tag stripping
-            let plain_text = strip_html_tags(transcription);
+            let plain_text = export::strip_html_tags(transcription);
             content.push_str(&plain_text);
             content.push_str("\n");
         }
@@ -739,90 +1447,315 @@ async fn export_transcribed_text(
     Ok(export_path.to_string_lossy().to_string())
 }
 
-/// Simple HTML tag stripping helper
-fn strip_html_tags(html: &str) -> String {
-    let mut result = String::new();
-    let mut in_tag = false;
+/// Export the selected slices as a browsable folder: an `index.html` with each transcript and
+/// an audio player for its copied recording. Returns the path of the page.
+#[tauri::command]
+async fn export_html(
+    state: State<'_, AppState>,
+    slice_ids: Option<Vec<i64>>,
+    query: Option<SliceQuery>,
+) -> Result<String, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let db = state.db()?;
+    let slice_ids = export_selection(&db, slice_ids, query.as_ref())?;
 
-    for c in html.chars() {
-        match c {
-            '<' => in_tag = true,
-            '>' => {
-                in_tag = false;
-                // Add space after closing tags that typically end blocks
-            }
-            _ if !in_tag => result.push(c),
-            _ => {}
-        }
-    }
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let export_dir = config.ciderpress_home_path().join("exports").join(format!("html_export_{}", timestamp));
+
+    let report = export::export_html(&config, &db, &slice_ids, &export_dir)?;
+    let page = export_dir.join("index.html");
+
+    logging::log_export(
+        "html",
+        &slice_ids,
+        Some(page.to_string_lossy().as_ref()),
+    );
+    info!("Exported HTML page with {} audio files ({} failed) to {:?}", report.exported_files.len(), report.errors.len(), page);
 
-    // Clean up multiple whitespace and trim
-    result
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
+    Ok(page.to_string_lossy().to_string())
 }
 
+/// Bundle the selected slices' audio and transcripts into one zip with a manifest, at
+/// `dest_path` or in the exports folder. A `password` AES-encrypts the whole archive.
 #[tauri::command]
-async fn export_audio(
+async fn export_zip(
     state: State<'_, AppState>,
-    recording_ids: Vec<i64>,
-    dest_dir: String,
-    _reencode: Option<bool>,
-) -> Result<u32, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
+    slice_ids: Option<Vec<i64>>,
+    query: Option<SliceQuery>,
+    dest_path: Option<String>,
+    password: Option<String>,
+) -> Result<LibraryExportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
         kind: "LockError".to_string(),
-    })?;
-    
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
-    
-    let recordings = db.list_recordings(None, None)?;
-    let dest_path = PathBuf::from(&dest_dir);
-    
-    std::fs::create_dir_all(&dest_path)?;
-    
-    let mut exported_count = 0u32;
-    
-    for recording in recordings {
-        if recording_ids.contains(&recording.recording.id.unwrap_or(-1)) {
-            if let Some(copied_path) = &recording.recording.copied_path {
-                let source = PathBuf::from(copied_path);
-                let default_name = format!("{}.m4a", recording.recording.apple_id);
-                let filename = source.file_name().unwrap_or_else(|| {
-                    std::ffi::OsStr::new(&default_name)
-                });
-                let dest = dest_path.join(filename);
-                
-                std::fs::copy(&source, &dest)?;
-                exported_count += 1;
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let dest = match dest_path {
+            Some(path) => PathBuf::from(path),
+            None => {
+                let exports_dir = config.ciderpress_home_path().join("exports");
+                std::fs::create_dir_all(&exports_dir)?;
+                let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+                exports_dir.join(format!("slices_export_{}.zip", timestamp))
             }
-        }
-    }
-    
-    info!("Exported {} audio files to {:?}", exported_count, dest_path);
-    Ok(exported_count)
+        };
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        let slice_ids = export_selection(&db, slice_ids, query.as_ref())?;
+        let report = export::export_zip(&config, &db, &slice_ids, &dest, password.as_deref())?;
+        logging::log_export("zip", &slice_ids, Some(&report.destination));
+        Ok::<_, anyhow::Error>(report)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Export task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
 }
 
+/// Open a draft email with the selected transcripts, addressed to `to` if given. With
+/// `attach_audio` the recordings are attached as well, composed in Apple Mail.
 #[tauri::command]
-#[allow(non_snake_case)]
-async fn update_slice_name(
+async fn compose_email(
     state: State<'_, AppState>,
-    sliceId: i64,
-    newName: String,
+    slice_ids: Option<Vec<i64>>,
+    query: Option<SliceQuery>,
+    to: Option<String>,
+    attach_audio: Option<bool>,
 ) -> Result<(), ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
         kind: "LockError".to_string(),
-    })?;
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        let slice_ids = export_selection(&db, slice_ids, query.as_ref())?;
+        email::compose_email(&config, &db, &slice_ids, to.as_deref(), attach_audio.unwrap_or(false))?;
+        logging::log_export("email", &slice_ids, None);
+        Ok::<_, anyhow::Error>(())
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Export task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Create a note in Apple Notes for each selected transcribed slice, in `folder` or the
+/// configured Apple Notes folder.
+#[tauri::command]
+async fn send_to_apple_notes(
+    state: State<'_, AppState>,
+    slice_ids: Option<Vec<i64>>,
+    query: Option<SliceQuery>,
+    folder: Option<String>,
+) -> Result<LibraryExportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    let folder = folder.unwrap_or_else(|| config.apple_notes_folder.clone());
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        let slice_ids = export_selection(&db, slice_ids, query.as_ref())?;
+        let report = apple_notes::send_to_apple_notes(&db, &slice_ids, &folder)?;
+        logging::log_export("apple_notes", &slice_ids, Some(&folder));
+        Ok::<_, anyhow::Error>(report)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Export task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Export a year and/or a label's transcripts as one chronological Markdown book with a
+/// table of contents and a section per month, for printing a voice journal. Returns the
+/// written file's path.
+#[tauri::command]
+async fn export_book(
+    state: State<'_, AppState>,
+    year: Option<i32>,
+    label_id: Option<i64>,
+    title: Option<String>,
+) -> Result<String, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let db = state.db()?;
+
+    let query = SliceQuery {
+        label_id,
+        filter: year.map(export::year_filter).transpose()?.unwrap_or_default(),
+        ..SliceQuery::default()
+    };
+    let title = title.unwrap_or_else(|| match year {
+        Some(year) => format!("Voice Journal {}", year),
+        None => "Voice Journal".to_string(),
+    });
+
+    let exports_dir = config.ciderpress_home_path().join("exports");
+    std::fs::create_dir_all(&exports_dir)?;
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let export_path = exports_dir.join(format!("book_export_{}.md", timestamp));
+
+    let exported = export::export_book(&db, &query, &title, &export_path)?;
+    if exported == 0 {
+        let _ = std::fs::remove_file(&export_path);
+        return Err(ApiError {
+            message: "No transcribed slices match the selection".to_string(),
+            kind: "NoDataError".to_string(),
+        });
+    }
+
+    logging::log_export("book", &[], Some(export_path.to_string_lossy().as_ref()));
+
+    Ok(export_path.to_string_lossy().to_string())
+}
+
+/// Export the selected slices with all their fields, labels and metadata as a JSON array,
+/// or as JSONL (one object per line) when `jsonl` is set. Returns the written file's path.
+#[tauri::command]
+async fn export_slices_json(
+    state: State<'_, AppState>,
+    slice_ids: Option<Vec<i64>>,
+    query: Option<SliceQuery>,
+    jsonl: Option<bool>,
+) -> Result<String, ApiError> {
+    let jsonl = jsonl.unwrap_or(false);
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let db = state.db()?;
+    let slice_ids = export_selection(&db, slice_ids, query.as_ref())?;
+
+    let exports_dir = config.ciderpress_home_path().join("exports");
+    std::fs::create_dir_all(&exports_dir)?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let extension = if jsonl { "jsonl" } else { "json" };
+    let export_path = exports_dir.join(format!("slices_export_{}.{}", timestamp, extension));
+
+    let exported = export::export_slices_json(&db, &slice_ids, &export_path, jsonl)?;
+    if exported == 0 {
+        let _ = std::fs::remove_file(&export_path);
+        return Err(ApiError {
+            message: "No slices found in selection".to_string(),
+            kind: "NoDataError".to_string(),
+        });
+    }
+
+    logging::log_export(
+        extension,
+        &slice_ids,
+        Some(export_path.to_string_lossy().as_ref()),
+    );
+
+    Ok(export_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn export_audio(
+    state: State<'_, AppState>,
+    recording_ids: Vec<i64>,
+    dest_dir: String,
+    _reencode: Option<bool>,
+) -> Result<u32, ApiError> {
+    let db = state.db()?;
     
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let recordings = db.list_recordings(None, None)?;
+    let dest_path = PathBuf::from(&dest_dir);
+    
+    std::fs::create_dir_all(&dest_path)?;
+    
+    let mut exported_count = 0u32;
+    
+    for recording in recordings {
+        if recording_ids.contains(&recording.recording.id.unwrap_or(-1)) {
+            if let Some(copied_path) = &recording.recording.copied_path {
+                let source = PathBuf::from(copied_path);
+                let default_name = format!("{}.m4a", recording.recording.apple_id);
+                let filename = source.file_name().unwrap_or_else(|| {
+                    std::ffi::OsStr::new(&default_name)
+                });
+                let dest = dest_path.join(filename);
+                
+                std::fs::copy(&source, &dest)?;
+                exported_count += 1;
+            }
+        }
+    }
+    
+    info!("Exported {} audio files to {:?}", exported_count, dest_path);
+    Ok(exported_count)
+}
+
+/// Export recordings as a Voice Memos-style folder of dated `.m4a` files, for moving the
+/// library elsewhere. Exports the slices matching `query` if given, else every audio slice
+/// when `slice_ids` is omitted; with `embed_transcript` each file's comment tag carries the
+/// start of its transcript.
+#[tauri::command]
+async fn export_voice_memos_layout(
+    state: State<'_, AppState>,
+    dest_dir: String,
+    slice_ids: Option<Vec<i64>>,
+    query: Option<SliceQuery>,
+    embed_transcript: Option<bool>,
+) -> Result<LibraryExportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        let slice_ids = match (slice_ids, &query) {
+            (None, Some(query)) => Some(db.query_slice_ids(&with_dates(query))?),
+            (slice_ids, _) => slice_ids,
+        };
+        let report = export::export_voice_memos_layout(
+            &config,
+            &db,
+            Path::new(&dest_dir),
+            slice_ids.as_deref(),
+            embed_transcript.unwrap_or(false),
+        )?;
+        logging::log_export("voice_memos_layout", slice_ids.as_deref().unwrap_or_default(), Some(&dest_dir));
+        Ok::<_, anyhow::Error>(report)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Export task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn update_slice_name(
+    state: State<'_, AppState>,
+    sliceId: i64,
+    newName: String,
+) -> Result<(), ApiError> {
+    let db = state.db()?;
     
     db.update_slice_name(sliceId, &newName).map_err(ApiError::from)
 }
@@ -832,15 +1765,7 @@ async fn update_slice(
     state: State<'_, AppState>,
     slice: Slice,
 ) -> Result<(), ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-    
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
     
     let slice_id = slice.id.ok_or_else(|| ApiError {
         message: "Slice ID is required for update".to_string(),
@@ -1173,6 +2098,58 @@ async fn pick_directory(
         .map(|p| p.to_string_lossy().to_string()))
 }
 
+#[tauri::command]
+async fn get_slice_transcription(state: State<'_, AppState>, slice_id: i64) -> Result<Option<String>, ApiError> {
+    let db = state.db()?;
+    let transcription = db.get_slice_transcription(slice_id)?;
+    note_slice_opened(&db, slice_id);
+    Ok(transcription)
+}
+
+/// Stamp a slice as just opened for the recent list; a failure here never fails the fetch.
+fn note_slice_opened(db: &PooledDatabase, slice_id: i64) {
+    if let Err(e) = db.mark_slice_opened(slice_id, chrono::Utc::now().timestamp()) {
+        tracing::warn!("Failed to record opening slice {}: {}", slice_id, e);
+    }
+}
+
+/// Slices whose audio or transcript was opened most recently, newest first.
+#[tauri::command]
+async fn list_recent_slices(state: State<'_, AppState>, limit: Option<u32>) -> Result<Vec<RecentSlice>, ApiError> {
+    let db = state.db()?;
+    db.list_recent_slices(limit.unwrap_or(20)).map_err(ApiError::from)
+}
+
+/// One slice with the recordings linked to it, for the detail view.
+#[tauri::command]
+async fn get_slice_details(state: State<'_, AppState>, slice_id: i64) -> Result<SliceDetails, ApiError> {
+    let db = state.db()?;
+
+    let slice = db.get_slice(slice_id)?
+        .ok_or_else(|| ApiError {
+            message: format!("Slice with ID {} not found", slice_id),
+            kind: "NotFoundError".to_string(),
+        })?;
+    let related = db.get_related_slices(slice_id)?;
+    note_slice_opened(&db, slice_id);
+
+    Ok(SliceDetails { slice, related })
+}
+
+/// Link two slices, e.g. `relation` "part" from part 1 to part 2, or "follow_up" from a memo
+/// to the one answering it. Replaces any existing link between the pair.
+#[tauri::command]
+async fn link_slices(state: State<'_, AppState>, from_id: i64, to_id: i64, relation: String) -> Result<(), ApiError> {
+    let db = state.db()?;
+    db.link_slices(from_id, to_id, &relation).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn unlink_slices(state: State<'_, AppState>, from_id: i64, to_id: i64) -> Result<bool, ApiError> {
+    let db = state.db()?;
+    db.unlink_slices(from_id, to_id).map_err(ApiError::from)
+}
+
 #[tauri::command]
 async fn get_slice_audio_bytes(
     state: State<'_, AppState>,
@@ -1183,19 +2160,9 @@ async fn get_slice_audio_bytes(
         kind: "LockError".to_string(),
     })?;
 
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
 
-    // Get all slices and find the one with matching ID
-    let slices = db.list_all_slices()?;
-    let slice = slices.iter().find(|s| s.id == Some(slice_id))
+    let slice = db.get_slice(slice_id)?
         .ok_or_else(|| ApiError {
             message: format!("Slice with ID {} not found", slice_id),
             kind: "NotFoundError".to_string(),
@@ -1217,6 +2184,7 @@ async fn get_slice_audio_bytes(
         message: format!("Failed to read audio file: {}", e),
         kind: "IoError".to_string(),
     })?;
+    note_slice_opened(&db, slice_id);
 
     Ok(bytes)
 }
@@ -1232,16 +2200,8 @@ async fn update_slice_names_from_audio(
         kind: "LockError".to_string(),
     })?.clone();
 
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
     // Verify database is initialized
-    db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    state.db_pool()?;
 
     // Clone the database connection for the background task
     let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
@@ -1249,7 +2209,7 @@ async fn update_slice_names_from_audio(
     // Spawn the work in a blocking thread pool
     tokio::task::spawn_blocking(move || {
         // Create a new database connection for this task
-        match Database::new(&db_path) {
+        match db_pool::connect(&db_path) {
             Ok(db) => {
                 let transcription_engine = TranscriptionEngine::new(&config, &db);
                 for slice_id in slice_ids {
@@ -1284,31 +2244,61 @@ async fn update_recording_title(
     slice_id: i64,
     new_title: String,
 ) -> Result<(), ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
 
     db.update_recording_title_by_slice(slice_id, &new_title)
         .map_err(ApiError::from)
 }
 
+/// Star or unstar a slice; returns whether it is now starred.
 #[tauri::command]
-async fn auto_populate_titles(state: State<'_, AppState>) -> Result<u32, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
+async fn toggle_slice_starred(state: State<'_, AppState>, slice_id: i64) -> Result<bool, ApiError> {
+    let db = state.db()?;
+    db.toggle_slice_starred(slice_id).map_err(ApiError::from)
+}
 
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+/// Set the user's notes on a slice; an empty string clears them.
+#[tauri::command]
+async fn update_slice_notes(state: State<'_, AppState>, slice_id: i64, notes: Option<String>) -> Result<(), ApiError> {
+    let db = state.db()?;
+    db.update_slice_notes(slice_id, notes.as_deref()).map_err(ApiError::from)
+}
+
+/// Pin a slice to the top of the library listing, or unpin it.
+#[tauri::command]
+async fn set_slice_pinned(state: State<'_, AppState>, slice_id: i64, pinned: bool) -> Result<(), ApiError> {
+    let db = state.db()?;
+    db.set_slice_pinned(slice_id, pinned).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn get_slice_history(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<SliceChange>, ApiError> {
+    let db = state.db()?;
+    db.get_slice_history(slice_id).map_err(ApiError::from)
+}
+
+/// Undo one edit from a slice's history, restoring the value it replaced.
+#[tauri::command]
+async fn revert_slice_change(state: State<'_, AppState>, change_id: i64) -> Result<(), ApiError> {
+    let db = state.db()?;
+    db.revert_slice_change(change_id).map_err(ApiError::from)
+}
+
+/// Find/replace across stored transcriptions. With `dry_run` (the default) only reports
+/// what would change; otherwise applies it, each edit revertible from the slice's history.
+#[tauri::command]
+async fn replace_in_transcriptions(
+    state: State<'_, AppState>,
+    replacement: TranscriptReplacement,
+    dry_run: Option<bool>,
+) -> Result<ReplaceReport, ApiError> {
+    let db = state.db()?;
+    db.replace_in_transcriptions(&replacement, dry_run.unwrap_or(true)).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn auto_populate_titles(state: State<'_, AppState>) -> Result<u32, ApiError> {
+    let db = state.db()?;
 
     let count = db.auto_populate_titles().map_err(ApiError::from)?;
     Ok(count)
@@ -1321,15 +2311,7 @@ async fn populate_audio_durations(state: State<'_, AppState>) -> Result<u32, Api
         kind: "LockError".to_string(),
     })?.clone();
 
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
 
     // Clear any corrupted durations from a prior unit-conversion bug
     match db.clear_corrupt_audio_durations() {
@@ -1369,15 +2351,7 @@ async fn populate_audio_durations(state: State<'_, AppState>) -> Result<u32, Api
 
 #[tauri::command]
 async fn backfill_recording_dates(state: State<'_, AppState>) -> Result<u32, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
 
     let count = db.backfill_recording_dates().map_err(ApiError::from)?;
     if count > 0 {
@@ -1394,6 +2368,44 @@ async fn nlm_get_status() -> Result<backend::nlm::NlmStatus, ApiError> {
     Ok(backend::nlm::get_nlm_status())
 }
 
+#[tauri::command]
+async fn nlm_check_binary(check_for_update: Option<bool>) -> Result<backend::nlm_provision::NlmBinaryHealth, ApiError> {
+    Ok(backend::nlm_provision::check_health(check_for_update.unwrap_or(false)).await)
+}
+
+/// Download (or update to) the latest nlm release for this machine, emitting
+/// `nlm-install-progress` events.
+#[tauri::command]
+async fn nlm_install_binary() -> Result<backend::nlm_provision::NlmBinaryHealth, ApiError> {
+    let emit = |percentage: f32, status: &str, error_message: Option<String>| {
+        if let Some(handle) = APP_HANDLE.get() {
+            let progress = ModelDownloadProgress {
+                model_name: "nlm".to_string(),
+                percentage,
+                status: status.to_string(),
+                error_message,
+            };
+            let _ = handle.emit("nlm-install-progress", progress);
+        }
+    };
+
+    emit(0.0, "started", None);
+    let result = backend::nlm_provision::install_latest(|pct| emit(pct, "progress", None)).await;
+    match result {
+        Ok(_) => {
+            emit(100.0, "completed", None);
+            Ok(backend::nlm_provision::check_health(false).await)
+        }
+        Err(e) => {
+            emit(0.0, "error", Some(e.to_string()));
+            Err(ApiError {
+                message: format!("Failed to install nlm: {:#}", e),
+                kind: "DownloadError".to_string(),
+            })
+        }
+    }
+}
+
 #[tauri::command]
 async fn nlm_authenticate() -> Result<String, ApiError> {
     // Run in blocking thread to avoid freezing async runtime
@@ -1402,10 +2414,7 @@ async fn nlm_authenticate() -> Result<String, ApiError> {
     }).await.map_err(|e| ApiError {
         message: format!("Task failed: {}", e),
         kind: "TaskError".to_string(),
-    })?.map_err(|e| ApiError {
-        message: e.to_string(),
-        kind: "NlmError".to_string(),
-    })
+    })?.map_err(nlm_api_error)
 }
 
 #[tauri::command]
@@ -1415,31 +2424,34 @@ async fn nlm_list_notebooks() -> Result<Vec<backend::nlm::NlmNotebook>, ApiError
     }).await.map_err(|e| ApiError {
         message: format!("Task failed: {}", e),
         kind: "TaskError".to_string(),
-    })?.map_err(|e| ApiError {
-        message: e.to_string(),
-        kind: "NlmError".to_string(),
-    })
+    })?.map_err(nlm_api_error)
 }
 
 #[tauri::command]
 async fn nlm_add_text(
+    state: State<'_, AppState>,
     notebook_id: String,
     text: String,
     title: Option<String>,
 ) -> Result<String, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
     tokio::task::spawn_blocking(move || {
-        backend::nlm::add_text_to_notebook(
-            &notebook_id,
-            &text,
-            title.as_deref(),
-        )
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        // Text the notebook already has isn't sent again
+        let output = match nlm_upload::upload_text(&db, &notebook_id, &text, title.as_deref())? {
+            nlm_upload::UploadOutcome::Uploaded(output) => output,
+            nlm_upload::UploadOutcome::AlreadyPresent(source_id) => format!("already present (source {})", source_id),
+        };
+        Ok::<_, anyhow::Error>(output)
     }).await.map_err(|e| ApiError {
         message: format!("Task failed: {}", e),
         kind: "TaskError".to_string(),
-    })?.map_err(|e| ApiError {
-        message: e.to_string(),
-        kind: "NlmError".to_string(),
-    })
+    })?.map_err(nlm_api_error)
 }
 
 #[tauri::command]
@@ -1448,25 +2460,16 @@ async fn nlm_add_audio(
     notebook_id: String,
     slice_id: i64,
 ) -> Result<String, ApiError> {
-    // Resolve the audio path while holding locks, then drop them before await
-    let audio_path_str = {
-        let config = state.config.lock().map_err(|e| ApiError {
-            message: format!("Failed to lock config: {}", e),
-            kind: "LockError".to_string(),
-        })?.clone();
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
 
-        let db_guard = state.db.lock().map_err(|e| ApiError {
-            message: format!("Failed to lock database: {}", e),
-            kind: "LockError".to_string(),
-        })?;
+    // Check the slice and its audio up front, then drop the connection before await
+    let slice = {
+        let db = state.db()?;
 
-        let db = db_guard.as_ref().ok_or_else(|| ApiError {
-            message: "Database not initialized".to_string(),
-            kind: "DatabaseError".to_string(),
-        })?;
-
-        let slices = db.list_all_slices()?;
-        let slice = slices.iter().find(|s| s.id == Some(slice_id))
+        let slice = db.get_slice(slice_id)?
             .ok_or_else(|| ApiError {
                 message: format!("Slice with ID {} not found", slice_id),
                 kind: "NotFoundError".to_string(),
@@ -1479,147 +2482,947 @@ async fn nlm_add_audio(
                 kind: "FileNotFoundError".to_string(),
             });
         }
-        audio_path.to_string_lossy().to_string()
+        slice
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        // Audio the notebook already has isn't sent again
+        let output = match nlm_upload::upload_slice(&config, &db, &notebook_id, &slice, NlmSourceKind::Audio)? {
+            nlm_upload::UploadOutcome::Uploaded(output) => output,
+            nlm_upload::UploadOutcome::AlreadyPresent(source_id) => format!("already present (source {})", source_id),
+        };
+        Ok::<_, anyhow::Error>(output)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+/// Upload the transcripts (and with `include_audio`, the audio) of several slices to a
+/// notebook, one at a time, with an `nlm-batch-progress` event after each. Items that fail
+/// are reported and can be retried with `nlm_resume_batch`.
+#[tauri::command]
+async fn nlm_upload_batch(
+    state: State<'_, AppState>,
+    notebook_id: String,
+    slice_ids: Vec<i64>,
+    include_text: Option<bool>,
+    include_audio: Option<bool>,
+) -> Result<NlmBatchReport, ApiError> {
+    let mut kinds = Vec::new();
+    if include_text.unwrap_or(true) {
+        kinds.push(NlmSourceKind::Text);
+    }
+    if include_audio.unwrap_or(false) {
+        kinds.push(NlmSourceKind::Audio);
+    }
+    let batch_id = {
+        let db = state.db()?;
+        nlm_upload::create_batch(&db, &notebook_id, &slice_ids, &kinds)?
+    };
+    run_nlm_batch(&state, batch_id).await
+}
+
+/// Carry on with a batch: uploads whatever didn't make it last time (failed or never tried).
+#[tauri::command]
+async fn nlm_resume_batch(state: State<'_, AppState>, batch_id: String) -> Result<NlmBatchReport, ApiError> {
+    run_nlm_batch(&state, batch_id).await
+}
+
+#[tauri::command]
+async fn nlm_get_batch(state: State<'_, AppState>, batch_id: String) -> Result<Vec<NlmBatchItem>, ApiError> {
+    let db = state.db()?;
+    db.get_nlm_batch_items(&batch_id).map_err(ApiError::from)
+}
+
+/// Take a source back out of a notebook, e.g. a transcript uploaded by mistake.
+#[tauri::command]
+async fn nlm_remove_source(state: State<'_, AppState>, notebook_id: String, source_id: String) -> Result<(), ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        nlm_upload::remove_source(&db, &notebook_id, &source_id)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+/// First step of deleting a notebook: returns a token to pass to `nlm_delete_notebook`
+/// once the user has confirmed. Tokens are single use and expire after two minutes.
+#[tauri::command]
+async fn nlm_request_notebook_deletion(notebook_id: String) -> Result<String, ApiError> {
+    Ok(backend::nlm::issue_delete_token(&notebook_id))
+}
+
+/// Delete a notebook in NotebookLM, with the token from `nlm_request_notebook_deletion`.
+#[tauri::command]
+async fn nlm_delete_notebook(state: State<'_, AppState>, notebook_id: String, confirmation_token: String) -> Result<String, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let output = backend::nlm::delete_notebook(&notebook_id, &confirmation_token)?;
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        db_pool::connect(&db_path)?.forget_nlm_notebook(&notebook_id)?;
+        Ok(output)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+/// Sources made from a slice that are still in a notebook, as far as CiderPress knows.
+#[tauri::command]
+async fn nlm_get_slice_sources(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<NlmSource>, ApiError> {
+    let db = state.db()?;
+    db.get_slice_nlm_sources(slice_id).map_err(ApiError::from)
+}
+
+/// How a long transcript of a slice was split into sources, with each part's range.
+#[tauri::command]
+async fn nlm_get_slice_source_parts(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<NlmSourcePart>, ApiError> {
+    let db = state.db()?;
+    db.get_slice_nlm_source_parts(slice_id).map_err(ApiError::from)
+}
+
+/// Every upload of a slice to NotebookLM, including sources since removed, newest first.
+#[tauri::command]
+async fn nlm_get_slice_uploads(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<NlmUpload>, ApiError> {
+    let db = state.db()?;
+    db.get_slice_nlm_uploads(slice_id).map_err(ApiError::from)
+}
+
+/// Send slices with `label_id` to `notebook_id` as they're transcribed; `None` stops it.
+#[tauri::command]
+async fn nlm_set_label_notebook(state: State<'_, AppState>, label_id: i64, notebook_id: Option<String>) -> Result<(), ApiError> {
+    let db = state.db()?;
+    db.set_label_notebook(label_id, notebook_id.as_deref()).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn nlm_list_label_notebooks(state: State<'_, AppState>) -> Result<Vec<LabelNotebook>, ApiError> {
+    let db = state.db()?;
+    db.list_label_notebooks().map_err(ApiError::from)
+}
+
+async fn run_nlm_batch(state: &AppState, batch_id: String) -> Result<NlmBatchReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        nlm_upload::run_batch(&config, &db, &batch_id, emit_nlm_batch_progress)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+#[tauri::command]
+async fn nlm_list_profiles() -> Result<Vec<backend::nlm::NlmBrowserProfile>, ApiError> {
+    // Reads potentially large Chrome Preferences files, run off the async runtime
+    tokio::task::spawn_blocking(|| {
+        backend::nlm::list_browser_profiles()
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })
+}
+
+#[tauri::command]
+async fn nlm_list_accounts() -> Result<Vec<backend::nlm::NlmAccountInfo>, ApiError> {
+    Ok(backend::nlm::list_accounts())
+}
+
+/// Save NLM's current login under `name`, so it can be switched back to later.
+#[tauri::command]
+async fn nlm_save_account(name: String) -> Result<(), ApiError> {
+    backend::nlm::save_account(&name).map_err(nlm_api_error)
+}
+
+#[tauri::command]
+async fn nlm_switch_account(name: String) -> Result<(), ApiError> {
+    backend::nlm::switch_account(&name).map_err(nlm_api_error)
+}
+
+#[tauri::command]
+async fn nlm_auth_with_profile(profile_name: String) -> Result<String, ApiError> {
+    tokio::task::spawn_blocking(move || {
+        backend::nlm::auth_with_profile(&profile_name)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+#[tauri::command]
+async fn nlm_create_notebook(title: String) -> Result<String, ApiError> {
+    tokio::task::spawn_blocking(move || {
+        backend::nlm::create_notebook(&title)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+#[tauri::command]
+async fn nlm_rename_notebook(id: String, title: String) -> Result<String, ApiError> {
+    tokio::task::spawn_blocking(move || {
+        backend::nlm::rename_notebook(&id, &title)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+/// Past runs of the nlm binary, newest first, to look into failed uploads.
+#[tauri::command]
+async fn nlm_get_invocations(
+    state: State<'_, AppState>,
+    failed_only: Option<bool>,
+    command: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<NlmInvocation>, ApiError> {
+    let db = state.db()?;
+    db.list_nlm_invocations(failed_only.unwrap_or(false), command.as_deref(), limit.unwrap_or(100), offset.unwrap_or(0))
+        .map_err(ApiError::from)
+}
+
+/// Make a notebook viewable by link and return the link, to hand it to someone else.
+#[tauri::command]
+async fn nlm_get_share_link(notebook_id: String) -> Result<String, ApiError> {
+    tokio::task::spawn_blocking(move || {
+        backend::nlm::share_notebook(&notebook_id)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+/// Import a notebook's notes as text slices labelled with the notebook. Notes imported
+/// before are skipped.
+#[tauri::command]
+async fn nlm_import_notes(
+    state: State<'_, AppState>,
+    notebook_id: String,
+    notebook_title: String,
+) -> Result<FolderImportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        nlm_notes::import_notes(&db, &notebook_id, &notebook_title)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+/// Generate an Audio Overview of a notebook, wait for it and import it as a new slice.
+/// Takes several minutes; progress arrives as `nlm-audio-overview-progress` events.
+#[tauri::command]
+async fn nlm_generate_audio_overview(
+    state: State<'_, AppState>,
+    notebook_id: String,
+    notebook_title: String,
+    instructions: Option<String>,
+) -> Result<i64, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        nlm_audio::generate_into_library(&config, &db, &notebook_id, &notebook_title, instructions.as_deref(), emit_audio_overview_progress)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+#[tauri::command]
+async fn nlm_get_notebook_details(notebook_id: String, title: String) -> Result<backend::nlm::NlmNotebookDetails, ApiError> {
+    tokio::task::spawn_blocking(move || {
+        backend::nlm::get_notebook_details(&notebook_id, &title)
+    }).await.map_err(|e| ApiError {
+        message: format!("Task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?.map_err(nlm_api_error)
+}
+
+// ==================== Google Drive commands ====================
+
+/// Sign in to Google Drive in the browser. Returns once the user has finished (or after
+/// five minutes).
+#[tauri::command]
+async fn drive_authorize(state: State<'_, AppState>) -> Result<(), ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    drive::authorize(&config).await.map_err(|e| ApiError {
+        message: e.to_string(),
+        kind: "DriveError".to_string(),
+    })
+}
+
+#[tauri::command]
+async fn drive_is_connected(state: State<'_, AppState>) -> Result<bool, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    Ok(drive::is_connected(&config))
+}
+
+#[tauri::command]
+async fn drive_disconnect(state: State<'_, AppState>) -> Result<(), ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    drive::disconnect(&config).map_err(ApiError::from)
+}
+
+/// Upload slices' transcripts and/or audio to Drive, skipping what's there unchanged.
+/// Progress arrives as `drive-sync-progress` events.
+#[tauri::command]
+async fn drive_sync_slices(
+    state: State<'_, AppState>,
+    slice_ids: Vec<i64>,
+    kinds: Vec<NlmSourceKind>,
+) -> Result<DriveSyncReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    drive::sync_slices(&config, &slice_ids, &kinds, |progress| {
+        if let Some(handle) = APP_HANDLE.get() {
+            let _ = handle.emit("drive-sync-progress", progress.clone());
+        }
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: e.to_string(),
+        kind: "DriveError".to_string(),
+    })
+}
+
+#[tauri::command]
+async fn drive_get_slice_files(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<DriveFile>, ApiError> {
+    let db = state.db()?;
+    db.get_slice_drive_files(slice_id).map_err(ApiError::from)
+}
+
+// ==================== Local LLM (Ollama) commands ====================
+
+fn llm_api_error(e: anyhow::Error) -> ApiError {
+    ApiError {
+        message: e.to_string(),
+        kind: "LlmError".to_string(),
+    }
+}
+
+/// Summarize a slice's transcript with the configured Ollama model and store it.
+#[tauri::command]
+async fn summarize_slice(state: State<'_, AppState>, slice_id: i64) -> Result<String, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let ollama = backend::ollama::Ollama::new(&config).map_err(llm_api_error)?;
+    backend::summarize::summarize_slice(&config, &ollama, slice_id)
+        .await
+        .map_err(llm_api_error)
+}
+
+/// Summarize several slices, emitting `summarize-progress` events as it goes. With
+/// `overwrite`, slices that already have a summary are redone.
+#[tauri::command]
+async fn summarize_slices(
+    state: State<'_, AppState>,
+    slice_ids: Vec<i64>,
+    overwrite: Option<bool>,
+) -> Result<LlmBatchReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    backend::summarize::summarize_slices(&config, &slice_ids, overwrite.unwrap_or(false), |progress| {
+        if let Some(handle) = APP_HANDLE.get() {
+            let _ = handle.emit("summarize-progress", progress.clone());
+        }
+    })
+    .await
+    .map_err(llm_api_error)
+}
+
+/// Title a slice from its whole transcript with the configured Ollama model, replacing
+/// its current title (kept in its history).
+#[tauri::command]
+async fn generate_slice_title(state: State<'_, AppState>, slice_id: i64) -> Result<String, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let ollama = backend::ollama::Ollama::new(&config).map_err(llm_api_error)?;
+    backend::titles::title_slice(&config, &ollama, slice_id)
+        .await
+        .map_err(llm_api_error)
+}
+
+/// Title slices with the local LLM, emitting `title-generation-progress` events: the given
+/// ones, or without `slice_ids` every untitled transcribed slice. Titled slices are only
+/// redone with `overwrite`.
+#[tauri::command]
+async fn generate_slice_titles(
+    state: State<'_, AppState>,
+    slice_ids: Option<Vec<i64>>,
+    overwrite: Option<bool>,
+) -> Result<LlmBatchReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    backend::titles::title_slices(&config, slice_ids, overwrite.unwrap_or(false), |progress| {
+        if let Some(handle) = APP_HANDLE.get() {
+            let _ = handle.emit("title-generation-progress", progress.clone());
+        }
+    })
+    .await
+    .map_err(llm_api_error)
+}
+
+/// Rate how positive or negative a slice sounds with the local LLM, and store it.
+#[tauri::command]
+async fn analyze_slice_sentiment(state: State<'_, AppState>, slice_id: i64) -> Result<SliceSentiment, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let ollama = backend::ollama::Ollama::new(&config).map_err(llm_api_error)?;
+    backend::sentiment::analyze_slice(&config, &ollama, slice_id)
+        .await
+        .map_err(llm_api_error)
+}
+
+/// Rate slices' sentiment, emitting `sentiment-progress` events: the given ones, or without
+/// `slice_ids` every transcribed slice not rated yet.
+#[tauri::command]
+async fn analyze_sentiment(
+    state: State<'_, AppState>,
+    slice_ids: Option<Vec<i64>>,
+    overwrite: Option<bool>,
+) -> Result<LlmBatchReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    backend::sentiment::analyze_slices(&config, slice_ids, overwrite.unwrap_or(false), |progress| {
+        if let Some(handle) = APP_HANDLE.get() {
+            let _ = handle.emit("sentiment-progress", progress.clone());
+        }
+    })
+    .await
+    .map_err(llm_api_error)
+}
+
+#[tauri::command]
+async fn get_slice_sentiment(state: State<'_, AppState>, slice_id: i64) -> Result<Option<SliceSentiment>, ApiError> {
+    let db = state.db()?;
+    db.get_slice_sentiment(slice_id).map_err(ApiError::from)
+}
+
+/// Flag phone numbers, email addresses, street addresses and card numbers in transcripts:
+/// of the given slices, or without `slice_ids` the whole library.
+#[tauri::command]
+async fn scan_pii(state: State<'_, AppState>, slice_ids: Option<Vec<i64>>) -> Result<PiiScanReport, ApiError> {
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        pii::scan(&db, slice_ids.as_deref())
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("PII scan task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Mask the personal information in the stored text of the given slices, or without
+/// `slice_ids` of every slice. A copy of the database is saved to the backups folder first.
+#[tauri::command]
+async fn redact_pii(state: State<'_, AppState>, slice_ids: Option<Vec<i64>>) -> Result<PiiRedactionReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        pii::redact_stored(&config, &db, slice_ids.as_deref())
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("PII redaction task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Turn redaction mode on or off. Turning it on masks all stored text right away; while it
+/// is on, new transcripts and exports are masked too.
+#[tauri::command]
+async fn set_pii_redaction(state: State<'_, AppState>, enabled: bool) -> Result<PiiRedactionReport, ApiError> {
+    let config = {
+        let mut config = state.config.lock().map_err(|e| ApiError {
+            message: format!("Failed to lock config: {}", e),
+            kind: "LockError".to_string(),
+        })?;
+        config.pii_redaction_enabled = enabled;
+        config.clone()
     };
+    config.save()?;
+    pii::apply_config(&config);
+    if !enabled {
+        return Ok(PiiRedactionReport::default());
+    }
+    redact_pii(state, None).await
+}
+
+/// Find the people, places and organizations a slice's transcript mentions with the local
+/// LLM, and store them.
+#[tauri::command]
+async fn extract_slice_entities(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<SliceEntity>, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let ollama = backend::ollama::Ollama::new(&config).map_err(llm_api_error)?;
+    backend::entities::extract_slice(&config, &ollama, slice_id)
+        .await
+        .map_err(llm_api_error)
+}
+
+/// Find entities in slices, emitting `entity-extraction-progress` events: the given ones, or
+/// without `slice_ids` every transcribed slice not scanned since its transcript changed.
+#[tauri::command]
+async fn extract_entities(
+    state: State<'_, AppState>,
+    slice_ids: Option<Vec<i64>>,
+    overwrite: Option<bool>,
+) -> Result<LlmBatchReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    backend::entities::extract_slices(&config, slice_ids, overwrite.unwrap_or(false), |progress| {
+        if let Some(handle) = APP_HANDLE.get() {
+            let _ = handle.emit("entity-extraction-progress", progress.clone());
+        }
+    })
+    .await
+    .map_err(llm_api_error)
+}
+
+#[tauri::command]
+async fn get_slice_entities(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<SliceEntity>, ApiError> {
+    let db = state.db()?;
+    db.get_slice_entities(slice_id).map_err(ApiError::from)
+}
+
+/// Entities across the library, in the most slices first, optionally of one kind or with
+/// `query` in their name.
+#[tauri::command]
+async fn list_entities(
+    state: State<'_, AppState>,
+    kind: Option<EntityKind>,
+    query: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<EntityCount>, ApiError> {
+    let db = state.db()?;
+    db.list_entities(kind, query.as_deref(), limit.unwrap_or(100)).map_err(ApiError::from)
+}
+
+/// Slices mentioning an entity, e.g. every memo that mentions "Dr. Alvarez".
+#[tauri::command]
+async fn find_slices_by_entity(
+    state: State<'_, AppState>,
+    name: String,
+    kind: Option<EntityKind>,
+) -> Result<Vec<Slice>, ApiError> {
+    let db = state.db()?;
+    db.find_slices_by_entity(&name, kind).map_err(ApiError::from)
+}
+
+/// Translate a slice's transcript into `target_lang` (a language name or code) with the
+/// local LLM and store it; a stored translation of the same transcript is reused unless
+/// `overwrite`.
+#[tauri::command]
+async fn translate_transcript(
+    state: State<'_, AppState>,
+    slice_id: i64,
+    target_lang: String,
+    overwrite: Option<bool>,
+) -> Result<SliceTranslation, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    backend::translate::translate_slice(&config, slice_id, &target_lang, overwrite.unwrap_or(false))
+        .await
+        .map_err(llm_api_error)
+}
+
+#[tauri::command]
+async fn get_slice_translations(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<SliceTranslation>, ApiError> {
+    let db = state.db()?;
+    db.list_slice_translations(slice_id).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn delete_slice_translation(state: State<'_, AppState>, slice_id: i64, language: String) -> Result<bool, ApiError> {
+    let db = state.db()?;
+    db.delete_slice_translation(slice_id, &backend::translate::normalize_language(&language))
+        .map_err(ApiError::from)
+}
+
+/// Embed transcripts that are new or changed since the last update into the transcript
+/// index, emitting `transcript-index-progress` events.
+#[tauri::command]
+async fn update_transcript_index(state: State<'_, AppState>) -> Result<LlmBatchReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    backend::embeddings::update_index(&config, |progress| {
+        if let Some(handle) = APP_HANDLE.get() {
+            let _ = handle.emit("transcript-index-progress", progress.clone());
+        }
+    })
+    .await
+    .map_err(llm_api_error)
+}
+
+/// Transcript passages closest in meaning to `query`, from the transcript index.
+#[tauri::command]
+async fn search_transcripts_semantic(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<TranscriptPassage>, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let ollama = backend::ollama::Ollama::new(&config).map_err(llm_api_error)?;
+    backend::embeddings::search(&config, &ollama, &query, limit.unwrap_or(20))
+        .await
+        .map_err(llm_api_error)
+}
+
+/// Answer a question about the library with the local LLM, from the most relevant
+/// transcript passages, citing the slices it drew on.
+#[tauri::command]
+async fn ask_transcripts(
+    state: State<'_, AppState>,
+    question: String,
+    max_passages: Option<usize>,
+) -> Result<TranscriptAnswer, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    backend::ask::ask(&config, &question, max_passages.unwrap_or(backend::ask::DEFAULT_PASSAGES))
+        .await
+        .map_err(llm_api_error)
+}
+
+// ==================== Label management commands ====================
+
+#[tauri::command]
+async fn list_labels(state: State<'_, AppState>) -> Result<Vec<Label>, ApiError> {
+    let db = state.db()?;
+
+    db.list_labels().map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn create_label(state: State<'_, AppState>, label: Label) -> Result<i64, ApiError> {
+    let db = state.db()?;
+
+    db.create_label(&label).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn update_label(state: State<'_, AppState>, id: i64, label: Label) -> Result<(), ApiError> {
+    let db = state.db()?;
+
+    db.update_label(id, &label).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn delete_label(state: State<'_, AppState>, id: i64) -> Result<(), ApiError> {
+    let db = state.db()?;
+
+    db.delete_label(id).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn list_collections(state: State<'_, AppState>) -> Result<Vec<Collection>, ApiError> {
+    let db = state.db()?;
+
+    db.list_collections().map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn create_collection(state: State<'_, AppState>, name: String, parent_id: Option<i64>) -> Result<i64, ApiError> {
+    let db = state.db()?;
+
+    db.create_collection(&name, parent_id).map_err(ApiError::from)
+}
+
+/// Move a collection under another one, or to the top level when `parent_id` is omitted.
+#[tauri::command]
+async fn move_collection(state: State<'_, AppState>, id: i64, parent_id: Option<i64>) -> Result<(), ApiError> {
+    let db = state.db()?;
+
+    db.move_collection(id, parent_id).map_err(ApiError::from)
+}
 
-    tokio::task::spawn_blocking(move || {
-        backend::nlm::add_audio_to_notebook(&notebook_id, &audio_path_str)
-    }).await.map_err(|e| ApiError {
-        message: format!("Task failed: {}", e),
-        kind: "TaskError".to_string(),
-    })?.map_err(|e| ApiError {
-        message: e.to_string(),
-        kind: "NlmError".to_string(),
-    })
+#[tauri::command]
+async fn delete_collection(state: State<'_, AppState>, id: i64) -> Result<(), ApiError> {
+    let db = state.db()?;
+
+    db.delete_collection(id).map_err(ApiError::from)
 }
 
+/// File slices in a collection, or take them out of their collection when `collection_id`
+/// is omitted. Returns the number of slices moved.
 #[tauri::command]
-async fn nlm_list_profiles() -> Result<Vec<backend::nlm::NlmBrowserProfile>, ApiError> {
-    // Reads potentially large Chrome Preferences files, run off the async runtime
-    tokio::task::spawn_blocking(|| {
-        backend::nlm::list_browser_profiles()
-    }).await.map_err(|e| ApiError {
-        message: format!("Task failed: {}", e),
-        kind: "TaskError".to_string(),
-    })
+async fn move_slices_to_collection(
+    state: State<'_, AppState>,
+    slice_ids: Vec<i64>,
+    collection_id: Option<i64>,
+) -> Result<u32, ApiError> {
+    let db = state.db()?;
+
+    db.move_slices_to_collection(&slice_ids, collection_id).map_err(ApiError::from)
 }
 
 #[tauri::command]
-async fn nlm_auth_with_profile(profile_name: String) -> Result<String, ApiError> {
-    tokio::task::spawn_blocking(move || {
-        backend::nlm::auth_with_profile(&profile_name)
-    }).await.map_err(|e| ApiError {
-        message: format!("Task failed: {}", e),
-        kind: "TaskError".to_string(),
-    })?.map_err(|e| ApiError {
-        message: e.to_string(),
-        kind: "NlmError".to_string(),
-    })
+async fn get_slice_labels(
+    state: State<'_, AppState>,
+) -> Result<HashMap<i64, Vec<Label>>, ApiError> {
+    let db = state.db()?;
+
+    db.get_labels_for_all_slices().map_err(ApiError::from)
 }
 
+/// Attach a label to slices; returns the number of new associations.
 #[tauri::command]
-async fn nlm_create_notebook(title: String) -> Result<String, ApiError> {
-    tokio::task::spawn_blocking(move || {
-        backend::nlm::create_notebook(&title)
-    }).await.map_err(|e| ApiError {
-        message: format!("Task failed: {}", e),
-        kind: "TaskError".to_string(),
-    })?.map_err(|e| ApiError {
-        message: e.to_string(),
-        kind: "NlmError".to_string(),
-    })
+async fn assign_label(
+    state: State<'_, AppState>,
+    label_id: i64,
+    slice_ids: Vec<i64>,
+) -> Result<u32, ApiError> {
+    let db = state.db()?;
+
+    db.assign_label(label_id, &slice_ids).map_err(ApiError::from)
 }
 
 #[tauri::command]
-async fn nlm_get_notebook_details(notebook_id: String, title: String) -> Result<backend::nlm::NlmNotebookDetails, ApiError> {
-    tokio::task::spawn_blocking(move || {
-        backend::nlm::get_notebook_details(&notebook_id, &title)
-    }).await.map_err(|e| ApiError {
-        message: format!("Task failed: {}", e),
-        kind: "TaskError".to_string(),
-    })?.map_err(|e| ApiError {
-        message: e.to_string(),
-        kind: "NlmError".to_string(),
-    })
+async fn get_slice_metadata(state: State<'_, AppState>, slice_id: i64) -> Result<BTreeMap<String, String>, ApiError> {
+    let db = state.db()?;
+    db.get_slice_metadata(slice_id).map_err(ApiError::from)
 }
 
-// ==================== Label management commands ====================
+/// Attach a key/value pair such as "project=alpha" to a slice, replacing the key's old value.
+#[tauri::command]
+async fn set_slice_metadata(state: State<'_, AppState>, slice_id: i64, key: String, value: String) -> Result<(), ApiError> {
+    let db = state.db()?;
+    db.set_slice_metadata(slice_id, &key, &value).map_err(ApiError::from)
+}
 
 #[tauri::command]
-async fn list_labels(state: State<'_, AppState>) -> Result<Vec<Label>, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
+async fn delete_slice_metadata(state: State<'_, AppState>, slice_id: i64, key: String) -> Result<bool, ApiError> {
+    let db = state.db()?;
+    db.delete_slice_metadata(slice_id, &key).map_err(ApiError::from)
+}
 
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+/// Detach a label from slices; returns the number of associations removed.
+#[tauri::command]
+async fn remove_label(
+    state: State<'_, AppState>,
+    label_id: i64,
+    slice_ids: Vec<i64>,
+) -> Result<u32, ApiError> {
+    let db = state.db()?;
 
-    db.list_labels().map_err(ApiError::from)
+    db.remove_label(label_id, &slice_ids).map_err(ApiError::from)
 }
 
+/// Slices carrying any of `label_ids`, or every one of them with `match_all`.
 #[tauri::command]
-async fn create_label(state: State<'_, AppState>, label: Label) -> Result<i64, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
+async fn get_slices_by_label(
+    state: State<'_, AppState>,
+    label_ids: Vec<i64>,
+    match_all: Option<bool>,
+) -> Result<Vec<Slice>, ApiError> {
+    let db = state.db()?;
 
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    db.list_slices_with_labels(&label_ids, match_all.unwrap_or(false)).map_err(ApiError::from)
+}
 
-    db.create_label(&label).map_err(ApiError::from)
+/// Assign labels whose keywords appear in slice transcripts. `match_mode` overrides the
+/// configured `keyword_match_mode` for this run.
+#[tauri::command]
+async fn auto_label_slices(
+    state: State<'_, AppState>,
+    match_mode: Option<KeywordMatchMode>,
+) -> Result<AutoLabelReport, ApiError> {
+    let mode = match match_mode {
+        Some(mode) => mode,
+        None => state.config.lock().map_err(|e| ApiError {
+            message: format!("Failed to lock config: {}", e),
+            kind: "LockError".to_string(),
+        })?.keyword_match_mode,
+    };
+    let pool = state.db_pool()?;
+
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        labeling::auto_label_slices(&db, mode)
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Auto-label task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
 }
 
+/// Labels that slices' transcripts resemble the labeled slices of: for the given slices, or
+/// without `slice_ids` every indexed slice that has no label. Needs the transcript index.
 #[tauri::command]
-async fn update_label(state: State<'_, AppState>, id: i64, label: Label) -> Result<(), ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
+async fn suggest_labels(
+    state: State<'_, AppState>,
+    slice_ids: Option<Vec<i64>>,
+    per_slice: Option<usize>,
+    min_score: Option<f32>,
+) -> Result<Vec<LabelSuggestion>, ApiError> {
+    let model = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
         kind: "LockError".to_string(),
-    })?;
+    })?.ollama_embedding_model.clone();
+    let pool = state.db_pool()?;
 
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    tokio::task::spawn_blocking(move || {
+        let db = pool.get()?;
+        label_suggestions::suggest_labels(
+            &db,
+            &model,
+            slice_ids.as_deref(),
+            per_slice.unwrap_or(label_suggestions::DEFAULT_SUGGESTIONS_PER_SLICE),
+            min_score.unwrap_or(label_suggestions::DEFAULT_MIN_SCORE),
+        )
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Label suggestion task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
 
-    db.update_label(id, &label).map_err(ApiError::from)
+/// Accept a suggested label, assigning it to the slice.
+#[tauri::command]
+async fn accept_label_suggestion(state: State<'_, AppState>, slice_id: i64, label_id: i64) -> Result<(), ApiError> {
+    let db = state.db()?;
+    label_suggestions::accept_suggestion(&db, slice_id, label_id).map_err(ApiError::from)
 }
 
+/// Reject a suggested label; it won't be suggested for the slice again.
 #[tauri::command]
-async fn delete_label(state: State<'_, AppState>, id: i64) -> Result<(), ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
+async fn reject_label_suggestion(state: State<'_, AppState>, slice_id: i64, label_id: i64) -> Result<(), ApiError> {
+    let db = state.db()?;
+    label_suggestions::reject_suggestion(&db, slice_id, label_id).map_err(ApiError::from)
+}
 
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+/// Set the prompt the local LLM runs on newly transcribed slices with this label, or with
+/// `None` remove it.
+#[tauri::command]
+async fn set_label_prompt(state: State<'_, AppState>, label_id: i64, prompt: Option<String>) -> Result<(), ApiError> {
+    let db = state.db()?;
+    db.set_label_prompt(label_id, prompt.as_deref()).map_err(ApiError::from)
+}
 
-    db.delete_label(id).map_err(ApiError::from)
+#[tauri::command]
+async fn list_label_prompts(state: State<'_, AppState>) -> Result<Vec<LabelPrompt>, ApiError> {
+    let db = state.db()?;
+    db.list_label_prompts().map_err(ApiError::from)
 }
 
+/// Run the prompts of a slice's labels now, e.g. after labeling an existing slice, and
+/// return the documents they made.
 #[tauri::command]
-async fn get_slice_labels(
-    state: State<'_, AppState>,
-) -> Result<HashMap<i64, Vec<Label>>, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
+async fn run_label_prompts(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<DerivedDocument>, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
         kind: "LockError".to_string(),
-    })?;
+    })?.clone();
 
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    backend::label_prompts::run_for_slice(&config, slice_id)
+        .await
+        .map_err(llm_api_error)
+}
 
-    db.get_labels_for_all_slices().map_err(ApiError::from)
+#[tauri::command]
+async fn get_derived_documents(state: State<'_, AppState>, slice_id: i64) -> Result<Vec<DerivedDocument>, ApiError> {
+    let db = state.db()?;
+    db.list_derived_documents(slice_id).map_err(ApiError::from)
+}
+
+#[tauri::command]
+async fn delete_derived_document(state: State<'_, AppState>, id: i64) -> Result<(), ApiError> {
+    let db = state.db()?;
+    db.delete_derived_document(id).map_err(ApiError::from)
 }
 
 // ==================== Logging commands ====================
@@ -1703,15 +3506,7 @@ async fn create_text_slice(
     title: String,
     content: String,
 ) -> Result<i64, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
 
     // Generate a unique filename for this text-based slice
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
@@ -1734,6 +3529,14 @@ async fn create_text_slice(
         transcription_word_count: Some(word_count),
         transcription_model: Some("manual".to_string()),
         recording_date: Some(chrono::Utc::now().timestamp()),
+        source: None,
+        starred: false,
+        was_edited: false,
+        content_hash: None,
+        source_relative_path: None,
+        notes: None,
+        pinned: false,
+        summary: None,
     };
 
     let id = db.insert_slice(&slice)?;
@@ -1752,15 +3555,7 @@ async fn import_audio_slice(
         kind: "LockError".to_string(),
     })?.clone();
 
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
 
     let source_path = PathBuf::from(&file_path);
     if !source_path.exists() {
@@ -1770,73 +3565,212 @@ async fn import_audio_slice(
         });
     }
 
-    let filename = source_path.file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| ApiError {
-            message: "Invalid filename".to_string(),
-            kind: "ValidationError".to_string(),
-        })?
-        .to_string();
-
-    // Check if a slice with this filename already exists
-    if db.slice_exists(&filename)? {
-        return Err(ApiError {
-            message: format!("A slice with filename '{}' already exists", filename),
+    match importer::import_audio_file(&config, &db, &source_path, title, None, None)? {
+        ImportOutcome::Imported(id) => {
+            info!("Imported audio slice with ID {} from {}", id, file_path);
+            Ok(id)
+        }
+        ImportOutcome::Duplicate(existing) => Err(ApiError {
+            message: format!("This audio is already in the library as '{}'", existing),
             kind: "DuplicateError".to_string(),
-        });
+        }),
+        ImportOutcome::NameTaken => Err(ApiError {
+            message: format!(
+                "A slice with filename '{}' already exists",
+                source_path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            kind: "DuplicateError".to_string(),
+        }),
     }
+}
 
-    // Copy audio file to CiderPress audio directory
-    let dest_path = config.audio_dir().join(&filename);
-    std::fs::copy(&source_path, &dest_path).map_err(|e| ApiError {
-        message: format!("Failed to copy audio file: {}", e),
-        kind: "IoError".to_string(),
-    })?;
+/// Recursively import every audio file in a folder. Progress is emitted as
+/// `folder-import-progress` events; the returned report lists duplicates and per-file errors.
+#[tauri::command]
+async fn import_audio_folder(
+    state: State<'_, AppState>,
+    folder_path: String,
+) -> Result<FolderImportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
 
-    // Get file metadata
-    let metadata = std::fs::metadata(&dest_path).map_err(|e| ApiError {
-        message: format!("Failed to read file metadata: {}", e),
-        kind: "IoError".to_string(),
-    })?;
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        importer::import_folder(&config, &db, &PathBuf::from(&folder_path), |progress| {
+            if let Some(handle) = APP_HANDLE.get() {
+                let _ = handle.emit("folder-import-progress", progress.clone());
+            }
+        })
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Folder import task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
 
-    let file_size = metadata.len() as i64;
-    let ext = source_path.extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("unknown")
-        .to_lowercase();
+/// Import WhatsApp voice notes from an exported chat folder, or from the macOS app's
+/// media container when no folder is given. Progress uses the `folder-import-progress` event.
+#[tauri::command]
+async fn import_whatsapp_voice_notes(
+    state: State<'_, AppState>,
+    folder_path: Option<String>,
+) -> Result<FolderImportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
 
-    // Try to get audio duration
-    let duration = get_audio_duration(&dest_path);
+    let folder = folder_path.map(PathBuf::from).unwrap_or_else(whatsapp::default_container_path);
 
-    // Estimate transcription time (roughly 1 second per 34KB)
-    let estimated_time = (file_size / 34000).max(1) as i32;
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        whatsapp::import_voice_notes(&config, &db, &folder, |progress| {
+            if let Some(handle) = APP_HANDLE.get() {
+                let _ = handle.emit("folder-import-progress", progress.clone());
+            }
+        })
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("WhatsApp import task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
 
-    let slice_title = title.unwrap_or_else(|| {
-        source_path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("Imported Audio")
-            .to_string()
-    });
+/// Import Telegram voice messages from a Telegram Desktop export folder.
+/// Progress uses the `folder-import-progress` event.
+#[tauri::command]
+async fn import_telegram_voice_messages(
+    state: State<'_, AppState>,
+    folder_path: String,
+) -> Result<FolderImportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
 
-    let slice = Slice {
-        id: None,
-        original_audio_file_name: filename,
-        title: Some(slice_title),
-        transcribed: false,
-        audio_file_size: file_size,
-        audio_file_type: ext,
-        estimated_time_to_transcribe: estimated_time,
-        audio_time_length_seconds: duration,
-        transcription: None,
-        transcription_time_taken: None,
-        transcription_word_count: None,
-        transcription_model: None,
-        recording_date: Some(chrono::Utc::now().timestamp()),
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        telegram::import_voice_messages(&config, &db, &PathBuf::from(&folder_path), |progress| {
+            if let Some(handle) = APP_HANDLE.get() {
+                let _ = handle.emit("folder-import-progress", progress.clone());
+            }
+        })
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Telegram import task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Import Zoom/Teams recordings from the given folders, or from their default local
+/// recording folders. Progress uses the `folder-import-progress` event.
+#[tauri::command]
+async fn import_meeting_recordings(
+    state: State<'_, AppState>,
+    folder_paths: Option<Vec<String>>,
+) -> Result<FolderImportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let folders = match folder_paths {
+        Some(paths) => paths.into_iter().map(PathBuf::from).collect(),
+        None => meetings::default_meeting_folders(),
     };
 
-    let id = db.insert_slice(&slice)?;
-    info!("Imported audio slice with ID {} from {}", id, file_path);
-    Ok(id)
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        meetings::import_meeting_recordings(&config, &db, &folders, |progress| {
+            if let Some(handle) = APP_HANDLE.get() {
+                let _ = handle.emit("folder-import-progress", progress.clone());
+            }
+        })
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Meeting import task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// Import recordings from a third-party recorder app, from `folder_path` or the app's
+/// default folder. Progress uses the `folder-import-progress` event.
+#[tauri::command]
+async fn import_recorder_app(
+    state: State<'_, AppState>,
+    app: RecorderApp,
+    folder_path: Option<String>,
+) -> Result<FolderImportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    let folder = folder_path.map(PathBuf::from)
+        .or_else(|| app.default_folder())
+        .ok_or_else(|| ApiError {
+            message: format!("A folder is required to import {:?} recordings", app),
+            kind: "ValidationError".to_string(),
+        })?;
+
+    tokio::task::spawn_blocking(move || {
+        let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
+        let db = db_pool::connect(&db_path)?;
+        recorders::import_recorder_app(&config, &db, app, &folder, |progress| {
+            if let Some(handle) = APP_HANDLE.get() {
+                let _ = handle.emit("folder-import-progress", progress.clone());
+            }
+        })
+    })
+    .await
+    .map_err(|e| ApiError {
+        message: format!("Recorder import task failed: {}", e),
+        kind: "TaskError".to_string(),
+    })?
+    .map_err(ApiError::from)
+}
+
+/// List the episodes of a podcast feed, for choosing which to import.
+#[tauri::command]
+async fn fetch_podcast_feed(feed_url: String) -> Result<PodcastFeed, ApiError> {
+    podcast::fetch_feed(&feed_url).await.map_err(ApiError::from)
+}
+
+/// Download the chosen episodes of a podcast feed and import them as slices, transcribing
+/// them afterwards when `transcribe` is set. Progress uses the `folder-import-progress` event.
+#[tauri::command]
+async fn import_podcast_episodes(
+    state: State<'_, AppState>,
+    feed_url: String,
+    episode_ids: Vec<String>,
+    transcribe: bool,
+) -> Result<FolderImportReport, ApiError> {
+    let config = state.config.lock().map_err(|e| ApiError {
+        message: format!("Failed to lock config: {}", e),
+        kind: "LockError".to_string(),
+    })?.clone();
+
+    podcast::import_episodes(&config, &feed_url, &episode_ids, transcribe, |progress| {
+        if let Some(handle) = APP_HANDLE.get() {
+            let _ = handle.emit("folder-import-progress", progress.clone());
+        }
+    })
+    .await
+    .map_err(ApiError::from)
 }
 
 #[tauri::command]
@@ -1845,15 +3779,7 @@ async fn import_text_file_slice(
     file_path: String,
     title: Option<String>,
 ) -> Result<i64, ApiError> {
-    let db_guard = state.db.lock().map_err(|e| ApiError {
-        message: format!("Failed to lock database: {}", e),
-        kind: "LockError".to_string(),
-    })?;
-
-    let db = db_guard.as_ref().ok_or_else(|| ApiError {
-        message: "Database not initialized".to_string(),
-        kind: "DatabaseError".to_string(),
-    })?;
+    let db = state.db()?;
 
     let source_path = PathBuf::from(&file_path);
     if !source_path.exists() {
@@ -1899,6 +3825,14 @@ async fn import_text_file_slice(
         transcription_word_count: Some(word_count),
         transcription_model: Some("imported".to_string()),
         recording_date: Some(chrono::Utc::now().timestamp()),
+        source: None,
+        starred: false,
+        was_edited: false,
+        content_hash: None,
+        source_relative_path: None,
+        notes: None,
+        pinned: false,
+        summary: None,
     };
 
     let id = db.insert_slice(&slice)?;
@@ -1940,8 +3874,11 @@ pub fn run() {
     ffmpeg_next::log::set_level(ffmpeg_next::log::Level::Fatal);
 
     // Initialize database
+    encryption::apply_config(&config);
+    pii::apply_config(&config);
+    labeling::apply_config(&config);
     let db_path = config.ciderpress_home_path().join("CiderPress-db.sqlite");
-    let db = match Database::new(&db_path) {
+    let db = match db_pool::pool_for(&db_path) {
         Ok(db) => Some(db),
         Err(e) => {
             eprintln!("Failed to initialize database: {}", e);
@@ -1964,10 +3901,44 @@ pub fn run() {
             update_config,
             validate_paths,
             start_migration,
+            migrate_source_profile,
+            set_watch_mode,
+            get_watch_mode_status,
+            set_inbox_folder,
+            get_inbox_status,
+            restore_backup,
+            enable_database_encryption,
+            export_portable_library,
+            import_portable_library,
+            set_migration_schedule,
+            set_sync_export,
+            run_sync_export,
             get_migration_stats,
+            rollback_last_migration,
+            get_last_migration_summary,
+            list_ios_backups,
+            start_ios_backup_migration,
             get_pre_migration_stats,
             clear_database,
+            verify_library,
+            check_database,
+            compact_database,
+            delete_slices,
+            trash_slices,
+            list_trash,
+            restore_slices,
+            empty_trash,
+            find_duplicate_slices,
+            archive_slices,
+            unarchive_slices,
             get_slice_records,
+            list_slices,
+            search_slices,
+            ranked_search,
+            set_phonetic_index,
+            get_phonetic_index_enabled,
+            list_search_history,
+            clear_search_history,
             get_stats,
             list_recordings,
             search_recordings,
@@ -1979,7 +3950,14 @@ pub fn run() {
             resume_transcription,
             stop_transcription,
             export_transcribed_text,
+            export_slices_json,
+            export_html,
+            export_book,
+            export_zip,
+            send_to_apple_notes,
+            compose_email,
             export_audio,
+            export_voice_memos_layout,
             update_slice_name,
             update_slice,
             update_transcription_model,
@@ -1987,9 +3965,20 @@ pub fn run() {
             get_downloaded_models,
             download_whisper_model,
             pick_directory,
+            get_slice_transcription,
+            list_recent_slices,
+            get_slice_details,
+            link_slices,
+            unlink_slices,
             get_slice_audio_bytes,
             update_slice_names_from_audio,
             update_recording_title,
+            toggle_slice_starred,
+            set_slice_pinned,
+            update_slice_notes,
+            get_slice_history,
+            revert_slice_change,
+            replace_in_transcriptions,
             auto_populate_titles,
             populate_audio_durations,
             backfill_recording_dates,
@@ -1997,27 +3986,110 @@ pub fn run() {
             create_label,
             update_label,
             delete_label,
+            list_collections,
+            create_collection,
+            move_collection,
+            delete_collection,
+            move_slices_to_collection,
             get_slice_labels,
+            assign_label,
+            remove_label,
+            get_slices_by_label,
+            get_slice_metadata,
+            set_slice_metadata,
+            delete_slice_metadata,
+            auto_label_slices,
+            suggest_labels,
+            accept_label_suggestion,
+            reject_label_suggestion,
+            set_label_prompt,
+            list_label_prompts,
+            run_label_prompts,
+            get_derived_documents,
+            delete_derived_document,
             log_user_action,
             nlm_get_status,
+            nlm_check_binary,
+            nlm_install_binary,
             nlm_authenticate,
             nlm_list_notebooks,
             nlm_add_text,
             nlm_add_audio,
+            nlm_upload_batch,
+            nlm_resume_batch,
+            nlm_get_batch,
+            nlm_remove_source,
+            nlm_get_slice_sources,
+            nlm_get_slice_uploads,
+            nlm_get_slice_source_parts,
+            nlm_set_label_notebook,
+            nlm_list_label_notebooks,
             nlm_list_profiles,
+            nlm_list_accounts,
+            nlm_save_account,
+            nlm_switch_account,
             nlm_auth_with_profile,
             nlm_create_notebook,
+            nlm_rename_notebook,
+            nlm_get_share_link,
+            nlm_get_invocations,
+            nlm_generate_audio_overview,
+            nlm_import_notes,
             nlm_get_notebook_details,
+            nlm_request_notebook_deletion,
+            nlm_delete_notebook,
+            drive_authorize,
+            drive_is_connected,
+            drive_disconnect,
+            drive_sync_slices,
+            drive_get_slice_files,
+            summarize_slice,
+            summarize_slices,
+            generate_slice_title,
+            generate_slice_titles,
+            analyze_slice_sentiment,
+            analyze_sentiment,
+            get_slice_sentiment,
+            scan_pii,
+            redact_pii,
+            set_pii_redaction,
+            extract_slice_entities,
+            extract_entities,
+            get_slice_entities,
+            list_entities,
+            find_slices_by_entity,
+            translate_transcript,
+            get_slice_translations,
+            delete_slice_translation,
+            update_transcript_index,
+            search_transcripts_semantic,
+            ask_transcripts,
             get_system_info,
             open_url,
             create_text_slice,
             import_audio_slice,
+            import_audio_folder,
+            import_whatsapp_voice_notes,
+            import_telegram_voice_messages,
+            import_recorder_app,
+            fetch_podcast_feed,
+            import_podcast_episodes,
+            import_meeting_recordings,
             import_text_file_slice
         ])
         .setup(|app| {
             // Initialize global app handle for event emission
             init_app_handle(app.handle().clone());
 
+            // Resume watch mode, scheduled migration, the inbox and the sync folder export if
+            // they were left enabled, and empty expired slices from the trash
+            let state = app.state::<AppState>();
+            if let Ok(config) = state.config.lock() {
+                backend::nlm::apply_config(&config);
+                // Also picks up NotebookLM uploads queued or waiting on a retry when the app quit
+                start_background_workers(&config);
+            }
+
             // Set window title with app version
             if let Some(window) = app.get_webview_window("main") {
                 let version = env!("CARGO_PKG_VERSION");
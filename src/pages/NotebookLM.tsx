@@ -44,6 +44,8 @@ interface NlmStatus {
 interface NlmNotebook {
   id: string;
   title: string;
+  source_count: number | null;
+  last_updated: string | null;
 }
 
 interface NlmBrowserProfile {
@@ -51,12 +53,35 @@ interface NlmBrowserProfile {
   display_name: string;
 }
 
+interface NlmSourceInfo {
+  id: string;
+  title: string;
+  source_type: string | null;
+  status: string | null;
+  last_updated: string | null;
+}
+
+interface NlmNote {
+  id: string;
+  title: string;
+  content: string;
+}
+
+interface NlmAnalytics {
+  source_count: number | null;
+  note_count: number | null;
+  view_count: number | null;
+  last_viewed: string | null;
+  [key: string]: unknown;
+}
+
 interface NlmNotebookDetails {
   id: string;
   title: string;
-  sources: string;
-  notes: string;
-  analytics: string;
+  sources: NlmSourceInfo[];
+  notes: NlmNote[];
+  analytics: NlmAnalytics | null;
+  errors: string[];
 }
 
 export default function NotebookLM() {
@@ -457,25 +482,54 @@ export default function NotebookLM() {
               <Code>{notebookDetails.id}</Code>
             </div>
 
+            {notebookDetails.errors.map(message => (
+              <Alert key={message} color="orange" icon={<IconInfoCircle size={16} />}>
+                {message}
+              </Alert>
+            ))}
+
             <div>
-              <Text size="sm" fw={500} mb={4}>Sources</Text>
-              <Code block style={{ whiteSpace: 'pre-wrap', maxHeight: 200, overflow: 'auto' }}>
-                {notebookDetails.sources || 'No sources found.'}
-              </Code>
+              <Text size="sm" fw={500} mb={4}>Sources ({notebookDetails.sources.length})</Text>
+              {notebookDetails.sources.length === 0 ? (
+                <Text size="sm" c="dimmed">No sources found.</Text>
+              ) : (
+                <Stack gap={4} style={{ maxHeight: 200, overflow: 'auto' }}>
+                  {notebookDetails.sources.map(source => (
+                    <Group key={source.id} gap="xs" wrap="nowrap">
+                      <Text size="sm" truncate>{source.title || source.id}</Text>
+                      {source.source_type && <Badge size="xs" variant="light">{source.source_type}</Badge>}
+                      {source.status && <Badge size="xs" variant="outline">{source.status}</Badge>}
+                    </Group>
+                  ))}
+                </Stack>
+              )}
             </div>
 
             <div>
-              <Text size="sm" fw={500} mb={4}>Notes</Text>
-              <Code block style={{ whiteSpace: 'pre-wrap', maxHeight: 200, overflow: 'auto' }}>
-                {notebookDetails.notes || 'No notes found.'}
-              </Code>
+              <Text size="sm" fw={500} mb={4}>Notes ({notebookDetails.notes.length})</Text>
+              {notebookDetails.notes.length === 0 ? (
+                <Text size="sm" c="dimmed">No notes found.</Text>
+              ) : (
+                <Stack gap="xs" style={{ maxHeight: 200, overflow: 'auto' }}>
+                  {notebookDetails.notes.map(note => (
+                    <div key={note.id}>
+                      <Text size="sm" fw={500}>{note.title || '(untitled)'}</Text>
+                      <Text size="xs" c="dimmed" lineClamp={3} style={{ whiteSpace: 'pre-wrap' }}>{note.content}</Text>
+                    </div>
+                  ))}
+                </Stack>
+              )}
             </div>
 
             <div>
               <Text size="sm" fw={500} mb={4}>Analytics</Text>
-              <Code block style={{ whiteSpace: 'pre-wrap', maxHeight: 200, overflow: 'auto' }}>
-                {notebookDetails.analytics || 'No analytics available.'}
-              </Code>
+              {notebookDetails.analytics ? (
+                <Code block style={{ whiteSpace: 'pre-wrap', maxHeight: 200, overflow: 'auto' }}>
+                  {JSON.stringify(notebookDetails.analytics, null, 2)}
+                </Code>
+              ) : (
+                <Text size="sm" c="dimmed">No analytics available.</Text>
+              )}
             </div>
           </Stack>
         ) : null}
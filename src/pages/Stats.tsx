@@ -30,7 +30,7 @@ import {
   Card,
   SimpleGrid
 } from '@mantine/core';
-import { BarChart } from '@mantine/charts';
+import { BarChart, LineChart } from '@mantine/charts';
 import { IconMicrophone, IconClock, IconFileText, IconTrendingUp, IconX } from '@tabler/icons-react';
 
 interface YearCount {
@@ -43,6 +43,12 @@ interface AudioLengthBucket {
   count: number;
 }
 
+interface MoodPoint {
+  month: string;
+  average_score: number;
+  count: number;
+}
+
 interface Stats {
   total_files: number;
   total_transcribed: number;
@@ -52,6 +58,7 @@ interface Stats {
   avg_file_bytes: number;
   count_by_year: YearCount[];
   count_by_audio_length: AudioLengthBucket[];
+  mood_by_month: MoodPoint[];
 }
 
 export default function Stats() {
@@ -238,6 +245,30 @@ export default function Stats() {
               </Stack>
             </Paper>
           </Grid.Col>
+
+          {/* Mood over Time */}
+          <Grid.Col span={12}>
+            <Paper p="lg" withBorder>
+              <Stack gap="md">
+                <Title order={3}>Mood over Time</Title>
+                {stats.mood_by_month.length > 0 ? (
+                  <LineChart
+                    h={200}
+                    data={stats.mood_by_month}
+                    dataKey="month"
+                    series={[{ name: 'average_score', label: 'Average mood', color: 'grape.6' }]}
+                    yAxisProps={{ domain: [-1, 1] }}
+                    referenceLines={[{ y: 0, color: 'gray.5' }]}
+                    gridAxis="none"
+                  />
+                ) : (
+                  <Text size="sm" c="dimmed" ta="center">
+                    No sentiment data yet. Analyze transcripts to see mood over time.
+                  </Text>
+                )}
+              </Stack>
+            </Paper>
+          </Grid.Col>
         </Grid>
 
         {/* File Size Information */}